@@ -0,0 +1,183 @@
+//! BABE consensus data provider of the pending blocks of the Ethereum RPC.
+//!
+//! The Ethereum RPC builds a pending block on top of the best block to serve the `pending` tag
+//! of `eth_call`, `eth_estimateGas` and the other state methods. On a BABE chain, the runtime
+//! reads the slot and the author of a block from its BABE pre-digest, so the pending block is
+//! given the pre-digest of a secondary claim of the slot found in its inherent data.
+
+use codec::Encode;
+use fc_rpc::pending::ConsensusDataProvider;
+use sp_api::ProvideRuntimeApi;
+use sp_application_crypto::ByteArray;
+use sp_consensus_babe::{
+    digests::{CompatibleDigestItem, PreDigest, SecondaryPlainPreDigest},
+    inherents::BabeInherentData,
+    BabeApi, Epoch, Randomness, Slot, KEY_TYPE,
+};
+use sp_core::{blake2_256, U256};
+use sp_inherents::InherentData;
+use sp_keystore::KeystorePtr;
+use sp_runtime::{
+    traits::{Block as BlockT, Header as HeaderT},
+    Digest, DigestItem,
+};
+use std::{marker::PhantomData, sync::Arc};
+
+/// Provides the BABE pre-digest of the pending blocks.
+pub struct BabeConsensusDataProvider<B, C> {
+    client: Arc<C>,
+    keystore: KeystorePtr,
+    _marker: PhantomData<B>,
+}
+
+impl<B, C> BabeConsensusDataProvider<B, C> {
+    pub fn new(client: Arc<C>, keystore: KeystorePtr) -> Self {
+        Self {
+            client,
+            keystore,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<B, C> ConsensusDataProvider<B> for BabeConsensusDataProvider<B, C>
+where
+    B: BlockT,
+    C: ProvideRuntimeApi<B> + Send + Sync,
+    C::Api: BabeApi<B>,
+{
+    fn create_digest(
+        &self,
+        parent: &B::Header,
+        data: &InherentData,
+    ) -> Result<Digest, sp_inherents::Error> {
+        let slot = data
+            .babe_inherent_data()?
+            .ok_or_else(|| sp_inherents::Error::Application(Box::from("missing BABE slot")))?;
+        let epoch = self
+            .client
+            .runtime_api()
+            .current_epoch(parent.hash())
+            .map_err(|err| sp_inherents::Error::Application(Box::new(err)))?;
+        let pre_digest = pre_digest(slot, &epoch, &self.keystore).ok_or_else(|| {
+            sp_inherents::Error::Application(Box::from("no BABE authorities in the current epoch"))
+        })?;
+
+        Ok(Digest {
+            logs: vec![<DigestItem as CompatibleDigestItem>::babe_pre_digest(
+                pre_digest,
+            )],
+        })
+    }
+}
+
+/// Returns the pre-digest of a secondary claim of `slot` in `epoch`, claimed by the first
+/// authority of the epoch whose key is in `keystore`, or else by the secondary author of the
+/// slot. Returns `None` if the epoch has no authorities.
+pub fn pre_digest(slot: Slot, epoch: &Epoch, keystore: &KeystorePtr) -> Option<PreDigest> {
+    if epoch.authorities.is_empty() {
+        return None;
+    }
+    let authority_index = epoch
+        .authorities
+        .iter()
+        .position(|(authority, _)| keystore.has_keys(&[(authority.to_raw_vec(), KEY_TYPE)]))
+        .unwrap_or_else(|| secondary_slot_author(slot, epoch.authorities.len(), &epoch.randomness));
+
+    Some(PreDigest::SecondaryPlain(SecondaryPlainPreDigest {
+        authority_index: authority_index as u32,
+        slot,
+    }))
+}
+
+/// Returns the index of the author of the secondary claims of `slot`, chosen like the BABE
+/// client does.
+fn secondary_slot_author(slot: Slot, authorities: usize, randomness: &Randomness) -> usize {
+    let random = U256::from_big_endian(&(randomness, slot).using_encoded(blake2_256));
+    (random % U256::from(authorities)).as_usize()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sp_consensus_babe::{AllowedSlots, AuthorityId, BabeEpochConfiguration};
+    use sp_keystore::{testing::MemoryKeystore, Keystore};
+
+    fn epoch(authorities: Vec<AuthorityId>) -> Epoch {
+        Epoch {
+            epoch_index: 0,
+            start_slot: Slot::from(0),
+            duration: 10,
+            authorities: authorities
+                .into_iter()
+                .map(|authority| (authority, 1))
+                .collect(),
+            randomness: [7; 32],
+            config: BabeEpochConfiguration {
+                c: (1, 4),
+                allowed_slots: AllowedSlots::PrimaryAndSecondaryPlainSlots,
+            },
+        }
+    }
+
+    fn authority(keystore: &dyn Keystore) -> AuthorityId {
+        keystore
+            .sr25519_generate_new(KEY_TYPE, None)
+            .unwrap()
+            .into()
+    }
+
+    fn authority_index(pre_digest: Option<PreDigest>) -> u32 {
+        match pre_digest {
+            Some(PreDigest::SecondaryPlain(pre_digest)) => pre_digest.authority_index,
+            other => panic!("unexpected pre-digest {:?}", other),
+        }
+    }
+
+    #[test]
+    fn claims_the_slot_for_the_local_authority() {
+        let remote = MemoryKeystore::new();
+        let local: KeystorePtr = Arc::new(MemoryKeystore::new());
+        let epoch = epoch(vec![
+            authority(&remote),
+            authority(&*local),
+            authority(&remote),
+        ]);
+
+        for slot in 0..10u64 {
+            let pre_digest = pre_digest(Slot::from(slot), &epoch, &local);
+            assert_eq!(
+                pre_digest.as_ref().map(|p| p.slot()),
+                Some(Slot::from(slot))
+            );
+            assert_eq!(authority_index(pre_digest), 1);
+        }
+    }
+
+    #[test]
+    fn claims_the_slot_for_the_secondary_author_without_local_authority() {
+        let remote = MemoryKeystore::new();
+        let keystore: KeystorePtr = Arc::new(MemoryKeystore::new());
+        let epoch = epoch(vec![
+            authority(&remote),
+            authority(&remote),
+            authority(&remote),
+        ]);
+
+        for slot in 0..10u64 {
+            let slot = Slot::from(slot);
+            let expected = secondary_slot_author(slot, 3, &epoch.randomness);
+            assert!(expected < 3);
+            assert_eq!(
+                authority_index(pre_digest(slot, &epoch, &keystore)),
+                expected as u32
+            );
+        }
+    }
+
+    #[test]
+    fn no_claim_without_authorities() {
+        let keystore: KeystorePtr = Arc::new(MemoryKeystore::new());
+        assert!(pre_digest(Slot::from(1), &epoch(Vec::new()), &keystore).is_none());
+    }
+}
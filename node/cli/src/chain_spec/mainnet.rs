@@ -23,7 +23,8 @@
 use common_runtime::AccountId;
 use hex_literal::hex;
 use kitchensink_mainnet_runtime::{
-    constants::currency::*, wasm_binary_unwrap, Block, MaxNominations, SessionKeys, StakerStatus,
+    constants::currency::*, wasm_binary_unwrap, Block, DefaultBaseFeePerGas, MaxNominations,
+    SessionKeys, StakerStatus,
 };
 use ecdsa_keyring::Keyring;
 use pallet_im_online::sr25519::AuthorityId as ImOnlineId;
@@ -38,7 +39,7 @@ use sp_consensus_beefy::ecdsa_crypto::AuthorityId as BeefyId;
 use sp_consensus_grandpa::AuthorityId as GrandpaId;
 use sp_core::ecdsa;
 use sp_core::{crypto::UncheckedInto, Pair, Public};
-use sp_core::{H160, U256};
+use sp_core::{Get, H160, U256};
 use sp_mixnet::types::AuthorityId as MixnetId;
 use sp_runtime::{
     traits::{IdentifyAccount, Verify},
@@ -406,6 +407,8 @@ pub fn testnet_genesis(
             "minJoinBond": 1 * DOLLARS,
         },
         "evmChainId": { "chainId": evm_chain_id },
+        "dynamicFee": { "minGasPrice": DefaultBaseFeePerGas::get() },
+        "deploymentAllowlist": { "allowedDeployers": vec![H160::from(root_key.clone())] },
     })
 }
 
@@ -24,7 +24,8 @@ use common_runtime::AccountId;
 use ecdsa_keyring::Keyring;
 use hex_literal::hex;
 use kitchensink_testnet_runtime::{
-    constants::currency::*, wasm_binary_unwrap, Block, MaxNominations, SessionKeys, StakerStatus,
+    constants::currency::*, wasm_binary_unwrap, Block, DefaultBaseFeePerGas, MaxNominations,
+    SessionKeys, StakerStatus,
 };
 use pallet_im_online::sr25519::AuthorityId as ImOnlineId;
 use polkadot_sdk::*;
@@ -38,7 +39,7 @@ use sp_consensus_beefy::ecdsa_crypto::AuthorityId as BeefyId;
 use sp_consensus_grandpa::AuthorityId as GrandpaId;
 use sp_core::ecdsa;
 use sp_core::{crypto::UncheckedInto, sr25519, Pair, Public};
-use sp_core::{H160, U256};
+use sp_core::{Get, H160, U256};
 use sp_mixnet::types::AuthorityId as MixnetId;
 use sp_runtime::{
     traits::{IdentifyAccount, Verify},
@@ -438,6 +439,8 @@ pub fn testnet_genesis(
             "minJoinBond": 1 * DOLLARS,
         },
         "evmChainId": { "chainId": evm_chain_id },
+        "dynamicFee": { "minGasPrice": DefaultBaseFeePerGas::get() },
+        "deploymentAllowlist": { "open": true },
     })
 }
 
@@ -82,6 +82,31 @@ pub struct EthConfiguration {
     /// Default value is 200MB.
     #[arg(long, default_value = "209715200")]
     pub frontier_sql_backend_cache_size: u64,
+
+    /// Enable the `debug_traceTransaction`, `debug_traceBlockByNumber`,
+    /// `debug_traceBlockByHash`, `debug_traceCall` and `eth_createAccessList` RPC methods.
+    #[arg(long)]
+    pub enable_debug_tracing: bool,
+
+    /// Enable the `trace_block`, `trace_transaction` and `trace_filter` RPC methods.
+    #[arg(long)]
+    pub enable_trace_api: bool,
+
+    /// Number of the most recent blocks whose traces are kept in the trace cache.
+    #[arg(long, default_value = "300")]
+    pub trace_cache_blocks: u32,
+
+    /// Maximum number of blocks replayed at once by the `debug_trace*` and `trace_*` RPC methods.
+    #[arg(long, default_value = "10")]
+    pub trace_max_permits: usize,
+
+    /// Maximum number of traces returned by `trace_filter`.
+    #[arg(long, default_value = "500")]
+    pub trace_filter_max_count: u32,
+
+    /// Maximum number of blocks in the range of `trace_filter`.
+    #[arg(long, default_value = "100")]
+    pub trace_max_block_range: u32,
 }
 
 impl Default for EthConfiguration {
@@ -99,6 +124,12 @@ impl Default for EthConfiguration {
             frontier_sql_backend_num_ops_timeout: 10000000,
             frontier_sql_backend_thread_count: 4,
             frontier_sql_backend_cache_size: 209715200,
+            enable_debug_tracing: false,
+            enable_trace_api: false,
+            trace_cache_blocks: 300,
+            trace_max_permits: 10,
+            trace_filter_max_count: 500,
+            trace_max_block_range: 100,
         }
     }
 }
@@ -492,6 +492,15 @@ pub fn new_full_base<N: NetworkBackend<Block, <Block as BlockT>::Hash>>(
             eth_config.eth_statuses_cache,
             prometheus_registry.clone(),
         ));
+        let trace_permits = node_rpc::TracePermits::new(eth_config.trace_max_permits);
+        let trace_cache = eth_config.enable_trace_api.then(|| {
+            Arc::new(node_rpc::TraceCacheTask::new(
+                task_manager.spawn_handle(),
+                client.clone(),
+                eth_config.trace_cache_blocks,
+                trace_permits.clone(),
+            ))
+        });
         let pubsub_notification_sinks: fc_mapping_sync::EthereumBlockNotificationSinks<
             fc_mapping_sync::EthereumBlockNotification<Block>,
         > = Default::default();
@@ -548,6 +557,11 @@ pub fn new_full_base<N: NetworkBackend<Block, <Block as BlockT>::Hash>>(
                     execute_gas_limit_multiplier,
                     forced_parent_hashes: None,
                     pending_create_inherent_data_providers,
+                    enable_debug_tracing: eth_config.enable_debug_tracing,
+                    trace_permits: trace_permits.clone(),
+                    trace_cache: trace_cache.clone(),
+                    trace_filter_max_count: eth_config.trace_filter_max_count,
+                    trace_filter_max_block_range: eth_config.trace_max_block_range,
                 };
 
                 let deps = node_rpc::FullDeps {
@@ -668,6 +682,7 @@ pub fn new_full_base<N: NetworkBackend<Block, <Block as BlockT>::Hash>>(
 
         let client_clone = client.clone();
         let slot_duration = import_setup.2.config().slot_duration().clone();
+        let target_gas_price = eth_config.target_gas_price;
         let babe_config = sc_consensus_babe::BabeParams {
             keystore: keystore_container.keystore(),
             client: client.clone(),
@@ -693,7 +708,10 @@ pub fn new_full_base<N: NetworkBackend<Block, <Block as BlockT>::Hash>>(
                             &parent,
                         )?;
 
-                    Ok((slot, timestamp, storage_proof))
+                    let dynamic_fee =
+                        fp_dynamic_fee::InherentDataProvider(U256::from(target_gas_price));
+
+                    Ok((slot, timestamp, storage_proof, dynamic_fee))
                 }
             },
             force_authoring,
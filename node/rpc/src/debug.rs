@@ -0,0 +1,785 @@
+//! `debug_trace*` RPC methods, replaying Ethereum transactions with the tracers of the runtime.
+
+use evm_tracing::{
+    opcode_name, AccountState, CallFrame, CallType, DebugRuntimeApi, StructLoggerConfig,
+    StructLogs, Trace, Tracer,
+};
+use fc_rpc::{frontier_backend_client, internal_err};
+use fc_rpc_core::types::{BlockNumberOrHash, Bytes};
+use jsonrpsee::core::{async_trait, RpcResult};
+use jsonrpsee::proc_macros::rpc;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::sync::Arc;
+use tokio::sync::{Semaphore, SemaphorePermit};
+// Substrate
+use common_runtime::opaque::Block;
+use sc_client_api::BlockBackend;
+use sp_api::{ApiExt, ProvideRuntimeApi};
+use sp_blockchain::HeaderBackend;
+use sp_core::{bytes::to_hex, H160, H256, U256};
+use sp_runtime::traits::{Block as BlockT, Header as HeaderT};
+// Frontier
+use fp_rpc::EthereumRuntimeRPCApi;
+
+/// Options of the `debug_trace*` methods, as accepted by geth.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TraceParams {
+    /// Name of the tracer, the struct logger if unset.
+    pub tracer: Option<String>,
+    /// Configuration of the tracer.
+    pub tracer_config: Option<TracerConfig>,
+    pub disable_storage: Option<bool>,
+    pub disable_stack: Option<bool>,
+    pub enable_memory: Option<bool>,
+    /// Ignored, tracing is bounded by the gas limit of the transactions.
+    pub timeout: Option<String>,
+}
+
+/// Configuration of the `callTracer`.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TracerConfig {
+    pub only_top_call: Option<bool>,
+}
+
+/// Call traced by `debug_traceCall`.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TraceCallRequest {
+    pub from: Option<H160>,
+    pub to: Option<H160>,
+    pub gas: Option<U256>,
+    pub gas_price: Option<U256>,
+    pub max_fee_per_gas: Option<U256>,
+    pub max_priority_fee_per_gas: Option<U256>,
+    pub value: Option<U256>,
+    #[serde(alias = "input")]
+    pub data: Option<Bytes>,
+    pub nonce: Option<U256>,
+    pub access_list: Option<Vec<AccessListItem>>,
+}
+
+/// Entry of the access list of a traced call.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccessListItem {
+    pub address: H160,
+    pub storage_keys: Vec<H256>,
+}
+
+impl From<(H160, Vec<H256>)> for AccessListItem {
+    fn from((address, storage_keys): (H160, Vec<H256>)) -> Self {
+        Self {
+            address,
+            storage_keys,
+        }
+    }
+}
+
+impl TryFrom<TraceParams> for Tracer {
+    type Error = jsonrpsee::types::ErrorObjectOwned;
+
+    fn try_from(params: TraceParams) -> Result<Self, Self::Error> {
+        match params.tracer.as_deref() {
+            None => Ok(Tracer::StructLogger(StructLoggerConfig {
+                disable_storage: params.disable_storage.unwrap_or_default(),
+                disable_stack: params.disable_stack.unwrap_or_default(),
+                enable_memory: params.enable_memory.unwrap_or_default(),
+            })),
+            Some("callTracer") => Ok(Tracer::CallTracer {
+                only_top_call: params
+                    .tracer_config
+                    .and_then(|config| config.only_top_call)
+                    .unwrap_or_default(),
+            }),
+            Some("prestateTracer") => Ok(Tracer::PrestateTracer),
+            Some(tracer) => Err(internal_err(format!("unsupported tracer: {tracer}"))),
+        }
+    }
+}
+
+/// Bound on the number of blocks replayed at once, shared by the `debug_trace*` and `trace_*`
+/// methods.
+#[derive(Clone)]
+pub struct TracePermits(Arc<Semaphore>);
+
+impl TracePermits {
+    pub fn new(max_permits: usize) -> Self {
+        Self(Arc::new(Semaphore::new(max_permits)))
+    }
+
+    /// Waits for a permit to replay a block.
+    pub(crate) async fn acquire(&self) -> RpcResult<SemaphorePermit<'_>> {
+        self.0
+            .acquire()
+            .await
+            .map_err(|_| internal_err("trace permits are closed"))
+    }
+}
+
+/// Runs `replay`, which replays EVM executions through the runtime, on a blocking thread, so it
+/// does not stall the RPC server.
+pub(crate) async fn replay_blocking<C, R>(
+    client: &Arc<C>,
+    replay: impl FnOnce(&C) -> RpcResult<R> + Send + 'static,
+) -> RpcResult<R>
+where
+    C: Send + Sync + 'static,
+    R: Send + 'static,
+{
+    let client = client.clone();
+    tokio::task::spawn_blocking(move || replay(client.as_ref()))
+        .await
+        .map_err(|err| internal_err(format!("{err:?}")))?
+}
+
+/// Debug trace API.
+#[rpc(server)]
+#[async_trait]
+pub trait DebugTraceApi {
+    /// Replays the transaction `transaction_hash` and returns its trace.
+    #[method(name = "debug_traceTransaction")]
+    async fn trace_transaction(
+        &self,
+        transaction_hash: H256,
+        params: Option<TraceParams>,
+    ) -> RpcResult<Value>;
+
+    /// Replays the block `number` and returns the traces of its transactions.
+    #[method(name = "debug_traceBlockByNumber")]
+    async fn trace_block_by_number(
+        &self,
+        number: BlockNumberOrHash,
+        params: Option<TraceParams>,
+    ) -> RpcResult<Value>;
+
+    /// Replays the block of the Ethereum block hash `hash` and returns the traces of its
+    /// transactions.
+    #[method(name = "debug_traceBlockByHash")]
+    async fn trace_block_by_hash(
+        &self,
+        hash: H256,
+        params: Option<TraceParams>,
+    ) -> RpcResult<Value>;
+
+    /// Executes a call on top of the block `number` and returns its trace.
+    #[method(name = "debug_traceCall")]
+    async fn trace_call(
+        &self,
+        request: TraceCallRequest,
+        number: Option<BlockNumberOrHash>,
+        params: Option<TraceParams>,
+    ) -> RpcResult<Value>;
+}
+
+/// Implementation of the debug trace API.
+pub struct DebugTrace<C> {
+    client: Arc<C>,
+    backend: Arc<dyn fc_api::Backend<Block>>,
+    permits: TracePermits,
+}
+
+impl<C> DebugTrace<C> {
+    pub fn new(
+        client: Arc<C>,
+        backend: Arc<dyn fc_api::Backend<Block>>,
+        permits: TracePermits,
+    ) -> Self {
+        Self {
+            client,
+            backend,
+            permits,
+        }
+    }
+}
+
+impl<C> DebugTrace<C>
+where
+    C: ProvideRuntimeApi<Block>
+        + BlockBackend<Block>
+        + HeaderBackend<Block>
+        + Send
+        + Sync
+        + 'static,
+    C::Api: DebugRuntimeApi<Block>,
+{
+    async fn trace_block(&self, hash: <Block as BlockT>::Hash, tracer: Tracer) -> RpcResult<Value> {
+        let _permit = self.permits.acquire().await?;
+        let traces = replay_blocking(&self.client, move |client| {
+            let (header, extrinsics) = load_block(client, hash)?;
+            ensure_tracing_runtime(client, *header.parent_hash())?;
+            client
+                .runtime_api()
+                .trace_block(*header.parent_hash(), extrinsics, &header, tracer)
+                .map_err(|err| internal_err(format!("{err:?}")))?
+                .map_err(|err| internal_err(format!("{err:?}")))
+        })
+        .await?;
+        Ok(Value::Array(
+            traces
+                .into_iter()
+                .map(|(transaction_hash, trace)| {
+                    json!({ "txHash": transaction_hash, "result": format_trace(trace) })
+                })
+                .collect(),
+        ))
+    }
+}
+
+#[async_trait]
+impl<C> DebugTraceApiServer for DebugTrace<C>
+where
+    C: ProvideRuntimeApi<Block>
+        + BlockBackend<Block>
+        + HeaderBackend<Block>
+        + Send
+        + Sync
+        + 'static,
+    C::Api: DebugRuntimeApi<Block> + EthereumRuntimeRPCApi<Block>,
+{
+    async fn trace_transaction(
+        &self,
+        transaction_hash: H256,
+        params: Option<TraceParams>,
+    ) -> RpcResult<Value> {
+        let tracer = Tracer::try_from(params.unwrap_or_default())?;
+        let hash = load_transaction_block_hash(
+            self.client.as_ref(),
+            self.backend.as_ref(),
+            transaction_hash,
+        )
+        .await?;
+
+        let _permit = self.permits.acquire().await?;
+        let trace = replay_blocking(&self.client, move |client| {
+            let (header, extrinsics) = load_block(client, hash)?;
+            ensure_tracing_runtime(client, *header.parent_hash())?;
+            client
+                .runtime_api()
+                .trace_transaction(
+                    *header.parent_hash(),
+                    extrinsics,
+                    &header,
+                    transaction_hash,
+                    tracer,
+                )
+                .map_err(|err| internal_err(format!("{err:?}")))?
+                .map_err(|err| internal_err(format!("{err:?}")))
+        })
+        .await?;
+        Ok(format_trace(trace))
+    }
+
+    async fn trace_block_by_number(
+        &self,
+        number: BlockNumberOrHash,
+        params: Option<TraceParams>,
+    ) -> RpcResult<Value> {
+        let tracer = Tracer::try_from(params.unwrap_or_default())?;
+        let hash =
+            load_block_hash(self.client.as_ref(), self.backend.as_ref(), Some(number)).await?;
+        self.trace_block(hash, tracer).await
+    }
+
+    async fn trace_block_by_hash(
+        &self,
+        hash: H256,
+        params: Option<TraceParams>,
+    ) -> RpcResult<Value> {
+        let tracer = Tracer::try_from(params.unwrap_or_default())?;
+        let hash =
+            load_ethereum_block_hash(self.client.as_ref(), self.backend.as_ref(), hash).await?;
+        self.trace_block(hash, tracer).await
+    }
+
+    async fn trace_call(
+        &self,
+        request: TraceCallRequest,
+        number: Option<BlockNumberOrHash>,
+        params: Option<TraceParams>,
+    ) -> RpcResult<Value> {
+        let tracer = Tracer::try_from(params.unwrap_or_default())?;
+        let hash = load_block_hash(self.client.as_ref(), self.backend.as_ref(), number).await?;
+        let _permit = self.permits.acquire().await?;
+        let trace = replay_blocking(&self.client, move |client| {
+            trace_call_at(client, hash, request, tracer)
+        })
+        .await?;
+        Ok(format_trace(trace))
+    }
+}
+
+/// Executes `request` on top of the block `hash` with `tracer`, filling in the gas limit of the
+/// block if the request has none.
+pub(crate) fn trace_call_at<C>(
+    client: &C,
+    hash: <Block as BlockT>::Hash,
+    request: TraceCallRequest,
+    tracer: Tracer,
+) -> RpcResult<Trace>
+where
+    C: ProvideRuntimeApi<Block>,
+    C::Api: DebugRuntimeApi<Block> + EthereumRuntimeRPCApi<Block>,
+{
+    ensure_tracing_runtime(client, hash)?;
+    let api = client.runtime_api();
+    let gas_limit = match request.gas {
+        Some(gas) => gas,
+        None => api
+            .current_block(hash)
+            .map_err(|err| internal_err(format!("{err:?}")))?
+            .map(|block| block.header.gas_limit)
+            .ok_or_else(|| internal_err("block gas limit not found"))?,
+    };
+    let (max_fee_per_gas, max_priority_fee_per_gas) = match request.gas_price {
+        Some(gas_price) => (Some(gas_price), Some(gas_price)),
+        None => (request.max_fee_per_gas, request.max_priority_fee_per_gas),
+    };
+    let access_list = request.access_list.map(|access_list| {
+        access_list
+            .into_iter()
+            .map(|item| (item.address, item.storage_keys))
+            .collect()
+    });
+
+    api.trace_call(
+        hash,
+        request.from.unwrap_or_default(),
+        request.to,
+        request.data.map(|data| data.0).unwrap_or_default(),
+        request.value.unwrap_or_default(),
+        gas_limit,
+        max_fee_per_gas,
+        max_priority_fee_per_gas,
+        request.nonce,
+        access_list,
+        tracer,
+    )
+    .map_err(|err| internal_err(format!("{err:?}")))?
+    .map_err(|err| internal_err(format!("{err:?}")))
+}
+
+/// Fails if the runtime at the block `hash` does not implement `DebugRuntimeApi`, which only the
+/// runtimes built with their `evm-tracing` feature do.
+pub(crate) fn ensure_tracing_runtime<C>(client: &C, hash: <Block as BlockT>::Hash) -> RpcResult<()>
+where
+    C: ProvideRuntimeApi<Block>,
+    C::Api: DebugRuntimeApi<Block>,
+{
+    let has_api = client
+        .runtime_api()
+        .has_api::<dyn DebugRuntimeApi<Block>>(hash)
+        .map_err(|err| internal_err(format!("{err:?}")))?;
+    if has_api {
+        Ok(())
+    } else {
+        Err(internal_err(
+            "the runtime does not support tracing, it must be built with the `evm-tracing` feature",
+        ))
+    }
+}
+
+/// Returns the header and the extrinsics of the block `hash`.
+pub(crate) fn load_block<C>(
+    client: &C,
+    hash: <Block as BlockT>::Hash,
+) -> RpcResult<(<Block as BlockT>::Header, Vec<<Block as BlockT>::Extrinsic>)>
+where
+    C: BlockBackend<Block> + HeaderBackend<Block>,
+{
+    let header = client
+        .header(hash)
+        .map_err(|err| internal_err(format!("{err:?}")))?
+        .ok_or_else(|| internal_err("header not found"))?;
+    let extrinsics = client
+        .block_body(hash)
+        .map_err(|err| internal_err(format!("{err:?}")))?
+        .ok_or_else(|| internal_err("block body not found"))?;
+    Ok((header, extrinsics))
+}
+
+/// Returns the hash of the block `number`, the latest block if unset.
+pub(crate) async fn load_block_hash<C>(
+    client: &C,
+    backend: &dyn fc_api::Backend<Block>,
+    number: Option<BlockNumberOrHash>,
+) -> RpcResult<<Block as BlockT>::Hash>
+where
+    C: HeaderBackend<Block> + 'static,
+{
+    let id = frontier_backend_client::native_block_id::<Block, C>(client, backend, number)
+        .await?
+        .ok_or_else(|| internal_err("block not found"))?;
+    client
+        .block_hash_from_id(&id)
+        .map_err(|err| internal_err(format!("{err:?}")))?
+        .ok_or_else(|| internal_err("block not found"))
+}
+
+/// Returns the hash of the block of the Ethereum block hash `hash`.
+pub(crate) async fn load_ethereum_block_hash<C>(
+    client: &C,
+    backend: &dyn fc_api::Backend<Block>,
+    hash: H256,
+) -> RpcResult<<Block as BlockT>::Hash>
+where
+    C: HeaderBackend<Block> + 'static,
+{
+    frontier_backend_client::load_hash::<Block, C>(client, backend, hash)
+        .await?
+        .ok_or_else(|| internal_err("block not found"))
+}
+
+/// Returns the hash of the canonical block including the Ethereum transaction
+/// `transaction_hash`.
+pub(crate) async fn load_transaction_block_hash<C>(
+    client: &C,
+    backend: &dyn fc_api::Backend<Block>,
+    transaction_hash: H256,
+) -> RpcResult<<Block as BlockT>::Hash>
+where
+    C: HeaderBackend<Block> + 'static,
+{
+    let (ethereum_block_hash, _) = frontier_backend_client::load_transactions::<Block, C>(
+        client,
+        backend,
+        transaction_hash,
+        true,
+    )
+    .await?
+    .ok_or_else(|| internal_err("transaction not found"))?;
+    load_ethereum_block_hash(client, backend, ethereum_block_hash).await
+}
+
+/// Formats `trace` like the tracers of geth.
+fn format_trace(trace: Trace) -> Value {
+    match trace {
+        Trace::StructLogs(logs) => format_struct_logs(logs),
+        Trace::Call(call) => format_call(call),
+        Trace::Prestate(accounts) => format_prestate(accounts),
+        Trace::AccessList(access_list) => json!(access_list
+            .into_iter()
+            .map(AccessListItem::from)
+            .collect::<Vec<_>>()),
+    }
+}
+
+fn format_struct_logs(logs: StructLogs) -> Value {
+    let struct_logs = logs
+        .struct_logs
+        .into_iter()
+        .map(|log| {
+            let mut entry = Map::new();
+            entry.insert("pc".into(), log.pc.into());
+            entry.insert("op".into(), opcode_name(log.op).into());
+            entry.insert("gas".into(), log.gas.into());
+            entry.insert("gasCost".into(), log.gas_cost.into());
+            entry.insert("depth".into(), log.depth.into());
+            if let Some(stack) = log.stack {
+                let stack = stack
+                    .into_iter()
+                    .map(|word| format!("{:#x}", U256::from_big_endian(word.as_bytes())).into())
+                    .collect();
+                entry.insert("stack".into(), Value::Array(stack));
+            }
+            if let Some(memory) = log.memory {
+                let memory = memory
+                    .chunks(32)
+                    .map(|word| unprefixed_hex(word).into())
+                    .collect();
+                entry.insert("memory".into(), Value::Array(memory));
+            }
+            if let Some(storage) = log.storage {
+                let storage = storage
+                    .into_iter()
+                    .map(|(index, value)| {
+                        (
+                            unprefixed_hex(index.as_bytes()),
+                            unprefixed_hex(value.as_bytes()).into(),
+                        )
+                    })
+                    .collect();
+                entry.insert("storage".into(), Value::Object(storage));
+            }
+            if let Some(error) = log.error {
+                entry.insert("error".into(), String::from_utf8_lossy(&error).into());
+            }
+            Value::Object(entry)
+        })
+        .collect::<Vec<_>>();
+
+    json!({
+        "gas": logs.gas,
+        "failed": logs.failed,
+        "returnValue": unprefixed_hex(&logs.return_value),
+        "structLogs": struct_logs,
+    })
+}
+
+fn format_call(call: CallFrame) -> Value {
+    let call_type = match call.call_type {
+        CallType::Call => "CALL",
+        CallType::StaticCall => "STATICCALL",
+        CallType::DelegateCall => "DELEGATECALL",
+        CallType::CallCode => "CALLCODE",
+        CallType::Create => "CREATE",
+        CallType::Create2 => "CREATE2",
+        CallType::SelfDestruct => "SELFDESTRUCT",
+    };
+
+    let mut frame = Map::new();
+    frame.insert("type".into(), call_type.into());
+    frame.insert("from".into(), json!(call.from));
+    frame.insert("to".into(), json!(call.to));
+    if let Some(value) = call.value {
+        frame.insert("value".into(), format!("{value:#x}").into());
+    }
+    frame.insert("gas".into(), format!("{:#x}", call.gas).into());
+    frame.insert("gasUsed".into(), format!("{:#x}", call.gas_used).into());
+    frame.insert("input".into(), to_hex(&call.input, false).into());
+    if !call.output.is_empty() {
+        frame.insert("output".into(), to_hex(&call.output, false).into());
+    }
+    if let Some(error) = call.error {
+        frame.insert("error".into(), String::from_utf8_lossy(&error).into());
+    }
+    if let Some(revert_reason) = call.revert_reason {
+        frame.insert(
+            "revertReason".into(),
+            String::from_utf8_lossy(&revert_reason).into(),
+        );
+    }
+    if !call.calls.is_empty() {
+        let calls = call.calls.into_iter().map(format_call).collect();
+        frame.insert("calls".into(), Value::Array(calls));
+    }
+    Value::Object(frame)
+}
+
+fn format_prestate(accounts: Vec<AccountState>) -> Value {
+    let accounts = accounts
+        .into_iter()
+        .map(|account| {
+            let mut state = Map::new();
+            state.insert("balance".into(), format!("{:#x}", account.balance).into());
+            state.insert("nonce".into(), account.nonce.low_u64().into());
+            if !account.code.is_empty() {
+                state.insert("code".into(), to_hex(&account.code, false).into());
+            }
+            if !account.storage.is_empty() {
+                let storage = account
+                    .storage
+                    .into_iter()
+                    .map(|(index, value)| (to_hex(index.as_bytes(), false), json!(value)))
+                    .collect();
+                state.insert("storage".into(), Value::Object(storage));
+            }
+            (
+                to_hex(account.address.as_bytes(), false),
+                Value::Object(state),
+            )
+        })
+        .collect();
+    Value::Object(accounts)
+}
+
+/// Hex encoding without the `0x` prefix, as geth formats the struct logs.
+fn unprefixed_hex(bytes: &[u8]) -> String {
+    to_hex(bytes, false)[2..].to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use evm_tracing::StructLog;
+
+    fn word(value: u64) -> H256 {
+        H256::from_low_u64_be(value)
+    }
+
+    fn struct_log(op: u8) -> StructLog {
+        StructLog {
+            pc: 2,
+            op,
+            gas: 79_000,
+            gas_cost: 3,
+            depth: 1,
+            stack: None,
+            memory: None,
+            storage: None,
+            error: None,
+        }
+    }
+
+    #[test]
+    fn formats_struct_logs() {
+        let mut log = struct_log(0x55);
+        log.stack = Some(vec![word(42), word(0)]);
+        log.memory = Some([word(1).as_bytes(), word(2).as_bytes()].concat());
+        log.storage = Some(vec![(word(0), word(42))]);
+        let logs = StructLogs {
+            gas: 43_000,
+            failed: false,
+            return_value: vec![0xab, 0xcd],
+            struct_logs: vec![log, struct_log(0x60)],
+        };
+
+        let slot = "0000000000000000000000000000000000000000000000000000000000000000";
+        let value = "000000000000000000000000000000000000000000000000000000000000002a";
+        assert_eq!(
+            format_trace(Trace::StructLogs(logs)),
+            json!({
+                "gas": 43_000,
+                "failed": false,
+                "returnValue": "abcd",
+                "structLogs": [
+                    {
+                        "pc": 2,
+                        "op": "SSTORE",
+                        "gas": 79_000,
+                        "gasCost": 3,
+                        "depth": 1,
+                        "stack": ["0x2a", "0x0"],
+                        "memory": [
+                            "0000000000000000000000000000000000000000000000000000000000000001",
+                            "0000000000000000000000000000000000000000000000000000000000000002",
+                        ],
+                        "storage": { slot: value },
+                    },
+                    { "pc": 2, "op": "PUSH1", "gas": 79_000, "gasCost": 3, "depth": 1 },
+                ],
+            })
+        );
+    }
+
+    #[test]
+    fn formats_the_error_of_struct_logs() {
+        let mut log = struct_log(0x01);
+        log.error = Some(b"StackUnderflow".to_vec());
+        let logs = StructLogs {
+            gas: 100_000,
+            failed: true,
+            return_value: Vec::new(),
+            struct_logs: vec![log],
+        };
+
+        let formatted = format_trace(Trace::StructLogs(logs));
+        assert_eq!(formatted["failed"], json!(true));
+        assert_eq!(formatted["returnValue"], json!(""));
+        assert_eq!(formatted["structLogs"][0]["op"], json!("ADD"));
+        assert_eq!(formatted["structLogs"][0]["error"], json!("StackUnderflow"));
+    }
+
+    #[test]
+    fn formats_calls() {
+        let subcall = CallFrame {
+            call_type: CallType::StaticCall,
+            from: H160::repeat_byte(0xbb),
+            to: H160::repeat_byte(0xcc),
+            value: None,
+            gas: 0x100,
+            gas_used: 0x10,
+            input: vec![0x01],
+            output: Vec::new(),
+            error: Some(b"execution reverted".to_vec()),
+            revert_reason: Some(b"nope".to_vec()),
+            calls: Vec::new(),
+        };
+        let call = CallFrame {
+            call_type: CallType::Call,
+            from: H160::repeat_byte(0xaa),
+            to: H160::repeat_byte(0xbb),
+            value: Some(U256::from(1_000)),
+            gas: 0x5208,
+            gas_used: 0x520a,
+            input: vec![0x12, 0x34],
+            output: vec![0x56],
+            error: None,
+            revert_reason: None,
+            calls: vec![subcall],
+        };
+
+        assert_eq!(
+            format_trace(Trace::Call(call)),
+            json!({
+                "type": "CALL",
+                "from": "0xaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+                "to": "0xbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb",
+                "value": "0x3e8",
+                "gas": "0x5208",
+                "gasUsed": "0x520a",
+                "input": "0x1234",
+                "output": "0x56",
+                "calls": [{
+                    "type": "STATICCALL",
+                    "from": "0xbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb",
+                    "to": "0xcccccccccccccccccccccccccccccccccccccccc",
+                    "gas": "0x100",
+                    "gasUsed": "0x10",
+                    "input": "0x01",
+                    "error": "execution reverted",
+                    "revertReason": "nope",
+                }],
+            })
+        );
+    }
+
+    #[test]
+    fn formats_prestates() {
+        let accounts = vec![
+            AccountState {
+                address: H160::repeat_byte(0xaa),
+                balance: U256::from(255),
+                nonce: U256::from(3),
+                code: Vec::new(),
+                storage: Vec::new(),
+            },
+            AccountState {
+                address: H160::repeat_byte(0xbb),
+                balance: U256::zero(),
+                nonce: U256::one(),
+                code: vec![0x60, 0x00],
+                storage: vec![(word(1), word(42))],
+            },
+        ];
+
+        assert_eq!(
+            format_trace(Trace::Prestate(accounts)),
+            json!({
+                "0xaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa": { "balance": "0xff", "nonce": 3 },
+                "0xbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb": {
+                    "balance": "0x0",
+                    "nonce": 1,
+                    "code": "0x6000",
+                    "storage": {
+                        "0x0000000000000000000000000000000000000000000000000000000000000001":
+                            "0x000000000000000000000000000000000000000000000000000000000000002a",
+                    },
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn formats_access_lists() {
+        let access_list = vec![
+            (H160::repeat_byte(0xaa), vec![word(1)]),
+            (H160::repeat_byte(0xbb), Vec::new()),
+        ];
+
+        assert_eq!(
+            format_trace(Trace::AccessList(access_list)),
+            json!([
+                {
+                    "address": "0xaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+                    "storageKeys": [
+                        "0x0000000000000000000000000000000000000000000000000000000000000001",
+                    ],
+                },
+                { "address": "0xbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb", "storageKeys": [] },
+            ])
+        );
+    }
+}
@@ -8,7 +8,7 @@ use fc_rpc::pending::ConsensusDataProvider;
 use sc_client_api::{
     backend::{Backend, StorageProvider},
     client::BlockchainEvents,
-    AuxStore, UsageProvider,
+    AuxStore, BlockBackend, ProofProvider, UsageProvider,
 };
 use sc_network::service::traits::NetworkService;
 use sc_network_sync::SyncingService;
@@ -26,6 +26,12 @@ pub use fc_rpc::{EthBlockDataCacheTask, EthConfig};
 pub use fc_rpc_core::types::{FeeHistoryCache, FeeHistoryCacheLimit, FilterPool};
 use fc_storage::StorageOverride;
 use fp_rpc::{ConvertTransaction, ConvertTransactionRuntimeApi, EthereumRuntimeRPCApi};
+// Tracing
+use crate::debug::{DebugTrace, DebugTraceApiServer, TracePermits};
+use crate::trace::{Trace, TraceApiServer, TraceCacheTask};
+use evm_tracing::DebugRuntimeApi;
+// Ethereum extensions
+use crate::eth_ext::{EthExt, EthExtApiServer, REPLACED_ETH_METHODS, TRACING_ETH_METHODS};
 
 /// Extra dependencies for Ethereum compatibility.
 pub struct EthDeps<C, P, A: ChainApi, CT, CIDP> {
@@ -66,6 +72,17 @@ pub struct EthDeps<C, P, A: ChainApi, CT, CIDP> {
     pub forced_parent_hashes: Option<BTreeMap<H256, H256>>,
     /// Something that can create the inherent data providers for pending state
     pub pending_create_inherent_data_providers: CIDP,
+    /// Whether to enable the `debug_trace*` methods and `eth_createAccessList`.
+    pub enable_debug_tracing: bool,
+    /// Bound on the number of blocks replayed at once by the `debug_trace*` methods and
+    /// `eth_createAccessList`, shared with `trace_cache`.
+    pub trace_permits: TracePermits,
+    /// Cache of the traces of the recent blocks, enabling the `trace_*` methods if set.
+    pub trace_cache: Option<Arc<TraceCacheTask<C>>>,
+    /// Maximum number of traces returned by `trace_filter`.
+    pub trace_filter_max_count: u32,
+    /// Maximum number of blocks in the range of `trace_filter`.
+    pub trace_filter_max_block_range: u32,
 }
 
 /// Instantiate Ethereum-compatible RPC extensions.
@@ -83,13 +100,16 @@ pub fn create_eth<C, BE, P, A, CT, CIDP, EC>(
 where
     // B: BlockT,
     C: CallApiAt<Block> + ProvideRuntimeApi<Block>,
-    C::Api:
-        BlockBuilderApi<Block> + ConvertTransactionRuntimeApi<Block> + EthereumRuntimeRPCApi<Block>,
-    C: HeaderBackend<Block> + HeaderMetadata<Block, Error = BlockChainError>,
+    C::Api: BlockBuilderApi<Block>
+        + ConvertTransactionRuntimeApi<Block>
+        + EthereumRuntimeRPCApi<Block>
+        + DebugRuntimeApi<Block>,
+    C: BlockBackend<Block> + HeaderBackend<Block> + HeaderMetadata<Block, Error = BlockChainError>,
     C: BlockchainEvents<Block>
         + AuxStore
         + UsageProvider<Block>
         + StorageProvider<Block, BE>
+        + ProofProvider<Block>
         + 'static,
     BE: Backend<Block> + 'static,
     P: TransactionPool<Block = Block> + 'static,
@@ -122,6 +142,11 @@ where
         execute_gas_limit_multiplier,
         forced_parent_hashes,
         pending_create_inherent_data_providers,
+        enable_debug_tracing,
+        trace_permits,
+        trace_cache,
+        trace_filter_max_count,
+        trace_filter_max_block_range,
     } = deps;
 
     let mut signers = Vec::new();
@@ -129,29 +154,30 @@ where
         signers.push(Box::new(EthDevSigner::new()) as Box<dyn EthSigner>);
     }
 
-    io.merge(
-        Eth::<_, C, P, CT, BE, A, CIDP, EC>::new(
-            client.clone(),
-            pool.clone(),
-            graph.clone(),
-            converter,
-            sync.clone(),
-            signers,
-            storage_override.clone(),
-            frontier_backend.clone(),
-            is_authority,
-            block_data_cache.clone(),
-            fee_history_cache,
-            fee_history_cache_limit,
-            execute_gas_limit_multiplier,
-            forced_parent_hashes,
-            pending_create_inherent_data_providers,
-            None,
-            // Some(pending_consenus_data_provider),
-        )
-        .replace_config::<EC>()
-        .into_rpc(),
-    )?;
+    let mut eth = Eth::<_, C, P, CT, BE, A, CIDP, EC>::new(
+        client.clone(),
+        pool.clone(),
+        graph.clone(),
+        converter,
+        sync.clone(),
+        signers,
+        storage_override.clone(),
+        frontier_backend.clone(),
+        is_authority,
+        block_data_cache.clone(),
+        fee_history_cache,
+        fee_history_cache_limit,
+        execute_gas_limit_multiplier,
+        forced_parent_hashes,
+        pending_create_inherent_data_providers,
+        Some(pending_consenus_data_provider),
+    )
+    .replace_config::<EC>()
+    .into_rpc();
+    for method in REPLACED_ETH_METHODS {
+        eth.remove_method(method);
+    }
+    io.merge(eth)?;
 
     if let Some(filter_pool) = filter_pool {
         io.merge(
@@ -192,16 +218,49 @@ where
 
     io.merge(Web3::new(client.clone()).into_rpc())?;
 
+    let mut eth_ext = EthExt::<C, BE>::new(
+        client.clone(),
+        frontier_backend.clone(),
+        storage_override.clone(),
+        trace_permits.clone(),
+    )
+    .into_rpc();
+    if !enable_debug_tracing {
+        for method in TRACING_ETH_METHODS {
+            eth_ext.remove_method(method);
+        }
+    }
+    io.merge(eth_ext)?;
+
     io.merge(
         Debug::new(
             client.clone(),
-            frontier_backend,
+            frontier_backend.clone(),
             storage_override,
             block_data_cache,
         )
         .into_rpc(),
     )?;
 
+    if enable_debug_tracing {
+        io.merge(
+            DebugTrace::new(client.clone(), frontier_backend.clone(), trace_permits).into_rpc(),
+        )?;
+    }
+
+    if let Some(trace_cache) = trace_cache {
+        io.merge(
+            Trace::new(
+                client.clone(),
+                frontier_backend,
+                trace_cache,
+                trace_filter_max_count,
+                trace_filter_max_block_range,
+            )
+            .into_rpc(),
+        )?;
+    }
+
     #[cfg(feature = "txpool")]
     io.merge(TxPool::new(client, graph).into_rpc())?;
 
@@ -0,0 +1,761 @@
+//! Ethereum RPC methods of the modern Ethereum clients that `fc_rpc::Eth` does not serve, or
+//! serves differently from geth.
+//!
+//! `eth_getProof` and `eth_createAccessList` are missing from Frontier. `eth_getBlockReceipts`,
+//! `eth_maxPriorityFeePerGas` and `eth_feeHistory` replace the methods of `fc_rpc::Eth`, listed in
+//! [`REPLACED_ETH_METHODS`]: they read the blocks stored by `pallet_ethereum` on demand rather
+//! than a cache filled as blocks are imported, and compute the priority fees and the reward
+//! percentiles from the price the transactions paid, like geth.
+//!
+//! `eth_createAccessList` replays the call with the tracers of the runtime, so it is only
+//! served by the nodes tracing EVM executions, listed in [`TRACING_ETH_METHODS`].
+
+use crate::debug::{
+    load_block_hash, replay_blocking, trace_call_at, AccessListItem, TraceCallRequest, TracePermits,
+};
+use codec::Decode;
+use ethereum::{EIP658ReceiptData, ReceiptV3, TransactionV2};
+use ethereum_types::Bloom;
+use evm_tracing::{DebugRuntimeApi, Trace, Tracer};
+use fc_rpc::internal_err;
+use fc_rpc_core::types::{BlockNumberOrHash, Bytes};
+use fc_storage::StorageOverride;
+use jsonrpsee::core::{async_trait, RpcResult};
+use jsonrpsee::proc_macros::rpc;
+use serde::Serialize;
+use std::{marker::PhantomData, sync::Arc};
+// Substrate
+use common_runtime::{opaque::Block, Balance, Nonce};
+use sc_client_api::{Backend, ProofProvider, StorageProvider};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_core::{
+    hashing::{blake2_128, keccak_256, twox_128},
+    storage::StorageKey,
+    H160, H256, U256,
+};
+use sp_runtime::traits::{Block as BlockT, Header as HeaderT, UniqueSaturatedInto};
+// Frontier
+use fp_rpc::{EthereumRuntimeRPCApi, TransactionStatus};
+
+/// Methods of `fc_rpc::Eth` served by [`EthExt`] instead, to remove from the module of
+/// `fc_rpc::Eth` before merging the module of [`EthExt`].
+pub const REPLACED_ETH_METHODS: [&str; 3] = [
+    "eth_getBlockReceipts",
+    "eth_maxPriorityFeePerGas",
+    "eth_feeHistory",
+];
+
+/// Methods of [`EthExt`] replaying calls with the tracers of the runtime, to remove from its
+/// module unless the node traces EVM executions.
+pub const TRACING_ETH_METHODS: [&str; 1] = ["eth_createAccessList"];
+
+/// Maximum number of storage slots proven by `eth_getProof`.
+const MAX_PROOF_STORAGE_KEYS: usize = 256;
+/// Maximum number of blocks returned by `eth_feeHistory`, larger block counts are capped.
+const MAX_FEE_HISTORY_BLOCKS: u64 = 1024;
+/// Maximum number of reward percentiles of `eth_feeHistory`.
+const MAX_REWARD_PERCENTILES: usize = 100;
+/// Number of recent blocks whose priority fees `eth_maxPriorityFeePerGas` samples.
+const PRIORITY_FEE_BLOCKS: u32 = 20;
+/// Percentile of the gas used in the sampled blocks `eth_maxPriorityFeePerGas` suggests the
+/// priority fee of.
+const PRIORITY_FEE_PERCENTILE: f64 = 60.0;
+
+/// Proof of an account and of some of its storage slots, as returned by `eth_getProof`.
+///
+/// The format is not the one of EIP-1186. The EVM state lives in the Substrate state trie rather
+/// than in Merkle Patricia tries, so the proofs are the Substrate trie nodes proving the storage
+/// entries of the account against the state root of the block. Accounts have no storage root:
+/// `storageHash` is left out, so EIP-1186 verifiers fail rather than check the proofs against
+/// a wrong root, and the state root is returned as `stateRoot`.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountProof {
+    pub address: H160,
+    /// Substrate trie nodes proving the `frame_system` account, holding the nonce and the
+    /// balance, and the code.
+    pub account_proof: Vec<Bytes>,
+    /// Free balance of the proven `frame_system` account.
+    pub balance: U256,
+    /// Hash of the proven code.
+    pub code_hash: H256,
+    /// Nonce of the proven `frame_system` account.
+    pub nonce: U256,
+    /// State root of the block, which all the proofs are against.
+    pub state_root: H256,
+    pub storage_proof: Vec<StorageProof>,
+}
+
+/// Proof of a storage slot.
+#[derive(Clone, Debug, Serialize)]
+pub struct StorageProof {
+    pub key: H256,
+    pub value: U256,
+    /// Substrate trie nodes proving the storage slot.
+    pub proof: Vec<Bytes>,
+}
+
+/// Result of `eth_createAccessList`.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccessListResult {
+    pub access_list: Vec<AccessListItem>,
+    /// Gas used by the call with the access list.
+    pub gas_used: U256,
+    /// Error the call with the access list failed with.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Receipt of a transaction, as returned by `eth_getBlockReceipts`.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockReceipt {
+    pub transaction_hash: H256,
+    pub transaction_index: U256,
+    pub block_hash: H256,
+    pub block_number: U256,
+    pub from: H160,
+    pub to: Option<H160>,
+    /// Gas used by the transaction and the ones before it in the block.
+    pub cumulative_gas_used: U256,
+    pub gas_used: U256,
+    /// Price paid per gas, the base fee and the priority fee.
+    pub effective_gas_price: U256,
+    /// Contract created by the transaction.
+    pub contract_address: Option<H160>,
+    pub logs: Vec<ReceiptLog>,
+    pub logs_bloom: Bloom,
+    /// EIP-2718 type of the transaction.
+    #[serde(rename = "type")]
+    pub transaction_type: U256,
+    /// 1 if the transaction succeeded, 0 if it failed.
+    pub status: U256,
+}
+
+/// Log of a receipt.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReceiptLog {
+    pub address: H160,
+    pub topics: Vec<H256>,
+    pub data: Bytes,
+    pub block_hash: H256,
+    pub block_number: U256,
+    pub transaction_hash: H256,
+    pub transaction_index: U256,
+    /// Index of the log in the block.
+    pub log_index: U256,
+    pub removed: bool,
+}
+
+/// Result of `eth_feeHistory`.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeeHistoryResult {
+    pub oldest_block: U256,
+    /// Base fees of the blocks, followed by the base fee of the block after the newest one.
+    pub base_fee_per_gas: Vec<U256>,
+    /// Gas used by the blocks, relative to their gas limit.
+    pub gas_used_ratio: Vec<f64>,
+    /// Priority fees paid at the requested percentiles of the gas used in each block.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reward: Option<Vec<Vec<U256>>>,
+}
+
+/// Ethereum extension API.
+#[rpc(server)]
+#[async_trait]
+pub trait EthExtApi {
+    /// Returns the account `address` and at most 256 of its storage slots `storage_keys` at the
+    /// block `number`, with their proofs.
+    ///
+    /// The proofs are Substrate trie nodes, verified against the state root of the block
+    /// returned as `stateRoot`, rather than the Merkle Patricia proofs of EIP-1186: the result
+    /// has no `storageHash`.
+    #[method(name = "eth_getProof")]
+    async fn proof(
+        &self,
+        address: H160,
+        storage_keys: Vec<H256>,
+        number: Option<BlockNumberOrHash>,
+    ) -> RpcResult<AccountProof>;
+
+    /// Executes a call on top of the block `number` and returns the accounts and storage slots
+    /// it accesses, with the gas it uses given them as its access list.
+    ///
+    /// The call is replayed with the tracers of the runtime, so the method is only served by
+    /// the nodes tracing EVM executions.
+    #[method(name = "eth_createAccessList")]
+    async fn create_access_list(
+        &self,
+        request: TraceCallRequest,
+        number: Option<BlockNumberOrHash>,
+    ) -> RpcResult<AccessListResult>;
+
+    /// Returns the receipts of the transactions of the block `number`, or `None` if the block
+    /// has no Ethereum block.
+    #[method(name = "eth_getBlockReceipts")]
+    async fn block_receipts(
+        &self,
+        number: BlockNumberOrHash,
+    ) -> RpcResult<Option<Vec<BlockReceipt>>>;
+
+    /// Returns a priority fee likely to get a transaction included: the one paid at the 60th
+    /// percentile of the gas used in the last 20 blocks.
+    #[method(name = "eth_maxPriorityFeePerGas")]
+    async fn max_priority_fee_per_gas(&self) -> RpcResult<U256>;
+
+    /// Returns the base fees and the gas used ratios of the `block_count` blocks up to
+    /// `newest_block`, and the priority fees paid at the `reward_percentiles` of the gas used in
+    /// each of them.
+    #[method(name = "eth_feeHistory")]
+    async fn fee_history(
+        &self,
+        block_count: U256,
+        newest_block: BlockNumberOrHash,
+        reward_percentiles: Option<Vec<f64>>,
+    ) -> RpcResult<FeeHistoryResult>;
+}
+
+/// The Ethereum block of a block, with the receipts and the statuses of its transactions.
+struct EthereumBlock {
+    block: ethereum::BlockV2,
+    receipts: Vec<ReceiptV3>,
+    statuses: Vec<TransactionStatus>,
+    /// Base fee paid by the transactions of the block.
+    base_fee: U256,
+}
+
+impl EthereumBlock {
+    /// Returns the priority fee paid by each transaction, and the gas it used.
+    fn priority_fees(&self) -> Vec<(U256, u64)> {
+        let mut cumulative_gas_used = U256::zero();
+        self.block
+            .transactions
+            .iter()
+            .zip(&self.receipts)
+            .map(|(transaction, receipt)| {
+                let used_gas = receipt_data(receipt).used_gas;
+                let gas_used = used_gas.saturating_sub(cumulative_gas_used);
+                cumulative_gas_used = used_gas;
+                let (_, gas_price) = transaction_gas_price(transaction, self.base_fee);
+                (
+                    gas_price.saturating_sub(self.base_fee),
+                    gas_used.unique_saturated_into(),
+                )
+            })
+            .collect()
+    }
+}
+
+/// Implementation of the Ethereum extension API.
+pub struct EthExt<C, BE> {
+    client: Arc<C>,
+    backend: Arc<dyn fc_api::Backend<Block>>,
+    storage_override: Arc<dyn StorageOverride<Block>>,
+    permits: TracePermits,
+    _marker: PhantomData<BE>,
+}
+
+impl<C, BE> EthExt<C, BE> {
+    pub fn new(
+        client: Arc<C>,
+        backend: Arc<dyn fc_api::Backend<Block>>,
+        storage_override: Arc<dyn StorageOverride<Block>>,
+        permits: TracePermits,
+    ) -> Self {
+        Self {
+            client,
+            backend,
+            storage_override,
+            permits,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<C, BE> EthExt<C, BE>
+where
+    C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + ProofProvider<Block>,
+    C::Api: EthereumRuntimeRPCApi<Block>,
+{
+    /// Returns the trie nodes proving the storage entries `keys` at the block `hash`.
+    fn read_proof(&self, hash: <Block as BlockT>::Hash, keys: &[Vec<u8>]) -> RpcResult<Vec<Bytes>> {
+        let proof = self
+            .client
+            .read_proof(hash, &mut keys.iter().map(Vec::as_slice))
+            .map_err(|err| internal_err(format!("{err:?}")))?;
+        Ok(proof.into_iter_nodes().map(Bytes).collect())
+    }
+
+    /// Returns the Ethereum block of the block `hash`, or `None` if it has none.
+    fn ethereum_block(&self, hash: <Block as BlockT>::Hash) -> RpcResult<Option<EthereumBlock>> {
+        let (Some(block), Some(receipts), Some(statuses)) = (
+            self.storage_override.current_block(hash),
+            self.storage_override.current_receipts(hash),
+            self.storage_override.current_transaction_statuses(hash),
+        ) else {
+            return Ok(None);
+        };
+        let header = self
+            .client
+            .header(hash)
+            .map_err(|err| internal_err(format!("{err:?}")))?
+            .ok_or_else(|| internal_err("header not found"))?;
+        // The base fee is updated at the end of a block, for the next one.
+        let base_fee = if *header.number() == 0 {
+            self.base_fee(hash)?
+        } else {
+            self.base_fee(*header.parent_hash())?
+        };
+        Ok(Some(EthereumBlock {
+            block,
+            receipts,
+            statuses,
+            base_fee,
+        }))
+    }
+
+    /// Returns the base fee of the block after the block `hash`.
+    fn base_fee(&self, hash: <Block as BlockT>::Hash) -> RpcResult<U256> {
+        self.client
+            .runtime_api()
+            .gas_price(hash)
+            .map_err(|err| internal_err(format!("{err:?}")))
+    }
+
+    /// Returns the Ethereum block of the block `number` of the canonical chain, or `None` if it
+    /// has none.
+    fn canonical_ethereum_block(&self, number: u32) -> RpcResult<Option<EthereumBlock>> {
+        let hash = self
+            .client
+            .hash(number)
+            .map_err(|err| internal_err(format!("{err:?}")))?
+            .ok_or_else(|| internal_err("block not found"))?;
+        self.ethereum_block(hash)
+    }
+}
+
+#[async_trait]
+impl<C, BE> EthExtApiServer for EthExt<C, BE>
+where
+    C: ProvideRuntimeApi<Block>
+        + HeaderBackend<Block>
+        + ProofProvider<Block>
+        + StorageProvider<Block, BE>
+        + Send
+        + Sync
+        + 'static,
+    C::Api: DebugRuntimeApi<Block> + EthereumRuntimeRPCApi<Block>,
+    BE: Backend<Block> + 'static,
+{
+    async fn proof(
+        &self,
+        address: H160,
+        storage_keys: Vec<H256>,
+        number: Option<BlockNumberOrHash>,
+    ) -> RpcResult<AccountProof> {
+        if storage_keys.len() > MAX_PROOF_STORAGE_KEYS {
+            return Err(internal_err(format!(
+                "at most {MAX_PROOF_STORAGE_KEYS} storage keys can be proven at once"
+            )));
+        }
+        let hash = load_block_hash(self.client.as_ref(), self.backend.as_ref(), number).await?;
+        let header = self
+            .client
+            .header(hash)
+            .map_err(|err| internal_err(format!("{err:?}")))?
+            .ok_or_else(|| internal_err("header not found"))?;
+
+        // The nonce, the reference counters and the free balance, the start of the encoding of
+        // the `frame_system::AccountInfo` holding the `pallet_balances::AccountData`.
+        let (nonce, _, _, _, balance): (Nonce, u32, u32, u32, Balance) = self
+            .client
+            .storage(hash, &StorageKey(system_account_key(address)))
+            .map_err(|err| internal_err(format!("{err:?}")))?
+            .map(|data| Decode::decode(&mut &data.0[..]))
+            .transpose()
+            .map_err(|err| internal_err(format!("{err:?}")))?
+            .unwrap_or_default();
+        let code = self
+            .storage_override
+            .account_code_at(hash, address)
+            .unwrap_or_default();
+        let account_proof = self.read_proof(
+            hash,
+            &[system_account_key(address), evm_account_code_key(address)],
+        )?;
+
+        let storage_proof = storage_keys
+            .into_iter()
+            .map(|key| {
+                let value = self
+                    .storage_override
+                    .account_storage_at(hash, address, U256::from_big_endian(key.as_bytes()))
+                    .unwrap_or_default();
+                Ok(StorageProof {
+                    key,
+                    value: U256::from_big_endian(value.as_bytes()),
+                    proof: self.read_proof(hash, &[evm_account_storage_key(address, key)])?,
+                })
+            })
+            .collect::<RpcResult<Vec<_>>>()?;
+
+        Ok(AccountProof {
+            address,
+            account_proof,
+            balance: balance.into(),
+            code_hash: H256(keccak_256(&code)),
+            nonce: nonce.into(),
+            state_root: *header.state_root(),
+            storage_proof,
+        })
+    }
+
+    async fn create_access_list(
+        &self,
+        request: TraceCallRequest,
+        number: Option<BlockNumberOrHash>,
+    ) -> RpcResult<AccessListResult> {
+        let hash = load_block_hash(self.client.as_ref(), self.backend.as_ref(), number).await?;
+
+        let _permit = self.permits.acquire().await?;
+        replay_blocking(&self.client, move |client| {
+            let access_list =
+                match trace_call_at(client, hash, request.clone(), Tracer::AccessListTracer)? {
+                    Trace::AccessList(access_list) => access_list
+                        .into_iter()
+                        .map(AccessListItem::from)
+                        .collect::<Vec<_>>(),
+                    _ => return Err(internal_err("unexpected trace")),
+                };
+
+            // Accessing the listed accounts and slots is cheaper once they are in the access
+            // list, so the gas used is the one of the call given the access list.
+            let request = TraceCallRequest {
+                access_list: Some(access_list.clone()),
+                ..request
+            };
+            let call = match trace_call_at(
+                client,
+                hash,
+                request,
+                Tracer::CallTracer {
+                    only_top_call: true,
+                },
+            )? {
+                Trace::Call(call) => call,
+                _ => return Err(internal_err("unexpected trace")),
+            };
+
+            Ok(AccessListResult {
+                access_list,
+                gas_used: call.gas_used.into(),
+                error: call
+                    .error
+                    .map(|error| String::from_utf8_lossy(&error).into_owned()),
+            })
+        })
+        .await
+    }
+
+    async fn block_receipts(
+        &self,
+        number: BlockNumberOrHash,
+    ) -> RpcResult<Option<Vec<BlockReceipt>>> {
+        let hash =
+            load_block_hash(self.client.as_ref(), self.backend.as_ref(), Some(number)).await?;
+        let Some(EthereumBlock {
+            block,
+            receipts,
+            statuses,
+            base_fee,
+        }) = self.ethereum_block(hash)?
+        else {
+            return Ok(None);
+        };
+
+        let block_hash = block.header.hash();
+        let block_number = block.header.number;
+        let mut cumulative_gas_used = U256::zero();
+        let mut log_index = 0u32;
+        let receipts = block
+            .transactions
+            .iter()
+            .zip(receipts)
+            .zip(statuses)
+            .map(|((transaction, receipt), status)| {
+                let data = receipt_data(&receipt);
+                let gas_used = data.used_gas.saturating_sub(cumulative_gas_used);
+                cumulative_gas_used = data.used_gas;
+                let transaction_index = U256::from(status.transaction_index);
+                let logs = data
+                    .logs
+                    .iter()
+                    .map(|log| {
+                        log_index += 1;
+                        ReceiptLog {
+                            address: log.address,
+                            topics: log.topics.clone(),
+                            data: Bytes(log.data.clone()),
+                            block_hash,
+                            block_number,
+                            transaction_hash: status.transaction_hash,
+                            transaction_index,
+                            log_index: (log_index - 1).into(),
+                            removed: false,
+                        }
+                    })
+                    .collect();
+                let (transaction_type, effective_gas_price) =
+                    transaction_gas_price(transaction, base_fee);
+                BlockReceipt {
+                    transaction_hash: status.transaction_hash,
+                    transaction_index,
+                    block_hash,
+                    block_number,
+                    from: status.from,
+                    to: status.to,
+                    cumulative_gas_used: data.used_gas,
+                    gas_used,
+                    effective_gas_price,
+                    contract_address: status.contract_address,
+                    logs,
+                    logs_bloom: data.logs_bloom,
+                    transaction_type: transaction_type.into(),
+                    status: data.status_code.into(),
+                }
+            })
+            .collect();
+        Ok(Some(receipts))
+    }
+
+    async fn max_priority_fee_per_gas(&self) -> RpcResult<U256> {
+        let best: u32 = self.client.info().best_number.unique_saturated_into();
+        let mut fees = Vec::new();
+        for number in best.saturating_sub(PRIORITY_FEE_BLOCKS - 1)..=best {
+            if let Some(block) = self.canonical_ethereum_block(number)? {
+                fees.extend(block.priority_fees());
+            }
+        }
+        Ok(rewards(fees, &[PRIORITY_FEE_PERCENTILE])[0])
+    }
+
+    async fn fee_history(
+        &self,
+        block_count: U256,
+        newest_block: BlockNumberOrHash,
+        reward_percentiles: Option<Vec<f64>>,
+    ) -> RpcResult<FeeHistoryResult> {
+        if let Some(percentiles) = &reward_percentiles {
+            if percentiles.len() > MAX_REWARD_PERCENTILES {
+                return Err(internal_err(format!(
+                    "at most {MAX_REWARD_PERCENTILES} reward percentiles can be requested"
+                )));
+            }
+            let in_range = percentiles
+                .iter()
+                .all(|percentile| (0.0..=100.0).contains(percentile));
+            let increasing = percentiles.windows(2).all(|pair| pair[0] <= pair[1]);
+            if !in_range || !increasing {
+                return Err(internal_err(
+                    "reward percentiles must be increasing values between 0 and 100",
+                ));
+            }
+        }
+
+        let newest_hash = load_block_hash(
+            self.client.as_ref(),
+            self.backend.as_ref(),
+            Some(newest_block),
+        )
+        .await?;
+        let newest: u32 = self
+            .client
+            .number(newest_hash)
+            .map_err(|err| internal_err(format!("{err:?}")))?
+            .ok_or_else(|| internal_err("block not found"))?;
+        let block_count: u32 = block_count
+            .min(MAX_FEE_HISTORY_BLOCKS.into())
+            .min(U256::from(newest) + 1)
+            .as_u32();
+        if block_count == 0 {
+            return Ok(FeeHistoryResult {
+                oldest_block: U256::zero(),
+                base_fee_per_gas: Vec::new(),
+                gas_used_ratio: Vec::new(),
+                reward: None,
+            });
+        }
+
+        let oldest = newest + 1 - block_count;
+        let mut base_fee_per_gas = Vec::new();
+        let mut gas_used_ratio = Vec::new();
+        let mut reward = reward_percentiles.as_ref().map(|_| Vec::new());
+        for number in oldest..=newest {
+            let block = self
+                .canonical_ethereum_block(number)?
+                .ok_or_else(|| internal_err("Ethereum block not found"))?;
+            let header = &block.block.header;
+            gas_used_ratio.push(if header.gas_limit.is_zero() {
+                0.0
+            } else {
+                header.gas_used.low_u64() as f64 / header.gas_limit.low_u64() as f64
+            });
+            base_fee_per_gas.push(block.base_fee);
+            if let (Some(reward), Some(percentiles)) = (&mut reward, &reward_percentiles) {
+                reward.push(rewards(block.priority_fees(), percentiles));
+            }
+        }
+        base_fee_per_gas.push(self.base_fee(newest_hash)?);
+
+        Ok(FeeHistoryResult {
+            oldest_block: oldest.into(),
+            base_fee_per_gas,
+            gas_used_ratio,
+            reward,
+        })
+    }
+}
+
+/// Returns the receipt data of `receipt`, the same for all the transaction types.
+fn receipt_data(receipt: &ReceiptV3) -> &EIP658ReceiptData {
+    match receipt {
+        ReceiptV3::Legacy(data) | ReceiptV3::EIP2930(data) | ReceiptV3::EIP1559(data) => data,
+    }
+}
+
+/// Returns the EIP-2718 type of `transaction` and the price it paid per gas, given the base fee
+/// of its block.
+fn transaction_gas_price(transaction: &TransactionV2, base_fee: U256) -> (u8, U256) {
+    match transaction {
+        TransactionV2::Legacy(transaction) => (0, transaction.gas_price),
+        TransactionV2::EIP2930(transaction) => (1, transaction.gas_price),
+        TransactionV2::EIP1559(transaction) => (
+            2,
+            transaction
+                .max_fee_per_gas
+                .min(base_fee.saturating_add(transaction.max_priority_fee_per_gas)),
+        ),
+    }
+}
+
+/// Returns the priority fees paid at `percentiles` of the gas used by `fees`, the priority fees
+/// paid by some transactions and the gas they used, like geth computes the rewards of
+/// `eth_feeHistory`.
+fn rewards(mut fees: Vec<(U256, u64)>, percentiles: &[f64]) -> Vec<U256> {
+    if fees.is_empty() {
+        return vec![U256::zero(); percentiles.len()];
+    }
+    fees.sort_by_key(|(fee, _)| *fee);
+    let total_gas_used: u64 = fees.iter().map(|(_, gas_used)| gas_used).sum();
+
+    let mut index = 0;
+    let mut gas_used = fees[0].1;
+    percentiles
+        .iter()
+        .map(|percentile| {
+            let threshold = (total_gas_used as f64 * percentile / 100.0) as u64;
+            while gas_used < threshold && index < fees.len() - 1 {
+                index += 1;
+                gas_used += fees[index].1;
+            }
+            fees[index].0
+        })
+        .collect()
+}
+
+/// Key of the `frame_system` account of `address`, holding its nonce and its balance.
+fn system_account_key(address: H160) -> Vec<u8> {
+    storage_map_key(b"System", b"Account", address.as_bytes())
+}
+
+/// Key of the code of `address` in `pallet_evm`.
+fn evm_account_code_key(address: H160) -> Vec<u8> {
+    storage_map_key(b"EVM", b"AccountCodes", address.as_bytes())
+}
+
+/// Key of the storage slot `index` of `address` in `pallet_evm`.
+fn evm_account_storage_key(address: H160, index: H256) -> Vec<u8> {
+    let mut key = storage_map_key(b"EVM", b"AccountStorages", address.as_bytes());
+    key.extend(blake2_128_concat(index.as_bytes()));
+    key
+}
+
+/// Key of `key` in the `Blake2_128Concat` map `storage` of `pallet`.
+fn storage_map_key(pallet: &[u8], storage: &[u8], key: &[u8]) -> Vec<u8> {
+    [
+        &twox_128(pallet)[..],
+        &twox_128(storage)[..],
+        &blake2_128_concat(key),
+    ]
+    .concat()
+}
+
+fn blake2_128_concat(key: &[u8]) -> Vec<u8> {
+    [&blake2_128(key)[..], key].concat()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sp_core::bytes::to_hex;
+
+    #[test]
+    fn storage_keys_follow_the_storage_layout() {
+        let address = H160::repeat_byte(0xaa);
+        let key = system_account_key(address);
+        assert_eq!(
+            to_hex(&key[..32], false),
+            "0x26aa394eea5630e07c48ae0c9558cef7b99d880ec681799c0cf30e8886371da9"
+        );
+        assert_eq!(key[48..], *address.as_bytes());
+
+        let index = H256::repeat_byte(1);
+        let key = evm_account_storage_key(address, index);
+        assert_eq!(key.len(), 32 + 16 + 20 + 16 + 32);
+        assert_eq!(key[..16], twox_128(b"EVM"));
+        assert_eq!(key[16..32], twox_128(b"AccountStorages"));
+        assert_eq!(key[68..84], blake2_128(index.as_bytes()));
+        assert_eq!(key[84..], *index.as_bytes());
+    }
+
+    #[test]
+    fn account_proofs_return_the_state_root_rather_than_a_storage_hash() {
+        let proof = AccountProof {
+            address: H160::repeat_byte(0xaa),
+            account_proof: Vec::new(),
+            balance: U256::zero(),
+            code_hash: H256(keccak_256(&[])),
+            nonce: U256::zero(),
+            state_root: H256::repeat_byte(1),
+            storage_proof: Vec::new(),
+        };
+        let proof = serde_json::to_value(proof).unwrap();
+        assert_eq!(proof["stateRoot"], serde_json::json!(H256::repeat_byte(1)));
+        assert!(proof.get("storageHash").is_none());
+    }
+
+    #[test]
+    fn rewards_are_weighted_by_gas_used() {
+        let fees = vec![
+            (U256::from(30), 10_000),
+            (U256::from(10), 21_000),
+            (U256::from(20), 69_000),
+        ];
+        assert_eq!(
+            rewards(fees, &[0.0, 21.0, 25.0, 90.0, 100.0]),
+            [10, 10, 20, 20, 30].map(U256::from).to_vec()
+        );
+    }
+
+    #[test]
+    fn rewards_of_an_empty_block_are_zero() {
+        assert_eq!(
+            rewards(Vec::new(), &[10.0, 50.0]),
+            vec![U256::zero(), U256::zero()]
+        );
+    }
+}
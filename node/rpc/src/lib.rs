@@ -31,8 +31,14 @@
 #![allow(missing_docs)]
 #![allow(unused_crate_dependencies)]
 
+mod debug;
 mod eth;
+mod eth_ext;
+mod trace;
+pub use debug::*;
 pub use eth::*;
+pub use eth_ext::*;
+pub use trace::*;
 use sp_inherents::CreateInherentDataProviders;
 use std::sync::Arc;
 // use sc_consensus_grandpa_rpc::finality::RpcFinalityProofProvider;
@@ -168,7 +174,11 @@ where
     C::Api: sp_api::ApiExt<Block>,
     C::Api: fp_rpc::ConvertTransactionRuntimeApi<Block>,
     C::Api: fp_rpc::EthereumRuntimeRPCApi<Block>,
-    C: BlockchainEvents<Block> + UsageProvider<Block> + StorageProvider<Block, B>,
+    C::Api: evm_tracing::DebugRuntimeApi<Block>,
+    C: BlockchainEvents<Block>
+        + UsageProvider<Block>
+        + StorageProvider<Block, B>
+        + sc_client_api::ProofProvider<Block>,
     P: TransactionPool<Block = Block> + 'static,
     SC: SelectChain<Block> + 'static,
     B: sc_client_api::Backend<Block> + Send + Sync + 'static,
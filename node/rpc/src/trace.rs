@@ -0,0 +1,507 @@
+//! Parity-style `trace_*` RPC methods, served from a cache of the traces of the recent blocks.
+
+use crate::debug::{
+    ensure_tracing_runtime, load_block, load_block_hash, load_transaction_block_hash,
+    replay_blocking, TracePermits,
+};
+use evm_tracing::{CallFrame, CallType, DebugRuntimeApi, Trace as EvmTrace, Tracer};
+use fc_rpc::internal_err;
+use fc_rpc_core::types::BlockNumberOrHash;
+use futures::StreamExt;
+use jsonrpsee::core::{async_trait, RpcResult};
+use jsonrpsee::proc_macros::rpc;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+// Substrate
+use common_runtime::opaque::Block;
+use sc_client_api::{BlockBackend, BlockchainEvents};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_core::{bytes::to_hex, traits::SpawnNamed, H160, H256};
+use sp_runtime::traits::{Block as BlockT, Header as HeaderT, UniqueSaturatedInto};
+// Frontier
+use fp_rpc::EthereumRuntimeRPCApi;
+
+/// Filter of `trace_filter`.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TraceFilterRequest {
+    /// First block of the range, the latest block if unset.
+    pub from_block: Option<BlockNumberOrHash>,
+    /// Last block of the range, the latest block if unset.
+    pub to_block: Option<BlockNumberOrHash>,
+    /// Callers to match, any if unset.
+    pub from_address: Option<Vec<H160>>,
+    /// Callees to match, any if unset.
+    pub to_address: Option<Vec<H160>>,
+    /// Number of matching traces to skip.
+    pub after: Option<u32>,
+    /// Maximum number of traces to return.
+    pub count: Option<u32>,
+}
+
+/// A call of a transaction, flattened out of the call tree.
+#[derive(Clone, Debug)]
+pub struct FlatTrace {
+    pub transaction_hash: H256,
+    /// The calling account.
+    pub from: H160,
+    /// The called account, the created contract, or the beneficiary of a self-destruct.
+    pub to: H160,
+    /// The trace, formatted like the tracer of OpenEthereum.
+    pub trace: Value,
+}
+
+/// Cache of the traces of the recent blocks.
+///
+/// Blocks are traced on demand, sharing `permits` with the `debug_trace*` methods, and the traces
+/// of the blocks of the last `cache_blocks` blocks are kept until the best block moves past the
+/// window.
+pub struct TraceCacheTask<C> {
+    client: Arc<C>,
+    cache_blocks: u32,
+    permits: TracePermits,
+    blocks: Arc<Mutex<HashMap<<Block as BlockT>::Hash, (u32, Arc<Vec<FlatTrace>>)>>>,
+}
+
+impl<C> TraceCacheTask<C>
+where
+    C: ProvideRuntimeApi<Block>
+        + BlockBackend<Block>
+        + HeaderBackend<Block>
+        + BlockchainEvents<Block>
+        + Send
+        + Sync
+        + 'static,
+    C::Api: DebugRuntimeApi<Block> + EthereumRuntimeRPCApi<Block>,
+{
+    pub fn new(
+        spawn_handle: impl SpawnNamed,
+        client: Arc<C>,
+        cache_blocks: u32,
+        permits: TracePermits,
+    ) -> Self {
+        let blocks: Arc<Mutex<HashMap<_, (u32, Arc<Vec<FlatTrace>>)>>> = Default::default();
+
+        let mut import_notifications = client.import_notification_stream();
+        let evicted = blocks.clone();
+        spawn_handle.spawn(
+            "trace-cache",
+            Some("frontier"),
+            Box::pin(async move {
+                while let Some(notification) = import_notifications.next().await {
+                    if notification.is_new_best {
+                        let best: u32 = (*notification.header.number()).unique_saturated_into();
+                        evicted
+                            .lock()
+                            .expect("trace cache lock is not poisoned; qed")
+                            .retain(|_, (number, _)| number.saturating_add(cache_blocks) > best);
+                    }
+                }
+            }),
+        );
+
+        Self {
+            client,
+            cache_blocks,
+            permits,
+            blocks,
+        }
+    }
+
+    /// Returns the traces of the block `hash`, tracing it if it is not cached.
+    pub async fn block_traces(
+        &self,
+        hash: <Block as BlockT>::Hash,
+    ) -> RpcResult<Arc<Vec<FlatTrace>>> {
+        if let Some(traces) = self.cached(hash) {
+            return Ok(traces);
+        }
+        let _permit = self.permits.acquire().await?;
+        // The block may have been traced by another request while waiting for the permit.
+        if let Some(traces) = self.cached(hash) {
+            return Ok(traces);
+        }
+
+        let (number, traces) = replay_blocking(&self.client, move |client| {
+            let (header, extrinsics) = load_block(client, hash)?;
+            ensure_tracing_runtime(client, *header.parent_hash())?;
+            let number: u32 = (*header.number()).unique_saturated_into();
+            let api = client.runtime_api();
+            let ethereum_block_hash = api
+                .current_block(hash)
+                .map_err(|err| internal_err(format!("{err:?}")))?
+                .map(|block| block.header.hash())
+                .ok_or_else(|| internal_err("Ethereum block not found"))?;
+            let traces = api
+                .trace_block(
+                    *header.parent_hash(),
+                    extrinsics,
+                    &header,
+                    Tracer::CallTracer {
+                        only_top_call: false,
+                    },
+                )
+                .map_err(|err| internal_err(format!("{err:?}")))?
+                .map_err(|err| internal_err(format!("{err:?}")))?;
+
+            let mut flat_traces = Vec::new();
+            for (position, (transaction_hash, trace)) in traces.into_iter().enumerate() {
+                if let EvmTrace::Call(call) = trace {
+                    let context = json!({
+                        "blockHash": ethereum_block_hash,
+                        "blockNumber": number,
+                        "transactionHash": transaction_hash,
+                        "transactionPosition": position,
+                    });
+                    flatten(
+                        call,
+                        transaction_hash,
+                        &context,
+                        &mut Vec::new(),
+                        &mut flat_traces,
+                    );
+                }
+            }
+            Ok((number, flat_traces))
+        })
+        .await?;
+        let traces = Arc::new(traces);
+
+        let best = self.client.info().best_number;
+        if number.saturating_add(self.cache_blocks) > best {
+            self.blocks
+                .lock()
+                .expect("trace cache lock is not poisoned; qed")
+                .insert(hash, (number, traces.clone()));
+        }
+        Ok(traces)
+    }
+
+    fn cached(&self, hash: <Block as BlockT>::Hash) -> Option<Arc<Vec<FlatTrace>>> {
+        self.blocks
+            .lock()
+            .expect("trace cache lock is not poisoned; qed")
+            .get(&hash)
+            .map(|(_, traces)| traces.clone())
+    }
+}
+
+/// Trace API.
+#[rpc(server)]
+#[async_trait]
+pub trait TraceApi {
+    /// Returns the traces of the calls of the transactions of the block `number`.
+    #[method(name = "trace_block")]
+    async fn trace_block(&self, number: BlockNumberOrHash) -> RpcResult<Vec<Value>>;
+
+    /// Returns the traces of the calls of the transaction `transaction_hash`.
+    #[method(name = "trace_transaction")]
+    async fn trace_transaction(&self, transaction_hash: H256) -> RpcResult<Vec<Value>>;
+
+    /// Returns the traces of the calls matching `filter`.
+    #[method(name = "trace_filter")]
+    async fn trace_filter(&self, filter: TraceFilterRequest) -> RpcResult<Vec<Value>>;
+}
+
+/// Implementation of the trace API.
+pub struct Trace<C> {
+    client: Arc<C>,
+    backend: Arc<dyn fc_api::Backend<Block>>,
+    cache: Arc<TraceCacheTask<C>>,
+    max_count: u32,
+    max_block_range: u32,
+}
+
+impl<C> Trace<C> {
+    pub fn new(
+        client: Arc<C>,
+        backend: Arc<dyn fc_api::Backend<Block>>,
+        cache: Arc<TraceCacheTask<C>>,
+        max_count: u32,
+        max_block_range: u32,
+    ) -> Self {
+        Self {
+            client,
+            backend,
+            cache,
+            max_count,
+            max_block_range,
+        }
+    }
+}
+
+#[async_trait]
+impl<C> TraceApiServer for Trace<C>
+where
+    C: ProvideRuntimeApi<Block>
+        + BlockBackend<Block>
+        + HeaderBackend<Block>
+        + BlockchainEvents<Block>
+        + Send
+        + Sync
+        + 'static,
+    C::Api: DebugRuntimeApi<Block> + EthereumRuntimeRPCApi<Block>,
+{
+    async fn trace_block(&self, number: BlockNumberOrHash) -> RpcResult<Vec<Value>> {
+        let hash =
+            load_block_hash(self.client.as_ref(), self.backend.as_ref(), Some(number)).await?;
+        let traces = self.cache.block_traces(hash).await?;
+        Ok(traces.iter().map(|trace| trace.trace.clone()).collect())
+    }
+
+    async fn trace_transaction(&self, transaction_hash: H256) -> RpcResult<Vec<Value>> {
+        let hash = load_transaction_block_hash(
+            self.client.as_ref(),
+            self.backend.as_ref(),
+            transaction_hash,
+        )
+        .await?;
+        let traces = self.cache.block_traces(hash).await?;
+        Ok(traces
+            .iter()
+            .filter(|trace| trace.transaction_hash == transaction_hash)
+            .map(|trace| trace.trace.clone())
+            .collect())
+    }
+
+    async fn trace_filter(&self, filter: TraceFilterRequest) -> RpcResult<Vec<Value>> {
+        let count = filter.count.unwrap_or(self.max_count);
+        if count > self.max_count {
+            return Err(internal_err(format!(
+                "count must not exceed {}",
+                self.max_count
+            )));
+        }
+
+        let from = self.block_number(filter.from_block).await?;
+        let to = self.block_number(filter.to_block).await?;
+        if from > to {
+            return Err(internal_err("fromBlock must not be after toBlock"));
+        }
+        if to - from >= self.max_block_range {
+            return Err(internal_err(format!(
+                "block range must not exceed {} blocks",
+                self.max_block_range
+            )));
+        }
+
+        let matches = |trace: &FlatTrace| {
+            filter
+                .from_address
+                .as_ref()
+                .map_or(true, |addresses| addresses.contains(&trace.from))
+                && filter
+                    .to_address
+                    .as_ref()
+                    .map_or(true, |addresses| addresses.contains(&trace.to))
+        };
+        let mut skip = filter.after.unwrap_or_default() as usize;
+        let mut result = Vec::new();
+        for number in from..=to {
+            let hash = self
+                .client
+                .hash(number)
+                .map_err(|err| internal_err(format!("{err:?}")))?
+                .ok_or_else(|| internal_err("block not found"))?;
+            for trace in self.cache.block_traces(hash).await?.iter() {
+                if !matches(trace) {
+                    continue;
+                }
+                if skip > 0 {
+                    skip -= 1;
+                    continue;
+                }
+                if result.len() == count as usize {
+                    return Ok(result);
+                }
+                result.push(trace.trace.clone());
+            }
+        }
+        Ok(result)
+    }
+}
+
+impl<C> Trace<C>
+where
+    C: HeaderBackend<Block> + 'static,
+{
+    async fn block_number(&self, number: Option<BlockNumberOrHash>) -> RpcResult<u32> {
+        let hash = load_block_hash(self.client.as_ref(), self.backend.as_ref(), number).await?;
+        self.client
+            .number(hash)
+            .map_err(|err| internal_err(format!("{err:?}")))?
+            .ok_or_else(|| internal_err("block not found"))
+    }
+}
+
+/// Flattens `call` and its subcalls into `traces`, in the order they were made, addressing
+/// each by the indices of the calls leading to it.
+fn flatten(
+    call: CallFrame,
+    transaction_hash: H256,
+    context: &Value,
+    trace_address: &mut Vec<usize>,
+    traces: &mut Vec<FlatTrace>,
+) {
+    let CallFrame {
+        call_type,
+        from,
+        to,
+        value,
+        gas,
+        gas_used,
+        input,
+        output,
+        error,
+        calls,
+        ..
+    } = call;
+    let value = format!("{:#x}", value.unwrap_or_default());
+
+    let mut trace = context.as_object().cloned().unwrap_or_default();
+    let (kind, action, result) = match call_type {
+        CallType::Create | CallType::Create2 => (
+            "create",
+            json!({
+                "creationMethod": if call_type == CallType::Create { "create" } else { "create2" },
+                "from": from,
+                "gas": format!("{gas:#x}"),
+                "init": to_hex(&input, false),
+                "value": value,
+            }),
+            json!({
+                "address": to,
+                "code": to_hex(&output, false),
+                "gasUsed": format!("{gas_used:#x}"),
+            }),
+        ),
+        CallType::SelfDestruct => (
+            "suicide",
+            json!({ "address": from, "refundAddress": to, "balance": value }),
+            Value::Null,
+        ),
+        _ => (
+            "call",
+            json!({
+                "callType": match call_type {
+                    CallType::StaticCall => "staticcall",
+                    CallType::DelegateCall => "delegatecall",
+                    CallType::CallCode => "callcode",
+                    _ => "call",
+                },
+                "from": from,
+                "to": to,
+                "gas": format!("{gas:#x}"),
+                "input": to_hex(&input, false),
+                "value": value,
+            }),
+            json!({
+                "gasUsed": format!("{gas_used:#x}"),
+                "output": to_hex(&output, false),
+            }),
+        ),
+    };
+    trace.insert("type".into(), kind.into());
+    trace.insert("action".into(), action);
+    match error {
+        Some(error) => {
+            let error = String::from_utf8_lossy(&error);
+            let error = if error == "execution reverted" {
+                "Reverted".into()
+            } else {
+                error.into_owned()
+            };
+            trace.insert("error".into(), error.into());
+        }
+        None => {
+            trace.insert("result".into(), result);
+        }
+    }
+    trace.insert("subtraces".into(), calls.len().into());
+    trace.insert("traceAddress".into(), json!(trace_address));
+
+    traces.push(FlatTrace {
+        transaction_hash,
+        from,
+        to,
+        trace: Value::Object(trace),
+    });
+    for (index, call) in calls.into_iter().enumerate() {
+        trace_address.push(index);
+        flatten(call, transaction_hash, context, trace_address, traces);
+        trace_address.pop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn call(call_type: CallType, from: u64, to: u64, calls: Vec<CallFrame>) -> CallFrame {
+        CallFrame {
+            call_type,
+            from: H160::from_low_u64_be(from),
+            to: H160::from_low_u64_be(to),
+            value: None,
+            gas: 100,
+            gas_used: 10,
+            input: Vec::new(),
+            output: Vec::new(),
+            error: None,
+            revert_reason: None,
+            calls,
+        }
+    }
+
+    #[test]
+    fn flattens_the_call_tree_depth_first() {
+        let mut reverted = call(CallType::Call, 2, 4, Vec::new());
+        reverted.error = Some(b"execution reverted".to_vec());
+        let root = call(
+            CallType::Call,
+            1,
+            2,
+            vec![
+                call(
+                    CallType::StaticCall,
+                    2,
+                    3,
+                    vec![call(CallType::Create, 3, 5, Vec::new())],
+                ),
+                reverted,
+            ],
+        );
+
+        let mut traces = Vec::new();
+        flatten(root, H256::zero(), &json!({}), &mut Vec::new(), &mut traces);
+
+        let summary: Vec<_> = traces
+            .iter()
+            .map(|trace| {
+                (
+                    trace.to.to_low_u64_be(),
+                    trace.trace["type"].clone(),
+                    trace.trace["traceAddress"].clone(),
+                    trace.trace["subtraces"].clone(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (2, json!("call"), json!([]), json!(2)),
+                (3, json!("call"), json!([0]), json!(1)),
+                (5, json!("create"), json!([0, 0]), json!(0)),
+                (4, json!("call"), json!([1]), json!(0)),
+            ]
+        );
+        assert_eq!(traces[1].trace["action"]["callType"], json!("staticcall"));
+        assert_eq!(traces[3].trace["error"], json!("Reverted"));
+        assert!(traces[3].trace.get("result").is_none());
+    }
+}
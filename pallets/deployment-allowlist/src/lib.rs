@@ -0,0 +1,369 @@
+//! # Deployment Allow-list Pallet
+//!
+//! Restricts EVM contract creation to an allow-list of deployer accounts, managed by
+//! `ManagerOrigin`. The restriction can be lifted at once by switching the open mode on, in
+//! which case every account may deploy.
+//!
+//! The restriction is enforced by [`Runner`], which wraps the runner of `pallet_evm`, on the
+//! contract creations of every execution: Ethereum transactions, `pallet_evm` calls and the
+//! runtime API (`eth_call`, `eth_estimateGas`). A contract creation of an account not allowed
+//! to deploy runs init code reverting with [`DEPLOYER_NOT_ALLOWED_REASON`], so the transaction
+//! is included and charged like any reverting deployment.
+//!
+//! Calls are executed unchanged, so the contracts deployed by allowed accounts may create
+//! contracts whoever calls them. Allowed deployers are responsible for the factories they
+//! deploy.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+pub use pallet::*;
+
+use core::marker::PhantomData;
+use frame_support::traits::Get;
+use pallet_evm::{CallInfo, CreateInfo, EvmConfig, RunnerError};
+use polkadot_sdk::{frame_support, frame_system, sp_runtime, sp_std};
+use sp_core::{H160, H256, U256};
+use sp_runtime::DispatchError;
+use sp_std::vec::Vec;
+
+/// Revert reason of the contract creations of accounts not allowed to deploy.
+pub const DEPLOYER_NOT_ALLOWED_REASON: &str = "Deployer not allowed";
+
+/// Init code reverting with `Error(DEPLOYER_NOT_ALLOWED_REASON)`, run instead of the init code
+/// of the contract creations of accounts not allowed to deploy.
+pub fn deployer_not_allowed_init_code() -> Vec<u8> {
+    let reason = DEPLOYER_NOT_ALLOWED_REASON.as_bytes();
+    let mut padded_reason = [0u8; 32];
+    padded_reason[..reason.len()].copy_from_slice(reason);
+
+    // Error(string) selector, string offset, string length and padded string.
+    let mut revert_data = Vec::with_capacity(100);
+    revert_data.extend_from_slice(&[0x08, 0xc3, 0x79, 0xa0]);
+    revert_data.extend_from_slice(H256::from_low_u64_be(32).as_bytes());
+    revert_data.extend_from_slice(H256::from_low_u64_be(reason.len() as u64).as_bytes());
+    revert_data.extend_from_slice(&padded_reason);
+
+    let len = revert_data.len() as u8;
+    // PUSH1 len PUSH1 12 PUSH1 0 CODECOPY PUSH1 len PUSH1 0 REVERT, followed by the data.
+    let mut code = sp_std::vec![0x60, len, 0x60, 12, 0x60, 0, 0x39, 0x60, len, 0x60, 0, 0xfd];
+    code.extend_from_slice(&revert_data);
+    code
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+    use super::*;
+    use frame_support::pallet_prelude::*;
+    use frame_system::pallet_prelude::*;
+
+    #[pallet::pallet]
+    pub struct Pallet<T>(_);
+
+    #[pallet::config]
+    pub trait Config: frame_system::Config + pallet_evm::Config {
+        /// The overarching event type.
+        type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+
+        /// The origin allowed to manage the allow-list and the open mode.
+        type ManagerOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+    }
+
+    /// The accounts allowed to deploy contracts.
+    #[pallet::storage]
+    pub type AllowedDeployers<T: Config> = StorageMap<_, Blake2_128Concat, H160, (), OptionQuery>;
+
+    /// Whether every account may deploy contracts.
+    #[pallet::storage]
+    pub type OpenMode<T: Config> = StorageValue<_, bool, ValueQuery>;
+
+    #[pallet::genesis_config]
+    #[derive(frame_support::DefaultNoBound)]
+    pub struct GenesisConfig<T: Config> {
+        /// Whether every account may deploy contracts.
+        pub open: bool,
+        /// The accounts allowed to deploy contracts.
+        pub allowed_deployers: Vec<H160>,
+        #[serde(skip)]
+        pub _config: PhantomData<T>,
+    }
+
+    #[pallet::genesis_build]
+    impl<T: Config> BuildGenesisConfig for GenesisConfig<T> {
+        fn build(&self) {
+            OpenMode::<T>::put(self.open);
+            for deployer in &self.allowed_deployers {
+                AllowedDeployers::<T>::insert(deployer, ());
+            }
+        }
+    }
+
+    #[pallet::event]
+    #[pallet::generate_deposit(pub(super) fn deposit_event)]
+    pub enum Event<T: Config> {
+        /// An account has been allowed to deploy contracts.
+        DeployerAllowed { deployer: H160 },
+        /// An account is no longer allowed to deploy contracts.
+        DeployerDisallowed { deployer: H160 },
+        /// The open mode has been switched.
+        OpenModeSet { open: bool },
+    }
+
+    #[pallet::error]
+    pub enum Error<T> {
+        /// The account is already allowed to deploy contracts.
+        AlreadyAllowed,
+        /// The account is not in the allow-list.
+        NotAllowed,
+    }
+
+    #[pallet::call]
+    impl<T: Config> Pallet<T> {
+        /// Allow `deployer` to deploy contracts.
+        ///
+        /// The dispatch origin must be `ManagerOrigin`.
+        #[pallet::call_index(0)]
+        #[pallet::weight(T::DbWeight::get().reads_writes(1, 1))]
+        pub fn allow_deployer(origin: OriginFor<T>, deployer: H160) -> DispatchResult {
+            T::ManagerOrigin::ensure_origin(origin)?;
+            ensure!(
+                !AllowedDeployers::<T>::contains_key(deployer),
+                Error::<T>::AlreadyAllowed
+            );
+
+            AllowedDeployers::<T>::insert(deployer, ());
+            Self::deposit_event(Event::DeployerAllowed { deployer });
+            Ok(())
+        }
+
+        /// Remove `deployer` from the allow-list.
+        ///
+        /// The dispatch origin must be `ManagerOrigin`.
+        #[pallet::call_index(1)]
+        #[pallet::weight(T::DbWeight::get().reads_writes(1, 1))]
+        pub fn disallow_deployer(origin: OriginFor<T>, deployer: H160) -> DispatchResult {
+            T::ManagerOrigin::ensure_origin(origin)?;
+            ensure!(
+                AllowedDeployers::<T>::contains_key(deployer),
+                Error::<T>::NotAllowed
+            );
+
+            AllowedDeployers::<T>::remove(deployer);
+            Self::deposit_event(Event::DeployerDisallowed { deployer });
+            Ok(())
+        }
+
+        /// Switch the open mode, in which every account may deploy contracts.
+        ///
+        /// The dispatch origin must be `ManagerOrigin`.
+        #[pallet::call_index(2)]
+        #[pallet::weight(T::DbWeight::get().writes(1))]
+        pub fn set_open_mode(origin: OriginFor<T>, open: bool) -> DispatchResult {
+            T::ManagerOrigin::ensure_origin(origin)?;
+
+            OpenMode::<T>::put(open);
+            Self::deposit_event(Event::OpenModeSet { open });
+            Ok(())
+        }
+    }
+
+    impl<T: Config> Pallet<T> {
+        /// Whether `deployer` may deploy contracts.
+        pub fn can_deploy(deployer: &H160) -> bool {
+            OpenMode::<T>::get() || AllowedDeployers::<T>::contains_key(deployer)
+        }
+    }
+}
+
+/// Runner of `pallet_evm` preventing the accounts not allowed to deploy from creating
+/// contracts, and delegating to `Inner`.
+pub struct Runner<T, Inner>(PhantomData<(T, Inner)>);
+
+impl<T: Config, Inner: pallet_evm::Runner<T>> Runner<T, Inner> {
+    /// `init`, or init code reverting with the reason if `source` may not deploy.
+    fn init_for(source: &H160, init: Vec<u8>) -> Vec<u8> {
+        if Pallet::<T>::can_deploy(source) {
+            init
+        } else {
+            deployer_not_allowed_init_code()
+        }
+    }
+
+    fn map_err(err: RunnerError<Inner::Error>) -> RunnerError<DispatchError> {
+        RunnerError {
+            error: err.error.into(),
+            weight: err.weight,
+        }
+    }
+}
+
+impl<T: Config, Inner: pallet_evm::Runner<T>> pallet_evm::Runner<T> for Runner<T, Inner> {
+    type Error = DispatchError;
+
+    fn validate(
+        source: H160,
+        target: Option<H160>,
+        input: Vec<u8>,
+        value: U256,
+        gas_limit: u64,
+        max_fee_per_gas: Option<U256>,
+        max_priority_fee_per_gas: Option<U256>,
+        nonce: Option<U256>,
+        access_list: Vec<(H160, Vec<H256>)>,
+        is_transactional: bool,
+        weight_limit: Option<frame_support::weights::Weight>,
+        proof_size_base_cost: Option<u64>,
+        evm_config: &EvmConfig,
+    ) -> Result<(), RunnerError<Self::Error>> {
+        Inner::validate(
+            source,
+            target,
+            input,
+            value,
+            gas_limit,
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
+            nonce,
+            access_list,
+            is_transactional,
+            weight_limit,
+            proof_size_base_cost,
+            evm_config,
+        )
+        .map_err(Self::map_err)
+    }
+
+    fn call(
+        source: H160,
+        target: H160,
+        input: Vec<u8>,
+        value: U256,
+        gas_limit: u64,
+        max_fee_per_gas: Option<U256>,
+        max_priority_fee_per_gas: Option<U256>,
+        nonce: Option<U256>,
+        access_list: Vec<(H160, Vec<H256>)>,
+        is_transactional: bool,
+        validate: bool,
+        weight_limit: Option<frame_support::weights::Weight>,
+        proof_size_base_cost: Option<u64>,
+        config: &EvmConfig,
+    ) -> Result<CallInfo, RunnerError<Self::Error>> {
+        Inner::call(
+            source,
+            target,
+            input,
+            value,
+            gas_limit,
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
+            nonce,
+            access_list,
+            is_transactional,
+            validate,
+            weight_limit,
+            proof_size_base_cost,
+            config,
+        )
+        .map_err(Self::map_err)
+    }
+
+    fn create(
+        source: H160,
+        init: Vec<u8>,
+        value: U256,
+        gas_limit: u64,
+        max_fee_per_gas: Option<U256>,
+        max_priority_fee_per_gas: Option<U256>,
+        nonce: Option<U256>,
+        access_list: Vec<(H160, Vec<H256>)>,
+        is_transactional: bool,
+        validate: bool,
+        weight_limit: Option<frame_support::weights::Weight>,
+        proof_size_base_cost: Option<u64>,
+        config: &EvmConfig,
+    ) -> Result<CreateInfo, RunnerError<Self::Error>> {
+        let init = Self::init_for(&source, init);
+
+        Inner::create(
+            source,
+            init,
+            value,
+            gas_limit,
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
+            nonce,
+            access_list,
+            is_transactional,
+            validate,
+            weight_limit,
+            proof_size_base_cost,
+            config,
+        )
+        .map_err(Self::map_err)
+    }
+
+    fn create2(
+        source: H160,
+        init: Vec<u8>,
+        salt: H256,
+        value: U256,
+        gas_limit: u64,
+        max_fee_per_gas: Option<U256>,
+        max_priority_fee_per_gas: Option<U256>,
+        nonce: Option<U256>,
+        access_list: Vec<(H160, Vec<H256>)>,
+        is_transactional: bool,
+        validate: bool,
+        weight_limit: Option<frame_support::weights::Weight>,
+        proof_size_base_cost: Option<u64>,
+        config: &EvmConfig,
+    ) -> Result<CreateInfo, RunnerError<Self::Error>> {
+        let init = Self::init_for(&source, init);
+
+        Inner::create2(
+            source,
+            init,
+            salt,
+            value,
+            gas_limit,
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
+            nonce,
+            access_list,
+            is_transactional,
+            validate,
+            weight_limit,
+            proof_size_base_cost,
+            config,
+        )
+        .map_err(Self::map_err)
+    }
+}
+
+pub mod migrations {
+    use super::*;
+    use frame_support::{traits::OnRuntimeUpgrade, weights::Weight};
+
+    /// Switches the open mode on when the allow-list is added to a running chain, so accounts
+    /// are not prevented from deploying until governance fills the allow-list.
+    pub struct SeedOpenMode<T>(PhantomData<T>);
+
+    impl<T: Config> OnRuntimeUpgrade for SeedOpenMode<T> {
+        fn on_runtime_upgrade() -> Weight {
+            let db_weight = <T as frame_system::Config>::DbWeight::get();
+            // The open mode is stored at genesis and by every switch, so it is only missing on
+            // chains the pallet has been added to.
+            if OpenMode::<T>::exists() {
+                return db_weight.reads(1);
+            }
+
+            OpenMode::<T>::put(true);
+            db_weight.reads_writes(1, 1)
+        }
+    }
+}
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
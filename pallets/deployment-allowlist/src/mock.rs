@@ -0,0 +1,135 @@
+//! Test runtime of the deployment allow-list pallet.
+
+use super::*;
+use crate as pallet_deployment_allowlist;
+use frame_support::{
+    construct_runtime, derive_impl, parameter_types,
+    traits::{ConstU128, ConstU64},
+    weights::{constants::RocksDbWeight, Weight},
+};
+use frame_system::EnsureRoot;
+use pallet_evm::{EnsureAddressNever, EnsureAddressRoot, IdentityAddressMapping};
+use polkadot_sdk::{pallet_balances, pallet_timestamp, sp_io};
+use sp_runtime::{traits::IdentityLookup, BuildStorage};
+
+pub type AccountId = H160;
+pub type Balance = u128;
+
+pub const ALICE: H160 = H160::repeat_byte(0xaa);
+pub const BOB: H160 = H160::repeat_byte(0xbb);
+
+construct_runtime!(
+    pub enum Runtime {
+        System: frame_system,
+        Balances: pallet_balances,
+        Timestamp: pallet_timestamp,
+        Evm: pallet_evm,
+        DeploymentAllowlist: pallet_deployment_allowlist,
+    }
+);
+
+#[derive_impl(frame_system::config_preludes::TestDefaultConfig)]
+impl frame_system::Config for Runtime {
+    type Block = frame_system::mocking::MockBlock<Runtime>;
+    type AccountId = AccountId;
+    type Lookup = IdentityLookup<AccountId>;
+    type AccountData = pallet_balances::AccountData<Balance>;
+    type DbWeight = RocksDbWeight;
+}
+
+#[derive_impl(pallet_balances::config_preludes::TestDefaultConfig)]
+impl pallet_balances::Config for Runtime {
+    type Balance = Balance;
+    type ExistentialDeposit = ConstU128<1>;
+    type AccountStore = System;
+}
+
+impl pallet_timestamp::Config for Runtime {
+    type Moment = u64;
+    type OnTimestampSet = ();
+    type MinimumPeriod = ConstU64<5>;
+    type WeightInfo = ();
+}
+
+parameter_types! {
+    pub BlockGasLimit: U256 = U256::from(u64::MAX);
+    pub const GasLimitPovSizeRatio: u64 = 16;
+    pub WeightPerGas: Weight = Weight::from_parts(20_000, 0);
+    pub SuicideQuickClearLimit: u32 = 0;
+}
+
+impl pallet_evm::Config for Runtime {
+    type FeeCalculator = ();
+    type GasWeightMapping = pallet_evm::FixedGasWeightMapping<Self>;
+    type WeightPerGas = WeightPerGas;
+    type BlockHashMapping = pallet_evm::SubstrateBlockHashMapping<Self>;
+    type CallOrigin = EnsureAddressRoot<AccountId>;
+    type WithdrawOrigin = EnsureAddressNever<AccountId>;
+    type AddressMapping = IdentityAddressMapping;
+    type Currency = Balances;
+    type RuntimeEvent = RuntimeEvent;
+    type PrecompilesType = ();
+    type PrecompilesValue = ();
+    type ChainId = ();
+    type BlockGasLimit = BlockGasLimit;
+    type Runner = Runner<Self, pallet_evm::runner::stack::Runner<Self>>;
+    type OnChargeTransaction = ();
+    type OnCreate = ();
+    type FindAuthor = ();
+    type GasLimitPovSizeRatio = GasLimitPovSizeRatio;
+    type SuicideQuickClearLimit = SuicideQuickClearLimit;
+    type Timestamp = Timestamp;
+    type WeightInfo = ();
+}
+
+impl Config for Runtime {
+    type RuntimeEvent = RuntimeEvent;
+    type ManagerOrigin = EnsureRoot<AccountId>;
+}
+
+pub(crate) struct ExtBuilder {
+    genesis: Option<GenesisConfig<Runtime>>,
+}
+
+impl Default for ExtBuilder {
+    /// Alice is the only account allowed to deploy.
+    fn default() -> Self {
+        Self {
+            genesis: Some(GenesisConfig {
+                open: false,
+                allowed_deployers: vec![ALICE],
+                ..Default::default()
+            }),
+        }
+    }
+}
+
+impl ExtBuilder {
+    /// Builds a chain the pallet has not been added to yet.
+    pub(crate) fn without_pallet(mut self) -> Self {
+        self.genesis = None;
+        self
+    }
+
+    pub(crate) fn build(self) -> sp_io::TestExternalities {
+        let mut t = frame_system::GenesisConfig::<Runtime>::default()
+            .build_storage()
+            .expect("Frame system builds valid default genesis config");
+
+        pallet_balances::GenesisConfig::<Runtime> {
+            balances: vec![(ALICE, 1_000_000), (BOB, 1_000_000)],
+        }
+        .assimilate_storage(&mut t)
+        .expect("Pallet balances storage can be assimilated");
+
+        if let Some(genesis) = self.genesis {
+            genesis
+                .assimilate_storage(&mut t)
+                .expect("Pallet deployment allow-list storage can be assimilated");
+        }
+
+        let mut ext = sp_io::TestExternalities::new(t);
+        ext.execute_with(|| System::set_block_number(1));
+        ext
+    }
+}
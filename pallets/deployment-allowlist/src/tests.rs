@@ -0,0 +1,238 @@
+use crate::{mock::*, *};
+use frame_support::{assert_noop, assert_ok, traits::OnRuntimeUpgrade};
+use pallet_evm::{ExitReason, Runner as _};
+use sp_runtime::traits::BadOrigin;
+
+/// Init code of a contract whose code is `STOP`.
+const CHILD_INIT_CODE: [u8; 10] = [0x60, 0x00, 0x60, 0x00, 0x53, 0x60, 0x01, 0x60, 0x00, 0xf3];
+
+/// Code creating a contract with `CHILD_INIT_CODE` on every call, and returning its address.
+fn factory_code() -> Vec<u8> {
+    let mut code = vec![0x69];
+    code.extend_from_slice(&CHILD_INIT_CODE);
+    code.extend_from_slice(&[
+        0x60, 0x00, 0x52, 0x60, 0x0a, 0x60, 0x16, 0x60, 0x00, 0xf0, 0x60, 0x00, 0x52, 0x60, 0x20,
+        0x60, 0x00, 0xf3,
+    ]);
+    code
+}
+
+fn call(source: H160, target: H160) -> CallInfo {
+    <Runtime as pallet_evm::Config>::Runner::call(
+        source,
+        target,
+        Vec::new(),
+        U256::zero(),
+        1_000_000,
+        None,
+        None,
+        None,
+        Vec::new(),
+        true,
+        false,
+        None,
+        None,
+        <Runtime as pallet_evm::Config>::config(),
+    )
+    .expect("the call is executed")
+}
+
+fn create(source: H160) -> CreateInfo {
+    <Runtime as pallet_evm::Config>::Runner::create(
+        source,
+        CHILD_INIT_CODE.to_vec(),
+        U256::zero(),
+        1_000_000,
+        None,
+        None,
+        None,
+        Vec::new(),
+        true,
+        false,
+        None,
+        None,
+        <Runtime as pallet_evm::Config>::config(),
+    )
+    .expect("the creation is executed")
+}
+
+fn create2(source: H160) -> CreateInfo {
+    <Runtime as pallet_evm::Config>::Runner::create2(
+        source,
+        CHILD_INIT_CODE.to_vec(),
+        H256::repeat_byte(1),
+        U256::zero(),
+        1_000_000,
+        None,
+        None,
+        None,
+        Vec::new(),
+        true,
+        false,
+        None,
+        None,
+        <Runtime as pallet_evm::Config>::config(),
+    )
+    .expect("the creation is executed")
+}
+
+fn code(address: H160) -> Vec<u8> {
+    pallet_evm::AccountCodes::<Runtime>::get(address)
+}
+
+#[test]
+fn genesis_config_is_applied() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert!(!OpenMode::<Runtime>::get());
+        assert!(DeploymentAllowlist::can_deploy(&ALICE));
+        assert!(!DeploymentAllowlist::can_deploy(&BOB));
+    });
+}
+
+#[test]
+fn allow_and_disallow_deployer() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert_noop!(
+            DeploymentAllowlist::allow_deployer(RuntimeOrigin::signed(ALICE), BOB),
+            BadOrigin
+        );
+        assert_noop!(
+            DeploymentAllowlist::allow_deployer(RuntimeOrigin::root(), ALICE),
+            Error::<Runtime>::AlreadyAllowed
+        );
+
+        assert_ok!(DeploymentAllowlist::allow_deployer(
+            RuntimeOrigin::root(),
+            BOB
+        ));
+        System::assert_last_event(Event::DeployerAllowed { deployer: BOB }.into());
+        assert!(DeploymentAllowlist::can_deploy(&BOB));
+
+        assert_noop!(
+            DeploymentAllowlist::disallow_deployer(RuntimeOrigin::signed(ALICE), BOB),
+            BadOrigin
+        );
+        assert_ok!(DeploymentAllowlist::disallow_deployer(
+            RuntimeOrigin::root(),
+            BOB
+        ));
+        System::assert_last_event(Event::DeployerDisallowed { deployer: BOB }.into());
+        assert!(!DeploymentAllowlist::can_deploy(&BOB));
+        assert_noop!(
+            DeploymentAllowlist::disallow_deployer(RuntimeOrigin::root(), BOB),
+            Error::<Runtime>::NotAllowed
+        );
+    });
+}
+
+#[test]
+fn open_mode_allows_every_account() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert_noop!(
+            DeploymentAllowlist::set_open_mode(RuntimeOrigin::signed(ALICE), true),
+            BadOrigin
+        );
+
+        assert_ok!(DeploymentAllowlist::set_open_mode(
+            RuntimeOrigin::root(),
+            true
+        ));
+        System::assert_last_event(Event::OpenModeSet { open: true }.into());
+        assert!(DeploymentAllowlist::can_deploy(&BOB));
+
+        assert_ok!(DeploymentAllowlist::set_open_mode(
+            RuntimeOrigin::root(),
+            false
+        ));
+        assert!(!DeploymentAllowlist::can_deploy(&BOB));
+    });
+}
+
+#[test]
+fn seed_open_mode_on_chains_without_the_pallet() {
+    ExtBuilder::default()
+        .without_pallet()
+        .build()
+        .execute_with(|| {
+            migrations::SeedOpenMode::<Runtime>::on_runtime_upgrade();
+            assert!(OpenMode::<Runtime>::get());
+            assert!(DeploymentAllowlist::can_deploy(&BOB));
+        });
+
+    ExtBuilder::default().build().execute_with(|| {
+        migrations::SeedOpenMode::<Runtime>::on_runtime_upgrade();
+        assert!(!OpenMode::<Runtime>::get());
+    });
+}
+
+#[test]
+fn seed_open_mode_keeps_the_mode_set_by_governance() {
+    ExtBuilder::default()
+        .without_pallet()
+        .build()
+        .execute_with(|| {
+            assert_ok!(DeploymentAllowlist::set_open_mode(
+                RuntimeOrigin::root(),
+                false
+            ));
+            migrations::SeedOpenMode::<Runtime>::on_runtime_upgrade();
+            assert!(!OpenMode::<Runtime>::get());
+        });
+}
+
+#[test]
+fn allowed_deployer_creates_contracts() {
+    ExtBuilder::default().build().execute_with(|| {
+        for info in [create(ALICE), create2(ALICE)] {
+            assert!(matches!(info.exit_reason, ExitReason::Succeed(_)));
+            assert_eq!(code(info.value), vec![0x00]);
+        }
+    });
+}
+
+#[test]
+fn creation_of_not_allowed_deployer_reverts() {
+    ExtBuilder::default().build().execute_with(|| {
+        for info in [create(BOB), create2(BOB)] {
+            assert!(matches!(info.exit_reason, ExitReason::Revert(_)));
+            assert!(code(info.value).is_empty());
+        }
+    });
+}
+
+#[test]
+fn creation_of_not_allowed_deployer_reverts_with_the_reason() {
+    ExtBuilder::default().build().execute_with(|| {
+        // The init code only reads its own code, so it runs the same as the code of a contract.
+        let contract = H160::repeat_byte(0xcc);
+        pallet_evm::Pallet::<Runtime>::create_account(contract, deployer_not_allowed_init_code());
+
+        let mut expected = vec![0x08, 0xc3, 0x79, 0xa0];
+        expected.extend_from_slice(&[0u8; 31]);
+        expected.push(0x20);
+        expected.extend_from_slice(&[0u8; 31]);
+        expected.push(20);
+        expected.extend_from_slice(b"Deployer not allowed");
+        expected.extend_from_slice(&[0u8; 12]);
+
+        let info = call(ALICE, contract);
+        assert!(matches!(info.exit_reason, ExitReason::Revert(_)));
+        assert_eq!(info.value, expected);
+    });
+}
+
+#[test]
+fn contracts_of_allowed_deployers_create_for_every_caller() {
+    ExtBuilder::default().build().execute_with(|| {
+        let factory = H160::repeat_byte(0xfa);
+        pallet_evm::Pallet::<Runtime>::create_account(factory, factory_code());
+
+        for source in [ALICE, BOB] {
+            let info = call(source, factory);
+            assert!(matches!(info.exit_reason, ExitReason::Succeed(_)));
+            let child = H160::from_slice(&info.value[12..]);
+            assert_ne!(child, H160::zero());
+            assert_eq!(code(child), vec![0x00]);
+        }
+    });
+}
@@ -0,0 +1,181 @@
+//! # Precompile Registry Pallet
+//!
+//! Stores which of the precompiles compiled into the runtime are active, so governance can
+//! disable a precompile, for instance during an incident, and enable it again without a
+//! runtime upgrade. The runtime precompile set consults [`Pallet::is_active`] before
+//! answering at an address.
+//!
+//! Every active precompile gets [`DUMMY_CODE`] inserted into `pallet_evm::AccountCodes`, so
+//! `extcodesize` is not zero and Solidity high-level calls accept the address as a contract.
+//! The code is kept when a precompile is disabled, so calls to it revert.
+//!
+//! When the pallet is added to a running chain, the first runtime upgrade activates every
+//! precompile so they keep answering. [`Initialized`] records that the registry was
+//! initialized, at genesis or by that upgrade, so precompiles disabled by governance stay
+//! disabled on later upgrades.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+pub use pallet::*;
+
+use polkadot_sdk::{frame_support, frame_system, sp_std};
+
+/// Dummy code inserted at the address of every active precompile (`PUSH1 0 PUSH1 0 REVERT`).
+pub const DUMMY_CODE: [u8; 5] = [0x60, 0x00, 0x60, 0x00, 0xfd];
+
+#[frame_support::pallet]
+pub mod pallet {
+    use super::*;
+    use frame_support::pallet_prelude::*;
+    use frame_system::pallet_prelude::*;
+    use sp_core::H160;
+    use sp_std::vec::Vec;
+
+    /// The in-code storage version.
+    const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
+    #[pallet::pallet]
+    #[pallet::storage_version(STORAGE_VERSION)]
+    pub struct Pallet<T>(_);
+
+    #[pallet::config]
+    pub trait Config: frame_system::Config + pallet_evm::Config {
+        /// The overarching event type.
+        type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+
+        /// The addresses of the precompiles of the runtime.
+        type PrecompileAddresses: Get<Vec<H160>>;
+
+        /// The origin allowed to enable and disable precompiles.
+        type ToggleOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+    }
+
+    /// The active precompiles.
+    #[pallet::storage]
+    pub type ActivePrecompiles<T: Config> = StorageMap<_, Blake2_128Concat, H160, (), OptionQuery>;
+
+    /// Whether the active precompiles have been initialized.
+    #[pallet::storage]
+    pub type Initialized<T: Config> = StorageValue<_, bool, ValueQuery>;
+
+    #[pallet::genesis_config]
+    #[derive(frame_support::DefaultNoBound)]
+    pub struct GenesisConfig<T: Config> {
+        /// Precompiles left disabled at genesis, every other precompile being active.
+        pub disabled_precompiles: Vec<H160>,
+        #[serde(skip)]
+        pub _config: core::marker::PhantomData<T>,
+    }
+
+    #[pallet::genesis_build]
+    impl<T: Config> BuildGenesisConfig for GenesisConfig<T> {
+        fn build(&self) {
+            for address in T::PrecompileAddresses::get() {
+                if !self.disabled_precompiles.contains(&address) {
+                    Pallet::<T>::activate(address);
+                }
+            }
+            Initialized::<T>::put(true);
+        }
+    }
+
+    #[pallet::hooks]
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        /// Activates every precompile of the runtime if the registry was added to a running
+        /// chain by this upgrade.
+        ///
+        /// The storage version of a pallet added by a runtime upgrade is set before the
+        /// migrations run, so it cannot tell whether the registry was initialized.
+        fn on_runtime_upgrade() -> Weight {
+            let db_weight = T::DbWeight::get();
+            if Initialized::<T>::get() {
+                return db_weight.reads(1);
+            }
+
+            let addresses = T::PrecompileAddresses::get();
+            for address in addresses.iter() {
+                Self::activate(*address);
+            }
+            Initialized::<T>::put(true);
+
+            let count = addresses.len() as u64;
+            db_weight.reads_writes(1 + count, 1 + 3 * count)
+        }
+    }
+
+    #[pallet::event]
+    #[pallet::generate_deposit(pub(super) fn deposit_event)]
+    pub enum Event<T: Config> {
+        /// A precompile has been enabled.
+        PrecompileEnabled { address: H160 },
+        /// A precompile has been disabled.
+        PrecompileDisabled { address: H160 },
+    }
+
+    #[pallet::error]
+    pub enum Error<T> {
+        /// The runtime has no precompile at this address.
+        NotAPrecompile,
+        /// The precompile is already active.
+        AlreadyActive,
+        /// The precompile is not active.
+        NotActive,
+    }
+
+    #[pallet::call]
+    impl<T: Config> Pallet<T> {
+        /// Enable the precompile at `address`, inserting the dummy code at its address if it
+        /// has no code.
+        ///
+        /// The dispatch origin must be `ToggleOrigin`.
+        #[pallet::call_index(0)]
+        #[pallet::weight(T::DbWeight::get().reads_writes(2, 3))]
+        pub fn enable_precompile(origin: OriginFor<T>, address: H160) -> DispatchResult {
+            T::ToggleOrigin::ensure_origin(origin)?;
+            ensure!(
+                T::PrecompileAddresses::get().contains(&address),
+                Error::<T>::NotAPrecompile
+            );
+            ensure!(!Self::is_active(address), Error::<T>::AlreadyActive);
+
+            Self::activate(address);
+            Self::deposit_event(Event::PrecompileEnabled { address });
+            Ok(())
+        }
+
+        /// Disable the precompile at `address`. Calls to it revert until it is enabled again.
+        ///
+        /// The dispatch origin must be `ToggleOrigin`.
+        #[pallet::call_index(1)]
+        #[pallet::weight(T::DbWeight::get().reads_writes(1, 1))]
+        pub fn disable_precompile(origin: OriginFor<T>, address: H160) -> DispatchResult {
+            T::ToggleOrigin::ensure_origin(origin)?;
+            ensure!(Self::is_active(address), Error::<T>::NotActive);
+
+            ActivePrecompiles::<T>::remove(address);
+            Self::deposit_event(Event::PrecompileDisabled { address });
+            Ok(())
+        }
+    }
+
+    impl<T: Config> Pallet<T> {
+        /// Whether the precompile at `address` is active.
+        pub fn is_active(address: H160) -> bool {
+            ActivePrecompiles::<T>::contains_key(address)
+        }
+
+        /// Mark the precompile at `address` as active, and insert the dummy code at its
+        /// address if it has no code.
+        pub(crate) fn activate(address: H160) {
+            ActivePrecompiles::<T>::insert(address, ());
+            if !pallet_evm::AccountCodes::<T>::contains_key(address) {
+                pallet_evm::Pallet::<T>::create_account(address, DUMMY_CODE.to_vec());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,141 @@
+//! Test runtime of the precompile registry pallet.
+
+use super::*;
+use crate as pallet_precompile_registry;
+use frame_support::{
+    construct_runtime, derive_impl, parameter_types,
+    traits::{ConstU128, ConstU64},
+    weights::{constants::RocksDbWeight, Weight},
+};
+use frame_system::EnsureRoot;
+use pallet_evm::{EnsureAddressNever, EnsureAddressRoot, IdentityAddressMapping};
+use polkadot_sdk::{pallet_balances, pallet_timestamp, sp_io, sp_runtime};
+use sp_core::{H160, U256};
+use sp_runtime::{traits::IdentityLookup, BuildStorage};
+
+pub type AccountId = H160;
+pub type Balance = u128;
+
+pub const ALICE: H160 = H160::repeat_byte(0xaa);
+
+/// The addresses of the precompiles of the test runtime.
+pub fn precompile(index: u64) -> H160 {
+    H160::from_low_u64_be(index)
+}
+
+construct_runtime!(
+    pub enum Runtime {
+        System: frame_system,
+        Balances: pallet_balances,
+        Timestamp: pallet_timestamp,
+        Evm: pallet_evm,
+        PrecompileRegistry: pallet_precompile_registry,
+    }
+);
+
+#[derive_impl(frame_system::config_preludes::TestDefaultConfig)]
+impl frame_system::Config for Runtime {
+    type Block = frame_system::mocking::MockBlock<Runtime>;
+    type AccountId = AccountId;
+    type Lookup = IdentityLookup<AccountId>;
+    type AccountData = pallet_balances::AccountData<Balance>;
+    type DbWeight = RocksDbWeight;
+}
+
+#[derive_impl(pallet_balances::config_preludes::TestDefaultConfig)]
+impl pallet_balances::Config for Runtime {
+    type Balance = Balance;
+    type ExistentialDeposit = ConstU128<1>;
+    type AccountStore = System;
+}
+
+impl pallet_timestamp::Config for Runtime {
+    type Moment = u64;
+    type OnTimestampSet = ();
+    type MinimumPeriod = ConstU64<5>;
+    type WeightInfo = ();
+}
+
+parameter_types! {
+    pub BlockGasLimit: U256 = U256::from(u64::MAX);
+    pub const GasLimitPovSizeRatio: u64 = 16;
+    pub WeightPerGas: Weight = Weight::from_parts(20_000, 0);
+    pub SuicideQuickClearLimit: u32 = 0;
+    pub PrecompileAddresses: Vec<H160> = vec![precompile(1), precompile(2), precompile(3)];
+}
+
+impl pallet_evm::Config for Runtime {
+    type FeeCalculator = ();
+    type GasWeightMapping = pallet_evm::FixedGasWeightMapping<Self>;
+    type WeightPerGas = WeightPerGas;
+    type BlockHashMapping = pallet_evm::SubstrateBlockHashMapping<Self>;
+    type CallOrigin = EnsureAddressRoot<AccountId>;
+    type WithdrawOrigin = EnsureAddressNever<AccountId>;
+    type AddressMapping = IdentityAddressMapping;
+    type Currency = Balances;
+    type RuntimeEvent = RuntimeEvent;
+    type PrecompilesType = ();
+    type PrecompilesValue = ();
+    type ChainId = ();
+    type BlockGasLimit = BlockGasLimit;
+    type Runner = pallet_evm::runner::stack::Runner<Self>;
+    type OnChargeTransaction = ();
+    type OnCreate = ();
+    type FindAuthor = ();
+    type GasLimitPovSizeRatio = GasLimitPovSizeRatio;
+    type SuicideQuickClearLimit = SuicideQuickClearLimit;
+    type Timestamp = Timestamp;
+    type WeightInfo = ();
+}
+
+impl Config for Runtime {
+    type RuntimeEvent = RuntimeEvent;
+    type PrecompileAddresses = PrecompileAddresses;
+    type ToggleOrigin = EnsureRoot<AccountId>;
+}
+
+pub(crate) struct ExtBuilder {
+    genesis: Option<GenesisConfig<Runtime>>,
+}
+
+impl Default for ExtBuilder {
+    /// The third precompile is disabled.
+    fn default() -> Self {
+        Self {
+            genesis: Some(GenesisConfig {
+                disabled_precompiles: vec![precompile(3)],
+                ..Default::default()
+            }),
+        }
+    }
+}
+
+impl ExtBuilder {
+    /// Builds a chain the pallet has not been added to yet.
+    pub(crate) fn without_pallet(mut self) -> Self {
+        self.genesis = None;
+        self
+    }
+
+    pub(crate) fn build(self) -> sp_io::TestExternalities {
+        let mut t = frame_system::GenesisConfig::<Runtime>::default()
+            .build_storage()
+            .expect("Frame system builds valid default genesis config");
+
+        pallet_balances::GenesisConfig::<Runtime> {
+            balances: vec![(ALICE, 1_000_000)],
+        }
+        .assimilate_storage(&mut t)
+        .expect("Pallet balances storage can be assimilated");
+
+        if let Some(genesis) = self.genesis {
+            genesis
+                .assimilate_storage(&mut t)
+                .expect("Pallet precompile registry storage can be assimilated");
+        }
+
+        let mut ext = sp_io::TestExternalities::new(t);
+        ext.execute_with(|| System::set_block_number(1));
+        ext
+    }
+}
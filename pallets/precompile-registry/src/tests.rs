@@ -0,0 +1,137 @@
+use crate::{mock::*, *};
+use frame_support::{assert_noop, assert_ok, traits::OnRuntimeUpgrade};
+use polkadot_sdk::sp_runtime;
+use sp_core::H160;
+use sp_runtime::traits::BadOrigin;
+
+fn code(address: H160) -> Vec<u8> {
+    pallet_evm::AccountCodes::<Runtime>::get(address)
+}
+
+#[test]
+fn genesis_config_is_applied() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert!(Initialized::<Runtime>::get());
+        assert!(PrecompileRegistry::is_active(precompile(1)));
+        assert!(PrecompileRegistry::is_active(precompile(2)));
+        assert!(!PrecompileRegistry::is_active(precompile(3)));
+        assert_eq!(code(precompile(1)), DUMMY_CODE.to_vec());
+        assert!(code(precompile(3)).is_empty());
+    });
+}
+
+#[test]
+fn enable_and_disable_precompile() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert_noop!(
+            PrecompileRegistry::enable_precompile(RuntimeOrigin::signed(ALICE), precompile(3)),
+            BadOrigin
+        );
+        assert_noop!(
+            PrecompileRegistry::enable_precompile(RuntimeOrigin::root(), precompile(4)),
+            Error::<Runtime>::NotAPrecompile
+        );
+        assert_noop!(
+            PrecompileRegistry::enable_precompile(RuntimeOrigin::root(), precompile(1)),
+            Error::<Runtime>::AlreadyActive
+        );
+
+        assert_ok!(PrecompileRegistry::enable_precompile(
+            RuntimeOrigin::root(),
+            precompile(3)
+        ));
+        System::assert_last_event(
+            Event::PrecompileEnabled {
+                address: precompile(3),
+            }
+            .into(),
+        );
+        assert!(PrecompileRegistry::is_active(precompile(3)));
+        assert_eq!(code(precompile(3)), DUMMY_CODE.to_vec());
+
+        assert_noop!(
+            PrecompileRegistry::disable_precompile(RuntimeOrigin::signed(ALICE), precompile(3)),
+            BadOrigin
+        );
+        assert_ok!(PrecompileRegistry::disable_precompile(
+            RuntimeOrigin::root(),
+            precompile(3)
+        ));
+        System::assert_last_event(
+            Event::PrecompileDisabled {
+                address: precompile(3),
+            }
+            .into(),
+        );
+        assert!(!PrecompileRegistry::is_active(precompile(3)));
+        // The dummy code is kept, so calls to the precompile revert.
+        assert_eq!(code(precompile(3)), DUMMY_CODE.to_vec());
+        assert_noop!(
+            PrecompileRegistry::disable_precompile(RuntimeOrigin::root(), precompile(3)),
+            Error::<Runtime>::NotActive
+        );
+    });
+}
+
+#[test]
+fn enabling_a_precompile_keeps_its_code() {
+    ExtBuilder::default().build().execute_with(|| {
+        pallet_evm::AccountCodes::<Runtime>::insert(precompile(3), vec![0x00]);
+        assert_ok!(PrecompileRegistry::enable_precompile(
+            RuntimeOrigin::root(),
+            precompile(3)
+        ));
+        assert_eq!(code(precompile(3)), vec![0x00]);
+    });
+}
+
+#[test]
+fn registry_is_initialized_on_chains_without_the_pallet() {
+    ExtBuilder::default()
+        .without_pallet()
+        .build()
+        .execute_with(|| {
+            assert!(!Initialized::<Runtime>::get());
+
+            <PrecompileRegistry as OnRuntimeUpgrade>::on_runtime_upgrade();
+            assert!(Initialized::<Runtime>::get());
+            for address in PrecompileAddresses::get() {
+                assert!(PrecompileRegistry::is_active(address));
+                assert_eq!(code(address), DUMMY_CODE.to_vec());
+            }
+        });
+}
+
+#[test]
+fn registry_is_initialized_once() {
+    ExtBuilder::default()
+        .without_pallet()
+        .build()
+        .execute_with(|| {
+            <PrecompileRegistry as OnRuntimeUpgrade>::on_runtime_upgrade();
+            assert_ok!(PrecompileRegistry::disable_precompile(
+                RuntimeOrigin::root(),
+                precompile(1)
+            ));
+            assert_ok!(PrecompileRegistry::disable_precompile(
+                RuntimeOrigin::root(),
+                precompile(2)
+            ));
+            assert_ok!(PrecompileRegistry::disable_precompile(
+                RuntimeOrigin::root(),
+                precompile(3)
+            ));
+
+            // Precompiles disabled by governance stay disabled on later upgrades, even when
+            // none is active.
+            <PrecompileRegistry as OnRuntimeUpgrade>::on_runtime_upgrade();
+            for address in PrecompileAddresses::get() {
+                assert!(!PrecompileRegistry::is_active(address));
+            }
+        });
+
+    ExtBuilder::default().build().execute_with(|| {
+        <PrecompileRegistry as OnRuntimeUpgrade>::on_runtime_upgrade();
+        assert!(!PrecompileRegistry::is_active(precompile(3)));
+    });
+}
@@ -0,0 +1,106 @@
+// SPDX-License-Identifier: GPL-3.0-only
+pragma solidity >=0.8.3;
+
+/// @dev The AssetConversionRouter contract's address.
+address constant ASSET_CONVERSION_ROUTER_ADDRESS = 0x000000000000000000000000000000000000080B;
+
+/// @dev The AssetConversionRouter contract's instance.
+AssetConversionRouter constant ASSET_CONVERSION_ROUTER_CONTRACT = AssetConversionRouter(
+    ASSET_CONVERSION_ROUTER_ADDRESS
+);
+
+/// @title Pallet AssetConversion Interface
+/// @dev A Uniswap V2 router like interface to the pools of pallet-asset-conversion.
+/// Tokens are designated by the address of their ERC-20 precompile: the native token
+/// precompile or a pallet-assets ERC-20 precompile. The calls spend the tokens of msg.sender
+/// directly, no ERC-20 approval is needed. Liquidity tokens are pallet-asset-conversion pool
+/// assets.
+interface AssetConversionRouter {
+    /// @dev Creates the pool of two tokens. The pool setup fee is paid by msg.sender.
+    /// @custom:selector e3433615
+    /// @param tokenA The first token of the pool.
+    /// @param tokenB The second token of the pool.
+    function createPool(address tokenA, address tokenB) external;
+
+    /// @dev Adds liquidity to the pool of two tokens.
+    /// @custom:selector e8e33700
+    /// @return amountA The amount of tokenA provided.
+    /// @return amountB The amount of tokenB provided.
+    /// @return liquidity The amount of liquidity tokens minted to `to`.
+    function addLiquidity(
+        address tokenA,
+        address tokenB,
+        uint256 amountADesired,
+        uint256 amountBDesired,
+        uint256 amountAMin,
+        uint256 amountBMin,
+        address to,
+        uint256 deadline
+    )
+        external
+        returns (
+            uint256 amountA,
+            uint256 amountB,
+            uint256 liquidity
+        );
+
+    /// @dev Burns liquidity tokens of msg.sender and withdraws the underlying tokens to `to`.
+    /// @custom:selector baa2abde
+    /// @return amountA The amount of tokenA withdrawn.
+    /// @return amountB The amount of tokenB withdrawn.
+    function removeLiquidity(
+        address tokenA,
+        address tokenB,
+        uint256 liquidity,
+        uint256 amountAMin,
+        uint256 amountBMin,
+        address to,
+        uint256 deadline
+    ) external returns (uint256 amountA, uint256 amountB);
+
+    /// @dev Swaps exactly `amountIn` of the first token of `path` for as much as possible of
+    /// the last one.
+    /// @custom:selector 38ed1739
+    /// @return amounts The amounts of every token of `path` along the swap.
+    function swapExactTokensForTokens(
+        uint256 amountIn,
+        uint256 amountOutMin,
+        address[] calldata path,
+        address to,
+        uint256 deadline
+    ) external returns (uint256[] memory amounts);
+
+    /// @dev Swaps as little as possible of the first token of `path` for exactly
+    /// `amountOut` of the last one.
+    /// @custom:selector 8803dbee
+    /// @return amounts The amounts of every token of `path` along the swap.
+    function swapTokensForExactTokens(
+        uint256 amountOut,
+        uint256 amountInMax,
+        address[] calldata path,
+        address to,
+        uint256 deadline
+    ) external returns (uint256[] memory amounts);
+
+    /// @dev Quotes the amounts of every token of `path` when swapping exactly `amountIn`.
+    /// @custom:selector d06ca61f
+    function getAmountsOut(uint256 amountIn, address[] calldata path)
+        external
+        view
+        returns (uint256[] memory amounts);
+
+    /// @dev Quotes the amounts of every token of `path` when swapping for exactly
+    /// `amountOut`.
+    /// @custom:selector 1f00ca74
+    function getAmountsIn(uint256 amountOut, address[] calldata path)
+        external
+        view
+        returns (uint256[] memory amounts);
+
+    /// @dev Returns the reserves of the pool of two tokens, zero if there is none.
+    /// @custom:selector d52bb6f4
+    function getReserves(address tokenA, address tokenB)
+        external
+        view
+        returns (uint256 reserveA, uint256 reserveB);
+}
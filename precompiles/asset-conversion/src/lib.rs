@@ -0,0 +1,433 @@
+//! Precompile to trade through the pools of `pallet_asset_conversion`, with an interface
+//! following the Uniswap V2 router.
+//!
+//! Tokens are designated by the address of their ERC-20 precompile, which the runtime maps to
+//! an asset kind through [`AddressToAssetKind`]. Calls are dispatched with a signed origin of
+//! the caller, so no ERC-20 approval of the router is needed. Liquidity tokens are held in
+//! the pool assets of the pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::MaxEncodedLen;
+use core::marker::PhantomData;
+use fp_evm::PrecompileHandle;
+use frame_support::{
+    dispatch::{GetDispatchInfo, PostDispatchInfo},
+    ensure,
+    traits::{fungibles::Inspect, OriginTrait},
+};
+use pallet_asset_conversion::{PoolLocator, Pools};
+use pallet_evm::AddressMapping;
+use polkadot_sdk::{
+    frame_support, frame_system, pallet_asset_conversion, pallet_timestamp, sp_runtime, sp_std,
+};
+use precompile_utils::prelude::*;
+use sp_core::{ConstU32, H160, U256};
+use sp_runtime::traits::Dispatchable;
+use sp_std::{boxed::Box, vec::Vec};
+
+/// Maximum number of tokens of a swap path accepted by the precompile. The pallet applies its
+/// own `MaxSwapPathLength`.
+pub const PATH_LIMIT: u32 = 2u32.pow(4);
+type GetPathLimit = ConstU32<PATH_LIMIT>;
+
+/// Alias for the Asset Kind type for the provided Runtime.
+pub type AssetKindOf<Runtime> = <Runtime as pallet_asset_conversion::Config>::AssetKind;
+
+/// Alias for the Balance type for the provided Runtime.
+pub type BalanceOf<Runtime> = <Runtime as pallet_asset_conversion::Config>::Balance;
+
+/// Maps the address of a token precompile to the asset kind of `pallet_asset_conversion`.
+pub trait AddressToAssetKind<AssetKind> {
+    /// Returns the asset kind of the token answering at `address`, if any.
+    fn address_to_asset_kind(address: H160) -> Option<AssetKind>;
+}
+
+/// A precompile to wrap the functionality from `pallet_asset_conversion`.
+pub struct AssetConversionRouterPrecompile<Runtime>(PhantomData<Runtime>);
+
+#[precompile_utils::precompile]
+impl<Runtime> AssetConversionRouterPrecompile<Runtime>
+where
+    Runtime: pallet_asset_conversion::Config + pallet_timestamp::Config + pallet_evm::Config,
+    Runtime: AddressToAssetKind<AssetKindOf<Runtime>>,
+    Runtime::RuntimeCall: Dispatchable<PostInfo = PostDispatchInfo> + GetDispatchInfo,
+    Runtime::RuntimeCall: From<pallet_asset_conversion::Call<Runtime>>,
+    <Runtime::RuntimeCall as Dispatchable>::RuntimeOrigin: From<Option<Runtime::AccountId>>,
+    <Runtime::RuntimeCall as Dispatchable>::RuntimeOrigin: OriginTrait,
+    <Runtime as pallet_timestamp::Config>::Moment: Into<U256>,
+    BalanceOf<Runtime>: TryFrom<U256> + Into<U256>,
+{
+    #[precompile::public("createPool(address,address)")]
+    fn create_pool(
+        handle: &mut impl PrecompileHandle,
+        token_a: Address,
+        token_b: Address,
+    ) -> EvmResult {
+        let asset1 = Self::asset_kind(token_a).in_field("tokenA")?;
+        let asset2 = Self::asset_kind(token_b).in_field("tokenB")?;
+        let origin = Runtime::AddressMapping::into_account_id(handle.context().caller);
+
+        RuntimeHelper::<Runtime>::try_dispatch(
+            handle,
+            Some(origin).into(),
+            pallet_asset_conversion::Call::<Runtime>::create_pool {
+                asset1: Box::new(asset1),
+                asset2: Box::new(asset2),
+            },
+        )?;
+
+        Ok(())
+    }
+
+    #[precompile::public(
+        "addLiquidity(address,address,uint256,uint256,uint256,uint256,address,uint256)"
+    )]
+    fn add_liquidity(
+        handle: &mut impl PrecompileHandle,
+        token_a: Address,
+        token_b: Address,
+        amount_a_desired: U256,
+        amount_b_desired: U256,
+        amount_a_min: U256,
+        amount_b_min: U256,
+        to: Address,
+        deadline: U256,
+    ) -> EvmResult<(U256, U256, U256)> {
+        Self::ensure_deadline(handle, deadline)?;
+
+        let asset1 = Self::asset_kind(token_a).in_field("tokenA")?;
+        let asset2 = Self::asset_kind(token_b).in_field("tokenB")?;
+        let origin = Runtime::AddressMapping::into_account_id(handle.context().caller);
+        let mint_to = Runtime::AddressMapping::into_account_id(to.into());
+
+        // The pallet does not return the amounts it used nor the minted liquidity, so they are
+        // measured around the call.
+        let (reserve_a, reserve_b) = Self::reserves(handle, &asset1, &asset2)?;
+        let liquidity = Self::liquidity_of(handle, &asset1, &asset2, &mint_to)?;
+
+        RuntimeHelper::<Runtime>::try_dispatch(
+            handle,
+            Some(origin).into(),
+            pallet_asset_conversion::Call::<Runtime>::add_liquidity {
+                asset1: Box::new(asset1.clone()),
+                asset2: Box::new(asset2.clone()),
+                amount1_desired: Self::u256_to_amount(amount_a_desired)
+                    .in_field("amountADesired")?,
+                amount2_desired: Self::u256_to_amount(amount_b_desired)
+                    .in_field("amountBDesired")?,
+                amount1_min: Self::u256_to_amount(amount_a_min).in_field("amountAMin")?,
+                amount2_min: Self::u256_to_amount(amount_b_min).in_field("amountBMin")?,
+                mint_to: mint_to.clone(),
+            },
+        )?;
+
+        let (new_reserve_a, new_reserve_b) = Self::reserves(handle, &asset1, &asset2)?;
+        let new_liquidity = Self::liquidity_of(handle, &asset1, &asset2, &mint_to)?;
+
+        Ok((
+            new_reserve_a.saturating_sub(reserve_a),
+            new_reserve_b.saturating_sub(reserve_b),
+            new_liquidity.saturating_sub(liquidity),
+        ))
+    }
+
+    #[precompile::public(
+        "removeLiquidity(address,address,uint256,uint256,uint256,address,uint256)"
+    )]
+    fn remove_liquidity(
+        handle: &mut impl PrecompileHandle,
+        token_a: Address,
+        token_b: Address,
+        liquidity: U256,
+        amount_a_min: U256,
+        amount_b_min: U256,
+        to: Address,
+        deadline: U256,
+    ) -> EvmResult<(U256, U256)> {
+        Self::ensure_deadline(handle, deadline)?;
+
+        let asset1 = Self::asset_kind(token_a).in_field("tokenA")?;
+        let asset2 = Self::asset_kind(token_b).in_field("tokenB")?;
+        let origin = Runtime::AddressMapping::into_account_id(handle.context().caller);
+        let withdraw_to = Runtime::AddressMapping::into_account_id(to.into());
+
+        let (reserve_a, reserve_b) = Self::reserves(handle, &asset1, &asset2)?;
+
+        RuntimeHelper::<Runtime>::try_dispatch(
+            handle,
+            Some(origin).into(),
+            pallet_asset_conversion::Call::<Runtime>::remove_liquidity {
+                asset1: Box::new(asset1.clone()),
+                asset2: Box::new(asset2.clone()),
+                lp_token_burn: Self::u256_to_amount(liquidity).in_field("liquidity")?,
+                amount1_min_receive: Self::u256_to_amount(amount_a_min).in_field("amountAMin")?,
+                amount2_min_receive: Self::u256_to_amount(amount_b_min).in_field("amountBMin")?,
+                withdraw_to,
+            },
+        )?;
+
+        let (new_reserve_a, new_reserve_b) = Self::reserves(handle, &asset1, &asset2)?;
+
+        Ok((
+            reserve_a.saturating_sub(new_reserve_a),
+            reserve_b.saturating_sub(new_reserve_b),
+        ))
+    }
+
+    #[precompile::public("swapExactTokensForTokens(uint256,uint256,address[],address,uint256)")]
+    fn swap_exact_tokens_for_tokens(
+        handle: &mut impl PrecompileHandle,
+        amount_in: U256,
+        amount_out_min: U256,
+        path: BoundedVec<Address, GetPathLimit>,
+        to: Address,
+        deadline: U256,
+    ) -> EvmResult<Vec<U256>> {
+        Self::ensure_deadline(handle, deadline)?;
+
+        let path = Self::asset_path(path).in_field("path")?;
+        let amount_in = Self::u256_to_amount(amount_in).in_field("amountIn")?;
+        let amounts = Self::amounts_out(handle, amount_in, &path)?;
+        let origin = Runtime::AddressMapping::into_account_id(handle.context().caller);
+
+        RuntimeHelper::<Runtime>::try_dispatch(
+            handle,
+            Some(origin).into(),
+            pallet_asset_conversion::Call::<Runtime>::swap_exact_tokens_for_tokens {
+                path: path.into_iter().map(Box::new).collect(),
+                amount_in,
+                amount_out_min: Self::u256_to_amount(amount_out_min).in_field("amountOutMin")?,
+                send_to: Runtime::AddressMapping::into_account_id(to.into()),
+                keep_alive: true,
+            },
+        )?;
+
+        Ok(amounts.into_iter().map(Into::into).collect())
+    }
+
+    #[precompile::public("swapTokensForExactTokens(uint256,uint256,address[],address,uint256)")]
+    fn swap_tokens_for_exact_tokens(
+        handle: &mut impl PrecompileHandle,
+        amount_out: U256,
+        amount_in_max: U256,
+        path: BoundedVec<Address, GetPathLimit>,
+        to: Address,
+        deadline: U256,
+    ) -> EvmResult<Vec<U256>> {
+        Self::ensure_deadline(handle, deadline)?;
+
+        let path = Self::asset_path(path).in_field("path")?;
+        let amount_out = Self::u256_to_amount(amount_out).in_field("amountOut")?;
+        let amounts = Self::amounts_in(handle, amount_out, &path)?;
+        let origin = Runtime::AddressMapping::into_account_id(handle.context().caller);
+
+        RuntimeHelper::<Runtime>::try_dispatch(
+            handle,
+            Some(origin).into(),
+            pallet_asset_conversion::Call::<Runtime>::swap_tokens_for_exact_tokens {
+                path: path.into_iter().map(Box::new).collect(),
+                amount_out,
+                amount_in_max: Self::u256_to_amount(amount_in_max).in_field("amountInMax")?,
+                send_to: Runtime::AddressMapping::into_account_id(to.into()),
+                keep_alive: true,
+            },
+        )?;
+
+        Ok(amounts.into_iter().map(Into::into).collect())
+    }
+
+    #[precompile::public("getAmountsOut(uint256,address[])")]
+    #[precompile::view]
+    fn get_amounts_out(
+        handle: &mut impl PrecompileHandle,
+        amount_in: U256,
+        path: BoundedVec<Address, GetPathLimit>,
+    ) -> EvmResult<Vec<U256>> {
+        let path = Self::asset_path(path).in_field("path")?;
+        let amount_in = Self::u256_to_amount(amount_in).in_field("amountIn")?;
+
+        Ok(Self::amounts_out(handle, amount_in, &path)?
+            .into_iter()
+            .map(Into::into)
+            .collect())
+    }
+
+    #[precompile::public("getAmountsIn(uint256,address[])")]
+    #[precompile::view]
+    fn get_amounts_in(
+        handle: &mut impl PrecompileHandle,
+        amount_out: U256,
+        path: BoundedVec<Address, GetPathLimit>,
+    ) -> EvmResult<Vec<U256>> {
+        let path = Self::asset_path(path).in_field("path")?;
+        let amount_out = Self::u256_to_amount(amount_out).in_field("amountOut")?;
+
+        Ok(Self::amounts_in(handle, amount_out, &path)?
+            .into_iter()
+            .map(Into::into)
+            .collect())
+    }
+
+    #[precompile::public("getReserves(address,address)")]
+    #[precompile::view]
+    fn get_reserves(
+        handle: &mut impl PrecompileHandle,
+        token_a: Address,
+        token_b: Address,
+    ) -> EvmResult<(U256, U256)> {
+        let asset1 = Self::asset_kind(token_a).in_field("tokenA")?;
+        let asset2 = Self::asset_kind(token_b).in_field("tokenB")?;
+
+        Self::reserves(handle, &asset1, &asset2)
+    }
+
+    /// Amounts of every token of `path` when swapping exactly `amount_in` of the first one.
+    fn amounts_out(
+        handle: &mut impl PrecompileHandle,
+        amount_in: BalanceOf<Runtime>,
+        path: &[AssetKindOf<Runtime>],
+    ) -> EvmResult<Vec<BalanceOf<Runtime>>> {
+        let mut amounts = Vec::with_capacity(path.len());
+        amounts.push(amount_in);
+        let mut amount = amount_in;
+        for pair in path.windows(2) {
+            Self::record_pool_read(handle)?;
+            amount =
+                pallet_asset_conversion::Pallet::<Runtime>::quote_price_exact_tokens_for_tokens(
+                    pair[0].clone(),
+                    pair[1].clone(),
+                    amount,
+                    true,
+                )
+                .ok_or_else(|| revert("Insufficient liquidity"))?;
+            amounts.push(amount);
+        }
+
+        Ok(amounts)
+    }
+
+    /// Amounts of every token of `path` when swapping for exactly `amount_out` of the last one.
+    fn amounts_in(
+        handle: &mut impl PrecompileHandle,
+        amount_out: BalanceOf<Runtime>,
+        path: &[AssetKindOf<Runtime>],
+    ) -> EvmResult<Vec<BalanceOf<Runtime>>> {
+        let mut amounts = Vec::with_capacity(path.len());
+        amounts.push(amount_out);
+        let mut amount = amount_out;
+        for pair in path.windows(2).rev() {
+            Self::record_pool_read(handle)?;
+            amount =
+                pallet_asset_conversion::Pallet::<Runtime>::quote_price_tokens_for_exact_tokens(
+                    pair[0].clone(),
+                    pair[1].clone(),
+                    amount,
+                    true,
+                )
+                .ok_or_else(|| revert("Insufficient liquidity"))?;
+            amounts.push(amount);
+        }
+        amounts.reverse();
+
+        Ok(amounts)
+    }
+
+    /// Reserves of the pool of `asset1` and `asset2`, zero if the pool does not exist or is
+    /// empty.
+    fn reserves(
+        handle: &mut impl PrecompileHandle,
+        asset1: &AssetKindOf<Runtime>,
+        asset2: &AssetKindOf<Runtime>,
+    ) -> EvmResult<(U256, U256)> {
+        Self::record_pool_read(handle)?;
+
+        Ok(
+            pallet_asset_conversion::Pallet::<Runtime>::get_reserves(
+                asset1.clone(),
+                asset2.clone(),
+            )
+            .map_or((U256::zero(), U256::zero()), |(reserve1, reserve2)| {
+                (reserve1.into(), reserve2.into())
+            }),
+        )
+    }
+
+    /// Liquidity tokens of the pool of `asset1` and `asset2` held by `who`.
+    fn liquidity_of(
+        handle: &mut impl PrecompileHandle,
+        asset1: &AssetKindOf<Runtime>,
+        asset2: &AssetKindOf<Runtime>,
+        who: &Runtime::AccountId,
+    ) -> EvmResult<U256> {
+        // Storage item: Pools:
+        // Blake2_128Concat(16) + PoolId + PoolInfo(PoolAssetId)
+        handle.record_db_read::<Runtime>(
+            16 + Runtime::PoolId::max_encoded_len() + Runtime::PoolAssetId::max_encoded_len(),
+        )?;
+        // Storage item: pool assets Account:
+        // Blake2_128(16) + PoolAssetId + Blake2_128(16) + AccountId + AssetAccount(Balance + 3)
+        handle.record_db_read::<Runtime>(
+            35 + Runtime::PoolAssetId::max_encoded_len()
+                + Runtime::AccountId::max_encoded_len()
+                + BalanceOf::<Runtime>::max_encoded_len(),
+        )?;
+
+        let pool_id =
+            Runtime::PoolLocator::pool_id(asset1, asset2).map_err(|_| revert("Invalid pool"))?;
+        let pool = Pools::<Runtime>::get(pool_id).ok_or_else(|| revert("Pool not found"))?;
+
+        Ok(Runtime::PoolAssets::balance(pool.lp_token, who).into())
+    }
+
+    fn record_pool_read(handle: &mut impl PrecompileHandle) -> EvmResult {
+        // Reserves: the balances of the pool account in both assets, either a System Account
+        // (Blake2_128Concat(16) + AccountId + AccountInfo(4 * 4 + 4 * Balance)) or an Assets
+        // Account (Blake2_128(16) + AssetId + Blake2_128(16) + AccountId + AssetAccount(Balance
+        // + 3)), the former being the largest.
+        handle.record_db_read::<Runtime>(
+            32 + Runtime::AccountId::max_encoded_len()
+                + 4 * BalanceOf::<Runtime>::max_encoded_len(),
+        )?;
+        handle.record_db_read::<Runtime>(
+            32 + Runtime::AccountId::max_encoded_len()
+                + 4 * BalanceOf::<Runtime>::max_encoded_len(),
+        )
+    }
+
+    fn ensure_deadline(handle: &mut impl PrecompileHandle, deadline: U256) -> EvmResult {
+        // Now: Timestamp(8)
+        handle.record_db_read::<Runtime>(8)?;
+
+        // pallet_timestamp is in ms while Ethereum use second timestamps.
+        let now: U256 = pallet_timestamp::Pallet::<Runtime>::get().into();
+        ensure!(deadline >= now / 1000, revert("Expired"));
+
+        Ok(())
+    }
+
+    fn asset_path(path: BoundedVec<Address, GetPathLimit>) -> MayRevert<Vec<AssetKindOf<Runtime>>> {
+        let path: Vec<Address> = path.into();
+        if path.len() < 2 {
+            return Err(RevertReason::custom("Path must contain at least two tokens").into());
+        }
+
+        path.into_iter().map(Self::asset_kind).collect()
+    }
+
+    fn asset_kind(token: Address) -> MayRevert<AssetKindOf<Runtime>> {
+        Runtime::address_to_asset_kind(token.into())
+            .ok_or_else(|| RevertReason::custom("Unknown token").into())
+    }
+
+    fn u256_to_amount(value: U256) -> MayRevert<BalanceOf<Runtime>> {
+        value
+            .try_into()
+            .map_err(|_| RevertReason::value_is_too_large("balance type").into())
+    }
+}
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
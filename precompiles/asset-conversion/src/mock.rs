@@ -0,0 +1,242 @@
+//! Test runtime of the asset conversion router precompile.
+
+use super::*;
+use frame_support::{
+    construct_runtime, derive_impl,
+    instances::{Instance1, Instance2},
+    parameter_types,
+    traits::{
+        fungible::{NativeFromLeft, NativeOrWithId, UnionOf},
+        tokens::imbalance::ResolveAssetTo,
+        AsEnsureOriginWithArg, ConstU128, ConstU32, ConstU64,
+    },
+    weights::{constants::RocksDbWeight, Weight},
+    PalletId,
+};
+use frame_system::{EnsureRoot, EnsureSigned};
+use pallet_asset_conversion::{AccountIdConverter, Ascending, Chain, WithFirstAsset};
+use pallet_evm::{EnsureAddressNever, EnsureAddressRoot, IdentityAddressMapping};
+use polkadot_sdk::{pallet_assets, pallet_balances, sp_io};
+use precompile_utils::{
+    precompile_set::{AddressU64, PrecompileAt, PrecompileSetBuilder},
+    testing::MockAccount,
+};
+use sp_runtime::{traits::IdentityLookup, BuildStorage, Permill};
+
+pub type AccountId = MockAccount;
+pub type AssetId = u32;
+pub type Balance = u128;
+pub type AssetKind = NativeOrWithId<AssetId>;
+
+/// The first four bytes of the address of every asset precompile.
+pub const ASSET_PRECOMPILE_ADDRESS_PREFIX: &[u8] = &[255u8; 4];
+
+construct_runtime!(
+    pub enum Runtime {
+        System: frame_system,
+        Balances: pallet_balances,
+        Timestamp: pallet_timestamp,
+        Evm: pallet_evm,
+        Assets: pallet_assets::<Instance1>,
+        PoolAssets: pallet_assets::<Instance2>,
+        AssetConversion: pallet_asset_conversion,
+    }
+);
+
+#[derive_impl(frame_system::config_preludes::TestDefaultConfig)]
+impl frame_system::Config for Runtime {
+    type Block = frame_system::mocking::MockBlock<Runtime>;
+    type AccountId = AccountId;
+    type Lookup = IdentityLookup<AccountId>;
+    type AccountData = pallet_balances::AccountData<Balance>;
+    type DbWeight = RocksDbWeight;
+}
+
+#[derive_impl(pallet_balances::config_preludes::TestDefaultConfig)]
+impl pallet_balances::Config for Runtime {
+    type Balance = Balance;
+    type ExistentialDeposit = ConstU128<1>;
+    type AccountStore = System;
+}
+
+impl pallet_timestamp::Config for Runtime {
+    type Moment = u64;
+    type OnTimestampSet = ();
+    type MinimumPeriod = ConstU64<5>;
+    type WeightInfo = ();
+}
+
+pub type Precompiles<R> =
+    PrecompileSetBuilder<R, (PrecompileAt<AddressU64<1>, AssetConversionRouterPrecompile<R>>,)>;
+
+parameter_types! {
+    pub BlockGasLimit: U256 = U256::max_value();
+    pub const GasLimitPovSizeRatio: u64 = 16;
+    pub PrecompilesValue: Precompiles<Runtime> = Precompiles::new();
+    pub WeightPerGas: Weight = Weight::from_parts(20_000, 0);
+    pub SuicideQuickClearLimit: u32 = 0;
+}
+
+impl pallet_evm::Config for Runtime {
+    type FeeCalculator = ();
+    type GasWeightMapping = pallet_evm::FixedGasWeightMapping<Self>;
+    type WeightPerGas = WeightPerGas;
+    type BlockHashMapping = pallet_evm::SubstrateBlockHashMapping<Self>;
+    type CallOrigin = EnsureAddressRoot<AccountId>;
+    type WithdrawOrigin = EnsureAddressNever<AccountId>;
+    type AddressMapping = IdentityAddressMapping;
+    type Currency = Balances;
+    type RuntimeEvent = RuntimeEvent;
+    type PrecompilesType = Precompiles<Self>;
+    type PrecompilesValue = PrecompilesValue;
+    type ChainId = ();
+    type BlockGasLimit = BlockGasLimit;
+    type Runner = pallet_evm::runner::stack::Runner<Self>;
+    type OnChargeTransaction = ();
+    type OnCreate = ();
+    type FindAuthor = ();
+    type GasLimitPovSizeRatio = GasLimitPovSizeRatio;
+    type SuicideQuickClearLimit = SuicideQuickClearLimit;
+    type Timestamp = Timestamp;
+    type WeightInfo = ();
+}
+
+impl pallet_assets::Config<Instance1> for Runtime {
+    type RuntimeEvent = RuntimeEvent;
+    type Balance = Balance;
+    type AssetId = AssetId;
+    type AssetIdParameter = AssetId;
+    type Currency = Balances;
+    type CreateOrigin = AsEnsureOriginWithArg<EnsureSigned<AccountId>>;
+    type ForceOrigin = EnsureRoot<AccountId>;
+    type AssetDeposit = ConstU128<0>;
+    type AssetAccountDeposit = ConstU128<0>;
+    type MetadataDepositBase = ConstU128<0>;
+    type MetadataDepositPerByte = ConstU128<0>;
+    type ApprovalDeposit = ConstU128<0>;
+    type StringLimit = ConstU32<50>;
+    type Freezer = ();
+    type Extra = ();
+    type CallbackHandle = ();
+    type WeightInfo = ();
+    type RemoveItemsLimit = ConstU32<1000>;
+}
+
+impl pallet_assets::Config<Instance2> for Runtime {
+    type RuntimeEvent = RuntimeEvent;
+    type Balance = Balance;
+    type AssetId = AssetId;
+    type AssetIdParameter = AssetId;
+    type Currency = Balances;
+    type CreateOrigin = AsEnsureOriginWithArg<EnsureSigned<AccountId>>;
+    type ForceOrigin = EnsureRoot<AccountId>;
+    type AssetDeposit = ConstU128<0>;
+    type AssetAccountDeposit = ConstU128<0>;
+    type MetadataDepositBase = ConstU128<0>;
+    type MetadataDepositPerByte = ConstU128<0>;
+    type ApprovalDeposit = ConstU128<0>;
+    type StringLimit = ConstU32<50>;
+    type Freezer = ();
+    type Extra = ();
+    type CallbackHandle = ();
+    type WeightInfo = ();
+    type RemoveItemsLimit = ConstU32<1000>;
+}
+
+parameter_types! {
+    pub const AssetConversionPalletId: PalletId = PalletId(*b"py/ascon");
+    pub const Native: AssetKind = NativeOrWithId::Native;
+    pub SetupFeeReceiver: AccountId = MockAccount::from_u64(0xfe);
+    pub const LiquidityWithdrawalFee: Permill = Permill::zero();
+}
+
+impl pallet_asset_conversion::Config for Runtime {
+    type RuntimeEvent = RuntimeEvent;
+    type Balance = Balance;
+    type HigherPrecisionBalance = U256;
+    type AssetKind = AssetKind;
+    type Assets = UnionOf<Balances, Assets, NativeFromLeft, AssetKind, AccountId>;
+    type PoolId = (AssetKind, AssetKind);
+    type PoolLocator = Chain<
+        WithFirstAsset<
+            Native,
+            AccountId,
+            AssetKind,
+            AccountIdConverter<AssetConversionPalletId, Self::PoolId>,
+        >,
+        Ascending<AccountId, AssetKind, AccountIdConverter<AssetConversionPalletId, Self::PoolId>>,
+    >;
+    type PoolAssetId = AssetId;
+    type PoolAssets = PoolAssets;
+    type PoolSetupFee = ConstU128<10>;
+    type PoolSetupFeeAsset = Native;
+    type PoolSetupFeeTarget = ResolveAssetTo<SetupFeeReceiver, Self::Assets>;
+    type PalletId = AssetConversionPalletId;
+    type LPFee = ConstU32<3>;
+    type LiquidityWithdrawalFee = LiquidityWithdrawalFee;
+    type WeightInfo = ();
+    type MaxSwapPathLength = ConstU32<3>;
+    type MintMinLiquidity = ConstU128<100>;
+}
+
+/// Address of the ERC-20 precompile of the native token.
+pub fn native_token() -> H160 {
+    H160::from_low_u64_be(2048)
+}
+
+/// Address of the ERC-20 precompile of `asset_id`.
+pub fn asset_token(asset_id: AssetId) -> H160 {
+    let mut address = [0u8; 20];
+    address[..4].copy_from_slice(ASSET_PRECOMPILE_ADDRESS_PREFIX);
+    address[16..].copy_from_slice(&asset_id.to_be_bytes());
+    H160(address)
+}
+
+impl AddressToAssetKind<AssetKind> for Runtime {
+    fn address_to_asset_kind(address: H160) -> Option<AssetKind> {
+        if address == native_token() {
+            return Some(NativeOrWithId::Native);
+        }
+
+        let (prefix, id) = address.as_bytes().split_at(16);
+        if prefix.starts_with(ASSET_PRECOMPILE_ADDRESS_PREFIX)
+            && prefix[ASSET_PRECOMPILE_ADDRESS_PREFIX.len()..]
+                .iter()
+                .all(|b| *b == 0)
+        {
+            let mut buf = [0u8; 4];
+            buf.copy_from_slice(id);
+            Some(NativeOrWithId::WithId(AssetId::from_be_bytes(buf)))
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct ExtBuilder {
+    balances: Vec<(AccountId, Balance)>,
+}
+
+impl ExtBuilder {
+    pub(crate) fn with_balances(mut self, balances: Vec<(AccountId, Balance)>) -> Self {
+        self.balances = balances;
+        self
+    }
+
+    pub(crate) fn build(self) -> sp_io::TestExternalities {
+        let mut t = frame_system::GenesisConfig::<Runtime>::default()
+            .build_storage()
+            .expect("Frame system builds valid default genesis config");
+
+        pallet_balances::GenesisConfig::<Runtime> {
+            balances: self.balances,
+        }
+        .assimilate_storage(&mut t)
+        .expect("Pallet balances storage can be assimilated");
+
+        let mut ext = sp_io::TestExternalities::new(t);
+        ext.execute_with(|| System::set_block_number(1));
+        ext
+    }
+}
@@ -0,0 +1,504 @@
+use crate::{mock::*, *};
+use frame_support::{assert_ok, traits::fungible::NativeOrWithId, weights::Weight};
+use pallet_asset_conversion::WeightInfo;
+use pallet_evm::GasWeightMapping;
+use precompile_utils::testing::*;
+
+type PCall = AssetConversionRouterPrecompileCall<Runtime>;
+type ConversionWeights = <Runtime as pallet_asset_conversion::Config>::WeightInfo;
+
+fn precompiles() -> Precompiles<Runtime> {
+    PrecompilesValue::get()
+}
+
+fn balances() -> ExtBuilder {
+    ExtBuilder::default().with_balances(vec![(Alice.into(), 1_000_000), (Bob.into(), 1_000)])
+}
+
+fn db_read() -> u64 {
+    RuntimeHelper::<Runtime>::db_read_gas_cost()
+}
+
+fn weight_to_gas(weight: Weight) -> u64 {
+    <Runtime as pallet_evm::Config>::GasWeightMapping::weight_to_gas(weight)
+}
+
+fn dispatch_error(output: &[u8], error: &str) -> bool {
+    core::str::from_utf8(output).map_or(false, |reason| reason.contains(error))
+}
+
+fn path(tokens: &[H160]) -> BoundedVec<Address, GetPathLimit> {
+    tokens
+        .iter()
+        .copied()
+        .map(Address)
+        .collect::<Vec<_>>()
+        .into()
+}
+
+/// Creates the sufficient asset `asset_id` and mints `amount` to Alice.
+fn create_asset(asset_id: AssetId, amount: Balance) {
+    assert_ok!(Assets::force_create(
+        RuntimeOrigin::root(),
+        asset_id,
+        Alice.into(),
+        true,
+        1
+    ));
+    assert_ok!(Assets::mint(
+        RuntimeOrigin::signed(Alice.into()),
+        asset_id,
+        Alice.into(),
+        amount
+    ));
+}
+
+/// Creates the pool of the native token and the asset 1, holding 10_000 native tokens and
+/// 40_000 of the asset provided by Alice.
+fn create_pool() {
+    create_asset(1, 1_000_000);
+    assert_ok!(AssetConversion::create_pool(
+        RuntimeOrigin::signed(Alice.into()),
+        Box::new(NativeOrWithId::Native),
+        Box::new(NativeOrWithId::WithId(1)),
+    ));
+    assert_ok!(AssetConversion::add_liquidity(
+        RuntimeOrigin::signed(Alice.into()),
+        Box::new(NativeOrWithId::Native),
+        Box::new(NativeOrWithId::WithId(1)),
+        10_000,
+        40_000,
+        1,
+        1,
+        Alice.into(),
+    ));
+}
+
+fn reserves() -> (Balance, Balance) {
+    AssetConversion::get_reserves(NativeOrWithId::Native, NativeOrWithId::WithId(1))
+        .expect("the pool exists")
+}
+
+fn quote_out(amount_in: Balance) -> Balance {
+    AssetConversion::quote_price_exact_tokens_for_tokens(
+        NativeOrWithId::Native,
+        NativeOrWithId::WithId(1),
+        amount_in,
+        true,
+    )
+    .expect("the pool has liquidity")
+}
+
+fn quote_in(amount_out: Balance) -> Balance {
+    AssetConversion::quote_price_tokens_for_exact_tokens(
+        NativeOrWithId::Native,
+        NativeOrWithId::WithId(1),
+        amount_out,
+        true,
+    )
+    .expect("the pool has liquidity")
+}
+
+#[test]
+fn selectors() {
+    assert!(PCall::create_pool_selectors().contains(&0xe3433615));
+    assert!(PCall::add_liquidity_selectors().contains(&0xe8e33700));
+    assert!(PCall::remove_liquidity_selectors().contains(&0xbaa2abde));
+    assert!(PCall::swap_exact_tokens_for_tokens_selectors().contains(&0x38ed1739));
+    assert!(PCall::swap_tokens_for_exact_tokens_selectors().contains(&0x8803dbee));
+    assert!(PCall::get_amounts_out_selectors().contains(&0xd06ca61f));
+    assert!(PCall::get_amounts_in_selectors().contains(&0x1f00ca74));
+    assert!(PCall::get_reserves_selectors().contains(&0xd52bb6f4));
+}
+
+#[test]
+fn create_pool_charges_the_setup_fee() {
+    balances().build().execute_with(|| {
+        create_asset(1, 1_000_000);
+
+        precompiles()
+            .prepare_test(
+                Alice,
+                Precompile1,
+                PCall::create_pool {
+                    token_a: Address(native_token()),
+                    token_b: Address(asset_token(1)),
+                },
+            )
+            .expect_cost(weight_to_gas(ConversionWeights::create_pool()))
+            .expect_no_logs()
+            .execute_returns(());
+
+        assert!(pallet_asset_conversion::Pools::<Runtime>::contains_key((
+            NativeOrWithId::Native,
+            NativeOrWithId::WithId(1)
+        )));
+        assert_eq!(
+            Balances::free_balance(AccountId::from(Alice)),
+            1_000_000 - 10
+        );
+        assert_eq!(Balances::free_balance(SetupFeeReceiver::get()), 10);
+
+        precompiles()
+            .prepare_test(
+                Alice,
+                Precompile1,
+                PCall::create_pool {
+                    token_a: Address(native_token()),
+                    token_b: Address(asset_token(1)),
+                },
+            )
+            .execute_reverts(|output| dispatch_error(output, "PoolExists"));
+    });
+}
+
+#[test]
+fn create_pool_of_an_unknown_token_reverts() {
+    balances().build().execute_with(|| {
+        precompiles()
+            .prepare_test(
+                Alice,
+                Precompile1,
+                PCall::create_pool {
+                    token_a: Address(native_token()),
+                    token_b: Address(Bob.into()),
+                },
+            )
+            .execute_reverts(|output| output == b"tokenB: Unknown token");
+    });
+}
+
+#[test]
+fn add_liquidity_returns_the_used_amounts_and_the_liquidity() {
+    balances().build().execute_with(|| {
+        create_asset(1, 1_000_000);
+        assert_ok!(AssetConversion::create_pool(
+            RuntimeOrigin::signed(Alice.into()),
+            Box::new(NativeOrWithId::Native),
+            Box::new(NativeOrWithId::WithId(1)),
+        ));
+
+        // The first provider gets sqrt(10_000 * 40_000) minus the minimum liquidity.
+        precompiles()
+            .prepare_test(
+                Alice,
+                Precompile1,
+                PCall::add_liquidity {
+                    token_a: Address(native_token()),
+                    token_b: Address(asset_token(1)),
+                    amount_a_desired: 10_000.into(),
+                    amount_b_desired: 40_000.into(),
+                    amount_a_min: 1.into(),
+                    amount_b_min: 1.into(),
+                    to: Address(Bob.into()),
+                    deadline: U256::MAX,
+                },
+            )
+            .expect_no_logs()
+            .execute_returns((U256::from(10_000), U256::from(40_000), U256::from(19_900)));
+
+        assert_eq!(reserves(), (10_000, 40_000));
+        assert_eq!(PoolAssets::balance(0, AccountId::from(Bob)), 19_900);
+
+        // Later providers are held to the price of the pool.
+        precompiles()
+            .prepare_test(
+                Alice,
+                Precompile1,
+                PCall::add_liquidity {
+                    token_a: Address(native_token()),
+                    token_b: Address(asset_token(1)),
+                    amount_a_desired: 1_000.into(),
+                    amount_b_desired: 8_000.into(),
+                    amount_a_min: 1.into(),
+                    amount_b_min: 1.into(),
+                    to: Address(Alice.into()),
+                    deadline: U256::MAX,
+                },
+            )
+            .execute_returns((U256::from(1_000), U256::from(4_000), U256::from(2_000)));
+
+        assert_eq!(reserves(), (11_000, 44_000));
+    });
+}
+
+#[test]
+fn add_liquidity_reverts() {
+    balances().build().execute_with(|| {
+        create_pool();
+
+        let add_liquidity = |amount_b_min: U256, deadline: U256| PCall::add_liquidity {
+            token_a: Address(native_token()),
+            token_b: Address(asset_token(1)),
+            amount_a_desired: 1_000.into(),
+            amount_b_desired: 4_000.into(),
+            amount_a_min: 1.into(),
+            amount_b_min,
+            to: Address(Alice.into()),
+            deadline,
+        };
+
+        precompiles()
+            .prepare_test(Alice, Precompile1, add_liquidity(1.into(), U256::MAX))
+            .execute_returns((U256::from(1_000), U256::from(4_000), U256::from(2_000)));
+        precompiles()
+            .prepare_test(Alice, Precompile1, add_liquidity(U256::MAX, U256::MAX))
+            .execute_reverts(|output| output == b"amountBMin: Value is too large for balance type");
+        precompiles()
+            .prepare_test(Alice, Precompile1, add_liquidity(4_001.into(), U256::MAX))
+            .execute_reverts(|output| dispatch_error(output, "AssetTwoDepositDidNotMeetMinimum"));
+
+        // `pallet_timestamp` is in milliseconds, deadlines are in seconds.
+        Timestamp::set_timestamp(10_000);
+        precompiles()
+            .prepare_test(Alice, Precompile1, add_liquidity(1.into(), 9.into()))
+            .execute_reverts(|output| output == b"Expired");
+        precompiles()
+            .prepare_test(Alice, Precompile1, add_liquidity(1.into(), 10.into()))
+            .execute_returns((U256::from(1_000), U256::from(4_000), U256::from(2_000)));
+
+        assert_eq!(reserves(), (12_000, 48_000));
+    });
+}
+
+#[test]
+fn remove_liquidity_returns_the_withdrawn_amounts() {
+    balances().build().execute_with(|| {
+        create_pool();
+
+        precompiles()
+            .prepare_test(
+                Alice,
+                Precompile1,
+                PCall::remove_liquidity {
+                    token_a: Address(native_token()),
+                    token_b: Address(asset_token(1)),
+                    liquidity: 2_000.into(),
+                    amount_a_min: 1.into(),
+                    amount_b_min: 1.into(),
+                    to: Address(Bob.into()),
+                    deadline: U256::MAX,
+                },
+            )
+            .expect_no_logs()
+            .execute_returns((U256::from(1_000), U256::from(4_000)));
+
+        assert_eq!(reserves(), (9_000, 36_000));
+        assert_eq!(
+            PoolAssets::balance(0, AccountId::from(Alice)),
+            19_900 - 2_000
+        );
+        assert_eq!(Balances::free_balance(AccountId::from(Bob)), 1_000 + 1_000);
+        assert_eq!(Assets::balance(1, AccountId::from(Bob)), 4_000);
+
+        precompiles()
+            .prepare_test(
+                Bob,
+                Precompile1,
+                PCall::remove_liquidity {
+                    token_a: Address(native_token()),
+                    token_b: Address(asset_token(1)),
+                    liquidity: 2_000.into(),
+                    amount_a_min: 1.into(),
+                    amount_b_min: 1.into(),
+                    to: Address(Bob.into()),
+                    deadline: U256::MAX,
+                },
+            )
+            .execute_reverts(|output| dispatch_error(output, "FundsUnavailable"));
+    });
+}
+
+#[test]
+fn get_reserves() {
+    balances().build().execute_with(|| {
+        precompiles()
+            .prepare_test(
+                Alice,
+                Precompile1,
+                PCall::get_reserves {
+                    token_a: Address(native_token()),
+                    token_b: Address(asset_token(1)),
+                },
+            )
+            .expect_cost(2 * db_read())
+            .expect_no_logs()
+            .execute_returns((U256::zero(), U256::zero()));
+
+        create_pool();
+
+        precompiles()
+            .prepare_test(
+                Alice,
+                Precompile1,
+                PCall::get_reserves {
+                    token_a: Address(native_token()),
+                    token_b: Address(asset_token(1)),
+                },
+            )
+            .execute_returns((U256::from(10_000), U256::from(40_000)));
+    });
+}
+
+#[test]
+fn get_amounts_out_and_in() {
+    balances().build().execute_with(|| {
+        create_pool();
+
+        precompiles()
+            .prepare_test(
+                Alice,
+                Precompile1,
+                PCall::get_amounts_out {
+                    amount_in: 1_000.into(),
+                    path: path(&[native_token(), asset_token(1)]),
+                },
+            )
+            .expect_cost(2 * db_read())
+            .expect_no_logs()
+            .execute_returns(vec![U256::from(1_000), quote_out(1_000).into()]);
+
+        precompiles()
+            .prepare_test(
+                Alice,
+                Precompile1,
+                PCall::get_amounts_in {
+                    amount_out: 1_000.into(),
+                    path: path(&[native_token(), asset_token(1)]),
+                },
+            )
+            .expect_cost(2 * db_read())
+            .expect_no_logs()
+            .execute_returns(vec![quote_in(1_000).into(), U256::from(1_000)]);
+    });
+}
+
+#[test]
+fn get_amounts_reverts() {
+    balances().build().execute_with(|| {
+        create_asset(2, 1_000);
+
+        let get_amounts_out = |tokens: &[H160]| PCall::get_amounts_out {
+            amount_in: 1_000.into(),
+            path: path(tokens),
+        };
+
+        precompiles()
+            .prepare_test(Alice, Precompile1, get_amounts_out(&[native_token()]))
+            .execute_reverts(|output| output == b"path: Path must contain at least two tokens");
+        precompiles()
+            .prepare_test(
+                Alice,
+                Precompile1,
+                get_amounts_out(&[native_token(), Bob.into()]),
+            )
+            .execute_reverts(|output| output == b"path: Unknown token");
+        precompiles()
+            .prepare_test(
+                Alice,
+                Precompile1,
+                get_amounts_out(&[native_token(), asset_token(2)]),
+            )
+            .execute_reverts(|output| output == b"Insufficient liquidity");
+    });
+}
+
+#[test]
+fn swap_exact_tokens_for_tokens() {
+    balances().build().execute_with(|| {
+        create_pool();
+        let amount_out = quote_out(1_000);
+
+        precompiles()
+            .prepare_test(
+                Alice,
+                Precompile1,
+                PCall::swap_exact_tokens_for_tokens {
+                    amount_in: 1_000.into(),
+                    amount_out_min: amount_out.into(),
+                    path: path(&[native_token(), asset_token(1)]),
+                    to: Address(Bob.into()),
+                    deadline: U256::MAX,
+                },
+            )
+            .expect_cost(
+                3 * db_read() + weight_to_gas(ConversionWeights::swap_exact_tokens_for_tokens(2)),
+            )
+            .expect_no_logs()
+            .execute_returns(vec![U256::from(1_000), amount_out.into()]);
+
+        assert_eq!(reserves(), (11_000, 40_000 - amount_out));
+        assert_eq!(Assets::balance(1, AccountId::from(Bob)), amount_out);
+    });
+}
+
+#[test]
+fn swap_exact_tokens_for_tokens_below_the_minimum_reverts() {
+    balances().build().execute_with(|| {
+        create_pool();
+        let amount_out = quote_out(1_000);
+
+        precompiles()
+            .prepare_test(
+                Alice,
+                Precompile1,
+                PCall::swap_exact_tokens_for_tokens {
+                    amount_in: 1_000.into(),
+                    amount_out_min: (amount_out + 1).into(),
+                    path: path(&[native_token(), asset_token(1)]),
+                    to: Address(Bob.into()),
+                    deadline: U256::MAX,
+                },
+            )
+            .execute_reverts(|output| {
+                dispatch_error(output, "ProvidedMinimumNotSufficientForSwap")
+            });
+
+        assert_eq!(reserves(), (10_000, 40_000));
+    });
+}
+
+#[test]
+fn swap_tokens_for_exact_tokens() {
+    balances().build().execute_with(|| {
+        create_pool();
+        let amount_in = quote_in(1_000);
+
+        precompiles()
+            .prepare_test(
+                Alice,
+                Precompile1,
+                PCall::swap_tokens_for_exact_tokens {
+                    amount_out: 1_000.into(),
+                    amount_in_max: amount_in.into(),
+                    path: path(&[native_token(), asset_token(1)]),
+                    to: Address(Bob.into()),
+                    deadline: U256::MAX,
+                },
+            )
+            .expect_cost(
+                3 * db_read() + weight_to_gas(ConversionWeights::swap_tokens_for_exact_tokens(2)),
+            )
+            .expect_no_logs()
+            .execute_returns(vec![amount_in.into(), U256::from(1_000)]);
+
+        assert_eq!(reserves(), (10_000 + amount_in, 39_000));
+        assert_eq!(Assets::balance(1, AccountId::from(Bob)), 1_000);
+
+        precompiles()
+            .prepare_test(
+                Alice,
+                Precompile1,
+                PCall::swap_tokens_for_exact_tokens {
+                    amount_out: 1_000.into(),
+                    amount_in_max: 1.into(),
+                    path: path(&[native_token(), asset_token(1)]),
+                    to: Address(Bob.into()),
+                    deadline: U256::MAX,
+                },
+            )
+            .execute_reverts(|output| {
+                dispatch_error(output, "ProvidedMaximumNotSufficientForSwap")
+            });
+    });
+}
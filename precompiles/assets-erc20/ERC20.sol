@@ -0,0 +1,63 @@
+// SPDX-License-Identifier: GPL-3.0-only
+pragma solidity >=0.8.3;
+
+/// @title ERC20 interface of a `pallet_assets` (Instance1) token.
+/// @dev Every asset id `id` is reachable at the address
+/// `0xFFFFFFFF` ++ `0x000000000000000000000000` ++ `id` (big-endian u32).
+interface IERC20 {
+    /// @dev Returns the name of the token.
+    /// @custom:selector 06fdde03
+    function name() external view returns (string memory);
+
+    /// @dev Returns the symbol of the token.
+    /// @custom:selector 95d89b41
+    function symbol() external view returns (string memory);
+
+    /// @dev Returns the decimals places of the token.
+    /// @custom:selector 313ce567
+    function decimals() external view returns (uint8);
+
+    /// @dev Total number of tokens in existence
+    /// @custom:selector 18160ddd
+    function totalSupply() external view returns (uint256);
+
+    /// @dev Gets the balance of the specified address.
+    /// @custom:selector 70a08231
+    function balanceOf(address owner) external view returns (uint256);
+
+    /// @dev Function to check the amount of tokens that an owner allowed to a spender.
+    /// @custom:selector dd62ed3e
+    function allowance(address owner, address spender)
+        external
+        view
+        returns (uint256);
+
+    /// @dev Transfer token for a specified address
+    /// @custom:selector a9059cbb
+    function transfer(address to, uint256 value) external returns (bool);
+
+    /// @dev Approve the passed address to spend the specified amount of tokens on behalf
+    /// of msg.sender.
+    /// @custom:selector 095ea7b3
+    function approve(address spender, uint256 value) external returns (bool);
+
+    /// @dev Transfer tokens from one address to another
+    /// @custom:selector 23b872dd
+    function transferFrom(
+        address from,
+        address to,
+        uint256 value
+    ) external returns (bool);
+
+    /// @dev Event emited when a transfer has been performed.
+    /// @custom:selector ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef
+    event Transfer(address indexed from, address indexed to, uint256 value);
+
+    /// @dev Event emited when an approval has been registered.
+    /// @custom:selector 8c5be1e5ebec7d5bd14f71427d1e84f3dd0314c0f7b2291e5b200ac8c7c3b925
+    event Approval(
+        address indexed owner,
+        address indexed spender,
+        uint256 value
+    );
+}
@@ -0,0 +1,483 @@
+// Copyright 2019-2022 PureStake Inc.
+// This file is part of SCS Chain. It is derived from Moonbeam's precompiles
+// (https://github.com/moonbeam-foundation/moonbeam).
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Precompile exposing the fungible tokens of a `pallet_assets` instance as ERC-20 contracts.
+//!
+//! Every asset id is mapped to a deterministic address by the runtime through
+//! [`AddressToAssetId`]. Calls to such an address are answered as if an ERC-20 contract
+//! was deployed there, and the usual `Transfer`/`Approval` logs are emitted.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::MaxEncodedLen;
+use core::marker::PhantomData;
+use fp_evm::PrecompileHandle;
+use frame_support::{
+    dispatch::{GetDispatchInfo, PostDispatchInfo},
+    migrations::{SteppedMigration, SteppedMigrationError},
+    traits::{
+        fungibles::{
+            approvals::Inspect as ApprovalInspect, metadata::Inspect as MetadataInspect, Inspect,
+        },
+        tokens::AssetsCallback,
+        Get, OriginTrait,
+    },
+    weights::WeightMeter,
+};
+use pallet_evm::AddressMapping;
+use polkadot_sdk::{frame_support, frame_system, pallet_assets, sp_runtime};
+use precompile_utils::prelude::*;
+use sp_core::{H160, U256};
+use sp_runtime::traits::{Bounded, Dispatchable, StaticLookup};
+
+/// Solidity selector of the Transfer log, which is the Keccak of the Log signature.
+pub const SELECTOR_LOG_TRANSFER: [u8; 32] = keccak256!("Transfer(address,address,uint256)");
+
+/// Solidity selector of the Approval log, which is the Keccak of the Log signature.
+pub const SELECTOR_LOG_APPROVAL: [u8; 32] = keccak256!("Approval(address,address,uint256)");
+
+/// Dummy code inserted at the address of every asset, so `extcodesize` is not zero and
+/// Solidity high-level calls accept the address as a contract (`PUSH1 0 PUSH1 0 REVERT`).
+pub const DUMMY_CODE: [u8; 5] = [0x60, 0x00, 0x60, 0x00, 0xfd];
+
+/// Alias for the Balance type for the provided Runtime and Instance.
+pub type BalanceOf<Runtime, Instance = ()> = <Runtime as pallet_assets::Config<Instance>>::Balance;
+
+/// Alias for the Asset Id type for the provided Runtime and Instance.
+pub type AssetIdOf<Runtime, Instance = ()> = <Runtime as pallet_assets::Config<Instance>>::AssetId;
+
+/// Maps asset ids to the address of their ERC-20 precompile and back.
+pub trait AddressToAssetId<AssetId> {
+    /// Returns the asset id answering at `address`, if `address` is an asset precompile address.
+    fn address_to_asset_id(address: H160) -> Option<AssetId>;
+
+    /// Returns the precompile address of `asset_id`.
+    fn asset_id_to_address(asset_id: AssetId) -> H160;
+}
+
+/// The ERC-20 precompile set. One precompile answers at the address of every existing asset.
+pub struct Erc20AssetsPrecompileSet<Runtime, Instance: 'static = ()>(
+    PhantomData<(Runtime, Instance)>,
+);
+
+impl<Runtime, Instance> Default for Erc20AssetsPrecompileSet<Runtime, Instance> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<Runtime, Instance> Erc20AssetsPrecompileSet<Runtime, Instance> {
+    pub fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+#[precompile_utils::precompile]
+#[precompile::precompile_set]
+impl<Runtime, Instance> Erc20AssetsPrecompileSet<Runtime, Instance>
+where
+    Instance: 'static,
+    Runtime: pallet_assets::Config<Instance> + pallet_evm::Config + frame_system::Config,
+    Runtime: AddressToAssetId<AssetIdOf<Runtime, Instance>>,
+    Runtime::RuntimeCall: Dispatchable<PostInfo = PostDispatchInfo> + GetDispatchInfo,
+    Runtime::RuntimeCall: From<pallet_assets::Call<Runtime, Instance>>,
+    <Runtime::RuntimeCall as Dispatchable>::RuntimeOrigin: From<Option<Runtime::AccountId>>,
+    <Runtime::RuntimeCall as Dispatchable>::RuntimeOrigin: OriginTrait,
+    BalanceOf<Runtime, Instance>: TryFrom<U256> + Into<U256> + solidity::Codec,
+{
+    /// PrecompileSet discriminant. Allows to know if the address maps to an existing asset,
+    /// and if this is the case which one.
+    #[precompile::discriminant]
+    fn discriminant(address: H160, gas: u64) -> DiscriminantResult<AssetIdOf<Runtime, Instance>> {
+        let extra_cost = RuntimeHelper::<Runtime>::db_read_gas_cost();
+        if gas < extra_cost {
+            return DiscriminantResult::OutOfGas;
+        }
+
+        let asset_id = match Runtime::address_to_asset_id(address) {
+            Some(asset_id) => asset_id,
+            None => return DiscriminantResult::None(extra_cost),
+        };
+
+        if pallet_assets::Pallet::<Runtime, Instance>::maybe_total_supply(asset_id.clone())
+            .is_some()
+        {
+            DiscriminantResult::Some(asset_id, extra_cost)
+        } else {
+            DiscriminantResult::None(extra_cost)
+        }
+    }
+
+    #[precompile::public("totalSupply()")]
+    #[precompile::view]
+    fn total_supply(
+        asset_id: AssetIdOf<Runtime, Instance>,
+        handle: &mut impl PrecompileHandle,
+    ) -> EvmResult<U256> {
+        // Storage item: Asset:
+        // Blake2_128(16) + AssetId(16) + AssetDetails((4 * AccountId(20)) + (3 * Balance(16)) + 15)
+        handle.record_db_read::<Runtime>(223)?;
+
+        Ok(pallet_assets::Pallet::<Runtime, Instance>::total_issuance(asset_id).into())
+    }
+
+    #[precompile::public("balanceOf(address)")]
+    #[precompile::view]
+    fn balance_of(
+        asset_id: AssetIdOf<Runtime, Instance>,
+        handle: &mut impl PrecompileHandle,
+        who: Address,
+    ) -> EvmResult<U256> {
+        // Storage item: Account:
+        // Blake2_128(16) + AssetId(16) + Blake2_128(16) + AccountId(20) + AssetAccount(19 + Extra)
+        handle.record_db_read::<Runtime>(
+            87 + <Runtime as pallet_assets::Config<Instance>>::Extra::max_encoded_len(),
+        )?;
+
+        let who = Runtime::AddressMapping::into_account_id(who.into());
+
+        Ok(pallet_assets::Pallet::<Runtime, Instance>::balance(asset_id, &who).into())
+    }
+
+    #[precompile::public("allowance(address,address)")]
+    #[precompile::view]
+    fn allowance(
+        asset_id: AssetIdOf<Runtime, Instance>,
+        handle: &mut impl PrecompileHandle,
+        owner: Address,
+        spender: Address,
+    ) -> EvmResult<U256> {
+        // Storage item: Approvals:
+        // Blake2_128(16) + AssetId(16) + (2 * Blake2_128(16) + AccountId(20)) + Approval(32)
+        handle.record_db_read::<Runtime>(148)?;
+
+        let owner = Runtime::AddressMapping::into_account_id(owner.into());
+        let spender = Runtime::AddressMapping::into_account_id(spender.into());
+
+        Ok(
+            pallet_assets::Pallet::<Runtime, Instance>::allowance(asset_id, &owner, &spender)
+                .into(),
+        )
+    }
+
+    #[precompile::public("approve(address,uint256)")]
+    fn approve(
+        asset_id: AssetIdOf<Runtime, Instance>,
+        handle: &mut impl PrecompileHandle,
+        spender: Address,
+        value: U256,
+    ) -> EvmResult<bool> {
+        handle.record_log_costs_manual(3, 32)?;
+
+        let owner = handle.context().caller;
+        let spender: H160 = spender.into();
+
+        Self::approve_inner(asset_id, handle, owner, spender, value)?;
+
+        log3(
+            handle.context().address,
+            SELECTOR_LOG_APPROVAL,
+            owner,
+            spender,
+            solidity::encode_event_data(value),
+        )
+        .record(handle)?;
+
+        Ok(true)
+    }
+
+    fn approve_inner(
+        asset_id: AssetIdOf<Runtime, Instance>,
+        handle: &mut impl PrecompileHandle,
+        owner: H160,
+        spender: H160,
+        value: U256,
+    ) -> EvmResult {
+        let owner = Runtime::AddressMapping::into_account_id(owner);
+        let spender = Runtime::AddressMapping::into_account_id(spender);
+        // Amount saturate if too high.
+        let amount: BalanceOf<Runtime, Instance> =
+            value.try_into().unwrap_or_else(|_| Bounded::max_value());
+
+        // Storage item: Approvals:
+        // Blake2_128(16) + AssetId(16) + (2 * Blake2_128(16) + AccountId(20)) + Approval(32)
+        handle.record_db_read::<Runtime>(148)?;
+
+        // `approve_transfer` adds to an existing approval, so the previous one is cleared
+        // first to get the ERC-20 "set" semantics.
+        if pallet_assets::Pallet::<Runtime, Instance>::allowance(asset_id.clone(), &owner, &spender)
+            != 0u32.into()
+        {
+            RuntimeHelper::<Runtime>::try_dispatch(
+                handle,
+                Some(owner.clone()).into(),
+                pallet_assets::Call::<Runtime, Instance>::cancel_approval {
+                    id: asset_id.clone().into(),
+                    delegate: Runtime::Lookup::unlookup(spender.clone()),
+                },
+            )?;
+        }
+
+        RuntimeHelper::<Runtime>::try_dispatch(
+            handle,
+            Some(owner).into(),
+            pallet_assets::Call::<Runtime, Instance>::approve_transfer {
+                id: asset_id.into(),
+                delegate: Runtime::Lookup::unlookup(spender),
+                amount,
+            },
+        )?;
+
+        Ok(())
+    }
+
+    #[precompile::public("transfer(address,uint256)")]
+    fn transfer(
+        asset_id: AssetIdOf<Runtime, Instance>,
+        handle: &mut impl PrecompileHandle,
+        to: Address,
+        value: U256,
+    ) -> EvmResult<bool> {
+        handle.record_log_costs_manual(3, 32)?;
+
+        let to: H160 = to.into();
+        let value = Self::u256_to_amount(value).in_field("value")?;
+
+        {
+            let origin = Runtime::AddressMapping::into_account_id(handle.context().caller);
+            let to = Runtime::AddressMapping::into_account_id(to);
+
+            RuntimeHelper::<Runtime>::try_dispatch(
+                handle,
+                Some(origin).into(),
+                pallet_assets::Call::<Runtime, Instance>::transfer {
+                    id: asset_id.into(),
+                    target: Runtime::Lookup::unlookup(to),
+                    amount: value,
+                },
+            )?;
+        }
+
+        log3(
+            handle.context().address,
+            SELECTOR_LOG_TRANSFER,
+            handle.context().caller,
+            to,
+            solidity::encode_event_data(value),
+        )
+        .record(handle)?;
+
+        Ok(true)
+    }
+
+    #[precompile::public("transferFrom(address,address,uint256)")]
+    fn transfer_from(
+        asset_id: AssetIdOf<Runtime, Instance>,
+        handle: &mut impl PrecompileHandle,
+        from: Address,
+        to: Address,
+        value: U256,
+    ) -> EvmResult<bool> {
+        handle.record_log_costs_manual(3, 32)?;
+
+        let from: H160 = from.into();
+        let to: H160 = to.into();
+        let value = Self::u256_to_amount(value).in_field("value")?;
+
+        {
+            let caller = Runtime::AddressMapping::into_account_id(handle.context().caller);
+            let from = Runtime::AddressMapping::into_account_id(from);
+            let to = Runtime::AddressMapping::into_account_id(to);
+
+            // If caller is "from", it can spend as much as it wants from its own balance.
+            if caller != from {
+                RuntimeHelper::<Runtime>::try_dispatch(
+                    handle,
+                    Some(caller).into(),
+                    pallet_assets::Call::<Runtime, Instance>::transfer_approved {
+                        id: asset_id.into(),
+                        owner: Runtime::Lookup::unlookup(from),
+                        destination: Runtime::Lookup::unlookup(to),
+                        amount: value,
+                    },
+                )?;
+            } else {
+                RuntimeHelper::<Runtime>::try_dispatch(
+                    handle,
+                    Some(from).into(),
+                    pallet_assets::Call::<Runtime, Instance>::transfer {
+                        id: asset_id.into(),
+                        target: Runtime::Lookup::unlookup(to),
+                        amount: value,
+                    },
+                )?;
+            }
+        }
+
+        log3(
+            handle.context().address,
+            SELECTOR_LOG_TRANSFER,
+            from,
+            to,
+            solidity::encode_event_data(value),
+        )
+        .record(handle)?;
+
+        Ok(true)
+    }
+
+    #[precompile::public("name()")]
+    #[precompile::view]
+    fn name(
+        asset_id: AssetIdOf<Runtime, Instance>,
+        handle: &mut impl PrecompileHandle,
+    ) -> EvmResult<UnboundedBytes> {
+        Self::record_metadata_read(handle)?;
+
+        Ok(pallet_assets::Pallet::<Runtime, Instance>::name(asset_id)
+            .as_slice()
+            .into())
+    }
+
+    #[precompile::public("symbol()")]
+    #[precompile::view]
+    fn symbol(
+        asset_id: AssetIdOf<Runtime, Instance>,
+        handle: &mut impl PrecompileHandle,
+    ) -> EvmResult<UnboundedBytes> {
+        Self::record_metadata_read(handle)?;
+
+        Ok(pallet_assets::Pallet::<Runtime, Instance>::symbol(asset_id)
+            .as_slice()
+            .into())
+    }
+
+    #[precompile::public("decimals()")]
+    #[precompile::view]
+    fn decimals(
+        asset_id: AssetIdOf<Runtime, Instance>,
+        handle: &mut impl PrecompileHandle,
+    ) -> EvmResult<u8> {
+        Self::record_metadata_read(handle)?;
+
+        Ok(pallet_assets::Pallet::<Runtime, Instance>::decimals(
+            asset_id,
+        ))
+    }
+
+    fn record_metadata_read(handle: &mut impl PrecompileHandle) -> EvmResult {
+        // Storage item: Metadata:
+        // Blake2_128(16) + AssetId(16) + AssetMetadata[deposit(16) + name(StringLimit)
+        // + symbol(StringLimit) + decimals(1) + is_frozen(1)]
+        handle.record_db_read::<Runtime>(
+            50 + (2 * <Runtime as pallet_assets::Config<Instance>>::StringLimit::get()) as usize,
+        )?;
+
+        Ok(())
+    }
+
+    fn u256_to_amount(value: U256) -> MayRevert<BalanceOf<Runtime, Instance>> {
+        value
+            .try_into()
+            .map_err(|_| RevertReason::value_is_too_large("balance type").into())
+    }
+}
+
+/// `pallet_assets` callback keeping the dummy code at the precompile address of every asset,
+/// so the address looks like a deployed contract from the EVM.
+pub struct AssetsErc20Callback<Runtime>(PhantomData<Runtime>);
+
+impl<Runtime, AssetId, AccountId> AssetsCallback<AssetId, AccountId>
+    for AssetsErc20Callback<Runtime>
+where
+    Runtime: pallet_evm::Config + AddressToAssetId<AssetId>,
+    AssetId: Clone,
+{
+    fn created(id: &AssetId, _owner: &AccountId) -> Result<(), ()> {
+        let address = Runtime::asset_id_to_address(id.clone());
+        pallet_evm::Pallet::<Runtime>::create_account(address, DUMMY_CODE.to_vec());
+        Ok(())
+    }
+
+    fn destroyed(id: &AssetId) -> Result<(), ()> {
+        let address = Runtime::asset_id_to_address(id.clone());
+        pallet_evm::Pallet::<Runtime>::remove_account(&address);
+        Ok(())
+    }
+}
+
+/// Identifier of [`InsertAssetsDummyCode`] in `pallet_migrations`.
+pub const DUMMY_CODE_MIGRATION_ID: [u8; 32] = *b"AssetsErc20Precompile::DummyCode";
+
+/// Inserts the dummy code at the precompile address of every asset created before
+/// [`AssetsErc20Callback`] was configured. Assets which already have code are skipped.
+///
+/// This is a multi-block migration, run by `pallet_migrations`: every step visits the assets
+/// following the last visited one, as long as the weight left in the block allows it.
+pub struct InsertAssetsDummyCode<Runtime, Instance>(PhantomData<(Runtime, Instance)>);
+
+impl<Runtime, Instance> SteppedMigration for InsertAssetsDummyCode<Runtime, Instance>
+where
+    Instance: 'static,
+    Runtime: pallet_assets::Config<Instance> + pallet_evm::Config,
+    Runtime: AddressToAssetId<AssetIdOf<Runtime, Instance>>,
+{
+    type Cursor = AssetIdOf<Runtime, Instance>;
+    type Identifier = [u8; 32];
+
+    fn id() -> Self::Identifier {
+        DUMMY_CODE_MIGRATION_ID
+    }
+
+    fn step(
+        mut cursor: Option<Self::Cursor>,
+        meter: &mut WeightMeter,
+    ) -> Result<Option<Self::Cursor>, SteppedMigrationError> {
+        // Reading the next asset and its code, inserting the code of the asset.
+        let required = <Runtime as frame_system::Config>::DbWeight::get().reads_writes(3, 3);
+        if meter.remaining().any_lt(required) {
+            return Err(SteppedMigrationError::InsufficientWeight { required });
+        }
+
+        type Assets<Runtime, Instance> = pallet_assets::Asset<Runtime, Instance>;
+        let mut assets = match &cursor {
+            Some(last) => Assets::<Runtime, Instance>::iter_keys_from(
+                Assets::<Runtime, Instance>::hashed_key_for(last),
+            ),
+            None => Assets::<Runtime, Instance>::iter_keys(),
+        };
+        while meter.try_consume(required).is_ok() {
+            let Some(asset_id) = assets.next() else {
+                log::info!(target: "runtime::assets-erc20", "Dummy code migration done");
+                return Ok(None);
+            };
+
+            let address = Runtime::asset_id_to_address(asset_id.clone());
+            if !pallet_evm::AccountCodes::<Runtime>::contains_key(address) {
+                pallet_evm::Pallet::<Runtime>::create_account(address, DUMMY_CODE.to_vec());
+            }
+            cursor = Some(asset_id);
+        }
+
+        Ok(cursor)
+    }
+}
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
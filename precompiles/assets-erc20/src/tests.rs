@@ -0,0 +1,399 @@
+use crate::{mock::*, *};
+use frame_support::{assert_ok, weights::Weight};
+use precompile_utils::testing::*;
+
+type PCall = Erc20AssetsPrecompileSetCall<Runtime, ()>;
+
+fn precompiles() -> Erc20AssetsPrecompileSet<Runtime> {
+    PrecompilesValue::get()
+}
+
+fn asset(asset_id: AssetId) -> H160 {
+    Runtime::asset_id_to_address(asset_id)
+}
+
+fn db_read() -> u64 {
+    RuntimeHelper::<Runtime>::db_read_gas_cost()
+}
+
+/// Creates the asset `asset_id` owned by Alice and mints `amount` to her.
+fn create_asset(asset_id: AssetId, amount: Balance) {
+    assert_ok!(Assets::force_create(
+        RuntimeOrigin::root(),
+        asset_id,
+        Alice.into(),
+        true,
+        1
+    ));
+    assert_ok!(Assets::force_set_metadata(
+        RuntimeOrigin::root(),
+        asset_id,
+        b"Test token".to_vec(),
+        b"TEST".to_vec(),
+        12,
+        false
+    ));
+    assert_ok!(Assets::mint(
+        RuntimeOrigin::signed(Alice.into()),
+        asset_id,
+        Alice.into(),
+        amount
+    ));
+}
+
+#[test]
+fn selectors() {
+    assert!(PCall::total_supply_selectors().contains(&0x18160ddd));
+    assert!(PCall::balance_of_selectors().contains(&0x70a08231));
+    assert!(PCall::allowance_selectors().contains(&0xdd62ed3e));
+    assert!(PCall::approve_selectors().contains(&0x095ea7b3));
+    assert!(PCall::transfer_selectors().contains(&0xa9059cbb));
+    assert!(PCall::transfer_from_selectors().contains(&0x23b872dd));
+    assert!(PCall::name_selectors().contains(&0x06fdde03));
+    assert!(PCall::symbol_selectors().contains(&0x95d89b41));
+    assert!(PCall::decimals_selectors().contains(&0x313ce567));
+}
+
+#[test]
+fn unknown_asset_is_not_a_precompile() {
+    ExtBuilder::default().build().execute_with(|| {
+        precompiles()
+            .prepare_test(Alice, asset(1), PCall::total_supply {})
+            .execute_none();
+    });
+}
+
+#[test]
+fn created_asset_gets_dummy_code() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_asset(1, 1000);
+
+        assert_eq!(
+            pallet_evm::AccountCodes::<Runtime>::get(asset(1)),
+            DUMMY_CODE.to_vec()
+        );
+    });
+}
+
+#[test]
+fn get_total_supply() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_asset(1, 1000);
+
+        precompiles()
+            .prepare_test(Alice, asset(1), PCall::total_supply {})
+            .expect_cost(2 * db_read())
+            .expect_no_logs()
+            .execute_returns(U256::from(1000));
+    });
+}
+
+#[test]
+fn get_balance_of() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_asset(1, 1000);
+
+        precompiles()
+            .prepare_test(
+                Bob,
+                asset(1),
+                PCall::balance_of {
+                    who: Address(Alice.into()),
+                },
+            )
+            .expect_cost(2 * db_read())
+            .expect_no_logs()
+            .execute_returns(U256::from(1000));
+
+        precompiles()
+            .prepare_test(
+                Bob,
+                asset(1),
+                PCall::balance_of {
+                    who: Address(Bob.into()),
+                },
+            )
+            .execute_returns(U256::zero());
+    });
+}
+
+#[test]
+fn get_metadata() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_asset(1, 1000);
+
+        precompiles()
+            .prepare_test(Alice, asset(1), PCall::name {})
+            .expect_cost(2 * db_read())
+            .expect_no_logs()
+            .execute_returns(UnboundedBytes::from(b"Test token".as_slice()));
+        precompiles()
+            .prepare_test(Alice, asset(1), PCall::symbol {})
+            .execute_returns(UnboundedBytes::from(b"TEST".as_slice()));
+        precompiles()
+            .prepare_test(Alice, asset(1), PCall::decimals {})
+            .execute_returns(12u8);
+    });
+}
+
+#[test]
+fn transfer() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_asset(1, 1000);
+
+        precompiles()
+            .prepare_test(
+                Alice,
+                asset(1),
+                PCall::transfer {
+                    to: Address(Bob.into()),
+                    value: 400.into(),
+                },
+            )
+            .expect_log(log3(
+                asset(1),
+                SELECTOR_LOG_TRANSFER,
+                Alice,
+                Bob,
+                solidity::encode_event_data(U256::from(400)),
+            ))
+            .execute_returns(true);
+
+        assert_eq!(Assets::balance(1, AccountId::from(Alice)), 600);
+        assert_eq!(Assets::balance(1, AccountId::from(Bob)), 400);
+    });
+}
+
+#[test]
+fn transfer_not_enough_funds() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_asset(1, 1000);
+
+        precompiles()
+            .prepare_test(
+                Alice,
+                asset(1),
+                PCall::transfer {
+                    to: Address(Bob.into()),
+                    value: 1400.into(),
+                },
+            )
+            .execute_reverts(|output| {
+                core::str::from_utf8(output).map_or(false, |reason| reason.contains("BalanceLow"))
+            });
+
+        assert_eq!(Assets::balance(1, AccountId::from(Alice)), 1000);
+    });
+}
+
+#[test]
+fn transfer_value_too_large() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_asset(1, 1000);
+
+        precompiles()
+            .prepare_test(
+                Alice,
+                asset(1),
+                PCall::transfer {
+                    to: Address(Bob.into()),
+                    value: U256::MAX,
+                },
+            )
+            .execute_reverts(|output| output == b"value: Value is too large for balance type");
+    });
+}
+
+#[test]
+fn approve_and_transfer_from() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_asset(1, 1000);
+
+        precompiles()
+            .prepare_test(
+                Alice,
+                asset(1),
+                PCall::approve {
+                    spender: Address(Bob.into()),
+                    value: 500.into(),
+                },
+            )
+            .expect_log(log3(
+                asset(1),
+                SELECTOR_LOG_APPROVAL,
+                Alice,
+                Bob,
+                solidity::encode_event_data(U256::from(500)),
+            ))
+            .execute_returns(true);
+
+        precompiles()
+            .prepare_test(
+                Alice,
+                asset(1),
+                PCall::allowance {
+                    owner: Address(Alice.into()),
+                    spender: Address(Bob.into()),
+                },
+            )
+            .expect_cost(2 * db_read())
+            .expect_no_logs()
+            .execute_returns(U256::from(500));
+
+        precompiles()
+            .prepare_test(
+                Bob,
+                asset(1),
+                PCall::transfer_from {
+                    from: Address(Alice.into()),
+                    to: Address(Charlie.into()),
+                    value: 400.into(),
+                },
+            )
+            .expect_log(log3(
+                asset(1),
+                SELECTOR_LOG_TRANSFER,
+                Alice,
+                Charlie,
+                solidity::encode_event_data(U256::from(400)),
+            ))
+            .execute_returns(true);
+
+        assert_eq!(Assets::balance(1, AccountId::from(Alice)), 600);
+        assert_eq!(Assets::balance(1, AccountId::from(Charlie)), 400);
+        assert_eq!(
+            Assets::allowance(1, &AccountId::from(Alice), &AccountId::from(Bob)),
+            100
+        );
+    });
+}
+
+#[test]
+fn approve_replaces_the_allowance() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_asset(1, 1000);
+
+        for value in [500u32, 300] {
+            precompiles()
+                .prepare_test(
+                    Alice,
+                    asset(1),
+                    PCall::approve {
+                        spender: Address(Bob.into()),
+                        value: value.into(),
+                    },
+                )
+                .execute_returns(true);
+        }
+
+        assert_eq!(
+            Assets::allowance(1, &AccountId::from(Alice), &AccountId::from(Bob)),
+            300
+        );
+    });
+}
+
+#[test]
+fn transfer_from_above_allowance() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_asset(1, 1000);
+
+        precompiles()
+            .prepare_test(
+                Alice,
+                asset(1),
+                PCall::approve {
+                    spender: Address(Bob.into()),
+                    value: 300.into(),
+                },
+            )
+            .execute_returns(true);
+
+        precompiles()
+            .prepare_test(
+                Bob,
+                asset(1),
+                PCall::transfer_from {
+                    from: Address(Alice.into()),
+                    to: Address(Bob.into()),
+                    value: 400.into(),
+                },
+            )
+            .execute_reverts(|output| {
+                core::str::from_utf8(output).map_or(false, |reason| reason.contains("Unapproved"))
+            });
+
+        assert_eq!(Assets::balance(1, AccountId::from(Alice)), 1000);
+    });
+}
+
+type DummyCodeMigration = InsertAssetsDummyCode<Runtime, ()>;
+
+/// Weight of the visit of one asset by [`DummyCodeMigration`].
+fn dummy_code_step_weight() -> Weight {
+    <Runtime as frame_system::Config>::DbWeight::get().reads_writes(3, 3)
+}
+
+#[test]
+fn dummy_code_migration_resumes_from_its_cursor() {
+    ExtBuilder::default().build().execute_with(|| {
+        for asset_id in 1..=3 {
+            create_asset(asset_id, 1000);
+            pallet_evm::Pallet::<Runtime>::remove_account(&asset(asset_id));
+        }
+        let with_code = || {
+            (1..=3)
+                .filter(|asset_id| {
+                    pallet_evm::AccountCodes::<Runtime>::contains_key(asset(*asset_id))
+                })
+                .count()
+        };
+
+        let mut meter = WeightMeter::with_limit(dummy_code_step_weight() * 2);
+        let cursor = DummyCodeMigration::step(None, &mut meter).unwrap();
+        assert!(cursor.is_some());
+        assert_eq!(with_code(), 2);
+
+        let mut meter = WeightMeter::with_limit(dummy_code_step_weight() * 2);
+        assert_eq!(DummyCodeMigration::step(cursor, &mut meter), Ok(None));
+        for asset_id in 1..=3 {
+            assert_eq!(
+                pallet_evm::AccountCodes::<Runtime>::get(asset(asset_id)),
+                DUMMY_CODE.to_vec()
+            );
+        }
+    });
+}
+
+#[test]
+fn dummy_code_migration_requires_the_weight_of_one_asset() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_asset(1, 1000);
+        pallet_evm::Pallet::<Runtime>::remove_account(&asset(1));
+
+        let mut meter = WeightMeter::with_limit(dummy_code_step_weight() / 2);
+        assert_eq!(
+            DummyCodeMigration::step(None, &mut meter),
+            Err(SteppedMigrationError::InsufficientWeight {
+                required: dummy_code_step_weight()
+            })
+        );
+        assert!(!pallet_evm::AccountCodes::<Runtime>::contains_key(asset(1)));
+    });
+}
+
+#[test]
+fn dummy_code_migration_keeps_existing_code() {
+    ExtBuilder::default().build().execute_with(|| {
+        create_asset(1, 1000);
+        pallet_evm::Pallet::<Runtime>::remove_account(&asset(1));
+        pallet_evm::Pallet::<Runtime>::create_account(asset(1), vec![0x00]);
+
+        let mut meter = WeightMeter::new();
+        assert_eq!(DummyCodeMigration::step(None, &mut meter), Ok(None));
+        assert_eq!(
+            pallet_evm::AccountCodes::<Runtime>::get(asset(1)),
+            vec![0x00]
+        );
+    });
+}
@@ -0,0 +1,299 @@
+// Copyright 2019-2022 PureStake Inc.
+// This file is part of SCS Chain. It is derived from Moonbeam's precompiles
+// (https://github.com/moonbeam-foundation/moonbeam).
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Precompile exposing the native currency (`pallet_balances`) as an ERC-20 contract.
+//!
+//! Balances are the plain `pallet_balances` free balances, no wrapping deposit is involved.
+//! Allowances are owned by the precompile and kept in a dedicated runtime storage map.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use core::marker::PhantomData;
+use fp_evm::PrecompileHandle;
+use frame_support::{
+    pallet_prelude::ValueQuery,
+    storage::types::{StorageDoubleMap, StorageInstance},
+    traits::{
+        fungible::{Inspect, Mutate},
+        tokens::{Fortitude, Preservation},
+    },
+    Blake2_128Concat,
+};
+use pallet_balances::WeightInfo;
+use pallet_evm::{AddressMapping, GasWeightMapping};
+use polkadot_sdk::{frame_support, frame_system, pallet_balances, sp_runtime};
+use precompile_utils::{prelude::*, substrate::TryDispatchError};
+use sp_core::{H160, U256};
+use sp_runtime::traits::{Bounded, CheckedSub};
+
+/// Solidity selector of the Transfer log, which is the Keccak of the Log signature.
+pub const SELECTOR_LOG_TRANSFER: [u8; 32] = keccak256!("Transfer(address,address,uint256)");
+
+/// Solidity selector of the Approval log, which is the Keccak of the Log signature.
+pub const SELECTOR_LOG_APPROVAL: [u8; 32] = keccak256!("Approval(address,address,uint256)");
+
+/// Alias for the Balance type of `pallet_balances`.
+pub type BalanceOf<Runtime> = <Runtime as pallet_balances::Config>::Balance;
+
+/// Storage prefix of the allowances.
+pub struct ApprovesPrefix;
+
+impl StorageInstance for ApprovesPrefix {
+    const STORAGE_PREFIX: &'static str = "Approves";
+
+    fn pallet_prefix() -> &'static str {
+        "Erc20BalancesPrecompile"
+    }
+}
+
+/// Allowances of the native ERC-20: owner => spender => amount.
+pub type ApprovesStorage<Runtime> = StorageDoubleMap<
+    ApprovesPrefix,
+    Blake2_128Concat,
+    <Runtime as frame_system::Config>::AccountId,
+    Blake2_128Concat,
+    <Runtime as frame_system::Config>::AccountId,
+    BalanceOf<Runtime>,
+    ValueQuery,
+>;
+
+/// Metadata of the native currency as returned by `name`, `symbol` and `decimals`.
+pub trait Erc20Metadata {
+    /// Returns the name of the token.
+    fn name() -> &'static str;
+
+    /// Returns the symbol of the token.
+    fn symbol() -> &'static str;
+
+    /// Returns the decimals places of the token.
+    fn decimals() -> u8;
+}
+
+/// The ERC-20 precompile of the native currency.
+pub struct Erc20BalancesPrecompile<Runtime, Metadata: Erc20Metadata>(
+    PhantomData<(Runtime, Metadata)>,
+);
+
+#[precompile_utils::precompile]
+impl<Runtime, Metadata> Erc20BalancesPrecompile<Runtime, Metadata>
+where
+    Metadata: Erc20Metadata,
+    Runtime: pallet_balances::Config + pallet_evm::Config + frame_system::Config,
+    BalanceOf<Runtime>: TryFrom<U256> + Into<U256>,
+{
+    #[precompile::public("totalSupply()")]
+    #[precompile::view]
+    fn total_supply(handle: &mut impl PrecompileHandle) -> EvmResult<U256> {
+        // Storage item: TotalIssuance:
+        // Balance(16)
+        handle.record_db_read::<Runtime>(16)?;
+
+        Ok(pallet_balances::Pallet::<Runtime>::total_issuance().into())
+    }
+
+    #[precompile::public("balanceOf(address)")]
+    #[precompile::view]
+    fn balance_of(handle: &mut impl PrecompileHandle, owner: Address) -> EvmResult<U256> {
+        // Storage item: Account:
+        // Blake2_128(16) + AccountId(20) + AccountInfo ((4 * 4) + AccountData(16 * 4))
+        handle.record_db_read::<Runtime>(116)?;
+
+        let owner = Runtime::AddressMapping::into_account_id(owner.into());
+
+        Ok(pallet_balances::Pallet::<Runtime>::reducible_balance(
+            &owner,
+            Preservation::Expendable,
+            Fortitude::Polite,
+        )
+        .into())
+    }
+
+    #[precompile::public("allowance(address,address)")]
+    #[precompile::view]
+    fn allowance(
+        handle: &mut impl PrecompileHandle,
+        owner: Address,
+        spender: Address,
+    ) -> EvmResult<U256> {
+        // Storage item: Approves:
+        // Twox128(16) + Twox128(16) + 2 * (Blake2_128(16) + AccountId(20)) + Balance(16)
+        handle.record_db_read::<Runtime>(120)?;
+
+        let owner = Runtime::AddressMapping::into_account_id(owner.into());
+        let spender = Runtime::AddressMapping::into_account_id(spender.into());
+
+        Ok(ApprovesStorage::<Runtime>::get(owner, spender).into())
+    }
+
+    #[precompile::public("approve(address,uint256)")]
+    fn approve(
+        handle: &mut impl PrecompileHandle,
+        spender: Address,
+        value: U256,
+    ) -> EvmResult<bool> {
+        handle.record_cost(RuntimeHelper::<Runtime>::db_write_gas_cost())?;
+        handle.record_log_costs_manual(3, 32)?;
+
+        let owner_address = handle.context().caller;
+        let spender_address: H160 = spender.into();
+
+        // Approve to self is a no-op for the storage, but the log is still emitted.
+        if owner_address != spender_address {
+            let owner = Runtime::AddressMapping::into_account_id(owner_address);
+            let spender = Runtime::AddressMapping::into_account_id(spender_address);
+            // Amount saturate if too high.
+            let amount: BalanceOf<Runtime> =
+                value.try_into().unwrap_or_else(|_| Bounded::max_value());
+
+            ApprovesStorage::<Runtime>::insert(owner, spender, amount);
+        }
+
+        log3(
+            handle.context().address,
+            SELECTOR_LOG_APPROVAL,
+            owner_address,
+            spender_address,
+            solidity::encode_event_data(value),
+        )
+        .record(handle)?;
+
+        Ok(true)
+    }
+
+    #[precompile::public("transfer(address,uint256)")]
+    fn transfer(handle: &mut impl PrecompileHandle, to: Address, value: U256) -> EvmResult<bool> {
+        handle.record_log_costs_manual(3, 32)?;
+
+        let caller = handle.context().caller;
+        let to: H160 = to.into();
+        let amount = Self::u256_to_amount(value).in_field("value")?;
+
+        Self::transfer_balance(handle, caller, to, amount)?;
+
+        log3(
+            handle.context().address,
+            SELECTOR_LOG_TRANSFER,
+            caller,
+            to,
+            solidity::encode_event_data(value),
+        )
+        .record(handle)?;
+
+        Ok(true)
+    }
+
+    #[precompile::public("transferFrom(address,address,uint256)")]
+    fn transfer_from(
+        handle: &mut impl PrecompileHandle,
+        from: Address,
+        to: Address,
+        value: U256,
+    ) -> EvmResult<bool> {
+        handle.record_cost(RuntimeHelper::<Runtime>::db_write_gas_cost())?;
+        handle.record_log_costs_manual(3, 32)?;
+
+        let caller = handle.context().caller;
+        let from: H160 = from.into();
+        let to: H160 = to.into();
+        let amount = Self::u256_to_amount(value).in_field("value")?;
+
+        // If caller is "from", it can spend as much as it wants.
+        if caller != from {
+            let caller = Runtime::AddressMapping::into_account_id(caller);
+            let owner = Runtime::AddressMapping::into_account_id(from);
+
+            ApprovesStorage::<Runtime>::try_mutate(owner, caller, |allowed| {
+                // Remove "value" from allowed, exit if underflow.
+                *allowed = allowed
+                    .checked_sub(&amount)
+                    .ok_or_else(|| revert("trying to spend more than allowed"))?;
+
+                EvmResult::Ok(())
+            })?;
+        }
+
+        Self::transfer_balance(handle, from, to, amount)?;
+
+        log3(
+            handle.context().address,
+            SELECTOR_LOG_TRANSFER,
+            from,
+            to,
+            solidity::encode_event_data(value),
+        )
+        .record(handle)?;
+
+        Ok(true)
+    }
+
+    #[precompile::public("name()")]
+    #[precompile::view]
+    fn name(_handle: &mut impl PrecompileHandle) -> EvmResult<UnboundedBytes> {
+        Ok(Metadata::name().as_bytes().into())
+    }
+
+    #[precompile::public("symbol()")]
+    #[precompile::view]
+    fn symbol(_handle: &mut impl PrecompileHandle) -> EvmResult<UnboundedBytes> {
+        Ok(Metadata::symbol().as_bytes().into())
+    }
+
+    #[precompile::public("decimals()")]
+    #[precompile::view]
+    fn decimals(_handle: &mut impl PrecompileHandle) -> EvmResult<u8> {
+        Ok(Metadata::decimals())
+    }
+
+    /// Transfers `value` from `from` to `to`, charging the weight of a balances transfer.
+    ///
+    /// The transfer is not dispatched as a `pallet_balances` call, so it is not subject to the
+    /// call filter of the runtime, like the transfers of value of the EVM.
+    fn transfer_balance(
+        handle: &mut impl PrecompileHandle,
+        from: H160,
+        to: H160,
+        value: BalanceOf<Runtime>,
+    ) -> EvmResult {
+        handle.record_cost(Runtime::GasWeightMapping::weight_to_gas(
+            <Runtime as pallet_balances::Config>::WeightInfo::transfer_allow_death(),
+        ))?;
+
+        let from = Runtime::AddressMapping::into_account_id(from);
+        let to = Runtime::AddressMapping::into_account_id(to);
+
+        <pallet_balances::Pallet<Runtime> as Mutate<_>>::transfer(
+            &from,
+            &to,
+            value,
+            Preservation::Expendable,
+        )
+        .map_err(TryDispatchError::Substrate)?;
+
+        Ok(())
+    }
+
+    fn u256_to_amount(value: U256) -> MayRevert<BalanceOf<Runtime>> {
+        value
+            .try_into()
+            .map_err(|_| RevertReason::value_is_too_large("balance type").into())
+    }
+}
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,287 @@
+use crate::{mock::*, *};
+use precompile_utils::{evm::costs::log_costs, testing::*};
+
+type PCall = Erc20BalancesPrecompileCall<Runtime, NativeErc20Metadata>;
+
+fn precompiles() -> Precompiles<Runtime> {
+    PrecompilesValue::get()
+}
+
+fn balances() -> ExtBuilder {
+    ExtBuilder::default().with_balances(vec![(Alice.into(), 1000), (Bob.into(), 500)])
+}
+
+fn approve(owner: impl Into<H160>, spender: impl Into<H160>, value: u32) {
+    precompiles()
+        .prepare_test(
+            owner,
+            Precompile1,
+            PCall::approve {
+                spender: Address(spender.into()),
+                value: value.into(),
+            },
+        )
+        .execute_returns(true);
+}
+
+#[test]
+fn selectors() {
+    assert!(PCall::total_supply_selectors().contains(&0x18160ddd));
+    assert!(PCall::balance_of_selectors().contains(&0x70a08231));
+    assert!(PCall::allowance_selectors().contains(&0xdd62ed3e));
+    assert!(PCall::approve_selectors().contains(&0x095ea7b3));
+    assert!(PCall::transfer_selectors().contains(&0xa9059cbb));
+    assert!(PCall::transfer_from_selectors().contains(&0x23b872dd));
+    assert!(PCall::name_selectors().contains(&0x06fdde03));
+    assert!(PCall::symbol_selectors().contains(&0x95d89b41));
+    assert!(PCall::decimals_selectors().contains(&0x313ce567));
+}
+
+#[test]
+fn get_total_supply() {
+    balances().build().execute_with(|| {
+        precompiles()
+            .prepare_test(Alice, Precompile1, PCall::total_supply {})
+            .expect_cost(RuntimeHelper::<Runtime>::db_read_gas_cost())
+            .expect_no_logs()
+            .execute_returns(U256::from(1500));
+    });
+}
+
+#[test]
+fn get_balance_of() {
+    balances().build().execute_with(|| {
+        precompiles()
+            .prepare_test(
+                Bob,
+                Precompile1,
+                PCall::balance_of {
+                    owner: Address(Alice.into()),
+                },
+            )
+            .expect_cost(RuntimeHelper::<Runtime>::db_read_gas_cost())
+            .expect_no_logs()
+            .execute_returns(U256::from(1000));
+
+        precompiles()
+            .prepare_test(
+                Bob,
+                Precompile1,
+                PCall::balance_of {
+                    owner: Address(Charlie.into()),
+                },
+            )
+            .execute_returns(U256::zero());
+    });
+}
+
+#[test]
+fn get_metadata() {
+    ExtBuilder::default().build().execute_with(|| {
+        precompiles()
+            .prepare_test(Alice, Precompile1, PCall::name {})
+            .expect_cost(0)
+            .expect_no_logs()
+            .execute_returns(UnboundedBytes::from(b"Mock token".as_slice()));
+        precompiles()
+            .prepare_test(Alice, Precompile1, PCall::symbol {})
+            .execute_returns(UnboundedBytes::from(b"MOCK".as_slice()));
+        precompiles()
+            .prepare_test(Alice, Precompile1, PCall::decimals {})
+            .execute_returns(18u8);
+    });
+}
+
+#[test]
+fn approve_sets_the_allowance() {
+    balances().build().execute_with(|| {
+        precompiles()
+            .prepare_test(
+                Alice,
+                Precompile1,
+                PCall::approve {
+                    spender: Address(Bob.into()),
+                    value: 500.into(),
+                },
+            )
+            .expect_cost(
+                RuntimeHelper::<Runtime>::db_write_gas_cost()
+                    + log_costs(3, 32).expect("log costs fit in u64"),
+            )
+            .expect_log(log3(
+                Precompile1,
+                SELECTOR_LOG_APPROVAL,
+                Alice,
+                Bob,
+                solidity::encode_event_data(U256::from(500)),
+            ))
+            .execute_returns(true);
+
+        approve(Alice, Bob, 300);
+
+        precompiles()
+            .prepare_test(
+                Alice,
+                Precompile1,
+                PCall::allowance {
+                    owner: Address(Alice.into()),
+                    spender: Address(Bob.into()),
+                },
+            )
+            .expect_cost(RuntimeHelper::<Runtime>::db_read_gas_cost())
+            .expect_no_logs()
+            .execute_returns(U256::from(300));
+    });
+}
+
+#[test]
+fn approve_self_only_emits_the_log() {
+    balances().build().execute_with(|| {
+        approve(Alice, Alice, 500);
+
+        assert_eq!(
+            ApprovesStorage::<Runtime>::get(AccountId::from(Alice), AccountId::from(Alice)),
+            0
+        );
+    });
+}
+
+#[test]
+fn transfer() {
+    balances().build().execute_with(|| {
+        precompiles()
+            .prepare_test(
+                Alice,
+                Precompile1,
+                PCall::transfer {
+                    to: Address(Bob.into()),
+                    value: 400.into(),
+                },
+            )
+            .expect_log(log3(
+                Precompile1,
+                SELECTOR_LOG_TRANSFER,
+                Alice,
+                Bob,
+                solidity::encode_event_data(U256::from(400)),
+            ))
+            .execute_returns(true);
+
+        assert_eq!(Balances::free_balance(AccountId::from(Alice)), 600);
+        assert_eq!(Balances::free_balance(AccountId::from(Bob)), 900);
+    });
+}
+
+#[test]
+fn transfer_not_enough_funds() {
+    balances().build().execute_with(|| {
+        precompiles()
+            .prepare_test(
+                Alice,
+                Precompile1,
+                PCall::transfer {
+                    to: Address(Bob.into()),
+                    value: 1400.into(),
+                },
+            )
+            .execute_reverts(|output| {
+                core::str::from_utf8(output)
+                    .map_or(false, |reason| reason.contains("FundsUnavailable"))
+            });
+
+        assert_eq!(Balances::free_balance(AccountId::from(Alice)), 1000);
+    });
+}
+
+#[test]
+fn transfer_value_too_large() {
+    balances().build().execute_with(|| {
+        precompiles()
+            .prepare_test(
+                Alice,
+                Precompile1,
+                PCall::transfer {
+                    to: Address(Bob.into()),
+                    value: U256::MAX,
+                },
+            )
+            .execute_reverts(|output| output == b"value: Value is too large for balance type");
+    });
+}
+
+#[test]
+fn transfer_from_spends_the_allowance() {
+    balances().build().execute_with(|| {
+        approve(Alice, Bob, 500);
+
+        precompiles()
+            .prepare_test(
+                Bob,
+                Precompile1,
+                PCall::transfer_from {
+                    from: Address(Alice.into()),
+                    to: Address(Charlie.into()),
+                    value: 400.into(),
+                },
+            )
+            .expect_log(log3(
+                Precompile1,
+                SELECTOR_LOG_TRANSFER,
+                Alice,
+                Charlie,
+                solidity::encode_event_data(U256::from(400)),
+            ))
+            .execute_returns(true);
+
+        assert_eq!(Balances::free_balance(AccountId::from(Alice)), 600);
+        assert_eq!(Balances::free_balance(AccountId::from(Charlie)), 400);
+        assert_eq!(
+            ApprovesStorage::<Runtime>::get(AccountId::from(Alice), AccountId::from(Bob)),
+            100
+        );
+    });
+}
+
+#[test]
+fn transfer_from_above_allowance() {
+    balances().build().execute_with(|| {
+        approve(Alice, Bob, 300);
+
+        precompiles()
+            .prepare_test(
+                Bob,
+                Precompile1,
+                PCall::transfer_from {
+                    from: Address(Alice.into()),
+                    to: Address(Bob.into()),
+                    value: 400.into(),
+                },
+            )
+            .execute_reverts(|output| output == b"trying to spend more than allowed");
+
+        assert_eq!(Balances::free_balance(AccountId::from(Alice)), 1000);
+        assert_eq!(
+            ApprovesStorage::<Runtime>::get(AccountId::from(Alice), AccountId::from(Bob)),
+            300
+        );
+    });
+}
+
+#[test]
+fn transfer_from_self_needs_no_allowance() {
+    balances().build().execute_with(|| {
+        precompiles()
+            .prepare_test(
+                Alice,
+                Precompile1,
+                PCall::transfer_from {
+                    from: Address(Alice.into()),
+                    to: Address(Bob.into()),
+                    value: 400.into(),
+                },
+            )
+            .execute_returns(true);
+
+        assert_eq!(Balances::free_balance(AccountId::from(Bob)), 900);
+    });
+}
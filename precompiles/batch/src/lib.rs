@@ -0,0 +1,297 @@
+// Copyright 2019-2022 PureStake Inc.
+// This file is part of SCS Chain. It is derived from Moonbeam's precompiles
+// (https://github.com/moonbeam-foundation/moonbeam).
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Precompile to batch multiple EVM calls in a single transaction.
+//!
+//! All subcalls are performed from the address calling the precompile, in one of three modes:
+//! all-or-nothing, best-effort, or stop on the first failure.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use core::marker::PhantomData;
+use fp_evm::{Context, ExitError, ExitReason, Log, PrecompileFailure, PrecompileHandle, Transfer};
+use polkadot_sdk::sp_std;
+use precompile_utils::{
+    evm::costs::{call_cost, log_costs},
+    prelude::*,
+};
+use sp_core::{ConstU32, H160, U256};
+use sp_std::{iter::repeat, vec, vec::Vec};
+
+/// Solidity selector of the SubcallSucceeded log, which is the Keccak of the Log signature.
+pub const LOG_SUBCALL_SUCCEEDED: [u8; 32] = keccak256!("SubcallSucceeded(uint256)");
+
+/// Solidity selector of the SubcallFailed log, which is the Keccak of the Log signature.
+pub const LOG_SUBCALL_FAILED: [u8; 32] = keccak256!("SubcallFailed(uint256)");
+
+/// Maximum number of subcalls of a batch.
+pub const ARRAY_LIMIT: u32 = 2u32.pow(9);
+type GetArrayLimit = ConstU32<ARRAY_LIMIT>;
+
+/// Maximum size of the call data of a subcall.
+pub const CALL_DATA_LIMIT: u32 = 2u32.pow(16);
+type GetCallDataLimit = ConstU32<CALL_DATA_LIMIT>;
+
+/// How the batch proceeds when a subcall fails.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Mode {
+    /// Subsequent subcalls are attempted.
+    BatchSome,
+    /// Subsequent subcalls are not attempted, the batch still succeeds.
+    BatchSomeUntilFailure,
+    /// The whole batch reverts.
+    BatchAll,
+}
+
+fn log_subcall_succeeded(address: impl Into<H160>, index: usize) -> Log {
+    log1(
+        address,
+        LOG_SUBCALL_SUCCEEDED,
+        solidity::encode_event_data(U256::from(index)),
+    )
+}
+
+fn log_subcall_failed(address: impl Into<H160>, index: usize) -> Log {
+    log1(
+        address,
+        LOG_SUBCALL_FAILED,
+        solidity::encode_event_data(U256::from(index)),
+    )
+}
+
+/// Batch precompile.
+#[derive(Debug, Clone)]
+pub struct BatchPrecompile<Runtime>(PhantomData<Runtime>);
+
+#[precompile_utils::precompile]
+impl<Runtime> BatchPrecompile<Runtime>
+where
+    Runtime: pallet_evm::Config,
+{
+    #[precompile::public("batchSome(address[],uint256[],bytes[],uint64[])")]
+    fn batch_some(
+        handle: &mut impl PrecompileHandle,
+        to: BoundedVec<Address, GetArrayLimit>,
+        value: BoundedVec<U256, GetArrayLimit>,
+        call_data: BoundedVec<BoundedBytes<GetCallDataLimit>, GetArrayLimit>,
+        gas_limit: BoundedVec<u64, GetArrayLimit>,
+    ) -> EvmResult {
+        Self::batch(handle, Mode::BatchSome, to, value, call_data, gas_limit)
+    }
+
+    #[precompile::public("batchSomeUntilFailure(address[],uint256[],bytes[],uint64[])")]
+    fn batch_some_until_failure(
+        handle: &mut impl PrecompileHandle,
+        to: BoundedVec<Address, GetArrayLimit>,
+        value: BoundedVec<U256, GetArrayLimit>,
+        call_data: BoundedVec<BoundedBytes<GetCallDataLimit>, GetArrayLimit>,
+        gas_limit: BoundedVec<u64, GetArrayLimit>,
+    ) -> EvmResult {
+        Self::batch(
+            handle,
+            Mode::BatchSomeUntilFailure,
+            to,
+            value,
+            call_data,
+            gas_limit,
+        )
+    }
+
+    #[precompile::public("batchAll(address[],uint256[],bytes[],uint64[])")]
+    fn batch_all(
+        handle: &mut impl PrecompileHandle,
+        to: BoundedVec<Address, GetArrayLimit>,
+        value: BoundedVec<U256, GetArrayLimit>,
+        call_data: BoundedVec<BoundedBytes<GetCallDataLimit>, GetArrayLimit>,
+        gas_limit: BoundedVec<u64, GetArrayLimit>,
+    ) -> EvmResult {
+        Self::batch(handle, Mode::BatchAll, to, value, call_data, gas_limit)
+    }
+
+    fn batch(
+        handle: &mut impl PrecompileHandle,
+        mode: Mode,
+        to: BoundedVec<Address, GetArrayLimit>,
+        value: BoundedVec<U256, GetArrayLimit>,
+        call_data: BoundedVec<BoundedBytes<GetCallDataLimit>, GetArrayLimit>,
+        gas_limit: BoundedVec<u64, GetArrayLimit>,
+    ) -> EvmResult {
+        let addresses = Vec::from(to).into_iter().enumerate();
+        let values = Vec::from(value).into_iter().map(Some).chain(repeat(None));
+        let calls_data = Vec::from(call_data)
+            .into_iter()
+            .map(|x| Some(x.into()))
+            .chain(repeat(None));
+        let gas_limits = Vec::from(gas_limit)
+            .into_iter()
+            .map(|x|
+                // x = 0 => forward all remaining gas
+                if x == 0 {
+                    None
+                } else {
+                    Some(x)
+                })
+            .chain(repeat(None));
+
+        // Cost of the log emitted after each subcall, reserved before performing it.
+        let log_cost = log_costs(1, 32)?;
+
+        for ((i, address), (value, (call_data, gas_limit))) in
+            addresses.zip(values.zip(calls_data.zip(gas_limits)))
+        {
+            let address = address.0;
+            let value = value.unwrap_or(U256::zero());
+            let call_data: Vec<u8> = call_data.unwrap_or(vec![]);
+
+            let sub_context = Context {
+                caller: handle.context().caller,
+                address,
+                apparent_value: value,
+            };
+
+            let transfer = if value.is_zero() {
+                None
+            } else {
+                Some(Transfer {
+                    source: handle.context().caller,
+                    target: address,
+                    value,
+                })
+            };
+
+            // We reserve enough gas to emit a final log and perform the subcall itself.
+            let remaining_gas = handle.remaining_gas();
+
+            let forwarded_gas = match (remaining_gas.checked_sub(log_cost), mode) {
+                (Some(remaining), _) => remaining,
+                (None, Mode::BatchAll) => {
+                    return Err(PrecompileFailure::Error {
+                        exit_status: ExitError::OutOfGas,
+                    })
+                }
+                (None, _) => {
+                    return Ok(());
+                }
+            };
+
+            // Cost of the call itself that the batch precompile must pay.
+            let call_cost = call_cost(value, <Runtime as pallet_evm::Config>::config());
+
+            let forwarded_gas = match forwarded_gas.checked_sub(call_cost) {
+                Some(remaining) => remaining,
+                None => {
+                    let log = log_subcall_failed(handle.code_address(), i);
+                    handle.record_log_costs(&[&log])?;
+                    log.record(handle)?;
+
+                    match mode {
+                        Mode::BatchAll => {
+                            return Err(PrecompileFailure::Error {
+                                exit_status: ExitError::OutOfGas,
+                            })
+                        }
+                        Mode::BatchSomeUntilFailure => return Ok(()),
+                        Mode::BatchSome => continue,
+                    }
+                }
+            };
+
+            // If there is a provided gas limit we ensure there is enough gas remaining.
+            let forwarded_gas = match gas_limit {
+                None => forwarded_gas, // provide all gas if no gas limit,
+                Some(limit) => {
+                    if limit > forwarded_gas {
+                        let log = log_subcall_failed(handle.code_address(), i);
+                        handle.record_log_costs(&[&log])?;
+                        log.record(handle)?;
+
+                        match mode {
+                            Mode::BatchAll => {
+                                return Err(PrecompileFailure::Error {
+                                    exit_status: ExitError::OutOfGas,
+                                })
+                            }
+                            Mode::BatchSomeUntilFailure => return Ok(()),
+                            Mode::BatchSome => continue,
+                        }
+                    }
+                    limit
+                }
+            };
+
+            let (reason, output) = handle.call(
+                address,
+                transfer,
+                call_data,
+                Some(forwarded_gas),
+                false,
+                &sub_context,
+            );
+
+            // Logs
+            // We reserved enough gas so this should not OOG.
+            match reason {
+                ExitReason::Revert(_) | ExitReason::Error(_) => {
+                    let log = log_subcall_failed(handle.code_address(), i);
+                    handle.record_log_costs(&[&log])?;
+                    log.record(handle)?
+                }
+                ExitReason::Succeed(_) => {
+                    let log = log_subcall_succeeded(handle.code_address(), i);
+                    handle.record_log_costs(&[&log])?;
+                    log.record(handle)?
+                }
+                _ => (),
+            }
+
+            // How to proceed
+            match (mode, reason) {
+                // _: Fatal is always fatal
+                (_, ExitReason::Fatal(exit_status)) => {
+                    return Err(PrecompileFailure::Fatal { exit_status })
+                }
+
+                // BatchAll : Reverts and errors are immediately forwarded.
+                (Mode::BatchAll, ExitReason::Revert(exit_status)) => {
+                    return Err(PrecompileFailure::Revert {
+                        exit_status,
+                        output,
+                    })
+                }
+                (Mode::BatchAll, ExitReason::Error(exit_status)) => {
+                    return Err(PrecompileFailure::Error { exit_status })
+                }
+
+                // BatchSomeUntilFailure : Reverts and errors prevent subsequent subcalls to
+                // be executed but the precompile still succeed.
+                (Mode::BatchSomeUntilFailure, ExitReason::Revert(_) | ExitReason::Error(_)) => {
+                    return Ok(())
+                }
+
+                // Success or ignored revert/error.
+                (_, _) => (),
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,274 @@
+use crate::{mock::*, *};
+use frame_support::assert_ok;
+use pallet_evm::PrecompileSet;
+use precompile_utils::testing::*;
+
+type PCall = BatchPrecompileCall<Runtime>;
+
+fn precompiles() -> Precompiles<Runtime> {
+    PrecompilesValue::get()
+}
+
+fn balances() -> ExtBuilder {
+    ExtBuilder::default().with_balances(vec![(Alice.into(), 1000)])
+}
+
+fn batch_all(to: Vec<H160>, call_data: Vec<Vec<u8>>) -> PCall {
+    PCall::batch_all {
+        to: to.into_iter().map(Address).collect::<Vec<_>>().into(),
+        value: vec![].into(),
+        call_data: call_data
+            .into_iter()
+            .map(BoundedBytes::<GetCallDataLimit>::from)
+            .collect::<Vec<_>>()
+            .into(),
+        gas_limit: vec![].into(),
+    }
+}
+
+/// Subcall handle succeeding on Bob and Charlie, and reverting on David.
+fn subcall_handle(subcall: Subcall) -> SubcallOutput {
+    let Subcall {
+        address, context, ..
+    } = subcall;
+    assert_eq!(context.caller, Alice.into());
+    assert_eq!(context.address, address);
+
+    match address {
+        a if a == Bob.into() || a == Charlie.into() => SubcallOutput {
+            cost: 13,
+            ..SubcallOutput::succeed()
+        },
+        a if a == David.into() => SubcallOutput {
+            output: b"subcall reverted".to_vec(),
+            cost: 17,
+            ..SubcallOutput::revert()
+        },
+        _ => panic!("unexpected subcall to {address:?}"),
+    }
+}
+
+#[test]
+fn selectors() {
+    assert!(PCall::batch_some_selectors().contains(&0x79df4b9c));
+    assert!(PCall::batch_some_until_failure_selectors().contains(&0xcf0491c7));
+    assert!(PCall::batch_all_selectors().contains(&0x96e292b8));
+}
+
+#[test]
+fn batch_some_attempts_every_subcall() {
+    balances().build().execute_with(|| {
+        precompiles()
+            .prepare_test(
+                Alice,
+                Precompile1,
+                PCall::batch_some {
+                    to: vec![Address(David.into()), Address(Bob.into())].into(),
+                    value: vec![].into(),
+                    call_data: vec![].into(),
+                    gas_limit: vec![].into(),
+                },
+            )
+            .with_subcall_handle(subcall_handle)
+            .expect_log(log_subcall_failed(Precompile1, 0))
+            .expect_log(log_subcall_succeeded(Precompile1, 1))
+            .execute_returns(());
+    });
+}
+
+#[test]
+fn batch_some_until_failure_stops_on_the_first_failure() {
+    balances().build().execute_with(|| {
+        precompiles()
+            .prepare_test(
+                Alice,
+                Precompile1,
+                PCall::batch_some_until_failure {
+                    to: vec![
+                        Address(Bob.into()),
+                        Address(David.into()),
+                        Address(Charlie.into()),
+                    ]
+                    .into(),
+                    value: vec![].into(),
+                    call_data: vec![].into(),
+                    gas_limit: vec![].into(),
+                },
+            )
+            .with_subcall_handle(|subcall: Subcall| {
+                assert_ne!(subcall.address, Charlie.into(), "Charlie is called");
+                subcall_handle(subcall)
+            })
+            .expect_log(log_subcall_succeeded(Precompile1, 0))
+            .expect_log(log_subcall_failed(Precompile1, 1))
+            .execute_returns(());
+    });
+}
+
+#[test]
+fn batch_all_forwards_the_revert() {
+    balances().build().execute_with(|| {
+        precompiles()
+            .prepare_test(
+                Alice,
+                Precompile1,
+                batch_all(vec![Bob.into(), David.into()], vec![]),
+            )
+            .with_subcall_handle(subcall_handle)
+            .execute_reverts(|output| output == b"subcall reverted");
+    });
+}
+
+#[test]
+fn batch_all_forwards_call_data_value_and_gas_limit() {
+    balances().build().execute_with(|| {
+        precompiles()
+            .prepare_test(
+                Alice,
+                Precompile1,
+                PCall::batch_all {
+                    to: vec![Address(Bob.into()), Address(Charlie.into())].into(),
+                    value: vec![U256::from(5)].into(),
+                    call_data: vec![BoundedBytes::<GetCallDataLimit>::from(b"bob".to_vec())].into(),
+                    gas_limit: vec![50_000].into(),
+                },
+            )
+            .with_subcall_handle(|subcall: Subcall| {
+                if subcall.address == Bob.into() {
+                    assert_eq!(subcall.input, b"bob".to_vec());
+                    assert_eq!(subcall.target_gas, Some(50_000));
+                    assert_eq!(subcall.context.apparent_value, U256::from(5));
+                    assert_eq!(
+                        subcall
+                            .transfer
+                            .map(|transfer| (transfer.source, transfer.value)),
+                        Some((Alice.into(), U256::from(5)))
+                    );
+                } else {
+                    assert!(subcall.input.is_empty());
+                    assert!(subcall.transfer.is_none());
+                }
+                subcall_handle(subcall)
+            })
+            .expect_log(log_subcall_succeeded(Precompile1, 0))
+            .expect_log(log_subcall_succeeded(Precompile1, 1))
+            .execute_returns(());
+    });
+}
+
+#[test]
+fn subcall_gas_limit_above_the_remaining_gas_fails() {
+    balances().build().execute_with(|| {
+        precompiles()
+            .prepare_test(
+                Alice,
+                Precompile1,
+                PCall::batch_some {
+                    to: vec![Address(Bob.into()), Address(Charlie.into())].into(),
+                    value: vec![].into(),
+                    call_data: vec![].into(),
+                    gas_limit: vec![100_000, 0].into(),
+                },
+            )
+            .with_target_gas(Some(90_000))
+            .with_subcall_handle(subcall_handle)
+            .expect_log(log_subcall_failed(Precompile1, 0))
+            .expect_log(log_subcall_succeeded(Precompile1, 1))
+            .execute_returns(());
+
+        precompiles()
+            .prepare_test(
+                Alice,
+                Precompile1,
+                PCall::batch_all {
+                    to: vec![Address(Bob.into()), Address(Charlie.into())].into(),
+                    value: vec![].into(),
+                    call_data: vec![].into(),
+                    gas_limit: vec![100_000, 0].into(),
+                },
+            )
+            .with_target_gas(Some(90_000))
+            .with_subcall_handle(subcall_handle)
+            .execute_error(ExitError::OutOfGas);
+    });
+}
+
+#[test]
+fn delegatecall_is_rejected() {
+    balances().build().execute_with(|| {
+        let mut handle = MockHandle::new(
+            Precompile1.into(),
+            Context {
+                address: Bob.into(),
+                caller: Alice.into(),
+                apparent_value: U256::zero(),
+            },
+        );
+        handle.input = batch_all(vec![Charlie.into()], vec![]).into();
+
+        match precompiles().execute(&mut handle) {
+            Some(Err(PrecompileFailure::Revert { output, .. })) => assert_eq!(
+                decode_revert_message(&output),
+                b"Cannot be called with DELEGATECALL or CALLCODE"
+            ),
+            _ => panic!("the batch precompile accepts delegatecall"),
+        }
+    });
+}
+
+/// Calls `input` on the batch precompile as Alice through the EVM, returning whether the call
+/// succeeded.
+fn evm_call(input: Vec<u8>) -> bool {
+    System::reset_events();
+    assert_ok!(Evm::call(
+        RuntimeOrigin::root(),
+        Alice.into(),
+        Precompile1.into(),
+        input,
+        U256::zero(),
+        1_000_000,
+        U256::zero(),
+        None,
+        None,
+        Vec::new(),
+    ));
+
+    System::events().iter().any(|record| {
+        matches!(
+            record.event,
+            RuntimeEvent::Evm(pallet_evm::Event::Executed { .. })
+        )
+    })
+}
+
+#[test]
+fn batch_nesting_is_limited() {
+    balances().build().execute_with(|| {
+        let inner: Vec<u8> = batch_all(vec![Bob.into()], vec![]).into();
+        let nested_once: Vec<u8> = batch_all(vec![Precompile1.into()], vec![inner.clone()]).into();
+        let nested_twice: Vec<u8> =
+            batch_all(vec![Precompile1.into()], vec![nested_once.clone()]).into();
+
+        assert!(evm_call(inner));
+        assert!(evm_call(nested_once));
+        assert!(!evm_call(nested_twice));
+    });
+}
+
+#[test]
+fn batch_all_reverts_with_a_reverting_precompile() {
+    balances().build().execute_with(|| {
+        assert!(!evm_call(
+            batch_all(vec![Bob.into(), revert_address()], vec![]).into()
+        ));
+        assert!(evm_call(
+            PCall::batch_some {
+                to: vec![Address(revert_address()), Address(Bob.into())].into(),
+                value: vec![].into(),
+                call_data: vec![].into(),
+                gas_limit: vec![].into(),
+            }
+            .into()
+        ));
+    });
+}
@@ -0,0 +1,457 @@
+//! BLS12-381 curve operation precompiles, as specified by EIP-2537.
+//!
+//! Field elements are encoded as 64 bytes big-endian, the top 16 bytes being zero, and the
+//! point at infinity as all zero coordinates. Scalars are encoded as 32 bytes big-endian.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use ark_bls12_381::{
+    g1, g2, Bls12_381, Fq, Fq2, Fr, G1Affine, G1Projective, G2Affine, G2Projective,
+};
+use ark_ec::{
+    hashing::{curve_maps::wb::WBMap, map_to_curve_hasher::MapToCurve},
+    pairing::Pairing,
+    AffineRepr, CurveGroup, VariableBaseMSM,
+};
+use ark_ff::{BigInt, BigInteger, One, PrimeField, Zero};
+use fp_evm::{
+    ExitError, ExitSucceed, Precompile, PrecompileFailure, PrecompileHandle, PrecompileOutput,
+    PrecompileResult,
+};
+use polkadot_sdk::sp_std;
+use sp_std::vec::Vec;
+
+/// Size of an encoded base field element.
+const FP_LENGTH: usize = 64;
+/// Size of the zero padding of an encoded base field element.
+const FP_PADDING_LENGTH: usize = 16;
+/// Size of an encoded scalar.
+const SCALAR_LENGTH: usize = 32;
+/// Size of an encoded G1 point.
+const G1_LENGTH: usize = 2 * FP_LENGTH;
+/// Size of an encoded G2 point.
+const G2_LENGTH: usize = 4 * FP_LENGTH;
+
+/// Cost of a G1 multiplication in the MSM precompile, before discount.
+const G1_MSM_MULTIPLICATION_COST: u64 = 12_000;
+/// Cost of a G2 multiplication in the MSM precompile, before discount.
+const G2_MSM_MULTIPLICATION_COST: u64 = 22_500;
+/// Denominator of the MSM discounts.
+const MSM_DISCOUNT_MULTIPLIER: u64 = 1_000;
+
+/// Discount of the G1 MSM precompile for `k` pairs, at index `k - 1`. Bigger inputs get the
+/// last discount.
+const G1_MSM_DISCOUNTS: [u16; 128] = [
+    1000, 949, 848, 797, 764, 750, 738, 728, 719, 712, 705, 698, 692, 687, 682, 677, 673, 669, 665,
+    661, 658, 654, 651, 648, 645, 642, 640, 637, 635, 632, 630, 627, 625, 623, 621, 619, 617, 615,
+    613, 611, 609, 608, 606, 604, 603, 601, 599, 598, 596, 595, 593, 592, 591, 589, 588, 586, 585,
+    584, 582, 581, 580, 579, 577, 576, 575, 574, 573, 572, 570, 569, 568, 567, 566, 565, 564, 563,
+    562, 561, 560, 559, 558, 557, 556, 555, 554, 553, 552, 551, 550, 549, 548, 547, 547, 546, 545,
+    544, 543, 542, 541, 540, 540, 539, 538, 537, 536, 536, 535, 534, 533, 532, 532, 531, 530, 529,
+    528, 528, 527, 526, 525, 525, 524, 523, 522, 522, 521, 520, 520, 519,
+];
+
+/// Discount of the G2 MSM precompile for `k` pairs, at index `k - 1`. Bigger inputs get the
+/// last discount.
+const G2_MSM_DISCOUNTS: [u16; 128] = [
+    1000, 1000, 923, 884, 855, 832, 812, 796, 782, 770, 759, 749, 740, 732, 724, 717, 711, 704,
+    699, 693, 688, 683, 679, 674, 670, 666, 663, 659, 655, 652, 649, 646, 643, 640, 637, 634, 632,
+    629, 627, 624, 622, 620, 618, 615, 613, 611, 609, 607, 606, 604, 602, 600, 598, 597, 595, 593,
+    592, 590, 589, 587, 586, 584, 583, 582, 580, 579, 578, 576, 575, 574, 573, 571, 570, 569, 568,
+    567, 566, 565, 563, 562, 561, 560, 559, 558, 557, 556, 555, 554, 553, 552, 552, 551, 550, 549,
+    548, 547, 546, 545, 545, 544, 543, 542, 541, 541, 540, 539, 538, 537, 537, 536, 535, 535, 534,
+    533, 532, 532, 531, 530, 530, 529, 528, 528, 527, 526, 526, 525, 524, 524,
+];
+
+fn error(message: &'static str) -> PrecompileFailure {
+    PrecompileFailure::Error {
+        exit_status: ExitError::Other(message.into()),
+    }
+}
+
+fn output(output: Vec<u8>) -> PrecompileResult {
+    Ok(PrecompileOutput {
+        exit_status: ExitSucceed::Returned,
+        output,
+    })
+}
+
+/// Gas cost of a MSM of `k` pairs, `k` being non zero.
+fn msm_cost(k: usize, multiplication_cost: u64, discounts: &[u16; 128]) -> u64 {
+    let discount = discounts[k.min(discounts.len()) - 1] as u64;
+    (k as u64)
+        .saturating_mul(multiplication_cost)
+        .saturating_mul(discount)
+        / MSM_DISCOUNT_MULTIPLIER
+}
+
+fn decode_fq(input: &[u8]) -> Result<Fq, PrecompileFailure> {
+    if input[..FP_PADDING_LENGTH].iter().any(|byte| *byte != 0) {
+        return Err(error("invalid field element"));
+    }
+
+    let mut limbs = [0u64; 6];
+    for (i, chunk) in input[FP_PADDING_LENGTH..FP_LENGTH].rchunks(8).enumerate() {
+        let mut limb = [0u8; 8];
+        limb.copy_from_slice(chunk);
+        limbs[i] = u64::from_be_bytes(limb);
+    }
+
+    Fq::from_bigint(BigInt::new(limbs)).ok_or_else(|| error("invalid field element"))
+}
+
+fn decode_fq2(input: &[u8]) -> Result<Fq2, PrecompileFailure> {
+    Ok(Fq2::new(
+        decode_fq(&input[..FP_LENGTH])?,
+        decode_fq(&input[FP_LENGTH..2 * FP_LENGTH])?,
+    ))
+}
+
+fn decode_scalar(input: &[u8]) -> Fr {
+    Fr::from_be_bytes_mod_order(input)
+}
+
+fn decode_g1(input: &[u8], subgroup_check: bool) -> Result<G1Affine, PrecompileFailure> {
+    let x = decode_fq(&input[..FP_LENGTH])?;
+    let y = decode_fq(&input[FP_LENGTH..G1_LENGTH])?;
+
+    if x.is_zero() && y.is_zero() {
+        return Ok(G1Affine::zero());
+    }
+
+    let point = G1Affine::new_unchecked(x, y);
+    if !point.is_on_curve() {
+        return Err(error("point is not on curve"));
+    }
+    if subgroup_check && !point.is_in_correct_subgroup_assuming_on_curve() {
+        return Err(error("point is not in the correct subgroup"));
+    }
+
+    Ok(point)
+}
+
+fn decode_g2(input: &[u8], subgroup_check: bool) -> Result<G2Affine, PrecompileFailure> {
+    let x = decode_fq2(&input[..2 * FP_LENGTH])?;
+    let y = decode_fq2(&input[2 * FP_LENGTH..G2_LENGTH])?;
+
+    if x.is_zero() && y.is_zero() {
+        return Ok(G2Affine::zero());
+    }
+
+    let point = G2Affine::new_unchecked(x, y);
+    if !point.is_on_curve() {
+        return Err(error("point is not on curve"));
+    }
+    if subgroup_check && !point.is_in_correct_subgroup_assuming_on_curve() {
+        return Err(error("point is not in the correct subgroup"));
+    }
+
+    Ok(point)
+}
+
+fn encode_fq(output: &mut Vec<u8>, fq: &Fq) {
+    output.extend_from_slice(&[0u8; FP_PADDING_LENGTH]);
+    output.extend_from_slice(&fq.into_bigint().to_bytes_be());
+}
+
+fn encode_g1(point: G1Affine) -> Vec<u8> {
+    let mut output = Vec::with_capacity(G1_LENGTH);
+    match point.xy() {
+        Some((x, y)) => {
+            encode_fq(&mut output, x);
+            encode_fq(&mut output, y);
+        }
+        None => output.resize(G1_LENGTH, 0),
+    }
+    output
+}
+
+fn encode_g2(point: G2Affine) -> Vec<u8> {
+    let mut output = Vec::with_capacity(G2_LENGTH);
+    match point.xy() {
+        Some((x, y)) => {
+            encode_fq(&mut output, &x.c0);
+            encode_fq(&mut output, &x.c1);
+            encode_fq(&mut output, &y.c0);
+            encode_fq(&mut output, &y.c1);
+        }
+        None => output.resize(G2_LENGTH, 0),
+    }
+    output
+}
+
+/// Addition of two G1 points, at `0x0b`. Points are not required to be in the subgroup.
+pub struct Bls12381G1Add;
+
+impl Bls12381G1Add {
+    const GAS_COST: u64 = 375;
+}
+
+impl Precompile for Bls12381G1Add {
+    fn execute(handle: &mut impl PrecompileHandle) -> PrecompileResult {
+        handle.record_cost(Self::GAS_COST)?;
+
+        let input = handle.input();
+        if input.len() != 2 * G1_LENGTH {
+            return Err(error("invalid input length"));
+        }
+
+        let p0 = decode_g1(&input[..G1_LENGTH], false)?;
+        let p1 = decode_g1(&input[G1_LENGTH..], false)?;
+
+        output(encode_g1((p0 + p1).into_affine()))
+    }
+}
+
+/// Multi-scalar multiplication of G1 points, at `0x0c`.
+pub struct Bls12381G1Msm;
+
+impl Precompile for Bls12381G1Msm {
+    fn execute(handle: &mut impl PrecompileHandle) -> PrecompileResult {
+        let pair_length = G1_LENGTH + SCALAR_LENGTH;
+        let input_length = handle.input().len();
+        if input_length == 0 || input_length % pair_length != 0 {
+            return Err(error("invalid input length"));
+        }
+
+        let k = input_length / pair_length;
+        handle.record_cost(msm_cost(k, G1_MSM_MULTIPLICATION_COST, &G1_MSM_DISCOUNTS))?;
+
+        let mut points = Vec::with_capacity(k);
+        let mut scalars = Vec::with_capacity(k);
+        for pair in handle.input().chunks_exact(pair_length) {
+            points.push(decode_g1(&pair[..G1_LENGTH], true)?);
+            scalars.push(decode_scalar(&pair[G1_LENGTH..]));
+        }
+
+        let result =
+            G1Projective::msm(&points, &scalars).map_err(|_| error("invalid input length"))?;
+
+        output(encode_g1(result.into_affine()))
+    }
+}
+
+/// Addition of two G2 points, at `0x0d`. Points are not required to be in the subgroup.
+pub struct Bls12381G2Add;
+
+impl Bls12381G2Add {
+    const GAS_COST: u64 = 600;
+}
+
+impl Precompile for Bls12381G2Add {
+    fn execute(handle: &mut impl PrecompileHandle) -> PrecompileResult {
+        handle.record_cost(Self::GAS_COST)?;
+
+        let input = handle.input();
+        if input.len() != 2 * G2_LENGTH {
+            return Err(error("invalid input length"));
+        }
+
+        let p0 = decode_g2(&input[..G2_LENGTH], false)?;
+        let p1 = decode_g2(&input[G2_LENGTH..], false)?;
+
+        output(encode_g2((p0 + p1).into_affine()))
+    }
+}
+
+/// Multi-scalar multiplication of G2 points, at `0x0e`.
+pub struct Bls12381G2Msm;
+
+impl Precompile for Bls12381G2Msm {
+    fn execute(handle: &mut impl PrecompileHandle) -> PrecompileResult {
+        let pair_length = G2_LENGTH + SCALAR_LENGTH;
+        let input_length = handle.input().len();
+        if input_length == 0 || input_length % pair_length != 0 {
+            return Err(error("invalid input length"));
+        }
+
+        let k = input_length / pair_length;
+        handle.record_cost(msm_cost(k, G2_MSM_MULTIPLICATION_COST, &G2_MSM_DISCOUNTS))?;
+
+        let mut points = Vec::with_capacity(k);
+        let mut scalars = Vec::with_capacity(k);
+        for pair in handle.input().chunks_exact(pair_length) {
+            points.push(decode_g2(&pair[..G2_LENGTH], true)?);
+            scalars.push(decode_scalar(&pair[G2_LENGTH..]));
+        }
+
+        let result =
+            G2Projective::msm(&points, &scalars).map_err(|_| error("invalid input length"))?;
+
+        output(encode_g2(result.into_affine()))
+    }
+}
+
+/// Pairing check of pairs of G1 and G2 points, at `0x0f`. Returns 1 as a 32 bytes word if the
+/// product of the pairings is the identity, 0 otherwise.
+pub struct Bls12381Pairing;
+
+impl Bls12381Pairing {
+    const BASE_GAS: u64 = 37_700;
+    const PER_PAIR_GAS: u64 = 32_600;
+}
+
+impl Precompile for Bls12381Pairing {
+    fn execute(handle: &mut impl PrecompileHandle) -> PrecompileResult {
+        let pair_length = G1_LENGTH + G2_LENGTH;
+        let input_length = handle.input().len();
+        if input_length == 0 || input_length % pair_length != 0 {
+            return Err(error("invalid input length"));
+        }
+
+        let k = input_length / pair_length;
+        handle.record_cost(
+            Self::PER_PAIR_GAS
+                .saturating_mul(k as u64)
+                .saturating_add(Self::BASE_GAS),
+        )?;
+
+        let mut g1_points = Vec::with_capacity(k);
+        let mut g2_points = Vec::with_capacity(k);
+        for pair in handle.input().chunks_exact(pair_length) {
+            g1_points.push(decode_g1(&pair[..G1_LENGTH], true)?);
+            g2_points.push(decode_g2(&pair[G1_LENGTH..], true)?);
+        }
+
+        let mut result = [0u8; 32];
+        if Bls12_381::multi_pairing(g1_points, g2_points).0.is_one() {
+            result[31] = 1;
+        }
+
+        output(result.to_vec())
+    }
+}
+
+/// Maps a base field element to a G1 point, at `0x10`.
+pub struct Bls12381MapFpToG1;
+
+impl Bls12381MapFpToG1 {
+    const GAS_COST: u64 = 5_500;
+}
+
+impl Precompile for Bls12381MapFpToG1 {
+    fn execute(handle: &mut impl PrecompileHandle) -> PrecompileResult {
+        handle.record_cost(Self::GAS_COST)?;
+
+        let input = handle.input();
+        if input.len() != FP_LENGTH {
+            return Err(error("invalid input length"));
+        }
+
+        let fq = decode_fq(input)?;
+        let point = WBMap::<g1::Config>::new()
+            .and_then(|map| map.map_to_curve(fq))
+            .map_err(|_| error("failed to map field element to curve"))?;
+
+        output(encode_g1(point.clear_cofactor()))
+    }
+}
+
+/// Maps an extension field element to a G2 point, at `0x11`.
+pub struct Bls12381MapFp2ToG2;
+
+impl Bls12381MapFp2ToG2 {
+    const GAS_COST: u64 = 23_800;
+}
+
+impl Precompile for Bls12381MapFp2ToG2 {
+    fn execute(handle: &mut impl PrecompileHandle) -> PrecompileResult {
+        handle.record_cost(Self::GAS_COST)?;
+
+        let input = handle.input();
+        if input.len() != 2 * FP_LENGTH {
+            return Err(error("invalid input length"));
+        }
+
+        let fq2 = decode_fq2(input)?;
+        let point = WBMap::<g2::Config>::new()
+            .and_then(|map| map.map_to_curve(fq2))
+            .map_err(|_| error("failed to map field element to curve"))?;
+
+        output(encode_g2(point.clear_cofactor()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pallet_evm_test_vector_support::{
+        test_precompile_failure_test_vectors, test_precompile_test_vectors,
+    };
+
+    #[test]
+    fn process_consensus_tests_for_g1_add() -> Result<(), String> {
+        test_precompile_test_vectors::<Bls12381G1Add>("../testdata/bls12381_g1_add.json")?;
+        test_precompile_failure_test_vectors::<Bls12381G1Add>(
+            "../testdata/bls12381_fail_g1_add.json",
+        )
+    }
+
+    #[test]
+    fn process_consensus_tests_for_g1_msm() -> Result<(), String> {
+        test_precompile_test_vectors::<Bls12381G1Msm>("../testdata/bls12381_g1_msm.json")?;
+        test_precompile_failure_test_vectors::<Bls12381G1Msm>(
+            "../testdata/bls12381_fail_g1_msm.json",
+        )
+    }
+
+    #[test]
+    fn process_consensus_tests_for_g2_add() -> Result<(), String> {
+        test_precompile_test_vectors::<Bls12381G2Add>("../testdata/bls12381_g2_add.json")?;
+        test_precompile_failure_test_vectors::<Bls12381G2Add>(
+            "../testdata/bls12381_fail_g2_add.json",
+        )
+    }
+
+    #[test]
+    fn process_consensus_tests_for_g2_msm() -> Result<(), String> {
+        test_precompile_test_vectors::<Bls12381G2Msm>("../testdata/bls12381_g2_msm.json")?;
+        test_precompile_failure_test_vectors::<Bls12381G2Msm>(
+            "../testdata/bls12381_fail_g2_msm.json",
+        )
+    }
+
+    #[test]
+    fn process_consensus_tests_for_pairing() -> Result<(), String> {
+        test_precompile_test_vectors::<Bls12381Pairing>("../testdata/bls12381_pairing.json")?;
+        test_precompile_failure_test_vectors::<Bls12381Pairing>(
+            "../testdata/bls12381_fail_pairing.json",
+        )
+    }
+
+    #[test]
+    fn process_consensus_tests_for_map_fp_to_g1() -> Result<(), String> {
+        test_precompile_test_vectors::<Bls12381MapFpToG1>(
+            "../testdata/bls12381_map_fp_to_g1.json",
+        )?;
+        test_precompile_failure_test_vectors::<Bls12381MapFpToG1>(
+            "../testdata/bls12381_fail_map_fp_to_g1.json",
+        )
+    }
+
+    #[test]
+    fn process_consensus_tests_for_map_fp2_to_g2() -> Result<(), String> {
+        test_precompile_test_vectors::<Bls12381MapFp2ToG2>(
+            "../testdata/bls12381_map_fp2_to_g2.json",
+        )?;
+        test_precompile_failure_test_vectors::<Bls12381MapFp2ToG2>(
+            "../testdata/bls12381_fail_map_fp2_to_g2.json",
+        )
+    }
+
+    #[test]
+    fn msm_discount_applies_beyond_table() {
+        assert_eq!(
+            msm_cost(1, G1_MSM_MULTIPLICATION_COST, &G1_MSM_DISCOUNTS),
+            12_000
+        );
+        assert_eq!(
+            msm_cost(2, G2_MSM_MULTIPLICATION_COST, &G2_MSM_DISCOUNTS),
+            45_000
+        );
+        assert_eq!(
+            msm_cost(200, G1_MSM_MULTIPLICATION_COST, &G1_MSM_DISCOUNTS),
+            200 * 12_000 * 519 / 1_000
+        );
+    }
+}
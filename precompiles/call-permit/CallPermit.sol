@@ -0,0 +1,55 @@
+// SPDX-License-Identifier: GPL-3.0-only
+pragma solidity >=0.8.3;
+
+/// @dev The CallPermit contract's address.
+address constant CALL_PERMIT_ADDRESS = 0x0000000000000000000000000000000000000808;
+
+/// @dev The CallPermit contract's instance.
+CallPermit constant CALL_PERMIT_CONTRACT = CallPermit(CALL_PERMIT_ADDRESS);
+
+/// @title Precompile for dispatching calls signed with an EIP-712 permit.
+/// @dev Allows a relayer to pay the fees of a call signed off-chain by `from`.
+/// The permit is signed over the following typed data:
+///
+/// EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)
+/// with name "Call Permit Precompile", version "1", the chain id of `EVMChainId` and this
+/// precompile address as verifying contract.
+///
+/// CallPermit(address from,address to,uint256 value,bytes data,uint64 gaslimit,uint256 nonce,uint256 deadline)
+interface CallPermit {
+    /// @dev Dispatch a call on the behalf of `from`, if the permit signature is valid.
+    /// @custom:selector b5ea0966
+    /// @param from Who made the permit and wants its call to be dispatched on its behalf.
+    /// @param to Which address the call is made to.
+    /// @param value Value being transferred from the "from" account.
+    /// @param data Call data
+    /// @param gaslimit Gaslimit the dispatched call requires.
+    ///     Providing it prevents the dispatcher to manipulate the gaslimit.
+    /// @param deadline Deadline in UNIX seconds after which the permit will no longer be valid.
+    /// @param v V part of the signature.
+    /// @param r R part of the signature.
+    /// @param s S part of the signature.
+    /// @return output Output of the call.
+    function dispatch(
+        address from,
+        address to,
+        uint256 value,
+        bytes memory data,
+        uint64 gaslimit,
+        uint256 deadline,
+        uint8 v,
+        bytes32 r,
+        bytes32 s
+    ) external returns (bytes memory output);
+
+    /// @dev Returns the current nonce for given owner.
+    /// A permit must have this nonce to be consumed, which will
+    /// increase the nonce by one.
+    /// @custom:selector 7ecebe00
+    function nonces(address owner) external view returns (uint256);
+
+    /// @dev Returns the EIP712 domain separator. It is used to avoid replay
+    /// attacks across assets or other similar EIP712 message structures.
+    /// @custom:selector 3644e515
+    function DOMAIN_SEPARATOR() external view returns (bytes32);
+}
@@ -0,0 +1,263 @@
+// Copyright 2019-2022 PureStake Inc.
+// This file is part of SCS Chain. It is derived from Moonbeam's precompiles
+// (https://github.com/moonbeam-foundation/moonbeam).
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Precompile dispatching EVM calls signed off-chain with an EIP-712 permit.
+//!
+//! A relayer submits the permit and pays the fees, while the call is executed with the
+//! signer as caller. Every signer has a nonce, increased by each dispatched permit.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use core::marker::PhantomData;
+use fp_evm::{Context, ExitReason, PrecompileFailure, PrecompileHandle, Transfer};
+use frame_support::{
+    ensure,
+    pallet_prelude::ValueQuery,
+    storage::types::{StorageInstance, StorageMap},
+    traits::Get,
+    Blake2_128Concat,
+};
+use polkadot_sdk::{frame_support, pallet_timestamp, sp_io, sp_std};
+use precompile_utils::{evm::costs::call_cost, prelude::*};
+use sp_core::{ConstU32, H160, H256, U256};
+use sp_io::hashing::keccak_256;
+use sp_std::vec::Vec;
+
+/// Storage prefix of the nonces.
+pub struct NoncesPrefix;
+
+impl StorageInstance for NoncesPrefix {
+    const STORAGE_PREFIX: &'static str = "Nonces";
+
+    fn pallet_prefix() -> &'static str {
+        "PrecompileCallPermit"
+    }
+}
+
+/// Nonces of the permit signers.
+pub type NoncesStorage = StorageMap<NoncesPrefix, Blake2_128Concat, H160, U256, ValueQuery>;
+
+/// EIP712 permit typehash.
+pub const PERMIT_TYPEHASH: [u8; 32] = keccak256!(
+    "CallPermit(address from,address to,uint256 value,bytes data,uint64 gaslimit\
+,uint256 nonce,uint256 deadline)"
+);
+
+/// EIP712 domain typehash.
+const EIP712_DOMAIN_TYPEHASH: [u8; 32] = keccak256!(
+    "EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)"
+);
+
+/// EIP712 domain name.
+const PERMIT_DOMAIN: [u8; 32] = keccak256!("Call Permit Precompile");
+
+/// Maximum size of the call data of a permit.
+pub const CALL_DATA_LIMIT: u32 = 2u32.pow(16);
+type GetCallDataLimit = ConstU32<CALL_DATA_LIMIT>;
+
+/// Precompile allowing to issue and dispatch call permits for gasless transactions.
+pub struct CallPermitPrecompile<Runtime>(PhantomData<Runtime>);
+
+impl<Runtime> CallPermitPrecompile<Runtime>
+where
+    Runtime: pallet_evm::Config,
+{
+    /// Computes the EIP712 domain separator of the precompile deployed at `address`.
+    pub fn compute_domain_separator(address: H160) -> [u8; 32] {
+        let name: H256 = PERMIT_DOMAIN.into();
+        let version: H256 = keccak256!("1").into();
+        let chain_id: U256 = <Runtime as pallet_evm::Config>::ChainId::get().into();
+
+        let domain_separator_inner = solidity::encode_arguments((
+            H256::from(EIP712_DOMAIN_TYPEHASH),
+            name,
+            version,
+            chain_id,
+            Address(address),
+        ));
+
+        keccak_256(&domain_separator_inner)
+    }
+
+    /// Computes the EIP712 digest signed for a permit.
+    pub fn generate_permit(
+        address: H160,
+        from: H160,
+        to: H160,
+        value: U256,
+        data: Vec<u8>,
+        gaslimit: u64,
+        nonce: U256,
+        deadline: U256,
+    ) -> [u8; 32] {
+        let domain_separator = Self::compute_domain_separator(address);
+
+        let permit_content = solidity::encode_arguments((
+            H256::from(PERMIT_TYPEHASH),
+            Address(from),
+            Address(to),
+            value,
+            // bytes are encoded as the keccak_256 of the content
+            H256::from(keccak_256(&data)),
+            gaslimit,
+            nonce,
+            deadline,
+        ));
+        let permit_content = keccak_256(&permit_content);
+        let mut pre_digest = Vec::with_capacity(2 + 32 + 32);
+        pre_digest.extend_from_slice(b"\x19\x01");
+        pre_digest.extend_from_slice(&domain_separator);
+        pre_digest.extend_from_slice(&permit_content);
+        keccak_256(&pre_digest)
+    }
+}
+
+#[precompile_utils::precompile]
+impl<Runtime> CallPermitPrecompile<Runtime>
+where
+    Runtime: pallet_evm::Config + pallet_timestamp::Config,
+    <Runtime as pallet_timestamp::Config>::Moment: Into<U256>,
+{
+    #[precompile::public(
+        "dispatch(address,address,uint256,bytes,uint64,uint256,uint8,bytes32,bytes32)"
+    )]
+    fn dispatch(
+        handle: &mut impl PrecompileHandle,
+        from: Address,
+        to: Address,
+        value: U256,
+        data: BoundedBytes<GetCallDataLimit>,
+        gas_limit: u64,
+        deadline: U256,
+        v: u8,
+        r: H256,
+        s: H256,
+    ) -> EvmResult<UnboundedBytes> {
+        // Now: Timestamp(8)
+        handle.record_db_read::<Runtime>(8)?;
+        // Nonces: Blake2_128Concat(16) + H160(20) + U256(32)
+        handle.record_db_read::<Runtime>(68)?;
+        // Nonces write.
+        handle.record_cost(RuntimeHelper::<Runtime>::db_write_gas_cost())?;
+
+        let from: H160 = from.into();
+        let to: H160 = to.into();
+        let data: Vec<u8> = data.into();
+
+        // ENSURE GASLIMIT IS SUFFICIENT
+        let call_cost = call_cost(value, <Runtime as pallet_evm::Config>::config());
+
+        let total_cost = gas_limit
+            .checked_add(call_cost)
+            .ok_or_else(|| revert("Call require too much gas (uint64 overflow)"))?;
+
+        if total_cost > handle.remaining_gas() {
+            return Err(revert("Gaslimit is too low to dispatch provided call"));
+        }
+
+        // VERIFY PERMIT
+
+        // pallet_timestamp is in ms while Ethereum use second timestamps.
+        let now: U256 = pallet_timestamp::Pallet::<Runtime>::get().into();
+        let timestamp = now / 1000;
+
+        ensure!(deadline >= timestamp, revert("Permit expired"));
+
+        let nonce = NoncesStorage::get(from);
+
+        let permit = Self::generate_permit(
+            handle.context().address,
+            from,
+            to,
+            value,
+            data.clone(),
+            gas_limit,
+            nonce,
+            deadline,
+        );
+
+        let mut sig = [0u8; 65];
+        sig[0..32].copy_from_slice(r.as_bytes());
+        sig[32..64].copy_from_slice(s.as_bytes());
+        sig[64] = v;
+
+        let signer = sp_io::crypto::secp256k1_ecdsa_recover(&sig, &permit)
+            .map_err(|_| revert("Invalid permit"))?;
+        let signer = H160::from(H256::from_slice(keccak_256(&signer).as_slice()));
+
+        ensure!(
+            signer != H160::zero() && signer == from,
+            revert("Invalid permit")
+        );
+
+        NoncesStorage::insert(from, nonce + U256::one());
+
+        // DISPATCH CALL
+        let sub_context = Context {
+            caller: from,
+            address: to,
+            apparent_value: value,
+        };
+
+        let transfer = if value.is_zero() {
+            None
+        } else {
+            Some(Transfer {
+                source: from,
+                target: to,
+                value,
+            })
+        };
+
+        let (reason, output) =
+            handle.call(to, transfer, data, Some(gas_limit), false, &sub_context);
+        match reason {
+            ExitReason::Error(exit_status) => Err(PrecompileFailure::Error { exit_status }),
+            ExitReason::Fatal(exit_status) => Err(PrecompileFailure::Fatal { exit_status }),
+            ExitReason::Revert(_) => Err(revert(output)),
+            ExitReason::Succeed(_) => Ok(output.into()),
+        }
+    }
+
+    #[precompile::public("nonces(address)")]
+    #[precompile::view]
+    fn nonces(handle: &mut impl PrecompileHandle, owner: Address) -> EvmResult<U256> {
+        // Nonces: Blake2_128Concat(16) + H160(20) + U256(32)
+        handle.record_db_read::<Runtime>(68)?;
+
+        let owner: H160 = owner.into();
+
+        Ok(NoncesStorage::get(owner))
+    }
+
+    #[precompile::public("DOMAIN_SEPARATOR()")]
+    #[precompile::view]
+    fn domain_separator(handle: &mut impl PrecompileHandle) -> EvmResult<H256> {
+        // ChainId: u64(8)
+        handle.record_db_read::<Runtime>(8)?;
+
+        let domain_separator: H256 =
+            Self::compute_domain_separator(handle.context().address).into();
+
+        Ok(domain_separator)
+    }
+}
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,235 @@
+use crate::{mock::*, *};
+use fp_evm::ExitError;
+use precompile_utils::testing::*;
+use sp_core::{ecdsa, Pair};
+
+type PCall = CallPermitPrecompileCall<Runtime>;
+
+fn precompiles() -> Precompiles<Runtime> {
+    PrecompilesValue::get()
+}
+
+fn balances() -> ExtBuilder {
+    ExtBuilder::default().with_balances(vec![(Alice.into(), 1000)])
+}
+
+/// The key signing the permits, and its Ethereum address.
+fn signer() -> (ecdsa::Pair, H160) {
+    let pair = ecdsa::Pair::from_seed(&[0x42; 32]);
+    let message = [0u8; 32];
+    let public = sp_io::crypto::secp256k1_ecdsa_recover(&sign(&pair, message), &message)
+        .expect("the signature is valid");
+    (pair, H160::from(H256::from(keccak_256(&public))))
+}
+
+fn sign(pair: &ecdsa::Pair, message: [u8; 32]) -> [u8; 65] {
+    <[u8; 65]>::try_from(pair.sign_prehashed(&message).as_ref())
+        .expect("ECDSA signatures are 65 bytes")
+}
+
+/// A permit of the signer to call Bob with `value` and `data`, signed with `pair`.
+fn permit(pair: &ecdsa::Pair, from: H160, value: U256, data: &[u8], deadline: U256) -> PCall {
+    let nonce = NoncesStorage::get(from);
+    let digest = CallPermitPrecompile::<Runtime>::generate_permit(
+        Precompile1.into(),
+        from,
+        Bob.into(),
+        value,
+        data.to_vec(),
+        100_000,
+        nonce,
+        deadline,
+    );
+    let signature = sign(pair, digest);
+
+    PCall::dispatch {
+        from: Address(from),
+        to: Address(Bob.into()),
+        value,
+        data: data.into(),
+        gas_limit: 100_000,
+        deadline,
+        v: signature[64] + 27,
+        r: H256::from_slice(&signature[0..32]),
+        s: H256::from_slice(&signature[32..64]),
+    }
+}
+
+#[test]
+fn selectors() {
+    assert!(PCall::dispatch_selectors().contains(&0xb5ea0966));
+    assert!(PCall::nonces_selectors().contains(&0x7ecebe00));
+    assert!(PCall::domain_separator_selectors().contains(&0x3644e515));
+}
+
+#[test]
+fn domain_separator() {
+    balances().build().execute_with(|| {
+        precompiles()
+            .prepare_test(Alice, Precompile1, PCall::domain_separator {})
+            .expect_cost(RuntimeHelper::<Runtime>::db_read_gas_cost())
+            .expect_no_logs()
+            .execute_returns(H256::from(
+                CallPermitPrecompile::<Runtime>::compute_domain_separator(Precompile1.into()),
+            ));
+    });
+}
+
+#[test]
+fn valid_permit_dispatches_as_the_signer() {
+    balances().build().execute_with(|| {
+        let (pair, from) = signer();
+
+        precompiles()
+            .prepare_test(
+                Alice,
+                Precompile1,
+                permit(&pair, from, U256::from(5), b"call data", U256::MAX),
+            )
+            .with_subcall_handle(move |subcall: Subcall| {
+                assert_eq!(subcall.address, Bob.into());
+                assert_eq!(subcall.context.caller, from);
+                assert_eq!(subcall.context.address, Bob.into());
+                assert_eq!(subcall.context.apparent_value, U256::from(5));
+                assert_eq!(
+                    subcall
+                        .transfer
+                        .map(|transfer| (transfer.source, transfer.value)),
+                    Some((from, U256::from(5)))
+                );
+                assert_eq!(subcall.input, b"call data".to_vec());
+                assert_eq!(subcall.target_gas, Some(100_000));
+
+                SubcallOutput {
+                    output: b"returned".to_vec(),
+                    cost: 13,
+                    ..SubcallOutput::succeed()
+                }
+            })
+            .execute_returns(UnboundedBytes::from(b"returned".as_slice()));
+
+        precompiles()
+            .prepare_test(
+                Alice,
+                Precompile1,
+                PCall::nonces {
+                    owner: Address(from),
+                },
+            )
+            .expect_cost(RuntimeHelper::<Runtime>::db_read_gas_cost())
+            .expect_no_logs()
+            .execute_returns(U256::one());
+    });
+}
+
+#[test]
+fn permit_cannot_be_replayed() {
+    balances().build().execute_with(|| {
+        let (pair, from) = signer();
+        let call = permit(&pair, from, U256::zero(), b"", U256::MAX);
+        let replayed_call = permit(&pair, from, U256::zero(), b"", U256::MAX);
+
+        precompiles()
+            .prepare_test(Alice, Precompile1, call)
+            .with_subcall_handle(|_| SubcallOutput::succeed())
+            .execute_returns(UnboundedBytes::from(b"".as_slice()));
+        precompiles()
+            .prepare_test(Alice, Precompile1, replayed_call)
+            .with_subcall_handle(|_| panic!("the permit is replayed"))
+            .execute_reverts(|output| output == b"Invalid permit");
+
+        assert_eq!(NoncesStorage::get(from), U256::one());
+    });
+}
+
+#[test]
+fn permit_of_another_signer_is_invalid() {
+    balances().build().execute_with(|| {
+        let (pair, _) = signer();
+
+        precompiles()
+            .prepare_test(
+                Alice,
+                Precompile1,
+                permit(&pair, Charlie.into(), U256::zero(), b"", U256::MAX),
+            )
+            .with_subcall_handle(|_| panic!("an invalid permit is dispatched"))
+            .execute_reverts(|output| output == b"Invalid permit");
+
+        assert_eq!(NoncesStorage::get(H160::from(Charlie)), U256::zero());
+    });
+}
+
+#[test]
+fn expired_permit_reverts() {
+    balances().build().execute_with(|| {
+        let (pair, from) = signer();
+        // pallet_timestamp is in milliseconds, deadlines in seconds.
+        Timestamp::set_timestamp(10_000_000);
+
+        precompiles()
+            .prepare_test(
+                Alice,
+                Precompile1,
+                permit(&pair, from, U256::zero(), b"", U256::from(9_999)),
+            )
+            .with_subcall_handle(|_| panic!("an expired permit is dispatched"))
+            .execute_reverts(|output| output == b"Permit expired");
+
+        precompiles()
+            .prepare_test(
+                Alice,
+                Precompile1,
+                permit(&pair, from, U256::zero(), b"", U256::from(10_000)),
+            )
+            .with_subcall_handle(|_| SubcallOutput::succeed())
+            .execute_returns(UnboundedBytes::from(b"".as_slice()));
+    });
+}
+
+#[test]
+fn gas_limit_above_the_remaining_gas_reverts() {
+    balances().build().execute_with(|| {
+        let (pair, from) = signer();
+
+        precompiles()
+            .prepare_test(
+                Alice,
+                Precompile1,
+                permit(&pair, from, U256::zero(), b"", U256::MAX),
+            )
+            .with_target_gas(Some(100_000))
+            .with_subcall_handle(|_| panic!("the call is dispatched without enough gas"))
+            .execute_reverts(|output| output == b"Gaslimit is too low to dispatch provided call");
+
+        assert_eq!(NoncesStorage::get(from), U256::zero());
+    });
+}
+
+#[test]
+fn subcall_failures_are_forwarded() {
+    balances().build().execute_with(|| {
+        let (pair, from) = signer();
+
+        precompiles()
+            .prepare_test(
+                Alice,
+                Precompile1,
+                permit(&pair, from, U256::zero(), b"", U256::MAX),
+            )
+            .with_subcall_handle(|_| SubcallOutput {
+                output: b"subcall reverted".to_vec(),
+                ..SubcallOutput::revert()
+            })
+            .execute_reverts(|output| output == b"subcall reverted");
+
+        precompiles()
+            .prepare_test(
+                Alice,
+                Precompile1,
+                permit(&pair, from, U256::zero(), b"", U256::MAX),
+            )
+            .with_subcall_handle(|_| SubcallOutput::out_of_gas())
+            .execute_error(ExitError::OutOfGas);
+    });
+}
@@ -0,0 +1,91 @@
+// SPDX-License-Identifier: GPL-3.0-only
+pragma solidity >=0.8.3;
+
+/// @dev The Conviction Voting contract's address.
+address constant CONVICTION_VOTING_ADDRESS = 0x0000000000000000000000000000000000000805;
+
+/// @dev The Conviction Voting contract's instance.
+ConvictionVoting constant CONVICTION_VOTING_CONTRACT = ConvictionVoting(
+    CONVICTION_VOTING_ADDRESS
+);
+
+/// @title Pallet Conviction Voting Interface
+/// @dev The interface through which solidity contracts will interact with
+/// pallet-conviction-voting. Every call is dispatched with the caller as the voter.
+/// Convictions range from 0 (0.1x votes, no lock) to 6 (6x votes, 32x lock periods).
+interface ConvictionVoting {
+    /// @dev Vote yes in a poll.
+    /// @custom:selector da9df518
+    /// @param pollIndex Index of the poll.
+    /// @param voteAmount Balance locked for the vote.
+    /// @param conviction Conviction multiplier for the lock period.
+    function voteYes(
+        uint32 pollIndex,
+        uint256 voteAmount,
+        uint8 conviction
+    ) external;
+
+    /// @dev Vote no in a poll.
+    /// @custom:selector cc600eba
+    /// @param pollIndex Index of the poll.
+    /// @param voteAmount Balance locked for the vote.
+    /// @param conviction Conviction multiplier for the lock period.
+    function voteNo(
+        uint32 pollIndex,
+        uint256 voteAmount,
+        uint8 conviction
+    ) external;
+
+    /// @dev Split the vote in a poll, without conviction.
+    /// @custom:selector dd6c52a4
+    /// @param pollIndex Index of the poll.
+    /// @param aye Balance locked for aye.
+    /// @param nay Balance locked for nay.
+    function voteSplit(
+        uint32 pollIndex,
+        uint256 aye,
+        uint256 nay
+    ) external;
+
+    /// @dev Split the vote in a poll with an abstain part, without conviction.
+    /// @custom:selector 52004540
+    /// @param pollIndex Index of the poll.
+    /// @param aye Balance locked for aye.
+    /// @param nay Balance locked for nay.
+    /// @param abstain Balance locked for abstain (support).
+    function voteSplitAbstain(
+        uint32 pollIndex,
+        uint256 aye,
+        uint256 nay,
+        uint256 abstain
+    ) external;
+
+    /// @dev Remove the vote of the caller in a poll.
+    /// @custom:selector 79cae220
+    /// @param pollIndex Index of the poll.
+    function removeVote(uint32 pollIndex) external;
+
+    /// @dev Delegate the voting power of the caller on a track.
+    /// @custom:selector 681750e8
+    /// @param trackId The track to delegate the votes of.
+    /// @param representative The account receiving the delegated votes.
+    /// @param conviction Conviction multiplier for the lock period.
+    /// @param amount Balance delegated.
+    function delegate(
+        uint16 trackId,
+        address representative,
+        uint8 conviction,
+        uint256 amount
+    ) external;
+
+    /// @dev Undelegate the voting power of the caller on a track.
+    /// @custom:selector 98be4094
+    /// @param trackId The track to undelegate the votes of.
+    function undelegate(uint16 trackId) external;
+
+    /// @dev Remove the expired locks of `target` on a track.
+    /// @custom:selector 4259d98c
+    /// @param trackId The track of the locks.
+    /// @param target The account to unlock.
+    function unlock(uint16 trackId, address target) external;
+}
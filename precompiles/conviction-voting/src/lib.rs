@@ -0,0 +1,216 @@
+//! Precompile to interact with `pallet_conviction_voting` from the EVM.
+//!
+//! Every call is dispatched as a signed origin of the caller, which acts as the voter.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use core::marker::PhantomData;
+use fp_evm::PrecompileHandle;
+use frame_support::{
+    dispatch::{GetDispatchInfo, PostDispatchInfo},
+    traits::OriginTrait,
+};
+use pallet_conviction_voting::{AccountVote, ClassOf, Conviction, PollIndexOf, Vote};
+use pallet_evm::AddressMapping;
+use polkadot_sdk::{frame_support, frame_system, pallet_conviction_voting, sp_runtime};
+use precompile_utils::prelude::*;
+use sp_core::U256;
+use sp_runtime::traits::{Dispatchable, StaticLookup};
+
+/// Alias for the Balance type used by `pallet_conviction_voting`.
+pub type BalanceOf<Runtime> = pallet_conviction_voting::BalanceOf<Runtime>;
+
+/// A precompile to wrap the functionality from `pallet_conviction_voting`.
+pub struct ConvictionVotingPrecompile<Runtime>(PhantomData<Runtime>);
+
+#[precompile_utils::precompile]
+impl<Runtime> ConvictionVotingPrecompile<Runtime>
+where
+    Runtime: pallet_conviction_voting::Config + pallet_evm::Config + frame_system::Config,
+    PollIndexOf<Runtime>: TryFrom<u32>,
+    ClassOf<Runtime>: TryFrom<u16>,
+    BalanceOf<Runtime>: TryFrom<U256>,
+    Runtime::RuntimeCall: Dispatchable<PostInfo = PostDispatchInfo> + GetDispatchInfo,
+    Runtime::RuntimeCall: From<pallet_conviction_voting::Call<Runtime>>,
+    <Runtime::RuntimeCall as Dispatchable>::RuntimeOrigin: From<Option<Runtime::AccountId>>,
+    <Runtime::RuntimeCall as Dispatchable>::RuntimeOrigin: OriginTrait,
+{
+    #[precompile::public("voteYes(uint32,uint256,uint8)")]
+    fn vote_yes(
+        handle: &mut impl PrecompileHandle,
+        poll_index: u32,
+        vote_amount: U256,
+        conviction: u8,
+    ) -> EvmResult {
+        Self::vote_standard(handle, poll_index, true, vote_amount, conviction)
+    }
+
+    #[precompile::public("voteNo(uint32,uint256,uint8)")]
+    fn vote_no(
+        handle: &mut impl PrecompileHandle,
+        poll_index: u32,
+        vote_amount: U256,
+        conviction: u8,
+    ) -> EvmResult {
+        Self::vote_standard(handle, poll_index, false, vote_amount, conviction)
+    }
+
+    #[precompile::public("voteSplit(uint32,uint256,uint256)")]
+    fn vote_split(
+        handle: &mut impl PrecompileHandle,
+        poll_index: u32,
+        aye: U256,
+        nay: U256,
+    ) -> EvmResult {
+        let poll_index = Self::u32_to_poll_index(poll_index).in_field("pollIndex")?;
+        let vote = AccountVote::Split {
+            aye: Self::u256_to_amount(aye).in_field("aye")?,
+            nay: Self::u256_to_amount(nay).in_field("nay")?,
+        };
+
+        Self::dispatch(
+            handle,
+            pallet_conviction_voting::Call::<Runtime>::vote { poll_index, vote },
+        )
+    }
+
+    #[precompile::public("voteSplitAbstain(uint32,uint256,uint256,uint256)")]
+    fn vote_split_abstain(
+        handle: &mut impl PrecompileHandle,
+        poll_index: u32,
+        aye: U256,
+        nay: U256,
+        abstain: U256,
+    ) -> EvmResult {
+        let poll_index = Self::u32_to_poll_index(poll_index).in_field("pollIndex")?;
+        let vote = AccountVote::SplitAbstain {
+            aye: Self::u256_to_amount(aye).in_field("aye")?,
+            nay: Self::u256_to_amount(nay).in_field("nay")?,
+            abstain: Self::u256_to_amount(abstain).in_field("abstain")?,
+        };
+
+        Self::dispatch(
+            handle,
+            pallet_conviction_voting::Call::<Runtime>::vote { poll_index, vote },
+        )
+    }
+
+    #[precompile::public("removeVote(uint32)")]
+    fn remove_vote(handle: &mut impl PrecompileHandle, poll_index: u32) -> EvmResult {
+        let index = Self::u32_to_poll_index(poll_index).in_field("pollIndex")?;
+
+        Self::dispatch(
+            handle,
+            pallet_conviction_voting::Call::<Runtime>::remove_vote { class: None, index },
+        )
+    }
+
+    #[precompile::public("delegate(uint16,address,uint8,uint256)")]
+    fn delegate(
+        handle: &mut impl PrecompileHandle,
+        track_id: u16,
+        representative: Address,
+        conviction: u8,
+        amount: U256,
+    ) -> EvmResult {
+        let class = Self::u16_to_class(track_id).in_field("trackId")?;
+        let to = Runtime::AddressMapping::into_account_id(representative.into());
+        let conviction = Self::u8_to_conviction(conviction).in_field("conviction")?;
+        let balance = Self::u256_to_amount(amount).in_field("amount")?;
+
+        Self::dispatch(
+            handle,
+            pallet_conviction_voting::Call::<Runtime>::delegate {
+                class,
+                to: Runtime::Lookup::unlookup(to),
+                conviction,
+                balance,
+            },
+        )
+    }
+
+    #[precompile::public("undelegate(uint16)")]
+    fn undelegate(handle: &mut impl PrecompileHandle, track_id: u16) -> EvmResult {
+        let class = Self::u16_to_class(track_id).in_field("trackId")?;
+
+        Self::dispatch(
+            handle,
+            pallet_conviction_voting::Call::<Runtime>::undelegate { class },
+        )
+    }
+
+    #[precompile::public("unlock(uint16,address)")]
+    fn unlock(handle: &mut impl PrecompileHandle, track_id: u16, target: Address) -> EvmResult {
+        let class = Self::u16_to_class(track_id).in_field("trackId")?;
+        let target = Runtime::AddressMapping::into_account_id(target.into());
+
+        Self::dispatch(
+            handle,
+            pallet_conviction_voting::Call::<Runtime>::unlock {
+                class,
+                target: Runtime::Lookup::unlookup(target),
+            },
+        )
+    }
+
+    fn vote_standard(
+        handle: &mut impl PrecompileHandle,
+        poll_index: u32,
+        aye: bool,
+        vote_amount: U256,
+        conviction: u8,
+    ) -> EvmResult {
+        let poll_index = Self::u32_to_poll_index(poll_index).in_field("pollIndex")?;
+        let vote = AccountVote::Standard {
+            vote: Vote {
+                aye,
+                conviction: Self::u8_to_conviction(conviction).in_field("conviction")?,
+            },
+            balance: Self::u256_to_amount(vote_amount).in_field("voteAmount")?,
+        };
+
+        Self::dispatch(
+            handle,
+            pallet_conviction_voting::Call::<Runtime>::vote { poll_index, vote },
+        )
+    }
+
+    fn dispatch(
+        handle: &mut impl PrecompileHandle,
+        call: pallet_conviction_voting::Call<Runtime>,
+    ) -> EvmResult {
+        let origin = Runtime::AddressMapping::into_account_id(handle.context().caller);
+        RuntimeHelper::<Runtime>::try_dispatch(handle, Some(origin).into(), call)?;
+
+        Ok(())
+    }
+
+    fn u8_to_conviction(conviction: u8) -> MayRevert<Conviction> {
+        conviction
+            .try_into()
+            .map_err(|_| RevertReason::custom("must be an integer in [0, 6]").into())
+    }
+
+    fn u32_to_poll_index(index: u32) -> MayRevert<PollIndexOf<Runtime>> {
+        index
+            .try_into()
+            .map_err(|_| RevertReason::value_is_too_large("index type").into())
+    }
+
+    fn u16_to_class(class: u16) -> MayRevert<ClassOf<Runtime>> {
+        class
+            .try_into()
+            .map_err(|_| RevertReason::value_is_too_large("class type").into())
+    }
+
+    fn u256_to_amount(value: U256) -> MayRevert<BalanceOf<Runtime>> {
+        value
+            .try_into()
+            .map_err(|_| RevertReason::value_is_too_large("balance type").into())
+    }
+}
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
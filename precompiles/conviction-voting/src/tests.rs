@@ -0,0 +1,146 @@
+use crate::{mock::*, *};
+use codec::Encode;
+use frame_support::{
+    assert_ok,
+    traits::{schedule::DispatchTime, Bounded},
+};
+use pallet_conviction_voting::Voting;
+use precompile_utils::testing::*;
+use sp_core::H256;
+use sp_runtime::traits::Hash;
+
+type PCall = ConvictionVotingPrecompileCall<Runtime>;
+
+fn precompiles() -> Precompiles<Runtime> {
+    PrecompilesValue::get()
+}
+
+fn balances() -> ExtBuilder {
+    ExtBuilder::default().with_balances(vec![(Alice.into(), 1000), (Bob.into(), 1000)])
+}
+
+fn dispatch_error(output: &[u8], error: &str) -> bool {
+    core::str::from_utf8(output).map_or(false, |reason| reason.contains(error))
+}
+
+/// Submits a referendum on the root track as Bob, its index being 0.
+fn submit_referendum() {
+    let proposal = RuntimeCall::System(frame_system::Call::remark { remark: vec![] }).encode();
+    assert_ok!(Preimage::note_preimage(
+        RuntimeOrigin::signed(Bob.into()),
+        proposal.clone()
+    ));
+    let hash: H256 = <Runtime as frame_system::Config>::Hashing::hash(&proposal);
+    assert_ok!(Referenda::submit(
+        RuntimeOrigin::signed(Bob.into()),
+        Box::new(frame_system::RawOrigin::Root.into()),
+        Bounded::Lookup {
+            hash,
+            len: proposal.len() as u32
+        },
+        DispatchTime::After(10)
+    ));
+}
+
+/// Alice's votes cast on the root track.
+fn root_track_votes() -> Vec<(u32, AccountVote<Balance>)> {
+    match pallet_conviction_voting::VotingFor::<Runtime>::get(AccountId::from(Alice), 0) {
+        Voting::Casting(casting) => casting.votes.into_inner(),
+        Voting::Delegating(_) => Vec::new(),
+    }
+}
+
+#[test]
+fn selectors() {
+    assert!(PCall::vote_yes_selectors().contains(&0xda9df518));
+    assert!(PCall::vote_no_selectors().contains(&0xcc600eba));
+    assert!(PCall::vote_split_selectors().contains(&0xdd6c52a4));
+    assert!(PCall::vote_split_abstain_selectors().contains(&0x52004540));
+    assert!(PCall::remove_vote_selectors().contains(&0x79cae220));
+    assert!(PCall::delegate_selectors().contains(&0x681750e8));
+    assert!(PCall::undelegate_selectors().contains(&0x98be4094));
+    assert!(PCall::unlock_selectors().contains(&0x4259d98c));
+}
+
+#[test]
+fn vote_and_remove() {
+    balances().build().execute_with(|| {
+        submit_referendum();
+
+        precompiles()
+            .prepare_test(
+                Alice,
+                Precompile1,
+                PCall::vote_yes {
+                    poll_index: 0,
+                    vote_amount: 10.into(),
+                    conviction: 1,
+                },
+            )
+            .expect_no_logs()
+            .execute_returns(());
+        assert_eq!(
+            root_track_votes(),
+            vec![(
+                0,
+                AccountVote::Standard {
+                    vote: Vote {
+                        aye: true,
+                        conviction: Conviction::Locked1x,
+                    },
+                    balance: 10,
+                }
+            )]
+        );
+
+        precompiles()
+            .prepare_test(
+                Alice,
+                Precompile1,
+                PCall::vote_split {
+                    poll_index: 0,
+                    aye: 3.into(),
+                    nay: 2.into(),
+                },
+            )
+            .execute_returns(());
+        assert_eq!(
+            root_track_votes(),
+            vec![(0, AccountVote::Split { aye: 3, nay: 2 })]
+        );
+
+        precompiles()
+            .prepare_test(Alice, Precompile1, PCall::remove_vote { poll_index: 0 })
+            .execute_returns(());
+        assert!(root_track_votes().is_empty());
+    });
+}
+
+#[test]
+fn reverts() {
+    balances().build().execute_with(|| {
+        precompiles()
+            .prepare_test(
+                Alice,
+                Precompile1,
+                PCall::vote_yes {
+                    poll_index: 0,
+                    vote_amount: 1.into(),
+                    conviction: 7,
+                },
+            )
+            .execute_reverts(|output| output == b"conviction: must be an integer in [0, 6]");
+        precompiles()
+            .prepare_test(
+                Alice,
+                Precompile1,
+                PCall::vote_no {
+                    poll_index: 0,
+                    vote_amount: 1.into(),
+                    conviction: 0,
+                },
+            )
+            .execute_reverts(|output| dispatch_error(output, "NotOngoing"));
+        assert!(root_track_votes().is_empty());
+    });
+}
@@ -0,0 +1,177 @@
+// SPDX-License-Identifier: GPL-3.0-only
+pragma solidity >=0.8.3;
+
+/// @dev The Identity contract's address.
+address constant IDENTITY_ADDRESS = 0x000000000000000000000000000000000000080A;
+
+/// @dev The Identity contract's instance.
+Identity constant IDENTITY_CONTRACT = Identity(IDENTITY_ADDRESS);
+
+/// @title Pallet Identity Interface
+/// @dev The interface through which solidity contracts will interact with pallet-identity.
+/// Every call is dispatched with the caller as the account whose identity is managed.
+interface Identity {
+    /// @dev Raw data of at most 32 bytes, or the 32 bytes hash of hashed data.
+    struct Data {
+        /// Is `true` if it contains data.
+        bool hasData;
+        /// The contained value.
+        bytes value;
+    }
+
+    /// @dev An additional field of an identity.
+    struct Additional {
+        /// The key of the field.
+        Data key;
+        /// The value of the field.
+        Data value;
+    }
+
+    /// @dev Information of an identity.
+    struct IdentityInfo {
+        /// Additional fields of the identity that are not catered for with the struct's explicit
+        /// fields.
+        Additional[] additional;
+        /// A reasonable display name for the controller of the account.
+        Data display;
+        /// The full legal name in the local jurisdiction of the entity.
+        Data legal;
+        /// A representative website held by the controller of the account.
+        Data web;
+        /// The Riot/Matrix handle held by the controller of the account.
+        Data riot;
+        /// The email address of the controller of the account.
+        Data email;
+        /// Is `true` if `pgpFingerprint` is set.
+        bool hasPgpFingerprint;
+        /// The PGP/GPG public key fingerprint of the controller of the account, 20 bytes.
+        bytes pgpFingerprint;
+        /// A graphic image representing the controller of the account.
+        Data image;
+        /// The Twitter identity of the controller of the account.
+        Data twitter;
+    }
+
+    /// @dev A judgement given by a registrar, only one of the flags is `true`.
+    struct Judgement {
+        /// The default value; no opinion is held.
+        bool isUnknown;
+        /// No judgement is yet in place, but a deposit is reserved as payment for providing one.
+        bool isFeePaid;
+        /// The deposit reserved for providing a judgement.
+        uint256 feePaidDeposit;
+        /// The data appears to be reasonably acceptable in terms of its accuracy.
+        bool isReasonable;
+        /// The target is known directly by the registrar and the registrar can fully attest to
+        /// the data being accurate.
+        bool isKnownGood;
+        /// The data was once good but is currently out of date.
+        bool isOutOfDate;
+        /// The data is imprecise or of sufficiently low-quality to be problematic.
+        bool isLowQuality;
+        /// The data is erroneous.
+        bool isErroneous;
+    }
+
+    /// @dev Judgement given by a registrar.
+    struct JudgementInfo {
+        /// The index of the registrar.
+        uint32 registrarIndex;
+        /// The judgement of the registrar.
+        Judgement judgement;
+    }
+
+    /// @dev Registered identity of an account.
+    struct Registration {
+        /// Is `false` if the account has no identity.
+        bool isValid;
+        /// The judgements given by registrars.
+        JudgementInfo[] judgements;
+        /// The deposit reserved for the identity.
+        uint256 deposit;
+        /// The information of the identity.
+        IdentityInfo info;
+    }
+
+    /// @dev Super account of a sub account.
+    struct SuperOf {
+        /// Is `false` if the account is not a sub account.
+        bool isValid;
+        /// The super account.
+        address account;
+        /// The name of the sub account.
+        Data data;
+    }
+
+    /// @dev Sub accounts of an account.
+    struct SubsOf {
+        /// The deposit reserved for the sub accounts.
+        uint256 deposit;
+        /// The sub accounts.
+        address[] accounts;
+    }
+
+    /// @dev A registrar.
+    struct Registrar {
+        /// Is `false` if the registrar has been removed.
+        bool isValid;
+        /// The index of the registrar.
+        uint32 index;
+        /// The account of the registrar.
+        address account;
+        /// The fee required to be paid for a judgement.
+        uint256 fee;
+        /// The fields the registrar cares about, as the bitflags of the legacy `IdentityField`.
+        uint64 fields;
+    }
+
+    /// @dev Retrieve the identity of an account.
+    /// @custom:selector f0eb5e54
+    /// @param who The account.
+    function identity(address who) external view returns (Registration memory);
+
+    /// @dev Retrieve the super account of a sub account.
+    /// @custom:selector c18110d6
+    /// @param who The sub account.
+    function superOf(address who) external view returns (SuperOf memory);
+
+    /// @dev Retrieve the sub accounts of an account.
+    /// @custom:selector 3f08986b
+    /// @param who The account.
+    function subsOf(address who) external view returns (SubsOf memory);
+
+    /// @dev Retrieve the registrars.
+    /// @custom:selector e88e512e
+    function registrars() external view returns (Registrar[] memory);
+
+    /// @dev Set the identity of the caller. Only raw data of at most 32 bytes is accepted.
+    /// @custom:selector 7e08b4cb
+    /// @param info The identity information.
+    function setIdentity(IdentityInfo memory info) external;
+
+    /// @dev Clear the identity of the caller.
+    /// @custom:selector 7a6a10c7
+    function clearIdentity() external;
+
+    /// @dev Request a judgement from a registrar, paying a fee.
+    /// @custom:selector d523ceb9
+    /// @param registrarIndex The index of the registrar.
+    /// @param maxFee The maximum fee that may be paid.
+    function requestJudgement(uint32 registrarIndex, uint256 maxFee) external;
+
+    /// @dev An identity was set.
+    /// @custom:selector eb08135b86fecead18fa00540531bb9fad981bcce5c31c9ee0c0481988ee6888
+    /// @param who The account of the identity.
+    event IdentitySet(address indexed who);
+
+    /// @dev An identity was cleared.
+    /// @custom:selector a777f634d44930c497bd4b851aefd038a52da2f15f7edebdb21f60877d2ac23f
+    /// @param who The account of the identity.
+    event IdentityCleared(address indexed who);
+
+    /// @dev A judgement was requested.
+    /// @custom:selector 4dcc1a1d64e8f4fff56a8c7b79fb3ccd1d43d395885c8055b43ffcd19973cce1
+    /// @param who The account of the identity.
+    /// @param registrarIndex The index of the registrar.
+    event JudgementRequested(address indexed who, uint32 registrarIndex);
+}
@@ -0,0 +1,519 @@
+// Copyright 2019-2022 PureStake Inc.
+// This file is part of SCS Chain. It is derived from Moonbeam's precompiles
+// (https://github.com/moonbeam-foundation/moonbeam).
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Precompile to interact with `pallet_identity` from the EVM.
+//!
+//! Only the legacy `IdentityInfo` identity information is supported. Calls are dispatched as a
+//! signed origin of the caller, which is the account whose identity is managed.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::MaxEncodedLen;
+use core::marker::PhantomData;
+use fp_evm::PrecompileHandle;
+use frame_support::{
+    dispatch::{GetDispatchInfo, PostDispatchInfo},
+    traits::{ConstU32, Currency, Get, OriginTrait},
+};
+use pallet_evm::AddressMapping;
+use pallet_identity::{
+    legacy::IdentityInfo as PalletIdentityInfo, Data as PalletData, Judgement as PalletJudgement,
+    RegistrarInfo, Registration as PalletRegistration,
+};
+use polkadot_sdk::{frame_support, frame_system, pallet_identity, sp_runtime, sp_std};
+use precompile_utils::prelude::*;
+use sp_core::{H160, U256};
+use sp_runtime::traits::Dispatchable;
+use sp_std::{boxed::Box, vec::Vec};
+
+/// Alias for the Balance type used by `pallet_identity`.
+pub type BalanceOf<Runtime> = <<Runtime as pallet_identity::Config>::Currency as Currency<
+    <Runtime as frame_system::Config>::AccountId,
+>>::Balance;
+
+/// Solidity selector of the IdentitySet log, which is the Keccak of the Log signature.
+pub const SELECTOR_LOG_IDENTITY_SET: [u8; 32] = keccak256!("IdentitySet(address)");
+
+/// Solidity selector of the IdentityCleared log, which is the Keccak of the Log signature.
+pub const SELECTOR_LOG_IDENTITY_CLEARED: [u8; 32] = keccak256!("IdentityCleared(address)");
+
+/// Solidity selector of the JudgementRequested log, which is the Keccak of the Log signature.
+pub const SELECTOR_LOG_JUDGEMENT_REQUESTED: [u8; 32] =
+    keccak256!("JudgementRequested(address,uint32)");
+
+/// Raw data of at most 32 bytes, or the 32 bytes hash of hashed data.
+#[derive(Eq, PartialEq, Debug, solidity::Codec)]
+pub struct Data {
+    pub has_data: bool,
+    pub value: BoundedBytes<ConstU32<32>>,
+}
+
+/// An additional field of an identity, as a key and a value.
+#[derive(Eq, PartialEq, Debug, solidity::Codec)]
+pub struct Additional {
+    pub key: Data,
+    pub value: Data,
+}
+
+/// The legacy `IdentityInfo` of an identity.
+#[derive(Eq, PartialEq, Debug, solidity::Codec)]
+pub struct IdentityInfo<FieldLimit> {
+    pub additional: BoundedVec<Additional, FieldLimit>,
+    pub display: Data,
+    pub legal: Data,
+    pub web: Data,
+    pub riot: Data,
+    pub email: Data,
+    pub has_pgp_fingerprint: bool,
+    pub pgp_fingerprint: BoundedBytes<ConstU32<20>>,
+    pub image: Data,
+    pub twitter: Data,
+}
+
+/// A registrar judgement, as one flag per `Judgement` variant.
+#[derive(Eq, PartialEq, Debug, solidity::Codec)]
+pub struct Judgement {
+    pub is_unknown: bool,
+    pub is_fee_paid: bool,
+    pub fee_paid_deposit: U256,
+    pub is_reasonable: bool,
+    pub is_known_good: bool,
+    pub is_out_of_date: bool,
+    pub is_low_quality: bool,
+    pub is_erroneous: bool,
+}
+
+/// The judgement given by a registrar.
+#[derive(Eq, PartialEq, Debug, solidity::Codec)]
+pub struct JudgementInfo {
+    pub registrar_index: u32,
+    pub judgement: Judgement,
+}
+
+/// The registered identity of an account, `is_valid` is false if it has none.
+#[derive(Eq, PartialEq, Debug, solidity::Codec)]
+pub struct Registration<FieldLimit> {
+    pub is_valid: bool,
+    pub judgements: Vec<JudgementInfo>,
+    pub deposit: U256,
+    pub info: IdentityInfo<FieldLimit>,
+}
+
+/// The super account of a sub account, `is_valid` is false if it has none.
+#[derive(Eq, PartialEq, Debug, solidity::Codec)]
+pub struct SuperOf {
+    pub is_valid: bool,
+    pub account: Address,
+    pub data: Data,
+}
+
+/// The sub accounts of an account and the deposit reserved for them.
+#[derive(Eq, PartialEq, Debug, solidity::Codec)]
+pub struct SubsOf {
+    pub deposit: U256,
+    pub accounts: Vec<Address>,
+}
+
+/// A registrar, `is_valid` is false if it has been removed.
+#[derive(Eq, PartialEq, Debug, solidity::Codec)]
+pub struct Registrar {
+    pub is_valid: bool,
+    pub index: u32,
+    pub account: Address,
+    pub fee: U256,
+    pub fields: u64,
+}
+
+/// A precompile to wrap the functionality from `pallet_identity`.
+pub struct IdentityPrecompile<Runtime, MaxAdditionalFields>(
+    PhantomData<(Runtime, MaxAdditionalFields)>,
+);
+
+#[precompile_utils::precompile]
+impl<Runtime, MaxAdditionalFields> IdentityPrecompile<Runtime, MaxAdditionalFields>
+where
+    MaxAdditionalFields: Get<u32> + 'static,
+    Runtime: pallet_identity::Config<IdentityInformation = PalletIdentityInfo<MaxAdditionalFields>>
+        + pallet_evm::Config,
+    Runtime::AccountId: Into<H160>,
+    Runtime::RuntimeCall: Dispatchable<PostInfo = PostDispatchInfo> + GetDispatchInfo,
+    Runtime::RuntimeCall: From<pallet_identity::Call<Runtime>>,
+    <Runtime::RuntimeCall as Dispatchable>::RuntimeOrigin: From<Option<Runtime::AccountId>>,
+    <Runtime::RuntimeCall as Dispatchable>::RuntimeOrigin: OriginTrait,
+    BalanceOf<Runtime>: TryFrom<U256> + Into<U256>,
+{
+    #[precompile::public(
+        "setIdentity((((bool,bytes),(bool,bytes))[],(bool,bytes),(bool,bytes),(bool,bytes),\
+        (bool,bytes),(bool,bytes),bool,bytes,(bool,bytes),(bool,bytes)))"
+    )]
+    fn set_identity(
+        handle: &mut impl PrecompileHandle,
+        info: IdentityInfo<MaxAdditionalFields>,
+    ) -> EvmResult {
+        handle.record_log_costs_manual(2, 0)?;
+
+        let info = Self::identity_to_input(info)?;
+
+        let caller = handle.context().caller;
+        let origin = Runtime::AddressMapping::into_account_id(caller);
+        RuntimeHelper::<Runtime>::try_dispatch(
+            handle,
+            Some(origin).into(),
+            pallet_identity::Call::<Runtime>::set_identity {
+                info: Box::new(info),
+            },
+        )?;
+
+        log2(
+            handle.context().address,
+            SELECTOR_LOG_IDENTITY_SET,
+            caller,
+            solidity::encode_event_data(()),
+        )
+        .record(handle)?;
+
+        Ok(())
+    }
+
+    #[precompile::public("clearIdentity()")]
+    fn clear_identity(handle: &mut impl PrecompileHandle) -> EvmResult {
+        handle.record_log_costs_manual(2, 0)?;
+
+        let caller = handle.context().caller;
+        let origin = Runtime::AddressMapping::into_account_id(caller);
+        RuntimeHelper::<Runtime>::try_dispatch(
+            handle,
+            Some(origin).into(),
+            pallet_identity::Call::<Runtime>::clear_identity {},
+        )?;
+
+        log2(
+            handle.context().address,
+            SELECTOR_LOG_IDENTITY_CLEARED,
+            caller,
+            solidity::encode_event_data(()),
+        )
+        .record(handle)?;
+
+        Ok(())
+    }
+
+    #[precompile::public("requestJudgement(uint32,uint256)")]
+    fn request_judgement(
+        handle: &mut impl PrecompileHandle,
+        registrar_index: u32,
+        max_fee: U256,
+    ) -> EvmResult {
+        handle.record_log_costs_manual(2, 32)?;
+
+        let max_fee = Self::u256_to_amount(max_fee).in_field("maxFee")?;
+
+        let caller = handle.context().caller;
+        let origin = Runtime::AddressMapping::into_account_id(caller);
+        RuntimeHelper::<Runtime>::try_dispatch(
+            handle,
+            Some(origin).into(),
+            pallet_identity::Call::<Runtime>::request_judgement {
+                reg_index: registrar_index,
+                max_fee,
+            },
+        )?;
+
+        log2(
+            handle.context().address,
+            SELECTOR_LOG_JUDGEMENT_REQUESTED,
+            caller,
+            solidity::encode_event_data(registrar_index),
+        )
+        .record(handle)?;
+
+        Ok(())
+    }
+
+    #[precompile::public("identity(address)")]
+    #[precompile::view]
+    fn identity(
+        handle: &mut impl PrecompileHandle,
+        who: Address,
+    ) -> EvmResult<Registration<MaxAdditionalFields>> {
+        // Storage item: IdentityOf:
+        // Twox64Concat(8) + AccountId(20) + Registration + Option<Username>
+        handle.record_db_read::<Runtime>(
+            28 + PalletRegistration::<
+                BalanceOf<Runtime>,
+                Runtime::MaxRegistrars,
+                Runtime::IdentityInformation,
+            >::max_encoded_len()
+                + 5
+                + Runtime::MaxUsernameLength::get() as usize,
+        )?;
+
+        let who = Runtime::AddressMapping::into_account_id(who.into());
+        let registration =
+            pallet_identity::Pallet::<Runtime>::identity(who).map(|(registration, _)| registration);
+
+        Ok(match registration {
+            Some(registration) => Registration {
+                is_valid: true,
+                judgements: registration
+                    .judgements
+                    .into_iter()
+                    .map(|(registrar_index, judgement)| JudgementInfo {
+                        registrar_index,
+                        judgement: Self::judgement_to_output(judgement),
+                    })
+                    .collect(),
+                deposit: registration.deposit.into(),
+                info: Self::identity_to_output(registration.info),
+            },
+            None => Registration {
+                is_valid: false,
+                judgements: Vec::new(),
+                deposit: U256::zero(),
+                info: IdentityInfo {
+                    additional: Vec::new().into(),
+                    display: Self::data_to_output(PalletData::None),
+                    legal: Self::data_to_output(PalletData::None),
+                    web: Self::data_to_output(PalletData::None),
+                    riot: Self::data_to_output(PalletData::None),
+                    email: Self::data_to_output(PalletData::None),
+                    has_pgp_fingerprint: false,
+                    pgp_fingerprint: Vec::new().into(),
+                    image: Self::data_to_output(PalletData::None),
+                    twitter: Self::data_to_output(PalletData::None),
+                },
+            },
+        })
+    }
+
+    #[precompile::public("superOf(address)")]
+    #[precompile::view]
+    fn super_of(handle: &mut impl PrecompileHandle, who: Address) -> EvmResult<SuperOf> {
+        // Storage item: SuperOf:
+        // Blake2_128Concat(16) + AccountId(20) + AccountId(20) + Data(33)
+        handle.record_db_read::<Runtime>(89)?;
+
+        let who = Runtime::AddressMapping::into_account_id(who.into());
+
+        Ok(match pallet_identity::Pallet::<Runtime>::super_of(who) {
+            Some((account, data)) => SuperOf {
+                is_valid: true,
+                account: Address(account.into()),
+                data: Self::data_to_output(data),
+            },
+            None => SuperOf {
+                is_valid: false,
+                account: Address(H160::zero()),
+                data: Self::data_to_output(PalletData::None),
+            },
+        })
+    }
+
+    #[precompile::public("subsOf(address)")]
+    #[precompile::view]
+    fn subs_of(handle: &mut impl PrecompileHandle, who: Address) -> EvmResult<SubsOf> {
+        // Storage item: SubsOf:
+        // Twox64Concat(8) + AccountId(20) + Balance(16) + BoundedVec(AccountId(20) * MaxSubAccounts)
+        handle.record_db_read::<Runtime>(44 + 20 * Runtime::MaxSubAccounts::get() as usize)?;
+
+        let who = Runtime::AddressMapping::into_account_id(who.into());
+        let (deposit, accounts) = pallet_identity::Pallet::<Runtime>::subs_of(who);
+
+        Ok(SubsOf {
+            deposit: deposit.into(),
+            accounts: accounts
+                .into_iter()
+                .map(|account| Address(account.into()))
+                .collect(),
+        })
+    }
+
+    #[precompile::public("registrars()")]
+    #[precompile::view]
+    fn registrars(handle: &mut impl PrecompileHandle) -> EvmResult<Vec<Registrar>> {
+        // Storage item: Registrars:
+        // BoundedVec(Option<RegistrarInfo> * MaxRegistrars)
+        handle.record_db_read::<Runtime>(
+            Option::<RegistrarInfo<BalanceOf<Runtime>, Runtime::AccountId, u64>>::max_encoded_len()
+                * Runtime::MaxRegistrars::get() as usize,
+        )?;
+
+        Ok(pallet_identity::Pallet::<Runtime>::registrars()
+            .into_iter()
+            .enumerate()
+            .map(|(index, registrar)| match registrar {
+                Some(registrar) => Registrar {
+                    is_valid: true,
+                    index: index as u32,
+                    account: Address(registrar.account.into()),
+                    fee: registrar.fee.into(),
+                    fields: registrar.fields,
+                },
+                None => Registrar {
+                    is_valid: false,
+                    index: index as u32,
+                    account: Address(H160::zero()),
+                    fee: U256::zero(),
+                    fields: 0,
+                },
+            })
+            .collect())
+    }
+
+    fn identity_to_input(
+        info: IdentityInfo<MaxAdditionalFields>,
+    ) -> MayRevert<PalletIdentityInfo<MaxAdditionalFields>> {
+        let additional: Vec<Additional> = info.additional.into();
+        let additional = additional
+            .into_iter()
+            .map(|field| {
+                Ok((
+                    Self::data_to_input(field.key)?,
+                    Self::data_to_input(field.value)?,
+                ))
+            })
+            .collect::<MayRevert<Vec<_>>>()
+            .in_field("additional")?
+            .try_into()
+            .map_err(|_| RevertReason::value_is_too_large("additional").in_field("additional"))?;
+
+        let pgp_fingerprint =
+            if info.has_pgp_fingerprint {
+                let fingerprint: Vec<u8> = info.pgp_fingerprint.into();
+                Some(fingerprint.try_into().map_err(|_| {
+                    RevertReason::custom("must be 20 bytes").in_field("pgpFingerprint")
+                })?)
+            } else {
+                None
+            };
+
+        Ok(PalletIdentityInfo {
+            additional,
+            display: Self::data_to_input(info.display).in_field("display")?,
+            legal: Self::data_to_input(info.legal).in_field("legal")?,
+            web: Self::data_to_input(info.web).in_field("web")?,
+            riot: Self::data_to_input(info.riot).in_field("riot")?,
+            email: Self::data_to_input(info.email).in_field("email")?,
+            pgp_fingerprint,
+            image: Self::data_to_input(info.image).in_field("image")?,
+            twitter: Self::data_to_input(info.twitter).in_field("twitter")?,
+        })
+    }
+
+    fn identity_to_output(
+        info: PalletIdentityInfo<MaxAdditionalFields>,
+    ) -> IdentityInfo<MaxAdditionalFields> {
+        IdentityInfo {
+            additional: info
+                .additional
+                .into_iter()
+                .map(|(key, value)| Additional {
+                    key: Self::data_to_output(key),
+                    value: Self::data_to_output(value),
+                })
+                .collect::<Vec<_>>()
+                .into(),
+            display: Self::data_to_output(info.display),
+            legal: Self::data_to_output(info.legal),
+            web: Self::data_to_output(info.web),
+            riot: Self::data_to_output(info.riot),
+            email: Self::data_to_output(info.email),
+            has_pgp_fingerprint: info.pgp_fingerprint.is_some(),
+            pgp_fingerprint: info
+                .pgp_fingerprint
+                .map(|fingerprint| fingerprint.to_vec())
+                .unwrap_or_default()
+                .into(),
+            image: Self::data_to_output(info.image),
+            twitter: Self::data_to_output(info.twitter),
+        }
+    }
+
+    /// Only raw data can be provided, hashed data is read back as its hash.
+    fn data_to_input(data: Data) -> MayRevert<PalletData> {
+        if !data.has_data {
+            return Ok(PalletData::None);
+        }
+
+        let value: Vec<u8> = data.value.into();
+        Ok(PalletData::Raw(
+            value
+                .try_into()
+                .map_err(|_| RevertReason::value_is_too_large("data"))?,
+        ))
+    }
+
+    fn data_to_output(data: PalletData) -> Data {
+        let value: Vec<u8> = match data {
+            PalletData::None => {
+                return Data {
+                    has_data: false,
+                    value: Vec::new().into(),
+                }
+            }
+            PalletData::Raw(value) => value.into(),
+            PalletData::BlakeTwo256(hash)
+            | PalletData::Sha256(hash)
+            | PalletData::Keccak256(hash)
+            | PalletData::ShaThree256(hash) => hash.to_vec(),
+        };
+
+        Data {
+            has_data: true,
+            value: value.into(),
+        }
+    }
+
+    fn judgement_to_output(judgement: PalletJudgement<BalanceOf<Runtime>>) -> Judgement {
+        let mut output = Judgement {
+            is_unknown: false,
+            is_fee_paid: false,
+            fee_paid_deposit: U256::zero(),
+            is_reasonable: false,
+            is_known_good: false,
+            is_out_of_date: false,
+            is_low_quality: false,
+            is_erroneous: false,
+        };
+
+        match judgement {
+            PalletJudgement::Unknown => output.is_unknown = true,
+            PalletJudgement::FeePaid(deposit) => {
+                output.is_fee_paid = true;
+                output.fee_paid_deposit = deposit.into();
+            }
+            PalletJudgement::Reasonable => output.is_reasonable = true,
+            PalletJudgement::KnownGood => output.is_known_good = true,
+            PalletJudgement::OutOfDate => output.is_out_of_date = true,
+            PalletJudgement::LowQuality => output.is_low_quality = true,
+            PalletJudgement::Erroneous => output.is_erroneous = true,
+        }
+
+        output
+    }
+
+    fn u256_to_amount(value: U256) -> MayRevert<BalanceOf<Runtime>> {
+        value
+            .try_into()
+            .map_err(|_| RevertReason::value_is_too_large("balance type").into())
+    }
+}
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
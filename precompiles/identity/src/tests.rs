@@ -0,0 +1,383 @@
+use crate::{mock::*, *};
+use codec::Encode;
+use frame_support::assert_ok;
+use precompile_utils::testing::*;
+
+type PCall = IdentityPrecompileCall<Runtime, MaxAdditionalFields>;
+
+fn precompiles() -> Precompiles<Runtime> {
+    PrecompilesValue::get()
+}
+
+fn balances() -> ExtBuilder {
+    ExtBuilder::default().with_balances(vec![
+        (Alice.into(), 1000),
+        (Bob.into(), 1000),
+        (Charlie.into(), 1000),
+    ])
+}
+
+fn db_read() -> u64 {
+    RuntimeHelper::<Runtime>::db_read_gas_cost()
+}
+
+fn dispatch_error(output: &[u8], error: &str) -> bool {
+    core::str::from_utf8(output).map_or(false, |reason| reason.contains(error))
+}
+
+fn data(value: &[u8]) -> Data {
+    Data {
+        has_data: true,
+        value: value.to_vec().into(),
+    }
+}
+
+fn no_data() -> Data {
+    Data {
+        has_data: false,
+        value: Vec::new().into(),
+    }
+}
+
+fn info() -> IdentityInfo<MaxAdditionalFields> {
+    IdentityInfo {
+        additional: vec![Additional {
+            key: data(b"discord"),
+            value: data(b"alice#0001"),
+        }]
+        .into(),
+        display: data(b"Alice"),
+        legal: no_data(),
+        web: data(b"https://alice.example"),
+        riot: no_data(),
+        email: data(b"alice@example.com"),
+        has_pgp_fingerprint: true,
+        pgp_fingerprint: [0x11; 20].to_vec().into(),
+        image: no_data(),
+        twitter: no_data(),
+    }
+}
+
+/// The deposit `pallet_identity` reserves for `info()`.
+fn deposit() -> Balance {
+    let info = IdentityPrecompile::<Runtime, MaxAdditionalFields>::identity_to_input(info())
+        .expect("info is valid");
+    10 + info.encoded_size() as Balance
+}
+
+fn set_identity(who: impl Into<H160>) {
+    precompiles()
+        .prepare_test(who, Precompile1, PCall::set_identity { info: info() })
+        .execute_returns(());
+}
+
+/// Adds Bob as registrar 0 with the given fee.
+fn add_registrar(fee: Balance) {
+    assert_ok!(Identity::add_registrar(RuntimeOrigin::root(), Bob.into()));
+    assert_ok!(Identity::set_fee(RuntimeOrigin::signed(Bob.into()), 0, fee));
+}
+
+#[test]
+fn selectors() {
+    assert!(PCall::set_identity_selectors().contains(&0x7e08b4cb));
+    assert!(PCall::clear_identity_selectors().contains(&0x7a6a10c7));
+    assert!(PCall::request_judgement_selectors().contains(&0xd523ceb9));
+    assert!(PCall::identity_selectors().contains(&0xf0eb5e54));
+    assert!(PCall::super_of_selectors().contains(&0xc18110d6));
+    assert!(PCall::subs_of_selectors().contains(&0x3f08986b));
+    assert!(PCall::registrars_selectors().contains(&0xe88e512e));
+}
+
+#[test]
+fn set_identity_reserves_the_deposit() {
+    balances().build().execute_with(|| {
+        precompiles()
+            .prepare_test(Alice, Precompile1, PCall::set_identity { info: info() })
+            .expect_log(log2(
+                Precompile1,
+                SELECTOR_LOG_IDENTITY_SET,
+                Alice,
+                solidity::encode_event_data(()),
+            ))
+            .execute_returns(());
+
+        assert_eq!(
+            Balances::reserved_balance(AccountId::from(Alice)),
+            deposit()
+        );
+
+        precompiles()
+            .prepare_test(
+                Bob,
+                Precompile1,
+                PCall::identity {
+                    who: Address(Alice.into()),
+                },
+            )
+            .expect_cost(db_read())
+            .expect_no_logs()
+            .execute_returns(Registration {
+                is_valid: true,
+                judgements: Vec::new(),
+                deposit: deposit().into(),
+                info: info(),
+            });
+    });
+}
+
+#[test]
+fn set_identity_reverts() {
+    balances().build().execute_with(|| {
+        let mut bad_fingerprint = info();
+        bad_fingerprint.pgp_fingerprint = [0x11; 10].to_vec().into();
+
+        precompiles()
+            .prepare_test(
+                Alice,
+                Precompile1,
+                PCall::set_identity {
+                    info: bad_fingerprint,
+                },
+            )
+            .execute_reverts(|output| output == b"pgpFingerprint: must be 20 bytes");
+
+        precompiles()
+            .prepare_test(David, Precompile1, PCall::set_identity { info: info() })
+            .execute_reverts(|output| dispatch_error(output, "InsufficientBalance"));
+
+        assert!(pallet_identity::IdentityOf::<Runtime>::get(AccountId::from(Alice)).is_none());
+    });
+}
+
+#[test]
+fn identity_of_an_unknown_account() {
+    balances().build().execute_with(|| {
+        precompiles()
+            .prepare_test(
+                Alice,
+                Precompile1,
+                PCall::identity {
+                    who: Address(Bob.into()),
+                },
+            )
+            .expect_cost(db_read())
+            .expect_no_logs()
+            .execute_returns(Registration {
+                is_valid: false,
+                judgements: Vec::new(),
+                deposit: U256::zero(),
+                info: IdentityInfo {
+                    additional: Vec::new().into(),
+                    display: no_data(),
+                    legal: no_data(),
+                    web: no_data(),
+                    riot: no_data(),
+                    email: no_data(),
+                    has_pgp_fingerprint: false,
+                    pgp_fingerprint: Vec::new().into(),
+                    image: no_data(),
+                    twitter: no_data(),
+                },
+            });
+    });
+}
+
+#[test]
+fn clear_identity_unreserves_the_deposit() {
+    balances().build().execute_with(|| {
+        set_identity(Alice);
+
+        precompiles()
+            .prepare_test(Alice, Precompile1, PCall::clear_identity {})
+            .expect_log(log2(
+                Precompile1,
+                SELECTOR_LOG_IDENTITY_CLEARED,
+                Alice,
+                solidity::encode_event_data(()),
+            ))
+            .execute_returns(());
+
+        assert_eq!(Balances::reserved_balance(AccountId::from(Alice)), 0);
+        assert!(pallet_identity::IdentityOf::<Runtime>::get(AccountId::from(Alice)).is_none());
+
+        precompiles()
+            .prepare_test(Alice, Precompile1, PCall::clear_identity {})
+            .execute_reverts(|output| dispatch_error(output, "NoIdentity"));
+    });
+}
+
+#[test]
+fn request_judgement_reserves_the_fee() {
+    balances().build().execute_with(|| {
+        add_registrar(5);
+        set_identity(Alice);
+
+        precompiles()
+            .prepare_test(
+                Alice,
+                Precompile1,
+                PCall::request_judgement {
+                    registrar_index: 0,
+                    max_fee: 10.into(),
+                },
+            )
+            .expect_log(log2(
+                Precompile1,
+                SELECTOR_LOG_JUDGEMENT_REQUESTED,
+                Alice,
+                solidity::encode_event_data(0u32),
+            ))
+            .execute_returns(());
+
+        assert_eq!(
+            Balances::reserved_balance(AccountId::from(Alice)),
+            deposit() + 5
+        );
+
+        precompiles()
+            .prepare_test(
+                Alice,
+                Precompile1,
+                PCall::identity {
+                    who: Address(Alice.into()),
+                },
+            )
+            .execute_returns(Registration {
+                is_valid: true,
+                judgements: vec![JudgementInfo {
+                    registrar_index: 0,
+                    judgement: Judgement {
+                        is_unknown: false,
+                        is_fee_paid: true,
+                        fee_paid_deposit: 5.into(),
+                        is_reasonable: false,
+                        is_known_good: false,
+                        is_out_of_date: false,
+                        is_low_quality: false,
+                        is_erroneous: false,
+                    },
+                }],
+                deposit: deposit().into(),
+                info: info(),
+            });
+    });
+}
+
+#[test]
+fn request_judgement_reverts() {
+    balances().build().execute_with(|| {
+        add_registrar(5);
+
+        let request = |registrar_index: u32, max_fee: U256| PCall::request_judgement {
+            registrar_index,
+            max_fee,
+        };
+
+        precompiles()
+            .prepare_test(Alice, Precompile1, request(0, 10.into()))
+            .execute_reverts(|output| dispatch_error(output, "NoIdentity"));
+
+        set_identity(Alice);
+
+        precompiles()
+            .prepare_test(Alice, Precompile1, request(0, 4.into()))
+            .execute_reverts(|output| dispatch_error(output, "FeeChanged"));
+        precompiles()
+            .prepare_test(Alice, Precompile1, request(1, 10.into()))
+            .execute_reverts(|output| dispatch_error(output, "EmptyIndex"));
+        precompiles()
+            .prepare_test(Alice, Precompile1, request(0, U256::MAX))
+            .execute_reverts(|output| output == b"maxFee: Value is too large for balance type");
+
+        assert_eq!(
+            Balances::reserved_balance(AccountId::from(Alice)),
+            deposit()
+        );
+    });
+}
+
+#[test]
+fn super_of_and_subs_of() {
+    balances().build().execute_with(|| {
+        set_identity(Alice);
+        assert_ok!(Identity::set_subs(
+            RuntimeOrigin::signed(Alice.into()),
+            vec![(
+                Charlie.into(),
+                PalletData::Raw(b"sub".to_vec().try_into().unwrap())
+            )]
+        ));
+
+        precompiles()
+            .prepare_test(
+                Bob,
+                Precompile1,
+                PCall::super_of {
+                    who: Address(Charlie.into()),
+                },
+            )
+            .expect_cost(db_read())
+            .expect_no_logs()
+            .execute_returns(SuperOf {
+                is_valid: true,
+                account: Address(Alice.into()),
+                data: data(b"sub"),
+            });
+        precompiles()
+            .prepare_test(
+                Bob,
+                Precompile1,
+                PCall::super_of {
+                    who: Address(Bob.into()),
+                },
+            )
+            .execute_returns(SuperOf {
+                is_valid: false,
+                account: Address(H160::zero()),
+                data: no_data(),
+            });
+
+        precompiles()
+            .prepare_test(
+                Bob,
+                Precompile1,
+                PCall::subs_of {
+                    who: Address(Alice.into()),
+                },
+            )
+            .expect_cost(db_read())
+            .expect_no_logs()
+            .execute_returns(SubsOf {
+                deposit: 5.into(),
+                accounts: vec![Address(Charlie.into())],
+            });
+    });
+}
+
+#[test]
+fn registrars() {
+    balances().build().execute_with(|| {
+        precompiles()
+            .prepare_test(Alice, Precompile1, PCall::registrars {})
+            .expect_cost(db_read())
+            .expect_no_logs()
+            .execute_returns(Vec::<Registrar>::new());
+
+        add_registrar(5);
+        assert_ok!(Identity::set_fields(
+            RuntimeOrigin::signed(Bob.into()),
+            0,
+            3
+        ));
+
+        precompiles()
+            .prepare_test(Alice, Precompile1, PCall::registrars {})
+            .execute_returns(vec![Registrar {
+                is_valid: true,
+                index: 0,
+                account: Address(Bob.into()),
+                fee: 5.into(),
+                fields: 3,
+            }]);
+    });
+}
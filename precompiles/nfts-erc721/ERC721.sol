@@ -0,0 +1,97 @@
+// SPDX-License-Identifier: GPL-3.0-only
+pragma solidity >=0.8.3;
+
+/// @title ERC721 interface of a `pallet_nfts` collection.
+/// @dev Every collection id `id` is reachable at the address
+/// `0xFFFFFFFE` ++ `0x000000000000000000000000` ++ `id` (big-endian u32).
+/// The token id of an item is its item id.
+interface IERC721 {
+    /// @dev Returns true if the contract implements the interface `interfaceId` (ERC-165).
+    /// @custom:selector 01ffc9a7
+    function supportsInterface(bytes4 interfaceId) external view returns (bool);
+
+    /// @dev Returns the number of tokens of the collection owned by `owner`.
+    /// @custom:selector 70a08231
+    function balanceOf(address owner) external view returns (uint256);
+
+    /// @dev Returns the owner of the token `tokenId`.
+    /// @custom:selector 6352211e
+    function ownerOf(uint256 tokenId) external view returns (address);
+
+    /// @dev Returns the account approved for the token `tokenId`, or the zero address.
+    /// @custom:selector 081812fc
+    function getApproved(uint256 tokenId) external view returns (address);
+
+    /// @dev Returns true if `operator` is approved for every token `owner` holds in the
+    /// collection.
+    /// @custom:selector e985e9c5
+    function isApprovedForAll(address owner, address operator)
+        external
+        view
+        returns (bool);
+
+    /// @dev Returns the metadata of the token `tokenId`.
+    /// @custom:selector c87b56dd
+    function tokenURI(uint256 tokenId) external view returns (string memory);
+
+    /// @dev Approves `approved` to transfer the token `tokenId`, replacing any previous
+    /// approval. The zero address clears the approvals.
+    /// @custom:selector 095ea7b3
+    function approve(address approved, uint256 tokenId) external;
+
+    /// @dev Approves or disapproves `operator` for every token msg.sender currently holds
+    /// in the collection. Tokens received afterwards are not covered.
+    /// @custom:selector a22cb465
+    function setApprovalForAll(address operator, bool approved) external;
+
+    /// @dev Transfers the token `tokenId` from `from` to `to`.
+    /// @custom:selector 23b872dd
+    function transferFrom(
+        address from,
+        address to,
+        uint256 tokenId
+    ) external;
+
+    /// @dev Transfers the token `tokenId` from `from` to `to`, checking that a contract
+    /// recipient accepts it.
+    /// @custom:selector 42842e0e
+    function safeTransferFrom(
+        address from,
+        address to,
+        uint256 tokenId
+    ) external;
+
+    /// @dev Transfers the token `tokenId` from `from` to `to`, checking that a contract
+    /// recipient accepts it. `data` is forwarded to `onERC721Received`.
+    /// @custom:selector b88d4fde
+    function safeTransferFrom(
+        address from,
+        address to,
+        uint256 tokenId,
+        bytes calldata data
+    ) external;
+
+    /// @dev Event emited when a token has been transferred.
+    /// @custom:selector ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef
+    event Transfer(
+        address indexed from,
+        address indexed to,
+        uint256 indexed tokenId
+    );
+
+    /// @dev Event emited when an account has been approved for a token.
+    /// @custom:selector 8c5be1e5ebec7d5bd14f71427d1e84f3dd0314c0f7b2291e5b200ac8c7c3b925
+    event Approval(
+        address indexed owner,
+        address indexed approved,
+        uint256 indexed tokenId
+    );
+
+    /// @dev Event emited when an operator has been approved or disapproved.
+    /// @custom:selector 17307eab39ab6107e8899845ad3d59bd9653f200f220920489ca2b5937696c31
+    event ApprovalForAll(
+        address indexed owner,
+        address indexed operator,
+        bool approved
+    );
+}
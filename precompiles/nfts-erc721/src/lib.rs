@@ -0,0 +1,650 @@
+//! Precompile exposing the collections of a `pallet_nfts` instance as ERC-721 contracts.
+//!
+//! Every collection id is mapped to a deterministic address by the runtime through
+//! [`AddressToCollectionId`], and the ERC-721 token id of an item is its item id. Calls to
+//! such an address are answered as if an ERC-721 contract was deployed there, and the usual
+//! `Transfer`/`Approval`/`ApprovalForAll` logs are emitted.
+//!
+//! ERC-721 approvals are owned by the precompile and kept in dedicated runtime storage maps,
+//! apart from the approvals of `pallet_nfts`. An operator is approved for every item of an
+//! owner in the collection, including items received later. The approval of a single item
+//! lapses once the item changes owner, even when it is transferred outside of the precompile.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::MaxEncodedLen;
+use core::marker::PhantomData;
+use fp_evm::{Context, ExitReason, PrecompileFailure, PrecompileHandle};
+use frame_support::{
+    ensure,
+    pallet_prelude::{NMapKey, ValueQuery},
+    storage::types::{StorageDoubleMap, StorageInstance, StorageNMap},
+    traits::{
+        tokens::nonfungibles_v2::{Inspect, InspectEnumerable, Transfer},
+        Get,
+    },
+    Blake2_128Concat,
+};
+use frame_system::pallet_prelude::BlockNumberFor;
+use pallet_evm::{AddressMapping, GasWeightMapping};
+use pallet_nfts::WeightInfo;
+use polkadot_sdk::{frame_support, frame_system, pallet_nfts, sp_std};
+use precompile_utils::{
+    prelude::*,
+    solidity::codec::{Codec, Reader, Writer},
+    substrate::TryDispatchError,
+};
+use sp_core::{H160, H256, U256};
+use sp_std::vec::Vec;
+
+/// Solidity selector of the Transfer log, which is the Keccak of the Log signature.
+pub const SELECTOR_LOG_TRANSFER: [u8; 32] = keccak256!("Transfer(address,address,uint256)");
+
+/// Solidity selector of the Approval log, which is the Keccak of the Log signature.
+pub const SELECTOR_LOG_APPROVAL: [u8; 32] = keccak256!("Approval(address,address,uint256)");
+
+/// Solidity selector of the ApprovalForAll log, which is the Keccak of the Log signature.
+pub const SELECTOR_LOG_APPROVAL_FOR_ALL: [u8; 32] =
+    keccak256!("ApprovalForAll(address,address,bool)");
+
+/// Selector of `onERC721Received(address,address,uint256,bytes)`, which an ERC-721 receiver
+/// contract must return to accept a safe transfer.
+pub const ERC721_RECEIVED: [u8; 4] = [0x15, 0x0b, 0x7a, 0x02];
+
+/// ERC-165 interface ids of the supported interfaces: ERC-165 and ERC-721.
+pub const SUPPORTED_INTERFACES: [[u8; 4]; 2] = [[0x01, 0xff, 0xc9, 0xa7], [0x80, 0xac, 0x58, 0xcd]];
+
+/// Alias for the Collection Id type for the provided Runtime and Instance.
+pub type CollectionIdOf<Runtime, Instance = ()> =
+    <Runtime as pallet_nfts::Config<Instance>>::CollectionId;
+
+/// Alias for the Item Id type for the provided Runtime and Instance.
+pub type ItemIdOf<Runtime, Instance = ()> = <Runtime as pallet_nfts::Config<Instance>>::ItemId;
+
+/// Maps collection ids to the address of their ERC-721 precompile and back.
+pub trait AddressToCollectionId<CollectionId> {
+    /// Returns the collection id answering at `address`, if `address` is a collection
+    /// precompile address.
+    fn address_to_collection_id(address: H160) -> Option<CollectionId>;
+
+    /// Returns the precompile address of `collection_id`.
+    fn collection_id_to_address(collection_id: CollectionId) -> H160;
+}
+
+/// ERC-165 interface id, encoded as the Solidity `bytes4` type.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InterfaceId(pub [u8; 4]);
+
+impl Codec for InterfaceId {
+    fn read(reader: &mut Reader) -> MayRevert<Self> {
+        let word: H256 = reader.read()?;
+        // A `bytes4` is left aligned in its word, which is padded with zeroes.
+        let (id, padding) = word.as_bytes().split_at(4);
+        if padding.iter().any(|byte| *byte != 0) {
+            return Err(RevertReason::custom("Invalid bytes4 padding").into());
+        }
+
+        let mut interface_id = [0u8; 4];
+        interface_id.copy_from_slice(id);
+        Ok(Self(interface_id))
+    }
+
+    fn write(writer: &mut Writer, value: Self) {
+        let mut word = H256::zero();
+        word.as_bytes_mut()[..4].copy_from_slice(&value.0);
+        H256::write(writer, word);
+    }
+
+    fn has_static_size() -> bool {
+        true
+    }
+
+    fn signature() -> String {
+        "bytes4".into()
+    }
+}
+
+/// Alias for the Account Id type of the provided Runtime.
+pub type AccountIdOf<Runtime> = <Runtime as frame_system::Config>::AccountId;
+
+/// Storage prefix of the approvals of single items.
+pub struct TokenApprovalsPrefix;
+
+impl StorageInstance for TokenApprovalsPrefix {
+    const STORAGE_PREFIX: &'static str = "TokenApprovals";
+
+    fn pallet_prefix() -> &'static str {
+        "Erc721NftsPrecompile"
+    }
+}
+
+/// Approvals of single items: collection => item => (owner, approved). The approval only holds
+/// while the item is owned by the account that gave it.
+pub type TokenApprovalsStorage<Runtime, Instance = ()> = StorageDoubleMap<
+    TokenApprovalsPrefix,
+    Blake2_128Concat,
+    CollectionIdOf<Runtime, Instance>,
+    Blake2_128Concat,
+    ItemIdOf<Runtime, Instance>,
+    (AccountIdOf<Runtime>, AccountIdOf<Runtime>),
+>;
+
+/// Storage prefix of the operator approvals.
+pub struct OperatorApprovalsPrefix;
+
+impl StorageInstance for OperatorApprovalsPrefix {
+    const STORAGE_PREFIX: &'static str = "OperatorApprovals";
+
+    fn pallet_prefix() -> &'static str {
+        "Erc721NftsPrecompile"
+    }
+}
+
+/// Operator approvals: (collection, owner, operator) => approved.
+pub type OperatorApprovalsStorage<Runtime, Instance = ()> = StorageNMap<
+    OperatorApprovalsPrefix,
+    (
+        NMapKey<Blake2_128Concat, CollectionIdOf<Runtime, Instance>>,
+        NMapKey<Blake2_128Concat, AccountIdOf<Runtime>>,
+        NMapKey<Blake2_128Concat, AccountIdOf<Runtime>>,
+    ),
+    bool,
+    ValueQuery,
+>;
+
+/// The ERC-721 precompile set. One precompile answers at the address of every existing
+/// collection.
+pub struct Erc721NftsPrecompileSet<Runtime, Instance: 'static = ()>(
+    PhantomData<(Runtime, Instance)>,
+);
+
+impl<Runtime, Instance> Default for Erc721NftsPrecompileSet<Runtime, Instance> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<Runtime, Instance> Erc721NftsPrecompileSet<Runtime, Instance> {
+    pub fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+#[precompile_utils::precompile]
+#[precompile::precompile_set]
+impl<Runtime, Instance> Erc721NftsPrecompileSet<Runtime, Instance>
+where
+    Instance: 'static,
+    Runtime: pallet_nfts::Config<Instance> + pallet_evm::Config + frame_system::Config,
+    Runtime: AddressToCollectionId<CollectionIdOf<Runtime, Instance>>,
+    Runtime::AccountId: Into<H160>,
+    ItemIdOf<Runtime, Instance>: TryFrom<U256>,
+{
+    /// PrecompileSet discriminant. Allows to know if the address maps to an existing
+    /// collection, and if this is the case which one.
+    #[precompile::discriminant]
+    fn discriminant(
+        address: H160,
+        gas: u64,
+    ) -> DiscriminantResult<CollectionIdOf<Runtime, Instance>> {
+        let extra_cost = RuntimeHelper::<Runtime>::db_read_gas_cost();
+        if gas < extra_cost {
+            return DiscriminantResult::OutOfGas;
+        }
+
+        let collection = match Runtime::address_to_collection_id(address) {
+            Some(collection) => collection,
+            None => return DiscriminantResult::None(extra_cost),
+        };
+
+        if pallet_nfts::Pallet::<Runtime, Instance>::collection_owner(&collection).is_some() {
+            DiscriminantResult::Some(collection, extra_cost)
+        } else {
+            DiscriminantResult::None(extra_cost)
+        }
+    }
+
+    #[precompile::public("supportsInterface(bytes4)")]
+    #[precompile::view]
+    fn supports_interface(
+        _collection: CollectionIdOf<Runtime, Instance>,
+        _handle: &mut impl PrecompileHandle,
+        interface_id: InterfaceId,
+    ) -> EvmResult<bool> {
+        Ok(SUPPORTED_INTERFACES.contains(&interface_id.0))
+    }
+
+    #[precompile::public("balanceOf(address)")]
+    #[precompile::view]
+    fn balance_of(
+        collection: CollectionIdOf<Runtime, Instance>,
+        handle: &mut impl PrecompileHandle,
+        owner: Address,
+    ) -> EvmResult<U256> {
+        let owner: H160 = owner.into();
+        ensure!(
+            !owner.is_zero(),
+            revert("Zero address is not a valid owner")
+        );
+        let owner = Runtime::AddressMapping::into_account_id(owner);
+
+        let mut balance = U256::zero();
+        Self::record_account_read(handle)?;
+        for _ in pallet_nfts::Pallet::<Runtime, Instance>::owned_in_collection(&collection, &owner)
+        {
+            Self::record_account_read(handle)?;
+            balance += U256::one();
+        }
+
+        Ok(balance)
+    }
+
+    #[precompile::public("ownerOf(uint256)")]
+    #[precompile::view]
+    fn owner_of(
+        collection: CollectionIdOf<Runtime, Instance>,
+        handle: &mut impl PrecompileHandle,
+        token_id: U256,
+    ) -> EvmResult<Address> {
+        let item = Self::u256_to_item(token_id).in_field("tokenId")?;
+
+        Ok(Address(Self::owner(handle, collection, item)?.into()))
+    }
+
+    #[precompile::public("getApproved(uint256)")]
+    #[precompile::view]
+    fn get_approved(
+        collection: CollectionIdOf<Runtime, Instance>,
+        handle: &mut impl PrecompileHandle,
+        token_id: U256,
+    ) -> EvmResult<Address> {
+        let item = Self::u256_to_item(token_id).in_field("tokenId")?;
+        let owner = Self::owner(handle, collection, item)?;
+
+        let approved =
+            Self::approved(handle, collection, item, &owner)?.map_or(H160::zero(), Into::into);
+
+        Ok(Address(approved))
+    }
+
+    #[precompile::public("isApprovedForAll(address,address)")]
+    #[precompile::view]
+    fn is_approved_for_all(
+        collection: CollectionIdOf<Runtime, Instance>,
+        handle: &mut impl PrecompileHandle,
+        owner: Address,
+        operator: Address,
+    ) -> EvmResult<bool> {
+        let owner = Runtime::AddressMapping::into_account_id(owner.into());
+        let operator = Runtime::AddressMapping::into_account_id(operator.into());
+
+        Self::is_operator(handle, collection, &owner, &operator)
+    }
+
+    #[precompile::public("tokenURI(uint256)")]
+    #[precompile::view]
+    fn token_uri(
+        collection: CollectionIdOf<Runtime, Instance>,
+        handle: &mut impl PrecompileHandle,
+        token_id: U256,
+    ) -> EvmResult<UnboundedBytes> {
+        let item = Self::u256_to_item(token_id).in_field("tokenId")?;
+        Self::owner(handle, collection, item)?;
+
+        // Storage item: ItemMetadataOf:
+        // Blake2_128(16) + CollectionId + Blake2_128(16) + ItemId
+        // + ItemMetadata[deposit(AccountId(21) + Balance(16)) + data(StringLimit)]
+        handle.record_db_read::<Runtime>(
+            69 + CollectionIdOf::<Runtime, Instance>::max_encoded_len()
+                + ItemIdOf::<Runtime, Instance>::max_encoded_len()
+                + <Runtime as pallet_nfts::Config<Instance>>::StringLimit::get() as usize,
+        )?;
+
+        // The metadata of an item is its attribute with an empty key.
+        Ok(
+            pallet_nfts::Pallet::<Runtime, Instance>::attribute(&collection, &item, &[])
+                .unwrap_or_default()
+                .as_slice()
+                .into(),
+        )
+    }
+
+    #[precompile::public("approve(address,uint256)")]
+    fn approve(
+        collection: CollectionIdOf<Runtime, Instance>,
+        handle: &mut impl PrecompileHandle,
+        approved: Address,
+        token_id: U256,
+    ) -> EvmResult {
+        handle.record_log_costs_manual(4, 0)?;
+
+        let approved: H160 = approved.into();
+        let item = Self::u256_to_item(token_id).in_field("tokenId")?;
+        let owner = Self::owner(handle, collection, item)?;
+
+        {
+            let caller = Runtime::AddressMapping::into_account_id(handle.context().caller);
+            ensure!(
+                caller == owner || Self::is_operator(handle, collection, &owner, &caller)?,
+                revert("Caller is not token owner or approved for all")
+            );
+
+            // An item has a single approved address, which replaces the previous one.
+            handle.record_cost(RuntimeHelper::<Runtime>::db_write_gas_cost())?;
+            if approved.is_zero() {
+                TokenApprovalsStorage::<Runtime, Instance>::remove(collection, item);
+            } else {
+                TokenApprovalsStorage::<Runtime, Instance>::insert(
+                    collection,
+                    item,
+                    (
+                        owner.clone(),
+                        Runtime::AddressMapping::into_account_id(approved),
+                    ),
+                );
+            }
+        }
+
+        log4(
+            handle.context().address,
+            SELECTOR_LOG_APPROVAL,
+            Into::<H160>::into(owner),
+            approved,
+            Self::token_topic(token_id),
+            Vec::new(),
+        )
+        .record(handle)?;
+
+        Ok(())
+    }
+
+    #[precompile::public("setApprovalForAll(address,bool)")]
+    fn set_approval_for_all(
+        collection: CollectionIdOf<Runtime, Instance>,
+        handle: &mut impl PrecompileHandle,
+        operator: Address,
+        approved: bool,
+    ) -> EvmResult {
+        handle.record_log_costs_manual(3, 32)?;
+
+        let operator: H160 = operator.into();
+        ensure!(
+            operator != handle.context().caller,
+            revert("Approve to caller")
+        );
+
+        {
+            let caller = Runtime::AddressMapping::into_account_id(handle.context().caller);
+            let operator = Runtime::AddressMapping::into_account_id(operator);
+
+            handle.record_cost(RuntimeHelper::<Runtime>::db_write_gas_cost())?;
+            if approved {
+                OperatorApprovalsStorage::<Runtime, Instance>::insert(
+                    (collection, caller, operator),
+                    true,
+                );
+            } else {
+                OperatorApprovalsStorage::<Runtime, Instance>::remove((
+                    collection, caller, operator,
+                ));
+            }
+        }
+
+        log3(
+            handle.context().address,
+            SELECTOR_LOG_APPROVAL_FOR_ALL,
+            handle.context().caller,
+            operator,
+            solidity::encode_event_data(approved),
+        )
+        .record(handle)?;
+
+        Ok(())
+    }
+
+    #[precompile::public("transferFrom(address,address,uint256)")]
+    fn transfer_from(
+        collection: CollectionIdOf<Runtime, Instance>,
+        handle: &mut impl PrecompileHandle,
+        from: Address,
+        to: Address,
+        token_id: U256,
+    ) -> EvmResult {
+        Self::transfer_inner(collection, handle, from.into(), to.into(), token_id)
+    }
+
+    #[precompile::public("safeTransferFrom(address,address,uint256)")]
+    fn safe_transfer_from(
+        collection: CollectionIdOf<Runtime, Instance>,
+        handle: &mut impl PrecompileHandle,
+        from: Address,
+        to: Address,
+        token_id: U256,
+    ) -> EvmResult {
+        Self::safe_transfer_inner(
+            collection,
+            handle,
+            from.into(),
+            to.into(),
+            token_id,
+            Vec::new(),
+        )
+    }
+
+    #[precompile::public("safeTransferFrom(address,address,uint256,bytes)")]
+    fn safe_transfer_from_with_data(
+        collection: CollectionIdOf<Runtime, Instance>,
+        handle: &mut impl PrecompileHandle,
+        from: Address,
+        to: Address,
+        token_id: U256,
+        data: UnboundedBytes,
+    ) -> EvmResult {
+        Self::safe_transfer_inner(
+            collection,
+            handle,
+            from.into(),
+            to.into(),
+            token_id,
+            data.into(),
+        )
+    }
+
+    fn transfer_inner(
+        collection: CollectionIdOf<Runtime, Instance>,
+        handle: &mut impl PrecompileHandle,
+        from: H160,
+        to: H160,
+        token_id: U256,
+    ) -> EvmResult {
+        handle.record_log_costs_manual(4, 0)?;
+
+        ensure!(!to.is_zero(), revert("Transfer to the zero address"));
+        let item = Self::u256_to_item(token_id).in_field("tokenId")?;
+        let owner = Self::owner(handle, collection, item)?;
+        ensure!(
+            Into::<H160>::into(owner.clone()) == from,
+            revert("From is not the owner of the token")
+        );
+
+        {
+            let caller = Runtime::AddressMapping::into_account_id(handle.context().caller);
+            let is_authorized = caller == owner
+                || Self::approved(handle, collection, item, &owner)?.as_ref() == Some(&caller)
+                || Self::is_operator(handle, collection, &owner, &caller)?;
+            ensure!(
+                is_authorized,
+                revert("Caller is not token owner or approved")
+            );
+
+            handle.record_cost(Runtime::GasWeightMapping::weight_to_gas(
+                <Runtime as pallet_nfts::Config<Instance>>::WeightInfo::transfer(),
+            ))?;
+            handle.record_cost(RuntimeHelper::<Runtime>::db_write_gas_cost())?;
+
+            <pallet_nfts::Pallet<Runtime, Instance> as Transfer<Runtime::AccountId>>::transfer(
+                &collection,
+                &item,
+                &Runtime::AddressMapping::into_account_id(to),
+            )
+            .map_err(TryDispatchError::Substrate)?;
+            TokenApprovalsStorage::<Runtime, Instance>::remove(collection, item);
+        }
+
+        log4(
+            handle.context().address,
+            SELECTOR_LOG_TRANSFER,
+            from,
+            to,
+            Self::token_topic(token_id),
+            Vec::new(),
+        )
+        .record(handle)?;
+
+        Ok(())
+    }
+
+    fn safe_transfer_inner(
+        collection: CollectionIdOf<Runtime, Instance>,
+        handle: &mut impl PrecompileHandle,
+        from: H160,
+        to: H160,
+        token_id: U256,
+        data: Vec<u8>,
+    ) -> EvmResult {
+        Self::transfer_inner(collection, handle, from, to, token_id)?;
+
+        // Contracts must acknowledge the reception of the token.
+        // AccountCodes: Blake2_128Concat(16) + H160(20) + Vec(5)
+        handle.record_db_read::<Runtime>(41)?;
+        if pallet_evm::AccountCodes::<Runtime>::decode_len(to).unwrap_or(0) == 0 {
+            return Ok(());
+        }
+
+        let mut call_data = ERC721_RECEIVED.to_vec();
+        call_data.extend(solidity::encode_arguments((
+            Address(handle.context().caller),
+            Address(from),
+            token_id,
+            UnboundedBytes::from(data),
+        )));
+
+        let sub_context = Context {
+            caller: handle.context().address,
+            address: to,
+            apparent_value: U256::zero(),
+        };
+
+        let (reason, output) = handle.call(
+            to,
+            None,
+            call_data,
+            Some(handle.remaining_gas()),
+            false,
+            &sub_context,
+        );
+        match reason {
+            ExitReason::Error(exit_status) => Err(PrecompileFailure::Error { exit_status }),
+            ExitReason::Fatal(exit_status) => Err(PrecompileFailure::Fatal { exit_status }),
+            ExitReason::Revert(_) => Err(revert(output)),
+            ExitReason::Succeed(_) if output.get(..4) == Some(&ERC721_RECEIVED[..]) => Ok(()),
+            ExitReason::Succeed(_) => Err(revert("Transfer to non ERC721Receiver implementer")),
+        }
+    }
+
+    /// Returns the owner of `item`, reverting if it does not exist.
+    fn owner(
+        handle: &mut impl PrecompileHandle,
+        collection: CollectionIdOf<Runtime, Instance>,
+        item: ItemIdOf<Runtime, Instance>,
+    ) -> EvmResult<Runtime::AccountId> {
+        Self::record_item_read(handle)?;
+
+        pallet_nfts::Pallet::<Runtime, Instance>::owner(&collection, &item)
+            .ok_or_else(|| revert("Token does not exist"))
+    }
+
+    /// Returns the account approved for `item` by its current `owner`, if any.
+    fn approved(
+        handle: &mut impl PrecompileHandle,
+        collection: CollectionIdOf<Runtime, Instance>,
+        item: ItemIdOf<Runtime, Instance>,
+        owner: &Runtime::AccountId,
+    ) -> EvmResult<Option<Runtime::AccountId>> {
+        // Storage item: TokenApprovals:
+        // Blake2_128(16) + CollectionId + Blake2_128(16) + ItemId + 2 * AccountId
+        handle.record_db_read::<Runtime>(
+            32 + CollectionIdOf::<Runtime, Instance>::max_encoded_len()
+                + ItemIdOf::<Runtime, Instance>::max_encoded_len()
+                + 2 * Runtime::AccountId::max_encoded_len(),
+        )?;
+
+        // Approvals given by a previous owner of the item are stale.
+        Ok(
+            TokenApprovalsStorage::<Runtime, Instance>::get(collection, item)
+                .filter(|(approver, _)| approver == owner)
+                .map(|(_, approved)| approved),
+        )
+    }
+
+    /// Returns whether `operator` may manage all the items of `owner` in the collection.
+    fn is_operator(
+        handle: &mut impl PrecompileHandle,
+        collection: CollectionIdOf<Runtime, Instance>,
+        owner: &Runtime::AccountId,
+        operator: &Runtime::AccountId,
+    ) -> EvmResult<bool> {
+        // Storage item: OperatorApprovals:
+        // Blake2_128(16) + CollectionId + 2 * (Blake2_128(16) + AccountId) + bool(1)
+        handle.record_db_read::<Runtime>(
+            49 + CollectionIdOf::<Runtime, Instance>::max_encoded_len()
+                + 2 * Runtime::AccountId::max_encoded_len(),
+        )?;
+
+        Ok(OperatorApprovalsStorage::<Runtime, Instance>::get((
+            collection, owner, operator,
+        )))
+    }
+
+    fn record_item_read(handle: &mut impl PrecompileHandle) -> EvmResult {
+        // Storage item: Item:
+        // Blake2_128(16) + CollectionId + Blake2_128(16) + ItemId + ItemDetails[owner(AccountId)
+        // + approvals(ApprovalsLimit * (AccountId + Option<BlockNumber>)) + deposit(AccountId
+        // + Balance(16))]
+        handle.record_db_read::<Runtime>(
+            48 + CollectionIdOf::<Runtime, Instance>::max_encoded_len()
+                + ItemIdOf::<Runtime, Instance>::max_encoded_len()
+                + 2 * Runtime::AccountId::max_encoded_len()
+                + (<Runtime as pallet_nfts::Config<Instance>>::ApprovalsLimit::get() as usize)
+                    * (Runtime::AccountId::max_encoded_len()
+                        + Option::<BlockNumberFor<Runtime>>::max_encoded_len()),
+        )
+    }
+
+    fn record_account_read(handle: &mut impl PrecompileHandle) -> EvmResult {
+        // Storage item: Account:
+        // Blake2_128(16) + AccountId + Blake2_128(16) + CollectionId + Blake2_128(16) + ItemId
+        handle.record_db_read::<Runtime>(
+            48 + Runtime::AccountId::max_encoded_len()
+                + CollectionIdOf::<Runtime, Instance>::max_encoded_len()
+                + ItemIdOf::<Runtime, Instance>::max_encoded_len(),
+        )
+    }
+
+    fn token_topic(token_id: U256) -> H256 {
+        let mut topic = H256::zero();
+        token_id.to_big_endian(topic.as_bytes_mut());
+        topic
+    }
+
+    fn u256_to_item(value: U256) -> MayRevert<ItemIdOf<Runtime, Instance>> {
+        value
+            .try_into()
+            .map_err(|_| RevertReason::value_is_too_large("item id type").into())
+    }
+}
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
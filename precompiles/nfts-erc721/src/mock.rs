@@ -0,0 +1,196 @@
+//! Test runtime of the ERC-721 collections precompile set.
+
+use super::*;
+use codec::{Decode, Encode};
+use frame_support::{
+    construct_runtime, derive_impl, parameter_types,
+    traits::{AsEnsureOriginWithArg, ConstU128, ConstU32, ConstU64},
+    weights::{constants::RocksDbWeight, Weight},
+};
+use frame_system::{EnsureRoot, EnsureSigned};
+use pallet_evm::{EnsureAddressNever, EnsureAddressRoot, IdentityAddressMapping};
+use pallet_nfts::PalletFeatures;
+use polkadot_sdk::{pallet_balances, pallet_timestamp, sp_io, sp_runtime};
+use precompile_utils::testing::MockAccount;
+use sp_runtime::{
+    traits::{IdentifyAccount, IdentityLookup, Lazy, Verify},
+    BuildStorage, RuntimeDebug,
+};
+
+pub type AccountId = MockAccount;
+pub type Balance = u128;
+pub type CollectionId = u32;
+
+/// The first four bytes of the address of every collection precompile.
+pub const COLLECTION_PRECOMPILE_ADDRESS_PREFIX: &[u8] = &[254u8; 4];
+
+construct_runtime!(
+    pub enum Runtime {
+        System: frame_system,
+        Balances: pallet_balances,
+        Timestamp: pallet_timestamp,
+        Evm: pallet_evm,
+        Nfts: pallet_nfts,
+    }
+);
+
+#[derive_impl(frame_system::config_preludes::TestDefaultConfig)]
+impl frame_system::Config for Runtime {
+    type Block = frame_system::mocking::MockBlock<Runtime>;
+    type AccountId = AccountId;
+    type Lookup = IdentityLookup<AccountId>;
+    type AccountData = pallet_balances::AccountData<Balance>;
+    type DbWeight = RocksDbWeight;
+}
+
+#[derive_impl(pallet_balances::config_preludes::TestDefaultConfig)]
+impl pallet_balances::Config for Runtime {
+    type Balance = Balance;
+    type ExistentialDeposit = ConstU128<1>;
+    type AccountStore = System;
+}
+
+impl pallet_timestamp::Config for Runtime {
+    type Moment = u64;
+    type OnTimestampSet = ();
+    type MinimumPeriod = ConstU64<5>;
+    type WeightInfo = ();
+}
+
+parameter_types! {
+    pub BlockGasLimit: U256 = U256::max_value();
+    pub const GasLimitPovSizeRatio: u64 = 16;
+    pub PrecompilesValue: Erc721NftsPrecompileSet<Runtime> = Erc721NftsPrecompileSet::new();
+    pub WeightPerGas: Weight = Weight::from_parts(20_000, 0);
+    pub SuicideQuickClearLimit: u32 = 0;
+}
+
+impl pallet_evm::Config for Runtime {
+    type FeeCalculator = ();
+    type GasWeightMapping = pallet_evm::FixedGasWeightMapping<Self>;
+    type WeightPerGas = WeightPerGas;
+    type BlockHashMapping = pallet_evm::SubstrateBlockHashMapping<Self>;
+    type CallOrigin = EnsureAddressRoot<AccountId>;
+    type WithdrawOrigin = EnsureAddressNever<AccountId>;
+    type AddressMapping = IdentityAddressMapping;
+    type Currency = Balances;
+    type RuntimeEvent = RuntimeEvent;
+    type PrecompilesType = Erc721NftsPrecompileSet<Self>;
+    type PrecompilesValue = PrecompilesValue;
+    type ChainId = ();
+    type BlockGasLimit = BlockGasLimit;
+    type Runner = pallet_evm::runner::stack::Runner<Self>;
+    type OnChargeTransaction = ();
+    type OnCreate = ();
+    type FindAuthor = ();
+    type GasLimitPovSizeRatio = GasLimitPovSizeRatio;
+    type SuicideQuickClearLimit = SuicideQuickClearLimit;
+    type Timestamp = Timestamp;
+    type WeightInfo = ();
+}
+
+/// Signature of the pre-signed mints, which never verifies as they are not tested.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug, scale_info::TypeInfo)]
+pub struct MockSignature;
+
+/// Signer of a `MockSignature`.
+pub struct MockSigner(AccountId);
+
+impl IdentifyAccount for MockSigner {
+    type AccountId = AccountId;
+
+    fn into_account(self) -> AccountId {
+        self.0
+    }
+}
+
+impl Verify for MockSignature {
+    type Signer = MockSigner;
+
+    fn verify<L: Lazy<[u8]>>(&self, _msg: L, _signer: &AccountId) -> bool {
+        false
+    }
+}
+
+parameter_types! {
+    pub Features: PalletFeatures = PalletFeatures::all_enabled();
+}
+
+impl pallet_nfts::Config for Runtime {
+    type RuntimeEvent = RuntimeEvent;
+    type CollectionId = CollectionId;
+    type ItemId = u32;
+    type Currency = Balances;
+    type ForceOrigin = EnsureRoot<AccountId>;
+    type CreateOrigin = AsEnsureOriginWithArg<EnsureSigned<AccountId>>;
+    type Locker = ();
+    type CollectionDeposit = ConstU128<0>;
+    type ItemDeposit = ConstU128<0>;
+    type MetadataDepositBase = ConstU128<0>;
+    type AttributeDepositBase = ConstU128<0>;
+    type DepositPerByte = ConstU128<0>;
+    type StringLimit = ConstU32<50>;
+    type KeyLimit = ConstU32<50>;
+    type ValueLimit = ConstU32<50>;
+    type ApprovalsLimit = ConstU32<10>;
+    type ItemAttributesApprovalsLimit = ConstU32<2>;
+    type MaxTips = ConstU32<10>;
+    type MaxDeadlineDuration = ConstU64<10_000>;
+    type MaxAttributesPerCall = ConstU32<2>;
+    type Features = Features;
+    type OffchainSignature = MockSignature;
+    type OffchainPublic = MockSigner;
+    type WeightInfo = ();
+}
+
+impl AddressToCollectionId<CollectionId> for Runtime {
+    fn address_to_collection_id(address: H160) -> Option<CollectionId> {
+        let (prefix, id) = address.as_bytes().split_at(16);
+        if prefix.starts_with(COLLECTION_PRECOMPILE_ADDRESS_PREFIX)
+            && prefix[COLLECTION_PRECOMPILE_ADDRESS_PREFIX.len()..]
+                .iter()
+                .all(|b| *b == 0)
+        {
+            let mut buf = [0u8; 4];
+            buf.copy_from_slice(id);
+            Some(CollectionId::from_be_bytes(buf))
+        } else {
+            None
+        }
+    }
+
+    fn collection_id_to_address(collection_id: CollectionId) -> H160 {
+        let mut address = [0u8; 20];
+        address[..4].copy_from_slice(COLLECTION_PRECOMPILE_ADDRESS_PREFIX);
+        address[16..].copy_from_slice(&collection_id.to_be_bytes());
+        H160(address)
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct ExtBuilder {
+    balances: Vec<(AccountId, Balance)>,
+}
+
+impl ExtBuilder {
+    pub(crate) fn with_balances(mut self, balances: Vec<(AccountId, Balance)>) -> Self {
+        self.balances = balances;
+        self
+    }
+
+    pub(crate) fn build(self) -> sp_io::TestExternalities {
+        let mut t = frame_system::GenesisConfig::<Runtime>::default()
+            .build_storage()
+            .expect("Frame system builds valid default genesis config");
+
+        pallet_balances::GenesisConfig::<Runtime> {
+            balances: self.balances,
+        }
+        .assimilate_storage(&mut t)
+        .expect("Pallet balances storage can be assimilated");
+
+        let mut ext = sp_io::TestExternalities::new(t);
+        ext.execute_with(|| System::set_block_number(1));
+        ext
+    }
+}
@@ -0,0 +1,593 @@
+use crate::{mock::*, *};
+use frame_support::assert_ok;
+use precompile_utils::{evm::costs::log_costs, testing::*};
+
+type PCall = Erc721NftsPrecompileSetCall<Runtime, ()>;
+
+fn precompiles() -> Erc721NftsPrecompileSet<Runtime> {
+    PrecompilesValue::get()
+}
+
+fn balances() -> ExtBuilder {
+    ExtBuilder::default().with_balances(vec![(Alice.into(), 1000), (Bob.into(), 1000)])
+}
+
+fn collection(collection_id: CollectionId) -> H160 {
+    Runtime::collection_id_to_address(collection_id)
+}
+
+fn db_read() -> u64 {
+    RuntimeHelper::<Runtime>::db_read_gas_cost()
+}
+
+fn dispatch_error(output: &[u8], error: &str) -> bool {
+    core::str::from_utf8(output).map_or(false, |reason| reason.contains(error))
+}
+
+/// Creates the next collection owned by Alice, and mints the given items of collection 0 to her.
+fn create_collection(items: impl IntoIterator<Item = u32>) {
+    assert_ok!(Nfts::force_create(
+        RuntimeOrigin::root(),
+        Alice.into(),
+        pallet_nfts::CollectionConfig {
+            settings: pallet_nfts::CollectionSettings::all_enabled(),
+            max_supply: None,
+            mint_settings: pallet_nfts::MintSettings::default(),
+        }
+    ));
+    for item in items {
+        mint(item, Alice);
+    }
+}
+
+fn mint(item: u32, owner: impl Into<AccountId>) {
+    assert_ok!(Nfts::force_mint(
+        RuntimeOrigin::root(),
+        0,
+        item,
+        owner.into(),
+        pallet_nfts::ItemConfig::default()
+    ));
+}
+
+fn owner_of(item: u32) -> Option<AccountId> {
+    pallet_nfts::Pallet::<Runtime>::owner(&0, &item)
+}
+
+fn approve(owner: impl Into<H160>, approved: impl Into<H160>, item: u32) {
+    precompiles()
+        .prepare_test(
+            owner,
+            collection(0),
+            PCall::approve {
+                approved: Address(approved.into()),
+                token_id: item.into(),
+            },
+        )
+        .execute_returns(());
+}
+
+fn set_approval_for_all(owner: impl Into<H160>, operator: impl Into<H160>, approved: bool) {
+    precompiles()
+        .prepare_test(
+            owner,
+            collection(0),
+            PCall::set_approval_for_all {
+                operator: Address(operator.into()),
+                approved,
+            },
+        )
+        .execute_returns(());
+}
+
+fn transfer_from(from: impl Into<H160>, to: impl Into<H160>, item: u32) -> PCall {
+    PCall::transfer_from {
+        from: Address(from.into()),
+        to: Address(to.into()),
+        token_id: item.into(),
+    }
+}
+
+fn token_topic(item: u32) -> H256 {
+    H256::from_low_u64_be(item.into())
+}
+
+#[test]
+fn selectors() {
+    assert!(PCall::supports_interface_selectors().contains(&0x01ffc9a7));
+    assert!(PCall::balance_of_selectors().contains(&0x70a08231));
+    assert!(PCall::owner_of_selectors().contains(&0x6352211e));
+    assert!(PCall::get_approved_selectors().contains(&0x081812fc));
+    assert!(PCall::is_approved_for_all_selectors().contains(&0xe985e9c5));
+    assert!(PCall::token_uri_selectors().contains(&0xc87b56dd));
+    assert!(PCall::approve_selectors().contains(&0x095ea7b3));
+    assert!(PCall::set_approval_for_all_selectors().contains(&0xa22cb465));
+    assert!(PCall::transfer_from_selectors().contains(&0x23b872dd));
+    assert!(PCall::safe_transfer_from_selectors().contains(&0x42842e0e));
+    assert!(PCall::safe_transfer_from_with_data_selectors().contains(&0xb88d4fde));
+}
+
+#[test]
+fn unknown_collection_is_not_a_precompile() {
+    balances().build().execute_with(|| {
+        precompiles()
+            .prepare_test(Alice, collection(0), PCall::owner_of { token_id: 1.into() })
+            .execute_none();
+    });
+}
+
+#[test]
+fn supports_interface() {
+    balances().build().execute_with(|| {
+        create_collection([]);
+
+        for (interface_id, supported) in [
+            ([0x01, 0xff, 0xc9, 0xa7], true),
+            ([0x80, 0xac, 0x58, 0xcd], true),
+            ([0x5b, 0x5e, 0x13, 0x9f], false),
+            ([0xff, 0xff, 0xff, 0xff], false),
+        ] {
+            precompiles()
+                .prepare_test(
+                    Alice,
+                    collection(0),
+                    PCall::supports_interface {
+                        interface_id: InterfaceId(interface_id),
+                    },
+                )
+                .expect_cost(db_read())
+                .expect_no_logs()
+                .execute_returns(supported);
+        }
+    });
+}
+
+#[test]
+fn supports_interface_rejects_a_padded_argument() {
+    balances().build().execute_with(|| {
+        create_collection([]);
+
+        let mut input = PCall::supports_interface_selectors()[0]
+            .to_be_bytes()
+            .to_vec();
+        input.extend_from_slice(&[0x80, 0xac, 0x58, 0xcd]);
+        input.extend_from_slice(&[0x01; 28]);
+
+        precompiles()
+            .prepare_test(Alice, collection(0), input)
+            .execute_reverts(|output| output == b"interfaceId: Invalid bytes4 padding");
+    });
+}
+
+#[test]
+fn get_balance_and_owner() {
+    balances().build().execute_with(|| {
+        create_collection([1, 2]);
+
+        precompiles()
+            .prepare_test(
+                Bob,
+                collection(0),
+                PCall::balance_of {
+                    owner: Address(Alice.into()),
+                },
+            )
+            .expect_cost(4 * db_read())
+            .expect_no_logs()
+            .execute_returns(U256::from(2));
+        precompiles()
+            .prepare_test(
+                Bob,
+                collection(0),
+                PCall::balance_of {
+                    owner: Address(Bob.into()),
+                },
+            )
+            .execute_returns(U256::zero());
+        precompiles()
+            .prepare_test(
+                Bob,
+                collection(0),
+                PCall::balance_of {
+                    owner: Address(H160::zero()),
+                },
+            )
+            .execute_reverts(|output| output == b"Zero address is not a valid owner");
+
+        precompiles()
+            .prepare_test(Bob, collection(0), PCall::owner_of { token_id: 2.into() })
+            .expect_cost(2 * db_read())
+            .expect_no_logs()
+            .execute_returns(Address(Alice.into()));
+        precompiles()
+            .prepare_test(Bob, collection(0), PCall::owner_of { token_id: 3.into() })
+            .execute_reverts(|output| output == b"Token does not exist");
+        precompiles()
+            .prepare_test(
+                Bob,
+                collection(0),
+                PCall::owner_of {
+                    token_id: U256::MAX,
+                },
+            )
+            .execute_reverts(|output| output == b"tokenId: Value is too large for item id type");
+    });
+}
+
+#[test]
+fn get_token_uri() {
+    balances().build().execute_with(|| {
+        create_collection([1]);
+        assert_ok!(Nfts::set_metadata(
+            RuntimeOrigin::root(),
+            0,
+            1,
+            b"ipfs://item-1".to_vec().try_into().unwrap()
+        ));
+
+        precompiles()
+            .prepare_test(Bob, collection(0), PCall::token_uri { token_id: 1.into() })
+            .expect_cost(3 * db_read())
+            .expect_no_logs()
+            .execute_returns(UnboundedBytes::from(b"ipfs://item-1".as_slice()));
+    });
+}
+
+#[test]
+fn approve_sets_the_approved_account() {
+    balances().build().execute_with(|| {
+        create_collection([1]);
+
+        precompiles()
+            .prepare_test(
+                Alice,
+                collection(0),
+                PCall::approve {
+                    approved: Address(Bob.into()),
+                    token_id: 1.into(),
+                },
+            )
+            .expect_cost(
+                2 * db_read()
+                    + RuntimeHelper::<Runtime>::db_write_gas_cost()
+                    + log_costs(4, 0).expect("log costs fit in u64"),
+            )
+            .expect_log(log4(
+                collection(0),
+                SELECTOR_LOG_APPROVAL,
+                Alice,
+                Bob,
+                token_topic(1),
+                Vec::new(),
+            ))
+            .execute_returns(());
+
+        precompiles()
+            .prepare_test(
+                Charlie,
+                collection(0),
+                PCall::get_approved { token_id: 1.into() },
+            )
+            .expect_cost(3 * db_read())
+            .expect_no_logs()
+            .execute_returns(Address(Bob.into()));
+
+        // Approving the zero address clears the approval.
+        approve(Alice, H160::zero(), 1);
+        precompiles()
+            .prepare_test(
+                Charlie,
+                collection(0),
+                PCall::get_approved { token_id: 1.into() },
+            )
+            .execute_returns(Address(H160::zero()));
+    });
+}
+
+#[test]
+fn approve_reverts() {
+    balances().build().execute_with(|| {
+        create_collection([1]);
+
+        precompiles()
+            .prepare_test(
+                Bob,
+                collection(0),
+                PCall::approve {
+                    approved: Address(Bob.into()),
+                    token_id: 1.into(),
+                },
+            )
+            .execute_reverts(|output| output == b"Caller is not token owner or approved for all");
+        precompiles()
+            .prepare_test(
+                Alice,
+                collection(0),
+                PCall::approve {
+                    approved: Address(Bob.into()),
+                    token_id: 2.into(),
+                },
+            )
+            .execute_reverts(|output| output == b"Token does not exist");
+
+        assert!(TokenApprovalsStorage::<Runtime>::get(0, 1).is_none());
+    });
+}
+
+#[test]
+fn transfer_from_by_the_approved_account() {
+    balances().build().execute_with(|| {
+        create_collection([1]);
+        approve(Alice, Bob, 1);
+
+        precompiles()
+            .prepare_test(Bob, collection(0), transfer_from(Alice, Charlie, 1))
+            .expect_log(log4(
+                collection(0),
+                SELECTOR_LOG_TRANSFER,
+                Alice,
+                Charlie,
+                token_topic(1),
+                Vec::new(),
+            ))
+            .execute_returns(());
+
+        assert_eq!(owner_of(1), Some(Charlie.into()));
+        assert!(TokenApprovalsStorage::<Runtime>::get(0, 1).is_none());
+
+        // The approval was consumed by the transfer.
+        precompiles()
+            .prepare_test(Bob, collection(0), transfer_from(Charlie, Bob, 1))
+            .execute_reverts(|output| output == b"Caller is not token owner or approved");
+    });
+}
+
+#[test]
+fn approval_lapses_when_the_item_changes_owner() {
+    balances().build().execute_with(|| {
+        create_collection([1]);
+        approve(Alice, Bob, 1);
+
+        // A transfer through the pallet, which does not know the ERC-721 approvals.
+        assert_ok!(Nfts::transfer(
+            RuntimeOrigin::signed(Alice.into()),
+            0,
+            1,
+            Charlie.into()
+        ));
+        assert_ok!(Nfts::transfer(
+            RuntimeOrigin::signed(Charlie.into()),
+            0,
+            1,
+            Alice.into()
+        ));
+
+        precompiles()
+            .prepare_test(
+                Bob,
+                collection(0),
+                PCall::get_approved { token_id: 1.into() },
+            )
+            .execute_returns(Address(H160::zero()));
+        precompiles()
+            .prepare_test(Bob, collection(0), transfer_from(Alice, Bob, 1))
+            .execute_reverts(|output| output == b"Caller is not token owner or approved");
+    });
+}
+
+#[test]
+fn operator_manages_all_items_of_the_owner() {
+    balances().build().execute_with(|| {
+        // Bob does not own any item when approving the operator.
+        create_collection([1]);
+
+        precompiles()
+            .prepare_test(
+                Bob,
+                collection(0),
+                PCall::set_approval_for_all {
+                    operator: Address(Charlie.into()),
+                    approved: true,
+                },
+            )
+            .expect_cost(
+                db_read()
+                    + RuntimeHelper::<Runtime>::db_write_gas_cost()
+                    + log_costs(3, 32).expect("log costs fit in u64"),
+            )
+            .expect_log(log3(
+                collection(0),
+                SELECTOR_LOG_APPROVAL_FOR_ALL,
+                Bob,
+                Charlie,
+                solidity::encode_event_data(true),
+            ))
+            .execute_returns(());
+
+        precompiles()
+            .prepare_test(
+                Alice,
+                collection(0),
+                PCall::is_approved_for_all {
+                    owner: Address(Bob.into()),
+                    operator: Address(Charlie.into()),
+                },
+            )
+            .expect_cost(2 * db_read())
+            .expect_no_logs()
+            .execute_returns(true);
+
+        // Items received after the approval are covered.
+        mint(2, Bob);
+        precompiles()
+            .prepare_test(Alice, collection(0), transfer_from(Alice, Bob, 1))
+            .execute_returns(());
+
+        // The operator may approve, and transfer on its own.
+        approve(Charlie, David, 1);
+        precompiles()
+            .prepare_test(Charlie, collection(0), transfer_from(Bob, Charlie, 2))
+            .execute_returns(());
+        precompiles()
+            .prepare_test(David, collection(0), transfer_from(Bob, David, 1))
+            .execute_returns(());
+
+        assert_eq!(owner_of(1), Some(David.into()));
+        assert_eq!(owner_of(2), Some(Charlie.into()));
+    });
+}
+
+#[test]
+fn revoked_operator_cannot_transfer() {
+    balances().build().execute_with(|| {
+        create_collection([1]);
+        set_approval_for_all(Alice, Bob, true);
+        set_approval_for_all(Alice, Bob, false);
+
+        precompiles()
+            .prepare_test(
+                Alice,
+                collection(0),
+                PCall::is_approved_for_all {
+                    owner: Address(Alice.into()),
+                    operator: Address(Bob.into()),
+                },
+            )
+            .execute_returns(false);
+        precompiles()
+            .prepare_test(Bob, collection(0), transfer_from(Alice, Bob, 1))
+            .execute_reverts(|output| output == b"Caller is not token owner or approved");
+
+        assert_eq!(owner_of(1), Some(Alice.into()));
+    });
+}
+
+#[test]
+fn set_approval_for_all_to_the_caller_reverts() {
+    balances().build().execute_with(|| {
+        create_collection([]);
+
+        precompiles()
+            .prepare_test(
+                Alice,
+                collection(0),
+                PCall::set_approval_for_all {
+                    operator: Address(Alice.into()),
+                    approved: true,
+                },
+            )
+            .execute_reverts(|output| output == b"Approve to caller");
+    });
+}
+
+#[test]
+fn operator_approvals_are_per_collection() {
+    balances().build().execute_with(|| {
+        create_collection([1]);
+        create_collection([]);
+        set_approval_for_all(Alice, Bob, true);
+
+        precompiles()
+            .prepare_test(
+                Alice,
+                collection(1),
+                PCall::is_approved_for_all {
+                    owner: Address(Alice.into()),
+                    operator: Address(Bob.into()),
+                },
+            )
+            .execute_returns(false);
+    });
+}
+
+#[test]
+fn transfer_from_reverts() {
+    balances().build().execute_with(|| {
+        create_collection([1]);
+
+        precompiles()
+            .prepare_test(Alice, collection(0), transfer_from(Bob, Charlie, 1))
+            .execute_reverts(|output| output == b"From is not the owner of the token");
+        precompiles()
+            .prepare_test(Alice, collection(0), transfer_from(Alice, H160::zero(), 1))
+            .execute_reverts(|output| output == b"Transfer to the zero address");
+        precompiles()
+            .prepare_test(Alice, collection(0), transfer_from(Alice, Bob, 2))
+            .execute_reverts(|output| output == b"Token does not exist");
+
+        assert_ok!(Nfts::lock_item_transfer(
+            RuntimeOrigin::signed(Alice.into()),
+            0,
+            1
+        ));
+        precompiles()
+            .prepare_test(Alice, collection(0), transfer_from(Alice, Bob, 1))
+            .execute_reverts(|output| dispatch_error(output, "ItemLocked"));
+
+        assert_eq!(owner_of(1), Some(Alice.into()));
+    });
+}
+
+#[test]
+fn safe_transfer_from_to_a_receiver() {
+    balances().build().execute_with(|| {
+        create_collection([1, 2]);
+        pallet_evm::AccountCodes::<Runtime>::insert(H160::from(Charlie), vec![0x00]);
+
+        let mut received = ERC721_RECEIVED.to_vec();
+        received.resize(32, 0);
+
+        precompiles()
+            .prepare_test(
+                Alice,
+                collection(0),
+                PCall::safe_transfer_from_with_data {
+                    from: Address(Alice.into()),
+                    to: Address(Charlie.into()),
+                    token_id: 1.into(),
+                    data: UnboundedBytes::from(b"hello".as_slice()),
+                },
+            )
+            .with_subcall_handle(move |subcall: Subcall| {
+                let Subcall {
+                    address,
+                    input,
+                    context,
+                    ..
+                } = subcall;
+                assert_eq!(address, Charlie.into());
+                assert_eq!(context.caller, collection(0));
+
+                let mut expected = ERC721_RECEIVED.to_vec();
+                expected.extend(solidity::encode_arguments((
+                    Address(Alice.into()),
+                    Address(Alice.into()),
+                    U256::from(1),
+                    UnboundedBytes::from(b"hello".as_slice()),
+                )));
+                assert_eq!(input, expected);
+
+                SubcallOutput {
+                    output: received.clone(),
+                    ..SubcallOutput::succeed()
+                }
+            })
+            .execute_returns(());
+
+        assert_eq!(owner_of(1), Some(Charlie.into()));
+
+        // Receivers not answering the ERC-721 selector reject the token.
+        precompiles()
+            .prepare_test(
+                Alice,
+                collection(0),
+                PCall::safe_transfer_from {
+                    from: Address(Alice.into()),
+                    to: Address(Charlie.into()),
+                    token_id: 2.into(),
+                },
+            )
+            .with_subcall_handle(|_| SubcallOutput::succeed())
+            .execute_reverts(|output| output == b"Transfer to non ERC721Receiver implementer");
+    });
+}
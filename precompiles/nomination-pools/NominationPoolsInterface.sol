@@ -0,0 +1,66 @@
+// SPDX-License-Identifier: GPL-3.0-only
+pragma solidity >=0.8.3;
+
+/// @dev The NominationPools contract's address.
+address constant NOMINATION_POOLS_ADDRESS = 0x0000000000000000000000000000000000000802;
+
+/// @dev The NominationPools contract's instance.
+NominationPools constant NOMINATION_POOLS_CONTRACT = NominationPools(
+    NOMINATION_POOLS_ADDRESS
+);
+
+/// @title Pallet Nomination Pools Interface
+/// @dev The interface through which solidity contracts will interact with
+/// pallet-nomination-pools. Every call is dispatched with the caller as the pool member.
+interface NominationPools {
+    /// @dev Rewards claimable by a pool member.
+    /// @custom:selector 31d7a262
+    /// @param member The pool member.
+    /// @return The pending rewards, 0 if `member` is not in a pool.
+    function pendingRewards(address member) external view returns (uint256);
+
+    /// @dev Pool membership of an account.
+    /// @custom:selector 24ac6ae2
+    /// @param member The pool member.
+    /// @return poolId The pool joined by `member`, 0 if none.
+    /// @return points The points of `member` in the pool.
+    /// @return balance The balance `points` are worth.
+    function poolMember(address member)
+        external
+        view
+        returns (
+            uint32 poolId,
+            uint256 points,
+            uint256 balance
+        );
+
+    /// @dev Join the pool `poolId` bonding `amount` of the caller's balance.
+    /// @custom:selector 98f15d02
+    function join(uint256 amount, uint32 poolId) external;
+
+    /// @dev Bond `extra` more of the caller's free balance into its pool.
+    /// @custom:selector eaca88de
+    function bondExtra(uint256 extra) external;
+
+    /// @dev Bond the pending rewards of the caller into its pool.
+    /// @custom:selector 026db81f
+    function bondExtraRewards() external;
+
+    /// @dev Claim the pending rewards of the caller.
+    /// @custom:selector e88499a9
+    function claimPayout() external;
+
+    /// @dev Unbond `unbondingPoints` of `member` from its pool.
+    /// @custom:selector a5d059ca
+    function unbond(address member, uint256 unbondingPoints) external;
+
+    /// @dev Withdraw the unbonded funds of the pool's bonded account.
+    /// @custom:selector 1332e2ef
+    function poolWithdrawUnbonded(uint32 poolId, uint32 numSlashingSpans)
+        external;
+
+    /// @dev Withdraw the unbonded funds of `member`.
+    /// @custom:selector b5fb9875
+    function withdrawUnbonded(address member, uint32 numSlashingSpans)
+        external;
+}
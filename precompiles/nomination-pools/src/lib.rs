@@ -0,0 +1,214 @@
+//! Precompile to interact with `pallet_nomination_pools` from the EVM.
+//!
+//! Every call is dispatched as a signed origin of the caller, which acts as the pool member.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use core::marker::PhantomData;
+use fp_evm::PrecompileHandle;
+use frame_support::{
+    dispatch::{GetDispatchInfo, PostDispatchInfo},
+    traits::OriginTrait,
+};
+use pallet_evm::AddressMapping;
+use pallet_nomination_pools::{BondExtra, PoolId};
+use polkadot_sdk::{frame_support, frame_system, pallet_nomination_pools, sp_runtime};
+use precompile_utils::prelude::*;
+use sp_core::U256;
+use sp_runtime::traits::{Dispatchable, StaticLookup};
+
+/// Alias for the Balance type used by `pallet_nomination_pools`.
+pub type BalanceOf<Runtime> = pallet_nomination_pools::BalanceOf<Runtime>;
+
+/// Storage item: PoolMembers:
+/// Twox64Concat(8) + AccountId(20) + PoolMember(PoolId(4) + Balance(16) + RewardCounter(16)
+/// + MaxUnbonding * (EraIndex(4) + Balance(16)))
+const POOL_MEMBER_SIZE: usize = 64 + 32 * 20;
+
+/// Storage item: BondedPools:
+/// Twox64Concat(8) + PoolId(4) + BondedPoolInner(commission(~90) + member_counter(4)
+/// + points(16) + roles(4 * (1 + AccountId(20))) + state(1))
+const BONDED_POOL_SIZE: usize = 207;
+
+/// Storage item: RewardPools:
+/// Twox64Concat(8) + PoolId(4) + RewardPool(RewardCounter(16) + 4 * Balance(16))
+const REWARD_POOL_SIZE: usize = 92;
+
+/// Storage item: System Account:
+/// Blake2_128(16) + AccountId(20) + AccountInfo((4 * 4) + AccountData(16 * 4))
+const ACCOUNT_SIZE: usize = 116;
+
+/// A precompile to wrap the functionality from `pallet_nomination_pools`.
+pub struct NominationPoolsPrecompile<Runtime>(PhantomData<Runtime>);
+
+#[precompile_utils::precompile]
+impl<Runtime> NominationPoolsPrecompile<Runtime>
+where
+    Runtime: pallet_nomination_pools::Config + pallet_evm::Config + frame_system::Config,
+    Runtime::RuntimeCall: Dispatchable<PostInfo = PostDispatchInfo> + GetDispatchInfo,
+    Runtime::RuntimeCall: From<pallet_nomination_pools::Call<Runtime>>,
+    <Runtime::RuntimeCall as Dispatchable>::RuntimeOrigin: From<Option<Runtime::AccountId>>,
+    <Runtime::RuntimeCall as Dispatchable>::RuntimeOrigin: OriginTrait,
+    BalanceOf<Runtime>: TryFrom<U256> + Into<U256>,
+{
+    #[precompile::public("pendingRewards(address)")]
+    #[precompile::view]
+    fn pending_rewards(handle: &mut impl PrecompileHandle, member: Address) -> EvmResult<U256> {
+        // Storage items: PoolMembers, BondedPools, RewardPools, and the reward account balance.
+        handle.record_db_read::<Runtime>(POOL_MEMBER_SIZE)?;
+        handle.record_db_read::<Runtime>(BONDED_POOL_SIZE)?;
+        handle.record_db_read::<Runtime>(REWARD_POOL_SIZE)?;
+        handle.record_db_read::<Runtime>(ACCOUNT_SIZE)?;
+
+        let member = Runtime::AddressMapping::into_account_id(member.into());
+
+        Ok(
+            pallet_nomination_pools::Pallet::<Runtime>::api_pending_rewards(member)
+                .unwrap_or_default()
+                .into(),
+        )
+    }
+
+    #[precompile::public("poolMember(address)")]
+    #[precompile::view]
+    fn pool_member(
+        handle: &mut impl PrecompileHandle,
+        member: Address,
+    ) -> EvmResult<(PoolId, U256, U256)> {
+        // Storage items: PoolMembers, BondedPools, and the bonded account ledger.
+        handle.record_db_read::<Runtime>(POOL_MEMBER_SIZE)?;
+        handle.record_db_read::<Runtime>(BONDED_POOL_SIZE)?;
+        handle.record_db_read::<Runtime>(ACCOUNT_SIZE)?;
+
+        let member = Runtime::AddressMapping::into_account_id(member.into());
+
+        Ok(
+            match pallet_nomination_pools::PoolMembers::<Runtime>::get(&member) {
+                Some(pool_member) => {
+                    let balance = pallet_nomination_pools::Pallet::<Runtime>::api_points_to_balance(
+                        pool_member.pool_id,
+                        pool_member.points,
+                    );
+                    (
+                        pool_member.pool_id,
+                        pool_member.points.into(),
+                        balance.into(),
+                    )
+                }
+                None => Default::default(),
+            },
+        )
+    }
+
+    #[precompile::public("join(uint256,uint32)")]
+    fn join(handle: &mut impl PrecompileHandle, amount: U256, pool_id: PoolId) -> EvmResult {
+        let amount = Self::u256_to_amount(amount).in_field("amount")?;
+
+        Self::dispatch(
+            handle,
+            pallet_nomination_pools::Call::<Runtime>::join { amount, pool_id },
+        )
+    }
+
+    #[precompile::public("bondExtra(uint256)")]
+    fn bond_extra(handle: &mut impl PrecompileHandle, extra: U256) -> EvmResult {
+        let extra = Self::u256_to_amount(extra).in_field("extra")?;
+
+        Self::dispatch(
+            handle,
+            pallet_nomination_pools::Call::<Runtime>::bond_extra {
+                extra: BondExtra::FreeBalance(extra),
+            },
+        )
+    }
+
+    #[precompile::public("bondExtraRewards()")]
+    fn bond_extra_rewards(handle: &mut impl PrecompileHandle) -> EvmResult {
+        Self::dispatch(
+            handle,
+            pallet_nomination_pools::Call::<Runtime>::bond_extra {
+                extra: BondExtra::Rewards,
+            },
+        )
+    }
+
+    #[precompile::public("claimPayout()")]
+    fn claim_payout(handle: &mut impl PrecompileHandle) -> EvmResult {
+        Self::dispatch(
+            handle,
+            pallet_nomination_pools::Call::<Runtime>::claim_payout {},
+        )
+    }
+
+    #[precompile::public("unbond(address,uint256)")]
+    fn unbond(
+        handle: &mut impl PrecompileHandle,
+        member: Address,
+        unbonding_points: U256,
+    ) -> EvmResult {
+        let member = Runtime::AddressMapping::into_account_id(member.into());
+        let unbonding_points =
+            Self::u256_to_amount(unbonding_points).in_field("unbondingPoints")?;
+
+        Self::dispatch(
+            handle,
+            pallet_nomination_pools::Call::<Runtime>::unbond {
+                member_account: Runtime::Lookup::unlookup(member),
+                unbonding_points,
+            },
+        )
+    }
+
+    #[precompile::public("poolWithdrawUnbonded(uint32,uint32)")]
+    fn pool_withdraw_unbonded(
+        handle: &mut impl PrecompileHandle,
+        pool_id: PoolId,
+        num_slashing_spans: u32,
+    ) -> EvmResult {
+        Self::dispatch(
+            handle,
+            pallet_nomination_pools::Call::<Runtime>::pool_withdraw_unbonded {
+                pool_id,
+                num_slashing_spans,
+            },
+        )
+    }
+
+    #[precompile::public("withdrawUnbonded(address,uint32)")]
+    fn withdraw_unbonded(
+        handle: &mut impl PrecompileHandle,
+        member: Address,
+        num_slashing_spans: u32,
+    ) -> EvmResult {
+        let member = Runtime::AddressMapping::into_account_id(member.into());
+
+        Self::dispatch(
+            handle,
+            pallet_nomination_pools::Call::<Runtime>::withdraw_unbonded {
+                member_account: Runtime::Lookup::unlookup(member),
+                num_slashing_spans,
+            },
+        )
+    }
+
+    fn dispatch(
+        handle: &mut impl PrecompileHandle,
+        call: pallet_nomination_pools::Call<Runtime>,
+    ) -> EvmResult {
+        let origin = Runtime::AddressMapping::into_account_id(handle.context().caller);
+        RuntimeHelper::<Runtime>::try_dispatch(handle, Some(origin).into(), call)?;
+
+        Ok(())
+    }
+
+    fn u256_to_amount(value: U256) -> MayRevert<BalanceOf<Runtime>> {
+        value
+            .try_into()
+            .map_err(|_| RevertReason::value_is_too_large("balance type").into())
+    }
+}
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
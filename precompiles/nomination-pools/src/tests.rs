@@ -0,0 +1,182 @@
+use crate::{mock::*, *};
+use frame_support::assert_ok;
+use polkadot_sdk::sp_io;
+use precompile_utils::testing::*;
+use sp_core::H160;
+
+type PCall = NominationPoolsPrecompileCall<Runtime>;
+
+fn precompiles() -> Precompiles<Runtime> {
+    PrecompilesValue::get()
+}
+
+/// Externalities where Bob has created the nomination pool 1.
+fn pool_created() -> sp_io::TestExternalities {
+    let mut ext = ExtBuilder::default()
+        .with_balances(vec![(Alice.into(), 1000), (Bob.into(), 1000)])
+        .build();
+    ext.execute_with(|| {
+        assert_ok!(Pools::create(
+            RuntimeOrigin::signed(Bob.into()),
+            100,
+            Bob.into(),
+            Bob.into(),
+            Bob.into()
+        ));
+    });
+    ext
+}
+
+fn db_read() -> u64 {
+    RuntimeHelper::<Runtime>::db_read_gas_cost()
+}
+
+fn dispatch_error(output: &[u8], error: &str) -> bool {
+    core::str::from_utf8(output).map_or(false, |reason| reason.contains(error))
+}
+
+fn join_pool(who: impl Into<H160>, amount: Balance) {
+    precompiles()
+        .prepare_test(
+            who,
+            Precompile1,
+            PCall::join {
+                amount: amount.into(),
+                pool_id: 1,
+            },
+        )
+        .execute_returns(());
+}
+
+#[test]
+fn selectors() {
+    assert!(PCall::pending_rewards_selectors().contains(&0x31d7a262));
+    assert!(PCall::pool_member_selectors().contains(&0x24ac6ae2));
+    assert!(PCall::join_selectors().contains(&0x98f15d02));
+    assert!(PCall::bond_extra_selectors().contains(&0xeaca88de));
+    assert!(PCall::bond_extra_rewards_selectors().contains(&0x026db81f));
+    assert!(PCall::claim_payout_selectors().contains(&0xe88499a9));
+    assert!(PCall::unbond_selectors().contains(&0xa5d059ca));
+    assert!(PCall::pool_withdraw_unbonded_selectors().contains(&0x1332e2ef));
+    assert!(PCall::withdraw_unbonded_selectors().contains(&0xb5fb9875));
+}
+
+#[test]
+fn join_and_pool_member() {
+    pool_created().execute_with(|| {
+        precompiles()
+            .prepare_test(
+                Bob,
+                Precompile1,
+                PCall::pool_member {
+                    member: Address(Alice.into()),
+                },
+            )
+            .expect_cost(3 * db_read())
+            .expect_no_logs()
+            .execute_returns((0u32, U256::zero(), U256::zero()));
+
+        join_pool(Alice, 10);
+
+        let joined = U256::from(10);
+        precompiles()
+            .prepare_test(
+                Bob,
+                Precompile1,
+                PCall::pool_member {
+                    member: Address(Alice.into()),
+                },
+            )
+            .execute_returns((1u32, joined, joined));
+        assert_eq!(
+            pallet_nomination_pools::PoolMembers::<Runtime>::get(AccountId::from(Alice))
+                .map(|member| member.pool_id),
+            Some(1)
+        );
+
+        precompiles()
+            .prepare_test(
+                Bob,
+                Precompile1,
+                PCall::pending_rewards {
+                    member: Address(Alice.into()),
+                },
+            )
+            .expect_cost(4 * db_read())
+            .expect_no_logs()
+            .execute_returns(U256::zero());
+    });
+}
+
+#[test]
+fn join_reverts() {
+    pool_created().execute_with(|| {
+        precompiles()
+            .prepare_test(
+                Alice,
+                Precompile1,
+                PCall::join {
+                    amount: 10.into(),
+                    pool_id: 2,
+                },
+            )
+            .execute_reverts(|output| dispatch_error(output, "PoolNotFound"));
+        precompiles()
+            .prepare_test(
+                Alice,
+                Precompile1,
+                PCall::join {
+                    amount: U256::MAX,
+                    pool_id: 1,
+                },
+            )
+            .execute_reverts(|output| output == b"amount: Value is too large for balance type");
+
+        join_pool(Alice, 10);
+        precompiles()
+            .prepare_test(
+                Alice,
+                Precompile1,
+                PCall::join {
+                    amount: 10.into(),
+                    pool_id: 1,
+                },
+            )
+            .execute_reverts(|output| dispatch_error(output, "AccountBelongsToOtherPool"));
+    });
+}
+
+#[test]
+fn bond_extra_and_unbond() {
+    pool_created().execute_with(|| {
+        join_pool(Alice, 10);
+
+        precompiles()
+            .prepare_test(Alice, Precompile1, PCall::bond_extra { extra: 2.into() })
+            .execute_returns(());
+        precompiles()
+            .prepare_test(
+                Alice,
+                Precompile1,
+                PCall::unbond {
+                    member: Address(Alice.into()),
+                    unbonding_points: 6.into(),
+                },
+            )
+            .execute_returns(());
+
+        let remaining = U256::from(6);
+        precompiles()
+            .prepare_test(
+                Alice,
+                Precompile1,
+                PCall::pool_member {
+                    member: Address(Alice.into()),
+                },
+            )
+            .execute_returns((1u32, remaining, remaining));
+        let member = pallet_nomination_pools::PoolMembers::<Runtime>::get(AccountId::from(Alice))
+            .expect("Alice is a pool member");
+        assert_eq!(member.unbonding_eras.values().sum::<Balance>(), 6);
+    });
+}
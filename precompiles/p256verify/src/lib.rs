@@ -0,0 +1,78 @@
+//! secp256r1 (P-256) signature verification precompile, as specified by RIP-7212.
+//!
+//! The input is the 32 bytes message hash followed by the `r` and `s` signature components and
+//! the `x` and `y` public key coordinates, each encoded as 32 bytes big-endian. The output is 1
+//! as a 32 bytes word if the signature is valid, and empty otherwise.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use fp_evm::{ExitSucceed, Precompile, PrecompileHandle, PrecompileOutput, PrecompileResult};
+use p256::{
+    ecdsa::{signature::hazmat::PrehashVerifier, Signature, VerifyingKey},
+    EncodedPoint, FieldBytes,
+};
+use polkadot_sdk::sp_std;
+use sp_std::vec::Vec;
+
+/// Size of the input of the precompile.
+const INPUT_LENGTH: usize = 160;
+
+/// Verification of secp256r1 signatures, at `0x100`.
+pub struct P256Verify;
+
+impl P256Verify {
+    const GAS_COST: u64 = 3_450;
+
+    /// Whether `input` is a well formed and valid signature of a message hash.
+    fn verify(input: &[u8]) -> bool {
+        if input.len() != INPUT_LENGTH {
+            return false;
+        }
+
+        let hash = &input[..32];
+        let r = FieldBytes::clone_from_slice(&input[32..64]);
+        let s = FieldBytes::clone_from_slice(&input[64..96]);
+        let x = FieldBytes::clone_from_slice(&input[96..128]);
+        let y = FieldBytes::clone_from_slice(&input[128..160]);
+
+        let Ok(signature) = Signature::from_scalars(r, s) else {
+            return false;
+        };
+        let point = EncodedPoint::from_affine_coordinates(&x, &y, false);
+        let Ok(public_key) = VerifyingKey::from_encoded_point(&point) else {
+            return false;
+        };
+
+        public_key.verify_prehash(hash, &signature).is_ok()
+    }
+}
+
+impl Precompile for P256Verify {
+    fn execute(handle: &mut impl PrecompileHandle) -> PrecompileResult {
+        handle.record_cost(Self::GAS_COST)?;
+
+        let output = if Self::verify(handle.input()) {
+            let mut output = Vec::from([0u8; 32]);
+            output[31] = 1;
+            output
+        } else {
+            Vec::new()
+        };
+
+        Ok(PrecompileOutput {
+            exit_status: ExitSucceed::Returned,
+            output,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pallet_evm_test_vector_support::test_precompile_test_vectors;
+
+    #[test]
+    fn process_consensus_tests_for_p256_verify() -> Result<(), String> {
+        test_precompile_test_vectors::<P256Verify>("../testdata/p256_verify.json")
+    }
+}
@@ -0,0 +1,33 @@
+// SPDX-License-Identifier: GPL-3.0-only
+pragma solidity >=0.8.3;
+
+/// @dev The Preimage contract's address.
+address constant PREIMAGE_ADDRESS = 0x0000000000000000000000000000000000000803;
+
+/// @dev The Preimage contract's instance.
+Preimage constant PREIMAGE_CONTRACT = Preimage(PREIMAGE_ADDRESS);
+
+/// @title Pallet Preimage Interface
+/// @dev The interface through which solidity contracts will interact with pallet-preimage.
+interface Preimage {
+    /// @dev Register a preimage on-chain, reserving the deposit from the caller.
+    /// @custom:selector cb00f603
+    /// @param encodedProposal The SCALE encoded call.
+    /// @return preimageHash The hash of the preimage.
+    function notePreimage(bytes memory encodedProposal)
+        external
+        returns (bytes32 preimageHash);
+
+    /// @dev Clear an unrequested preimage previously noted by the caller.
+    /// @custom:selector 02e71b45
+    /// @param hash The hash of the preimage.
+    function unnotePreimage(bytes32 hash) external;
+
+    /// @dev Emitted when a preimage is registered.
+    /// @custom:selector 8cb56a8ebdafbb14e25ec706da62a7dde761968dbf1fb45be207d1b15c88c187
+    event PreimageNoted(bytes32 hash);
+
+    /// @dev Emitted when a preimage is unregistered.
+    /// @custom:selector be6cb9502cce812b6de50cc08f2481900ff6c7c6466df7d39c9f27a5f2b9c572
+    event PreimageUnnoted(bytes32 hash);
+}
@@ -0,0 +1,101 @@
+//! Precompile to interact with `pallet_preimage` from the EVM.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use core::marker::PhantomData;
+use fp_evm::PrecompileHandle;
+use frame_support::{
+    dispatch::{GetDispatchInfo, PostDispatchInfo},
+    traits::{ConstU32, OriginTrait},
+};
+use pallet_evm::AddressMapping;
+use polkadot_sdk::{frame_support, frame_system, pallet_preimage, sp_runtime, sp_std};
+use precompile_utils::prelude::*;
+use sp_core::H256;
+use sp_runtime::traits::{Dispatchable, Hash};
+use sp_std::vec::Vec;
+
+/// Maximum size of a noted preimage.
+pub const ENCODED_PROPOSAL_SIZE_LIMIT: u32 = pallet_preimage::MAX_SIZE;
+type GetEncodedProposalSizeLimit = ConstU32<ENCODED_PROPOSAL_SIZE_LIMIT>;
+
+/// Solidity selector of the PreimageNoted log, which is the Keccak of the Log signature.
+pub const SELECTOR_LOG_PREIMAGE_NOTED: [u8; 32] = keccak256!("PreimageNoted(bytes32)");
+
+/// Solidity selector of the PreimageUnnoted log, which is the Keccak of the Log signature.
+pub const SELECTOR_LOG_PREIMAGE_UNNOTED: [u8; 32] = keccak256!("PreimageUnnoted(bytes32)");
+
+/// A precompile to wrap the functionality from `pallet_preimage`.
+pub struct PreimagePrecompile<Runtime>(PhantomData<Runtime>);
+
+#[precompile_utils::precompile]
+impl<Runtime> PreimagePrecompile<Runtime>
+where
+    Runtime: pallet_preimage::Config + pallet_evm::Config + frame_system::Config,
+    <Runtime as frame_system::Config>::Hash: Into<H256> + From<H256>,
+    Runtime::RuntimeCall: Dispatchable<PostInfo = PostDispatchInfo> + GetDispatchInfo,
+    Runtime::RuntimeCall: From<pallet_preimage::Call<Runtime>>,
+    <Runtime::RuntimeCall as Dispatchable>::RuntimeOrigin: From<Option<Runtime::AccountId>>,
+    <Runtime::RuntimeCall as Dispatchable>::RuntimeOrigin: OriginTrait,
+{
+    /// Register a preimage on-chain.
+    ///
+    /// Parameters:
+    /// * encoded_proposal: The preimage registered on-chain
+    #[precompile::public("notePreimage(bytes)")]
+    fn note_preimage(
+        handle: &mut impl PrecompileHandle,
+        encoded_proposal: BoundedBytes<GetEncodedProposalSizeLimit>,
+    ) -> EvmResult<H256> {
+        handle.record_log_costs_manual(1, 32)?;
+
+        let bytes: Vec<u8> = encoded_proposal.into();
+        let hash: H256 = <Runtime as frame_system::Config>::Hashing::hash(&bytes).into();
+
+        let origin = Runtime::AddressMapping::into_account_id(handle.context().caller);
+        RuntimeHelper::<Runtime>::try_dispatch(
+            handle,
+            Some(origin).into(),
+            pallet_preimage::Call::<Runtime>::note_preimage { bytes },
+        )?;
+
+        log1(
+            handle.context().address,
+            SELECTOR_LOG_PREIMAGE_NOTED,
+            solidity::encode_event_data(hash),
+        )
+        .record(handle)?;
+
+        Ok(hash)
+    }
+
+    /// Clear an unrequested preimage from the runtime storage.
+    ///
+    /// Parameters:
+    /// * hash: The preimage cleared from storage
+    #[precompile::public("unnotePreimage(bytes32)")]
+    fn unnote_preimage(handle: &mut impl PrecompileHandle, hash: H256) -> EvmResult {
+        handle.record_log_costs_manual(1, 32)?;
+
+        let origin = Runtime::AddressMapping::into_account_id(handle.context().caller);
+        RuntimeHelper::<Runtime>::try_dispatch(
+            handle,
+            Some(origin).into(),
+            pallet_preimage::Call::<Runtime>::unnote_preimage { hash: hash.into() },
+        )?;
+
+        log1(
+            handle.context().address,
+            SELECTOR_LOG_PREIMAGE_UNNOTED,
+            solidity::encode_event_data(hash),
+        )
+        .record(handle)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,125 @@
+use crate::{mock::*, *};
+use frame_support::traits::QueryPreimage;
+use precompile_utils::testing::*;
+use sp_runtime::traits::BlakeTwo256;
+
+type PCall = PreimagePrecompileCall<Runtime>;
+
+fn precompiles() -> Precompiles<Runtime> {
+    PrecompilesValue::get()
+}
+
+fn balances() -> ExtBuilder {
+    ExtBuilder::default().with_balances(vec![(Alice.into(), 1000), (Bob.into(), 1000)])
+}
+
+const PREIMAGE: &[u8] = b"an encoded proposal";
+
+fn note_preimage(who: impl Into<H160>) -> H256 {
+    let hash = BlakeTwo256::hash(PREIMAGE);
+    precompiles()
+        .prepare_test(
+            who,
+            Precompile1,
+            PCall::note_preimage {
+                encoded_proposal: PREIMAGE.into(),
+            },
+        )
+        .execute_returns(hash);
+    hash
+}
+
+fn dispatch_error(output: &[u8], error: &str) -> bool {
+    core::str::from_utf8(output).map_or(false, |reason| reason.contains(error))
+}
+
+#[test]
+fn selectors() {
+    assert!(PCall::note_preimage_selectors().contains(&0xcb00f603));
+    assert!(PCall::unnote_preimage_selectors().contains(&0x02e71b45));
+}
+
+#[test]
+fn note_preimage_stores_it() {
+    balances().build().execute_with(|| {
+        let hash = BlakeTwo256::hash(PREIMAGE);
+        precompiles()
+            .prepare_test(
+                Alice,
+                Precompile1,
+                PCall::note_preimage {
+                    encoded_proposal: PREIMAGE.into(),
+                },
+            )
+            .expect_log(log1(
+                Precompile1,
+                SELECTOR_LOG_PREIMAGE_NOTED,
+                solidity::encode_event_data(hash),
+            ))
+            .execute_returns(hash);
+
+        assert_eq!(
+            <Preimage as QueryPreimage>::len(&hash),
+            Some(PREIMAGE.len() as u32)
+        );
+        assert_eq!(
+            <Preimage as QueryPreimage>::fetch(&hash, None).map(|bytes| bytes.into_owned()),
+            Ok(PREIMAGE.to_vec())
+        );
+    });
+}
+
+#[test]
+fn note_preimage_twice_reverts() {
+    balances().build().execute_with(|| {
+        note_preimage(Alice);
+
+        precompiles()
+            .prepare_test(
+                Alice,
+                Precompile1,
+                PCall::note_preimage {
+                    encoded_proposal: PREIMAGE.into(),
+                },
+            )
+            .execute_reverts(|output| dispatch_error(output, "AlreadyNoted"));
+    });
+}
+
+#[test]
+fn unnote_preimage_removes_it() {
+    balances().build().execute_with(|| {
+        let hash = note_preimage(Alice);
+
+        precompiles()
+            .prepare_test(Alice, Precompile1, PCall::unnote_preimage { hash })
+            .expect_log(log1(
+                Precompile1,
+                SELECTOR_LOG_PREIMAGE_UNNOTED,
+                solidity::encode_event_data(hash),
+            ))
+            .execute_returns(());
+
+        assert_eq!(<Preimage as QueryPreimage>::len(&hash), None);
+    });
+}
+
+#[test]
+fn unnote_preimage_reverts() {
+    balances().build().execute_with(|| {
+        let hash = BlakeTwo256::hash(PREIMAGE);
+        precompiles()
+            .prepare_test(Alice, Precompile1, PCall::unnote_preimage { hash })
+            .execute_reverts(|output| dispatch_error(output, "NotNoted"));
+
+        note_preimage(Alice);
+        precompiles()
+            .prepare_test(Bob, Precompile1, PCall::unnote_preimage { hash })
+            .execute_reverts(|output| dispatch_error(output, "NotAuthorized"));
+
+        assert_eq!(
+            <Preimage as QueryPreimage>::len(&hash),
+            Some(PREIMAGE.len() as u32)
+        );
+    });
+}
@@ -0,0 +1,74 @@
+// SPDX-License-Identifier: GPL-3.0-only
+pragma solidity >=0.8.3;
+
+/// @dev The Proxy contract's address.
+address constant PROXY_ADDRESS = 0x0000000000000000000000000000000000000809;
+
+/// @dev The Proxy contract's instance.
+Proxy constant PROXY_CONTRACT = Proxy(PROXY_ADDRESS);
+
+/// @title Pallet Proxy Interface
+/// @dev The interface through which solidity contracts will interact with pallet-proxy.
+interface Proxy {
+    /// @dev Defines the proxy permission types, in the order of the runtime `ProxyType`.
+    enum ProxyType {
+        Any,
+        NonTransfer,
+        Governance,
+        Staking
+    }
+
+    /// @dev Register a proxy account for the sender that is able to make calls on its behalf.
+    /// A delegate can only be registered once per account, whatever the proxy type.
+    /// @custom:selector 74a34dd3
+    /// @param delegate The account that the caller would like to make a proxy.
+    /// @param proxyType The permissions allowed for this proxy account.
+    /// @param delay The announcement period required of the initial proxy, will generally be zero.
+    function addProxy(
+        address delegate,
+        ProxyType proxyType,
+        uint32 delay
+    ) external;
+
+    /// @dev Unregister a proxy account for the sender.
+    /// @custom:selector fef3f708
+    /// @param delegate The account that the caller would like to remove as a proxy.
+    /// @param proxyType The permissions currently enabled for the removed proxy account.
+    /// @param delay The announcement period of the removed proxy.
+    function removeProxy(
+        address delegate,
+        ProxyType proxyType,
+        uint32 delay
+    ) external;
+
+    /// @dev Unregister all proxy accounts for the sender.
+    /// @custom:selector 14a5b5fa
+    function removeProxies() external;
+
+    /// @dev Perform a call through a proxy, with `real` as the caller of the call.
+    /// The value sent to the precompile is given back to the sender and paid by `real` instead.
+    /// Only proxies without delay can be used, and the call must be allowed by the proxy type.
+    /// @custom:selector 0d3cff86
+    /// @param real The account that the proxy will make a call on behalf of.
+    /// @param callTo Recipient of the call to be made by the `real` account.
+    /// @param callData Data of the call to be made by the `real` account.
+    function proxy(
+        address real,
+        address callTo,
+        bytes memory callData
+    ) external payable;
+
+    /// @dev Checks if the delegate is a proxy of the real account with the given type and delay.
+    /// @custom:selector e26d38ed
+    /// @param real The account that the proxy would make calls on behalf of.
+    /// @param delegate The proxy account.
+    /// @param proxyType The permissions allowed for the proxy.
+    /// @param delay The announcement period of the proxy.
+    /// @return exists True if the proxy exists, false otherwise.
+    function isProxy(
+        address real,
+        address delegate,
+        ProxyType proxyType,
+        uint32 delay
+    ) external view returns (bool exists);
+}
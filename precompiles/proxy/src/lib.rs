@@ -0,0 +1,292 @@
+// Copyright 2019-2022 PureStake Inc.
+// This file is part of SCS Chain. It is derived from Moonbeam's precompiles
+// (https://github.com/moonbeam-foundation/moonbeam).
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Precompile to manage and use `pallet_proxy` proxies from the EVM.
+//!
+//! Proxies are managed with a signed origin of the caller. A proxy can then perform EVM
+//! calls on behalf of the account it is a proxy of, as long as the proxy type allows it.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::{Decode, MaxEncodedLen};
+use core::marker::PhantomData;
+use fp_evm::{Context, ExitReason, PrecompileFailure, PrecompileHandle, Transfer};
+use frame_support::{
+    dispatch::{GetDispatchInfo, PostDispatchInfo},
+    ensure,
+    traits::{fungible::Mutate, tokens::Preservation, Get, OriginTrait},
+};
+use frame_system::pallet_prelude::BlockNumberFor;
+use pallet_balances::WeightInfo;
+use pallet_evm::{AddressMapping, GasWeightMapping};
+use pallet_proxy::ProxyDefinition;
+use polkadot_sdk::{
+    frame_support, frame_system, pallet_balances, pallet_proxy, sp_runtime, sp_std,
+};
+use precompile_utils::{prelude::*, substrate::TryDispatchError};
+use sp_core::{ConstU32, H160, U256};
+use sp_runtime::traits::{Dispatchable, StaticLookup, Zero};
+use sp_std::vec::Vec;
+
+/// Maximum size of the call data of a proxied call.
+pub const CALL_DATA_LIMIT: u32 = 2u32.pow(16);
+type GetCallDataLimit = ConstU32<CALL_DATA_LIMIT>;
+
+/// An EVM call performed by a proxy on behalf of the account it is a proxy of.
+#[derive(Debug, Clone)]
+pub struct EvmSubCall {
+    pub to: H160,
+    pub value: U256,
+    pub call_data: Vec<u8>,
+}
+
+/// The EVM counterpart of `InstanceFilter<RuntimeCall>`, deciding which EVM calls a proxy type
+/// allows.
+pub trait EvmProxyCallFilter {
+    /// Whether a proxy of this type may perform `call`. Nothing is allowed by default.
+    fn is_evm_proxy_call_allowed(&self, _call: &EvmSubCall) -> bool {
+        false
+    }
+}
+
+/// A precompile to wrap the functionality from `pallet_proxy`.
+pub struct ProxyPrecompile<Runtime>(PhantomData<Runtime>);
+
+#[precompile_utils::precompile]
+impl<Runtime> ProxyPrecompile<Runtime>
+where
+    Runtime: pallet_proxy::Config + pallet_balances::Config + pallet_evm::Config,
+    Runtime::ProxyType: Decode + EvmProxyCallFilter,
+    Runtime::RuntimeCall: Dispatchable<PostInfo = PostDispatchInfo> + GetDispatchInfo,
+    Runtime::RuntimeCall: From<pallet_proxy::Call<Runtime>>,
+    <Runtime::RuntimeCall as Dispatchable>::RuntimeOrigin: From<Option<Runtime::AccountId>>,
+    <Runtime::RuntimeCall as Dispatchable>::RuntimeOrigin: OriginTrait,
+    <Runtime as pallet_balances::Config>::Balance: TryFrom<U256>,
+    BlockNumberFor<Runtime>: From<u32>,
+{
+    #[precompile::public("addProxy(address,uint8,uint32)")]
+    fn add_proxy(
+        handle: &mut impl PrecompileHandle,
+        delegate: Address,
+        proxy_type: u8,
+        delay: u32,
+    ) -> EvmResult {
+        Self::record_proxies_read(handle)?;
+
+        let origin = Runtime::AddressMapping::into_account_id(handle.context().caller);
+        let delegate = Runtime::AddressMapping::into_account_id(delegate.into());
+        let proxy_type = Self::u8_to_proxy_type(proxy_type).in_field("proxyType")?;
+
+        // A delegate can only be the proxy of an account once, whatever the proxy type.
+        ensure!(
+            pallet_proxy::Pallet::<Runtime>::find_proxy(&origin, &delegate, None).is_err(),
+            revert("Cannot add more than one proxy")
+        );
+
+        RuntimeHelper::<Runtime>::try_dispatch(
+            handle,
+            Some(origin).into(),
+            pallet_proxy::Call::<Runtime>::add_proxy {
+                delegate: Runtime::Lookup::unlookup(delegate),
+                proxy_type,
+                delay: delay.into(),
+            },
+        )?;
+
+        Ok(())
+    }
+
+    #[precompile::public("removeProxy(address,uint8,uint32)")]
+    fn remove_proxy(
+        handle: &mut impl PrecompileHandle,
+        delegate: Address,
+        proxy_type: u8,
+        delay: u32,
+    ) -> EvmResult {
+        let origin = Runtime::AddressMapping::into_account_id(handle.context().caller);
+        let delegate = Runtime::AddressMapping::into_account_id(delegate.into());
+        let proxy_type = Self::u8_to_proxy_type(proxy_type).in_field("proxyType")?;
+
+        RuntimeHelper::<Runtime>::try_dispatch(
+            handle,
+            Some(origin).into(),
+            pallet_proxy::Call::<Runtime>::remove_proxy {
+                delegate: Runtime::Lookup::unlookup(delegate),
+                proxy_type,
+                delay: delay.into(),
+            },
+        )?;
+
+        Ok(())
+    }
+
+    #[precompile::public("removeProxies()")]
+    fn remove_proxies(handle: &mut impl PrecompileHandle) -> EvmResult {
+        let origin = Runtime::AddressMapping::into_account_id(handle.context().caller);
+
+        RuntimeHelper::<Runtime>::try_dispatch(
+            handle,
+            Some(origin).into(),
+            pallet_proxy::Call::<Runtime>::remove_proxies {},
+        )?;
+
+        Ok(())
+    }
+
+    #[precompile::public("isProxy(address,address,uint8,uint32)")]
+    #[precompile::view]
+    fn is_proxy(
+        handle: &mut impl PrecompileHandle,
+        real: Address,
+        delegate: Address,
+        proxy_type: u8,
+        delay: u32,
+    ) -> EvmResult<bool> {
+        Self::record_proxies_read(handle)?;
+
+        let real = Runtime::AddressMapping::into_account_id(real.into());
+        let delegate = Runtime::AddressMapping::into_account_id(delegate.into());
+        let proxy_type = Self::u8_to_proxy_type(proxy_type).in_field("proxyType")?;
+        let delay: BlockNumberFor<Runtime> = delay.into();
+
+        let (proxies, _) = pallet_proxy::Proxies::<Runtime>::get(real);
+
+        Ok(proxies.iter().any(|proxy| {
+            proxy.delegate == delegate && proxy.proxy_type == proxy_type && proxy.delay == delay
+        }))
+    }
+
+    #[precompile::public("proxy(address,address,bytes)")]
+    #[precompile::payable]
+    fn proxy(
+        handle: &mut impl PrecompileHandle,
+        real: Address,
+        call_to: Address,
+        call_data: BoundedBytes<GetCallDataLimit>,
+    ) -> EvmResult<UnboundedBytes> {
+        let real: H160 = real.into();
+        let call = EvmSubCall {
+            to: call_to.into(),
+            value: handle.context().apparent_value,
+            call_data: call_data.into(),
+        };
+
+        // Proxy calls are only performed on behalf of externally owned accounts.
+        // AccountCodes: Blake2_128Concat(16) + H160(20) + Vec(5)
+        handle.record_db_read::<Runtime>(41)?;
+        ensure!(
+            pallet_evm::AccountCodes::<Runtime>::decode_len(real).unwrap_or(0) == 0,
+            revert("real address must be EOA")
+        );
+
+        Self::record_proxies_read(handle)?;
+
+        let who = Runtime::AddressMapping::into_account_id(handle.context().caller);
+        let real_account = Runtime::AddressMapping::into_account_id(real);
+
+        let proxy = pallet_proxy::Pallet::<Runtime>::find_proxy(&real_account, &who, None)
+            .map_err(|_| revert("Not proxy"))?;
+        ensure!(proxy.delay.is_zero(), revert("Unannounced"));
+        ensure!(
+            proxy.proxy_type.is_evm_proxy_call_allowed(&call),
+            revert("CallFiltered")
+        );
+
+        let EvmSubCall {
+            to,
+            value,
+            call_data,
+        } = call;
+
+        let sub_context = Context {
+            caller: real,
+            address: to,
+            apparent_value: value,
+        };
+
+        let transfer = if value.is_zero() {
+            None
+        } else {
+            // The value is paid by `real`, so the value received by the precompile is sent back
+            // to the proxy. It is not dispatched as a `pallet_balances` call, which the call
+            // filter of the runtime may forbid.
+            let amount = Self::u256_to_amount(value).in_field("value")?;
+            handle.record_cost(Runtime::GasWeightMapping::weight_to_gas(
+                <Runtime as pallet_balances::Config>::WeightInfo::transfer_allow_death(),
+            ))?;
+            let precompile = Runtime::AddressMapping::into_account_id(handle.context().address);
+            <pallet_balances::Pallet<Runtime> as Mutate<_>>::transfer(
+                &precompile,
+                &who,
+                amount,
+                Preservation::Expendable,
+            )
+            .map_err(TryDispatchError::Substrate)?;
+
+            Some(Transfer {
+                source: real,
+                target: to,
+                value,
+            })
+        };
+
+        let (reason, output) = handle.call(
+            to,
+            transfer,
+            call_data,
+            Some(handle.remaining_gas()),
+            false,
+            &sub_context,
+        );
+        match reason {
+            ExitReason::Error(exit_status) => Err(PrecompileFailure::Error { exit_status }),
+            ExitReason::Fatal(exit_status) => Err(PrecompileFailure::Fatal { exit_status }),
+            ExitReason::Revert(_) => Err(revert(output)),
+            ExitReason::Succeed(_) => Ok(output.into()),
+        }
+    }
+
+    fn record_proxies_read(handle: &mut impl PrecompileHandle) -> EvmResult {
+        // Storage item: Proxies:
+        // Twox64Concat(8) + AccountId(20) + BoundedVec(MaxProxies * ProxyDefinition)
+        // + Balance(16)
+        handle.record_db_read::<Runtime>(
+            44 + ProxyDefinition::<
+                Runtime::AccountId,
+                Runtime::ProxyType,
+                BlockNumberFor<Runtime>,
+            >::max_encoded_len()
+                * (Runtime::MaxProxies::get() as usize),
+        )
+    }
+
+    fn u256_to_amount(value: U256) -> MayRevert<<Runtime as pallet_balances::Config>::Balance> {
+        value
+            .try_into()
+            .map_err(|_| RevertReason::value_is_too_large("balance type").into())
+    }
+
+    fn u8_to_proxy_type(proxy_type: u8) -> MayRevert<Runtime::ProxyType> {
+        Runtime::ProxyType::decode(&mut [proxy_type].as_slice())
+            .map_err(|_| RevertReason::custom("unknown proxy type").into())
+    }
+}
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,271 @@
+use crate::{mock::*, *};
+use frame_support::assert_ok;
+use precompile_utils::testing::*;
+
+type PCall = ProxyPrecompileCall<Runtime>;
+
+fn precompiles() -> Precompiles<Runtime> {
+    PrecompilesValue::get()
+}
+
+fn balances() -> ExtBuilder {
+    ExtBuilder::default().with_balances(vec![
+        (Alice.into(), 1000),
+        (Bob.into(), 1000),
+        (Charlie.into(), 1000),
+    ])
+}
+
+fn add_proxy(real: impl Into<H160>, delegate: impl Into<H160>, proxy_type: ProxyType, delay: u32) {
+    precompiles()
+        .prepare_test(
+            real,
+            Precompile1,
+            PCall::add_proxy {
+                delegate: Address(delegate.into()),
+                proxy_type: proxy_type as u8,
+                delay,
+            },
+        )
+        .execute_returns(());
+}
+
+fn is_proxy(real: impl Into<H160>, delegate: impl Into<H160>, proxy_type: ProxyType) -> bool {
+    let (real, delegate): (H160, H160) = (real.into(), delegate.into());
+    pallet_proxy::Pallet::<Runtime>::find_proxy(&real.into(), &delegate.into(), Some(proxy_type))
+        .is_ok()
+}
+
+fn proxy_call(real: impl Into<H160>, call_to: impl Into<H160>) -> PCall {
+    PCall::proxy {
+        real: Address(real.into()),
+        call_to: Address(call_to.into()),
+        call_data: b"call data".as_slice().into(),
+    }
+}
+
+#[test]
+fn selectors() {
+    assert!(PCall::add_proxy_selectors().contains(&0x74a34dd3));
+    assert!(PCall::remove_proxy_selectors().contains(&0xfef3f708));
+    assert!(PCall::remove_proxies_selectors().contains(&0x14a5b5fa));
+    assert!(PCall::is_proxy_selectors().contains(&0xe26d38ed));
+    assert!(PCall::proxy_selectors().contains(&0x0d3cff86));
+}
+
+#[test]
+fn add_proxy_and_is_proxy() {
+    balances().build().execute_with(|| {
+        add_proxy(Alice, Bob, ProxyType::NoValue, 0);
+
+        assert!(is_proxy(Alice, Bob, ProxyType::NoValue));
+        assert_eq!(Balances::reserved_balance(AccountId::from(Alice)), 101);
+        precompiles()
+            .prepare_test(
+                Charlie,
+                Precompile1,
+                PCall::is_proxy {
+                    real: Address(Alice.into()),
+                    delegate: Address(Bob.into()),
+                    proxy_type: ProxyType::NoValue as u8,
+                    delay: 0,
+                },
+            )
+            .expect_cost(RuntimeHelper::<Runtime>::db_read_gas_cost())
+            .expect_no_logs()
+            .execute_returns(true);
+        precompiles()
+            .prepare_test(
+                Charlie,
+                Precompile1,
+                PCall::is_proxy {
+                    real: Address(Alice.into()),
+                    delegate: Address(Bob.into()),
+                    proxy_type: ProxyType::Any as u8,
+                    delay: 0,
+                },
+            )
+            .execute_returns(false);
+    });
+}
+
+#[test]
+fn add_proxy_reverts() {
+    balances().build().execute_with(|| {
+        precompiles()
+            .prepare_test(
+                Alice,
+                Precompile1,
+                PCall::add_proxy {
+                    delegate: Address(Bob.into()),
+                    proxy_type: 9,
+                    delay: 0,
+                },
+            )
+            .execute_reverts(|output| output == b"proxyType: unknown proxy type");
+
+        add_proxy(Alice, Bob, ProxyType::NoValue, 0);
+        precompiles()
+            .prepare_test(
+                Alice,
+                Precompile1,
+                PCall::add_proxy {
+                    delegate: Address(Bob.into()),
+                    proxy_type: ProxyType::Any as u8,
+                    delay: 0,
+                },
+            )
+            .execute_reverts(|output| output == b"Cannot add more than one proxy");
+
+        assert!(!is_proxy(Alice, Bob, ProxyType::Any));
+    });
+}
+
+#[test]
+fn remove_proxy_and_proxies() {
+    balances().build().execute_with(|| {
+        add_proxy(Alice, Bob, ProxyType::Any, 0);
+        add_proxy(Alice, Charlie, ProxyType::Any, 0);
+
+        precompiles()
+            .prepare_test(
+                Alice,
+                Precompile1,
+                PCall::remove_proxy {
+                    delegate: Address(Bob.into()),
+                    proxy_type: ProxyType::Any as u8,
+                    delay: 0,
+                },
+            )
+            .execute_returns(());
+        assert!(!is_proxy(Alice, Bob, ProxyType::Any));
+        assert!(is_proxy(Alice, Charlie, ProxyType::Any));
+
+        precompiles()
+            .prepare_test(Alice, Precompile1, PCall::remove_proxies {})
+            .execute_returns(());
+        assert!(!is_proxy(Alice, Charlie, ProxyType::Any));
+        assert_eq!(Balances::reserved_balance(AccountId::from(Alice)), 0);
+    });
+}
+
+#[test]
+fn proxy_calls_on_behalf_of_the_real_account() {
+    balances().build().execute_with(|| {
+        add_proxy(Alice, Bob, ProxyType::Any, 0);
+
+        precompiles()
+            .prepare_test(Bob, Precompile1, proxy_call(Alice, Charlie))
+            .with_subcall_handle(|subcall: Subcall| {
+                assert_eq!(subcall.address, Charlie.into());
+                assert_eq!(subcall.context.caller, Alice.into());
+                assert_eq!(subcall.context.address, Charlie.into());
+                assert!(subcall.transfer.is_none());
+                assert_eq!(subcall.input, b"call data".to_vec());
+
+                SubcallOutput {
+                    output: b"returned".to_vec(),
+                    cost: 13,
+                    ..SubcallOutput::succeed()
+                }
+            })
+            .execute_returns(UnboundedBytes::from(b"returned".as_slice()));
+    });
+}
+
+#[test]
+fn proxy_call_with_value() {
+    balances().build().execute_with(|| {
+        add_proxy(Alice, Bob, ProxyType::Any, 0);
+        // The EVM transfers the value of the call to the precompile before running it.
+        assert_ok!(<Balances as Mutate<AccountId>>::transfer(
+            &Bob.into(),
+            &Precompile1.into(),
+            100,
+            Preservation::Expendable,
+        ));
+
+        precompiles()
+            .prepare_test(Bob, Precompile1, proxy_call(Alice, Charlie))
+            .with_value(100)
+            .with_subcall_handle(|subcall: Subcall| {
+                assert_eq!(subcall.context.apparent_value, U256::from(100));
+                assert_eq!(
+                    subcall.transfer.map(|transfer| (
+                        transfer.source,
+                        transfer.target,
+                        transfer.value
+                    )),
+                    Some((Alice.into(), Charlie.into(), U256::from(100)))
+                );
+                SubcallOutput::succeed()
+            })
+            .execute_returns(UnboundedBytes::from(b"".as_slice()));
+
+        // The value is paid by Alice in the subcall, so Bob gets his back.
+        assert_eq!(Balances::free_balance(AccountId::from(Bob)), 1000);
+        assert_eq!(Balances::free_balance(AccountId::from(Precompile1)), 0);
+    });
+}
+
+#[test]
+fn proxy_reverts() {
+    balances().build().execute_with(|| {
+        precompiles()
+            .prepare_test(Bob, Precompile1, proxy_call(Alice, Charlie))
+            .execute_reverts(|output| output == b"Not proxy");
+
+        add_proxy(Alice, Bob, ProxyType::Any, 5);
+        precompiles()
+            .prepare_test(Bob, Precompile1, proxy_call(Alice, Charlie))
+            .execute_reverts(|output| output == b"Unannounced");
+
+        add_proxy(Charlie, Bob, ProxyType::Nothing, 0);
+        precompiles()
+            .prepare_test(Bob, Precompile1, proxy_call(Charlie, Alice))
+            .execute_reverts(|output| output == b"CallFiltered");
+    });
+}
+
+#[test]
+fn proxy_type_filters_value() {
+    balances().build().execute_with(|| {
+        add_proxy(Alice, Bob, ProxyType::NoValue, 0);
+
+        precompiles()
+            .prepare_test(Bob, Precompile1, proxy_call(Alice, Charlie))
+            .with_value(1)
+            .execute_reverts(|output| output == b"CallFiltered");
+        precompiles()
+            .prepare_test(Bob, Precompile1, proxy_call(Alice, Charlie))
+            .with_subcall_handle(|_| SubcallOutput::succeed())
+            .execute_returns(UnboundedBytes::from(b"".as_slice()));
+    });
+}
+
+#[test]
+fn proxy_of_a_contract_reverts() {
+    balances().build().execute_with(|| {
+        add_proxy(Alice, Bob, ProxyType::Any, 0);
+        pallet_evm::AccountCodes::<Runtime>::insert(H160::from(Alice), vec![0x00]);
+
+        precompiles()
+            .prepare_test(Bob, Precompile1, proxy_call(Alice, Charlie))
+            .execute_reverts(|output| output == b"real address must be EOA");
+    });
+}
+
+#[test]
+fn subcall_revert_is_forwarded() {
+    balances().build().execute_with(|| {
+        add_proxy(Alice, Bob, ProxyType::Any, 0);
+
+        precompiles()
+            .prepare_test(Bob, Precompile1, proxy_call(Alice, Charlie))
+            .with_subcall_handle(|_| SubcallOutput {
+                output: b"subcall reverted".to_vec(),
+                ..SubcallOutput::revert()
+            })
+            .execute_reverts(|output| output == b"subcall reverted");
+    });
+}
@@ -0,0 +1,94 @@
+// SPDX-License-Identifier: GPL-3.0-only
+pragma solidity >=0.8.3;
+
+/// @dev The Randomness contract's address.
+address constant RANDOMNESS_ADDRESS = 0x000000000000000000000000000000000000080C;
+
+/// @dev The Randomness contract's instance.
+Randomness constant RANDOMNESS_CONTRACT = Randomness(RANDOMNESS_ADDRESS);
+
+/// @title Pallet Babe Randomness Interface
+/// @dev The interface through which solidity contracts will read the randomness of
+/// pallet-babe, derived from the VRF outputs of the block authors.
+///
+/// The parent block randomness is known to the author of the parent block before the block
+/// is produced, and the epoch randomness is known to everyone during the whole epoch: both
+/// are predictable when a transaction is submitted and must not decide the outcome of a bet
+/// placed in the same transaction.
+///
+/// A request targets the randomness of the epoch three epochs after the current one, which
+/// is derived from the VRF outputs of the next epoch only, so it is unknown to everyone at
+/// the time of the request. The requester fulfils it during the target epoch; the request
+/// expires when the target epoch ends. Contracts should treat a request which is not
+/// fulfilled in time as lost, since a requester could otherwise abandon unfavourable
+/// outcomes. A block author can still bias the randomness by withholding blocks, at the cost
+/// of their rewards.
+interface Randomness {
+    /// @dev Returns the index of the current epoch.
+    /// @custom:selector b057edd1
+    function currentEpochIndex() external view returns (uint64);
+
+    /// @dev Returns the randomness of the parent block, mixed with `salt`.
+    /// Reverts if the parent block has no VRF output.
+    /// @custom:selector 4b899653
+    /// @return randomness The randomness.
+    /// @return blockNumber The block number the randomness is known since.
+    function parentBlockRandomness(bytes32 salt)
+        external
+        view
+        returns (bytes32 randomness, uint32 blockNumber);
+
+    /// @dev Returns the randomness of the current epoch, mixed with `salt`.
+    /// @custom:selector 32e73aae
+    /// @return randomness The randomness.
+    /// @return blockNumber The block number the randomness is known since.
+    function epochRandomness(bytes32 salt)
+        external
+        view
+        returns (bytes32 randomness, uint32 blockNumber);
+
+    /// @dev Requests `numWords` random words (at most 100) from the randomness of a future
+    /// epoch.
+    /// @custom:selector ab8b6315
+    /// @param salt Mixed into the randomness, so different requests get different words.
+    /// @param numWords The number of random words.
+    /// @return requestId The id of the request.
+    function requestRandomness(bytes32 salt, uint8 numWords)
+        external
+        returns (uint256 requestId);
+
+    /// @dev Returns a pending request.
+    /// @custom:selector c58343ef
+    function getRequest(uint256 requestId)
+        external
+        view
+        returns (
+            address requester,
+            bytes32 salt,
+            uint64 targetEpoch,
+            uint8 numWords
+        );
+
+    /// @dev Fulfils a request of msg.sender during its target epoch, and removes it.
+    /// @custom:selector 9a91eb0d
+    /// @return randomWords The random words.
+    function fulfillRequest(uint256 requestId)
+        external
+        returns (bytes32[] memory randomWords);
+
+    /// @dev Removes an expired request. Anyone can purge expired requests.
+    /// @custom:selector 1d26cbab
+    function purgeExpiredRequest(uint256 requestId) external;
+
+    /// @dev Event emited when a request has been made.
+    /// @custom:selector bfa49c32f3e255f69c9305a2271616d54ddbce050646b5d78f9facab6f95283a
+    event RandomnessRequested(
+        uint256 indexed requestId,
+        address indexed requester,
+        uint64 targetEpoch
+    );
+
+    /// @dev Event emited when a request has been fulfilled.
+    /// @custom:selector 1cedb001a5114ea90393cd9f134224e9e1312545ee8a9b9533d780be6a9bf8b7
+    event RequestFulfilled(uint256 indexed requestId);
+}
@@ -0,0 +1,376 @@
+// Copyright 2019-2022 PureStake Inc.
+// This file is part of SCS Chain. It is derived from Moonbeam's precompiles
+// (https://github.com/moonbeam-foundation/moonbeam).
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Precompile exposing the randomness of `pallet_babe`, derived from the VRF outputs of the
+//! block authors.
+//!
+//! Two instant sources are readable in the current block:
+//! - the parent block randomness, the VRF output of the author of the parent block. It is
+//!   known to that author before the block is produced, and an author may withhold a block
+//!   whose output is unfavourable to them.
+//! - the epoch randomness, derived from all the VRF outputs of the epoch before last. It is
+//!   known to everyone for the whole epoch.
+//!
+//! Both are thus predictable when a transaction is submitted. Contracts needing randomness
+//! nobody knows at the time of the request use the request/fulfil flow: a request stores a
+//! salt and targets the epoch [`EPOCH_DELAY`] epochs after the current one, whose randomness
+//! is derived from the VRF outputs of the next epoch only. The requester fulfils it during the
+//! target epoch to get random words; the request expires when the target epoch ends, and may
+//! then be purged by anyone.
+//!
+//! A deposit is reserved from the requester for the storage of every request. It is given
+//! back when the request is fulfilled, and goes to the account purging it once expired.
+//!
+//! A block author can still bias the outcome by withholding blocks
+//! of the next epoch, at the cost of their rewards.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::{Decode, Encode, MaxEncodedLen};
+use core::marker::PhantomData;
+use fp_evm::PrecompileHandle;
+use frame_support::{
+    ensure,
+    pallet_prelude::{OptionQuery, ValueQuery},
+    storage::types::{StorageInstance, StorageMap, StorageValue},
+    traits::{BalanceStatus, Get, Randomness, ReservableCurrency},
+    Blake2_128Concat,
+};
+use frame_system::pallet_prelude::BlockNumberFor;
+use pallet_babe::{ParentBlockRandomness, RandomnessFromOneEpochAgo};
+use pallet_evm::AddressMapping;
+use polkadot_sdk::{frame_support, frame_system, pallet_babe, pallet_balances, sp_std};
+use precompile_utils::{prelude::*, substrate::TryDispatchError};
+use sp_core::{H160, H256, U256};
+use sp_std::vec::Vec;
+
+/// Number of epochs between a request and the epoch whose randomness fulfils it.
+pub const EPOCH_DELAY: u64 = 3;
+
+/// Maximum number of random words of a request.
+pub const MAX_RANDOM_WORDS: u8 = 100;
+
+/// Solidity selector of the RandomnessRequested log, which is the Keccak of the Log signature.
+pub const SELECTOR_LOG_RANDOMNESS_REQUESTED: [u8; 32] =
+    keccak256!("RandomnessRequested(uint256,address,uint64)");
+
+/// Solidity selector of the RequestFulfilled log, which is the Keccak of the Log signature.
+pub const SELECTOR_LOG_REQUEST_FULFILLED: [u8; 32] = keccak256!("RequestFulfilled(uint256)");
+
+/// A request of random words from the randomness of a future epoch.
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode)]
+pub struct Request {
+    /// The account allowed to fulfil the request.
+    pub requester: H160,
+    /// Mixed into the randomness, so different requests get different words.
+    pub salt: H256,
+    /// The epoch during which the request can be fulfilled.
+    pub target_epoch: u64,
+    /// Number of random words returned by the fulfilment.
+    pub num_words: u8,
+}
+
+/// Storage prefix of the number of requests ever made.
+pub struct RequestCountPrefix;
+
+impl StorageInstance for RequestCountPrefix {
+    const STORAGE_PREFIX: &'static str = "RequestCount";
+
+    fn pallet_prefix() -> &'static str {
+        "PrecompileRandomness"
+    }
+}
+
+/// Number of requests ever made, which is also the id of the next request.
+pub type RequestCountStorage = StorageValue<RequestCountPrefix, u64, ValueQuery>;
+
+/// Storage prefix of the pending requests.
+pub struct RequestsPrefix;
+
+impl StorageInstance for RequestsPrefix {
+    const STORAGE_PREFIX: &'static str = "Requests";
+
+    fn pallet_prefix() -> &'static str {
+        "PrecompileRandomness"
+    }
+}
+
+/// Pending requests, by id.
+pub type RequestsStorage = StorageMap<RequestsPrefix, Blake2_128Concat, u64, Request, OptionQuery>;
+
+/// Alias for the Balance type of the provided Runtime.
+pub type BalanceOf<Runtime> = <Runtime as pallet_balances::Config>::Balance;
+
+/// Storage prefix of the deposits of the pending requests.
+pub struct RequestDepositsPrefix;
+
+impl StorageInstance for RequestDepositsPrefix {
+    const STORAGE_PREFIX: &'static str = "RequestDeposits";
+
+    fn pallet_prefix() -> &'static str {
+        "PrecompileRandomness"
+    }
+}
+
+/// Deposits reserved from the requesters of the pending requests, by request id.
+pub type RequestDepositsStorage<Runtime> =
+    StorageMap<RequestDepositsPrefix, Blake2_128Concat, u64, BalanceOf<Runtime>, ValueQuery>;
+
+/// Precompile exposing the BABE randomness and the randomness requests. `Deposit` is reserved
+/// from the requester for the storage of each request.
+pub struct RandomnessPrecompile<Runtime, Deposit>(PhantomData<(Runtime, Deposit)>);
+
+#[precompile_utils::precompile]
+impl<Runtime, Deposit> RandomnessPrecompile<Runtime, Deposit>
+where
+    Runtime: pallet_babe::Config
+        + pallet_balances::Config
+        + pallet_evm::Config
+        + frame_system::Config<Hash = H256>,
+    BlockNumberFor<Runtime>: Into<u32>,
+    Deposit: Get<BalanceOf<Runtime>>,
+{
+    #[precompile::public("currentEpochIndex()")]
+    #[precompile::view]
+    fn current_epoch_index(handle: &mut impl PrecompileHandle) -> EvmResult<u64> {
+        Self::record_epoch_index_read(handle)?;
+
+        Ok(pallet_babe::EpochIndex::<Runtime>::get())
+    }
+
+    #[precompile::public("parentBlockRandomness(bytes32)")]
+    #[precompile::view]
+    fn parent_block_randomness(
+        handle: &mut impl PrecompileHandle,
+        salt: H256,
+    ) -> EvmResult<(H256, u32)> {
+        // AuthorVrfRandomness: Option<[u8; 32]>(33)
+        handle.record_db_read::<Runtime>(33)?;
+
+        let (randomness, block) = ParentBlockRandomness::<Runtime>::random(salt.as_bytes());
+        let randomness =
+            randomness.ok_or_else(|| revert("Parent block randomness not available"))?;
+
+        Ok((randomness, block.into()))
+    }
+
+    #[precompile::public("epochRandomness(bytes32)")]
+    #[precompile::view]
+    fn epoch_randomness(handle: &mut impl PrecompileHandle, salt: H256) -> EvmResult<(H256, u32)> {
+        Self::record_epoch_randomness_read(handle)?;
+
+        let (randomness, block) = RandomnessFromOneEpochAgo::<Runtime>::random(salt.as_bytes());
+
+        Ok((randomness, block.into()))
+    }
+
+    #[precompile::public("requestRandomness(bytes32,uint8)")]
+    fn request_randomness(
+        handle: &mut impl PrecompileHandle,
+        salt: H256,
+        num_words: u8,
+    ) -> EvmResult<U256> {
+        handle.record_log_costs_manual(3, 32)?;
+        Self::record_epoch_index_read(handle)?;
+        // RequestCount: u64(8)
+        handle.record_db_read::<Runtime>(8)?;
+        Self::record_account_read(handle)?;
+        // RequestCount, Requests, RequestDeposits and requester account writes.
+        handle.record_cost(4 * RuntimeHelper::<Runtime>::db_write_gas_cost())?;
+
+        ensure!(
+            num_words > 0 && num_words <= MAX_RANDOM_WORDS,
+            revert("Number of random words out of bounds")
+        );
+
+        let requester = handle.context().caller;
+        let target_epoch = pallet_babe::EpochIndex::<Runtime>::get().saturating_add(EPOCH_DELAY);
+        let request_id = RequestCountStorage::get();
+
+        let deposit = Deposit::get();
+        pallet_balances::Pallet::<Runtime>::reserve(
+            &Runtime::AddressMapping::into_account_id(requester),
+            deposit,
+        )
+        .map_err(TryDispatchError::Substrate)?;
+
+        RequestCountStorage::put(request_id.saturating_add(1));
+        RequestDepositsStorage::<Runtime>::insert(request_id, deposit);
+        RequestsStorage::insert(
+            request_id,
+            Request {
+                requester,
+                salt,
+                target_epoch,
+                num_words,
+            },
+        );
+
+        log3(
+            handle.context().address,
+            SELECTOR_LOG_RANDOMNESS_REQUESTED,
+            H256::from_low_u64_be(request_id),
+            requester,
+            solidity::encode_event_data(target_epoch),
+        )
+        .record(handle)?;
+
+        Ok(request_id.into())
+    }
+
+    #[precompile::public("getRequest(uint256)")]
+    #[precompile::view]
+    fn get_request(
+        handle: &mut impl PrecompileHandle,
+        request_id: U256,
+    ) -> EvmResult<(Address, H256, u64, u8)> {
+        let request_id = Self::u256_to_request_id(request_id).in_field("requestId")?;
+        let request = Self::request(handle, request_id)?;
+
+        Ok((
+            Address(request.requester),
+            request.salt,
+            request.target_epoch,
+            request.num_words,
+        ))
+    }
+
+    #[precompile::public("fulfillRequest(uint256)")]
+    fn fulfill_request(
+        handle: &mut impl PrecompileHandle,
+        request_id: U256,
+    ) -> EvmResult<Vec<H256>> {
+        handle.record_log_costs_manual(2, 0)?;
+
+        let request_id = Self::u256_to_request_id(request_id).in_field("requestId")?;
+        let request = Self::request(handle, request_id)?;
+        ensure!(
+            request.requester == handle.context().caller,
+            revert("Only the requester can fulfill the request")
+        );
+
+        Self::record_epoch_index_read(handle)?;
+        let epoch = pallet_babe::EpochIndex::<Runtime>::get();
+        ensure!(
+            epoch >= request.target_epoch,
+            revert("Request not ready to be fulfilled")
+        );
+        ensure!(epoch == request.target_epoch, revert("Request expired"));
+
+        Self::record_epoch_randomness_read(handle)?;
+        Self::record_deposit_read(handle)?;
+        Self::record_account_read(handle)?;
+        // Requests and RequestDeposits removals, and requester account write.
+        handle.record_cost(3 * RuntimeHelper::<Runtime>::db_write_gas_cost())?;
+
+        RequestsStorage::remove(request_id);
+        pallet_balances::Pallet::<Runtime>::unreserve(
+            &Runtime::AddressMapping::into_account_id(request.requester),
+            RequestDepositsStorage::<Runtime>::take(request_id),
+        );
+
+        let random_words = (0..request.num_words)
+            .map(|index| {
+                let subject = (request.salt, request_id, index).encode();
+                RandomnessFromOneEpochAgo::<Runtime>::random(&subject).0
+            })
+            .collect();
+
+        log2(
+            handle.context().address,
+            SELECTOR_LOG_REQUEST_FULFILLED,
+            H256::from_low_u64_be(request_id),
+            Vec::new(),
+        )
+        .record(handle)?;
+
+        Ok(random_words)
+    }
+
+    #[precompile::public("purgeExpiredRequest(uint256)")]
+    fn purge_expired_request(handle: &mut impl PrecompileHandle, request_id: U256) -> EvmResult {
+        let request_id = Self::u256_to_request_id(request_id).in_field("requestId")?;
+        let request = Self::request(handle, request_id)?;
+
+        Self::record_epoch_index_read(handle)?;
+        ensure!(
+            pallet_babe::EpochIndex::<Runtime>::get() > request.target_epoch,
+            revert("Request not expired")
+        );
+
+        Self::record_deposit_read(handle)?;
+        Self::record_account_read(handle)?;
+        Self::record_account_read(handle)?;
+        // Requests and RequestDeposits removals, and requester and caller account writes.
+        handle.record_cost(4 * RuntimeHelper::<Runtime>::db_write_gas_cost())?;
+
+        RequestsStorage::remove(request_id);
+        // The deposit rewards the caller for freeing the storage of the unfulfilled request.
+        pallet_balances::Pallet::<Runtime>::repatriate_reserved(
+            &Runtime::AddressMapping::into_account_id(request.requester),
+            &Runtime::AddressMapping::into_account_id(handle.context().caller),
+            RequestDepositsStorage::<Runtime>::take(request_id),
+            BalanceStatus::Free,
+        )
+        .map_err(TryDispatchError::Substrate)?;
+
+        Ok(())
+    }
+
+    fn request(handle: &mut impl PrecompileHandle, request_id: u64) -> EvmResult<Request> {
+        // Requests: Blake2_128Concat(16) + u64(8) + Request(20 + 32 + 8 + 1)
+        handle.record_db_read::<Runtime>(85)?;
+
+        RequestsStorage::get(request_id).ok_or_else(|| revert("Request not found"))
+    }
+
+    fn record_deposit_read(handle: &mut impl PrecompileHandle) -> EvmResult {
+        // RequestDeposits: Blake2_128Concat(16) + u64(8) + Balance
+        handle.record_db_read::<Runtime>(24 + BalanceOf::<Runtime>::max_encoded_len())
+    }
+
+    fn record_account_read(handle: &mut impl PrecompileHandle) -> EvmResult {
+        // System Account: Blake2_128Concat(16) + AccountId + AccountInfo(4 * 4 + 4 * Balance)
+        handle.record_db_read::<Runtime>(
+            32 + Runtime::AccountId::max_encoded_len()
+                + 4 * BalanceOf::<Runtime>::max_encoded_len(),
+        )
+    }
+
+    fn record_epoch_index_read(handle: &mut impl PrecompileHandle) -> EvmResult {
+        // EpochIndex: u64(8)
+        handle.record_db_read::<Runtime>(8)
+    }
+
+    fn record_epoch_randomness_read(handle: &mut impl PrecompileHandle) -> EvmResult {
+        // Randomness: [u8; 32](32)
+        handle.record_db_read::<Runtime>(32)?;
+        // EpochStart: (BlockNumber, BlockNumber)(8)
+        handle.record_db_read::<Runtime>(8)
+    }
+
+    fn u256_to_request_id(value: U256) -> MayRevert<u64> {
+        value
+            .try_into()
+            .map_err(|_| RevertReason::value_is_too_large("request id").into())
+    }
+}
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,373 @@
+use crate::{mock::*, *};
+use frame_support::storage::{storage_prefix, unhashed};
+use polkadot_sdk::sp_runtime::traits::{BlakeTwo256, Hash};
+use precompile_utils::{evm::costs::log_costs, testing::*};
+
+type PCall = RandomnessPrecompileCall<Runtime, RequestDeposit>;
+
+const RANDOMNESS: [u8; 32] = [7u8; 32];
+const SALT: H256 = H256::repeat_byte(0x11);
+
+fn precompiles() -> Precompiles<Runtime> {
+    PrecompilesValue::get()
+}
+
+fn balances() -> ExtBuilder {
+    ExtBuilder::default().with_balances(vec![(Alice.into(), 1000), (Bob.into(), 1000)])
+}
+
+fn db_read() -> u64 {
+    RuntimeHelper::<Runtime>::db_read_gas_cost()
+}
+
+fn db_write() -> u64 {
+    RuntimeHelper::<Runtime>::db_write_gas_cost()
+}
+
+fn dispatch_error(output: &[u8], error: &str) -> bool {
+    core::str::from_utf8(output).map_or(false, |reason| reason.contains(error))
+}
+
+/// Sets the epoch randomness of `pallet_babe`, whose storage items are not all public.
+/// `Randomness` and `NextRandomness` are both set, so the expected values do not depend on
+/// which epoch the randomness is taken from.
+fn set_epoch_randomness() {
+    unhashed::put(&storage_prefix(b"Babe", b"Randomness"), &RANDOMNESS);
+    unhashed::put(&storage_prefix(b"Babe", b"NextRandomness"), &RANDOMNESS);
+    unhashed::put(&storage_prefix(b"Babe", b"EpochStart"), &(5u32, 5u32));
+}
+
+fn set_epoch(epoch: u64) {
+    pallet_babe::EpochIndex::<Runtime>::put(epoch);
+}
+
+fn expected_randomness(subject: &[u8]) -> H256 {
+    BlakeTwo256::hash(&[subject, &RANDOMNESS[..]].concat())
+}
+
+fn request(num_words: u8) {
+    precompiles()
+        .prepare_test(
+            Alice,
+            Precompile1,
+            PCall::request_randomness {
+                salt: SALT,
+                num_words,
+            },
+        )
+        .execute_some();
+}
+
+fn request_cost() -> u64 {
+    3 * db_read() + 4 * db_write() + log_costs(3, 32).expect("log costs fit in u64")
+}
+
+#[test]
+fn selectors() {
+    assert!(PCall::current_epoch_index_selectors().contains(&0xb057edd1));
+    assert!(PCall::parent_block_randomness_selectors().contains(&0x4b899653));
+    assert!(PCall::epoch_randomness_selectors().contains(&0x32e73aae));
+    assert!(PCall::request_randomness_selectors().contains(&0xab8b6315));
+    assert!(PCall::get_request_selectors().contains(&0xc58343ef));
+    assert!(PCall::fulfill_request_selectors().contains(&0x9a91eb0d));
+    assert!(PCall::purge_expired_request_selectors().contains(&0x1d26cbab));
+}
+
+#[test]
+fn current_epoch_index_is_read_from_babe() {
+    balances().build().execute_with(|| {
+        set_epoch(42);
+
+        precompiles()
+            .prepare_test(Alice, Precompile1, PCall::current_epoch_index {})
+            .expect_cost(db_read())
+            .expect_no_logs()
+            .execute_returns(42u64);
+    });
+}
+
+#[test]
+fn epoch_randomness_is_salted() {
+    balances().build().execute_with(|| {
+        set_epoch_randomness();
+
+        precompiles()
+            .prepare_test(Alice, Precompile1, PCall::epoch_randomness { salt: SALT })
+            .expect_cost(2 * db_read())
+            .expect_no_logs()
+            .execute_returns((expected_randomness(SALT.as_bytes()), 5u32));
+    });
+}
+
+#[test]
+fn parent_block_randomness_is_salted() {
+    balances().build().execute_with(|| {
+        unhashed::put(
+            &storage_prefix(b"Babe", b"AuthorVrfRandomness"),
+            &Some(RANDOMNESS),
+        );
+
+        precompiles()
+            .prepare_test(
+                Alice,
+                Precompile1,
+                PCall::parent_block_randomness { salt: SALT },
+            )
+            .expect_cost(db_read())
+            .expect_no_logs()
+            .execute_returns((expected_randomness(SALT.as_bytes()), 0u32));
+    });
+}
+
+#[test]
+fn parent_block_randomness_reverts_when_not_available() {
+    balances().build().execute_with(|| {
+        precompiles()
+            .prepare_test(
+                Alice,
+                Precompile1,
+                PCall::parent_block_randomness { salt: SALT },
+            )
+            .execute_reverts(|output| output == b"Parent block randomness not available");
+    });
+}
+
+#[test]
+fn request_randomness_stores_the_request_and_reserves_the_deposit() {
+    balances().build().execute_with(|| {
+        set_epoch(2);
+
+        precompiles()
+            .prepare_test(
+                Alice,
+                Precompile1,
+                PCall::request_randomness {
+                    salt: SALT,
+                    num_words: 2,
+                },
+            )
+            .expect_cost(request_cost())
+            .expect_log(log3(
+                Precompile1,
+                SELECTOR_LOG_RANDOMNESS_REQUESTED,
+                H256::from_low_u64_be(0),
+                Alice,
+                solidity::encode_event_data(5u64),
+            ))
+            .execute_returns(U256::zero());
+
+        assert_eq!(RequestCountStorage::get(), 1);
+        assert_eq!(RequestDepositsStorage::<Runtime>::get(0), Some(100));
+        assert_eq!(Balances::reserved_balance(AccountId::from(Alice)), 100);
+        assert_eq!(Balances::free_balance(AccountId::from(Alice)), 900);
+
+        precompiles()
+            .prepare_test(
+                Bob,
+                Precompile1,
+                PCall::get_request {
+                    request_id: 0.into(),
+                },
+            )
+            .expect_cost(db_read())
+            .expect_no_logs()
+            .execute_returns((Address(Alice.into()), SALT, 5u64, 2u8));
+    });
+}
+
+#[test]
+fn request_randomness_reverts_when_words_out_of_bounds() {
+    balances().build().execute_with(|| {
+        for num_words in [0, MAX_RANDOM_WORDS + 1] {
+            precompiles()
+                .prepare_test(
+                    Alice,
+                    Precompile1,
+                    PCall::request_randomness {
+                        salt: SALT,
+                        num_words,
+                    },
+                )
+                .execute_reverts(|output| output == b"Number of random words out of bounds");
+        }
+
+        assert_eq!(RequestCountStorage::get(), 0);
+    });
+}
+
+#[test]
+fn request_randomness_reverts_when_deposit_cannot_be_reserved() {
+    balances().build().execute_with(|| {
+        precompiles()
+            .prepare_test(
+                Charlie,
+                Precompile1,
+                PCall::request_randomness {
+                    salt: SALT,
+                    num_words: 1,
+                },
+            )
+            .execute_reverts(|output| dispatch_error(output, "InsufficientBalance"));
+
+        assert_eq!(RequestCountStorage::get(), 0);
+        assert_eq!(RequestsStorage::get(0), None);
+    });
+}
+
+#[test]
+fn get_request_reverts_on_unknown_or_too_large_id() {
+    balances().build().execute_with(|| {
+        precompiles()
+            .prepare_test(
+                Alice,
+                Precompile1,
+                PCall::get_request {
+                    request_id: 0.into(),
+                },
+            )
+            .execute_reverts(|output| output == b"Request not found");
+
+        precompiles()
+            .prepare_test(
+                Alice,
+                Precompile1,
+                PCall::get_request {
+                    request_id: U256::from(u64::MAX) + 1,
+                },
+            )
+            .execute_reverts(|output| output == b"requestId: Value is too large for request id");
+    });
+}
+
+#[test]
+fn fulfill_request_returns_the_words_and_the_deposit() {
+    balances().build().execute_with(|| {
+        set_epoch_randomness();
+        request(2);
+        set_epoch(EPOCH_DELAY);
+
+        let words: Vec<H256> = (0..2u8)
+            .map(|index| expected_randomness(&(SALT, 0u64, index).encode()))
+            .collect();
+
+        precompiles()
+            .prepare_test(
+                Alice,
+                Precompile1,
+                PCall::fulfill_request {
+                    request_id: 0.into(),
+                },
+            )
+            .expect_cost(
+                6 * db_read() + 3 * db_write() + log_costs(2, 0).expect("log costs fit in u64"),
+            )
+            .expect_log(log2(
+                Precompile1,
+                SELECTOR_LOG_REQUEST_FULFILLED,
+                H256::from_low_u64_be(0),
+                Vec::new(),
+            ))
+            .execute_returns(words);
+
+        assert_eq!(RequestsStorage::get(0), None);
+        assert_eq!(RequestDepositsStorage::<Runtime>::get(0), None);
+        assert_eq!(Balances::reserved_balance(AccountId::from(Alice)), 0);
+        assert_eq!(Balances::free_balance(AccountId::from(Alice)), 1000);
+    });
+}
+
+#[test]
+fn fulfill_request_reverts_before_and_after_the_target_epoch() {
+    balances().build().execute_with(|| {
+        request(1);
+
+        set_epoch(EPOCH_DELAY - 1);
+        precompiles()
+            .prepare_test(
+                Alice,
+                Precompile1,
+                PCall::fulfill_request {
+                    request_id: 0.into(),
+                },
+            )
+            .execute_reverts(|output| output == b"Request not ready to be fulfilled");
+
+        set_epoch(EPOCH_DELAY + 1);
+        precompiles()
+            .prepare_test(
+                Alice,
+                Precompile1,
+                PCall::fulfill_request {
+                    request_id: 0.into(),
+                },
+            )
+            .execute_reverts(|output| output == b"Request expired");
+
+        assert!(RequestsStorage::get(0).is_some());
+    });
+}
+
+#[test]
+fn fulfill_request_reverts_when_not_requester() {
+    balances().build().execute_with(|| {
+        request(1);
+        set_epoch(EPOCH_DELAY);
+
+        precompiles()
+            .prepare_test(
+                Bob,
+                Precompile1,
+                PCall::fulfill_request {
+                    request_id: 0.into(),
+                },
+            )
+            .execute_reverts(|output| output == b"Only the requester can fulfill the request");
+
+        precompiles()
+            .prepare_test(
+                Bob,
+                Precompile1,
+                PCall::fulfill_request {
+                    request_id: 1.into(),
+                },
+            )
+            .execute_reverts(|output| output == b"Request not found");
+    });
+}
+
+#[test]
+fn purge_expired_request_gives_the_deposit_to_the_caller() {
+    balances().build().execute_with(|| {
+        request(1);
+
+        set_epoch(EPOCH_DELAY);
+        precompiles()
+            .prepare_test(
+                Bob,
+                Precompile1,
+                PCall::purge_expired_request {
+                    request_id: 0.into(),
+                },
+            )
+            .execute_reverts(|output| output == b"Request not expired");
+
+        set_epoch(EPOCH_DELAY + 1);
+        precompiles()
+            .prepare_test(
+                Bob,
+                Precompile1,
+                PCall::purge_expired_request {
+                    request_id: 0.into(),
+                },
+            )
+            .expect_cost(5 * db_read() + 4 * db_write())
+            .expect_no_logs()
+            .execute_returns(());
+
+        assert_eq!(RequestsStorage::get(0), None);
+        assert_eq!(RequestDepositsStorage::<Runtime>::get(0), None);
+        assert_eq!(Balances::reserved_balance(AccountId::from(Alice)), 0);
+        assert_eq!(Balances::free_balance(AccountId::from(Alice)), 900);
+        assert_eq!(Balances::free_balance(AccountId::from(Bob)), 1100);
+    });
+}
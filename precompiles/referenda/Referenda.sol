@@ -0,0 +1,89 @@
+// SPDX-License-Identifier: GPL-3.0-only
+pragma solidity >=0.8.3;
+
+/// @dev The Referenda contract's address.
+address constant REFERENDA_ADDRESS = 0x0000000000000000000000000000000000000804;
+
+/// @dev The Referenda contract's instance.
+Referenda constant REFERENDA_CONTRACT = Referenda(REFERENDA_ADDRESS);
+
+/// @title Pallet Referenda Interface
+/// @dev The interface through which solidity contracts will interact with pallet-referenda.
+interface Referenda {
+    /// @dev Status of a referendum as returned by `referendumStatus`.
+    enum ReferendumStatus {
+        Ongoing,
+        Approved,
+        Rejected,
+        Cancelled,
+        TimedOut,
+        Killed
+    }
+
+    /// @dev Number of referenda submitted so far, which is the index of the next one.
+    /// @custom:selector 3a42ee31
+    function referendumCount() external view returns (uint32);
+
+    /// @dev Status of a referendum.
+    /// @custom:selector 8d407c0b
+    /// @param index The index of the referendum.
+    /// @return status The status of the referendum.
+    /// @return trackId The track of the referendum, 0 once it is not ongoing anymore.
+    /// @return submitted The block the referendum was submitted in, 0 once it is not ongoing.
+    /// @return decisionDepositPlaced Whether the decision deposit has been placed.
+    /// @return deciding Whether the referendum is in its decision period.
+    function referendumStatus(uint32 index)
+        external
+        view
+        returns (
+            ReferendumStatus status,
+            uint16 trackId,
+            uint32 submitted,
+            bool decisionDepositPlaced,
+            bool deciding
+        );
+
+    /// @dev Submit a referendum on a noted preimage, enacted at block `block`.
+    /// @custom:selector 131f3468
+    /// @param trackId The track of the referendum, which determines its origin.
+    /// @param proposalHash The hash of the noted preimage of the proposal.
+    /// @param proposalLen The length of the preimage.
+    /// @param block The block the proposal should be enacted at.
+    /// @return referendumIndex The index of the submitted referendum.
+    function submitAt(
+        uint16 trackId,
+        bytes32 proposalHash,
+        uint32 proposalLen,
+        uint32 block
+    ) external returns (uint32 referendumIndex);
+
+    /// @dev Submit a referendum on a noted preimage, enacted `block` blocks after approval.
+    /// @custom:selector 5b2479db
+    /// @param trackId The track of the referendum, which determines its origin.
+    /// @param proposalHash The hash of the noted preimage of the proposal.
+    /// @param proposalLen The length of the preimage.
+    /// @param block The number of blocks after approval the proposal should be enacted.
+    /// @return referendumIndex The index of the submitted referendum.
+    function submitAfter(
+        uint16 trackId,
+        bytes32 proposalHash,
+        uint32 proposalLen,
+        uint32 block
+    ) external returns (uint32 referendumIndex);
+
+    /// @dev Place the decision deposit of a referendum, reserved from the caller.
+    /// @custom:selector 245ce18d
+    function placeDecisionDeposit(uint32 index) external;
+
+    /// @dev Refund the decision deposit of a closed referendum to its depositor.
+    /// @custom:selector 1325d528
+    function refundDecisionDeposit(uint32 index) external;
+
+    /// @dev Emitted when a referendum has been submitted with `submitAt`.
+    /// @custom:selector e02a819ecfc92874b5016c6a0e26f56a5cb08771f32ab818bf548d84ca3ae94d
+    event SubmittedAt(uint16 indexed trackId, uint32 referendumIndex, bytes32 hash);
+
+    /// @dev Emitted when a referendum has been submitted with `submitAfter`.
+    /// @custom:selector a5117efbf0f4aa9e08dd135e69aa8ee4978f99fca86fc5154b5bd1b363eafdcf
+    event SubmittedAfter(uint16 indexed trackId, uint32 referendumIndex, bytes32 hash);
+}
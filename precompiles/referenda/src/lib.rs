@@ -0,0 +1,225 @@
+//! Precompile to interact with `pallet_referenda` from the EVM.
+//!
+//! Referenda are submitted on preimages noted beforehand (e.g. with the preimage precompile).
+//! The origin of a proposal is derived from its track through [`TrackOrigins`].
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use core::marker::PhantomData;
+use fp_evm::PrecompileHandle;
+use frame_support::{
+    dispatch::{GetDispatchInfo, PostDispatchInfo},
+    traits::{schedule::DispatchTime, Bounded, OriginTrait},
+};
+use frame_system::pallet_prelude::BlockNumberFor;
+use pallet_evm::AddressMapping;
+use pallet_referenda::{PalletsOriginOf, ReferendumIndex, ReferendumInfo, TrackIdOf};
+use polkadot_sdk::{frame_support, frame_system, pallet_referenda, sp_runtime, sp_std};
+use precompile_utils::prelude::*;
+use sp_core::{H256, U256};
+use sp_runtime::traits::Dispatchable;
+use sp_std::boxed::Box;
+
+/// Solidity selector of the SubmittedAt log, which is the Keccak of the Log signature.
+pub const SELECTOR_LOG_SUBMITTED_AT: [u8; 32] = keccak256!("SubmittedAt(uint16,uint32,bytes32)");
+
+/// Solidity selector of the SubmittedAfter log, which is the Keccak of the Log signature.
+pub const SELECTOR_LOG_SUBMITTED_AFTER: [u8; 32] =
+    keccak256!("SubmittedAfter(uint16,uint32,bytes32)");
+
+/// Status of a referendum as returned to Solidity, matching the `ReferendumStatus` enum.
+const STATUS_ONGOING: u8 = 0;
+const STATUS_APPROVED: u8 = 1;
+const STATUS_REJECTED: u8 = 2;
+const STATUS_CANCELLED: u8 = 3;
+const STATUS_TIMED_OUT: u8 = 4;
+const STATUS_KILLED: u8 = 5;
+
+/// Provides the origin a proposal submitted on a given track is dispatched with.
+pub trait TrackOrigins<TrackId, Origin> {
+    /// Returns the origin of the proposals of `track_id`, `None` if the track doesn't exist.
+    fn origin_of(track_id: TrackId) -> Option<Origin>;
+}
+
+/// A precompile to wrap the functionality from `pallet_referenda`.
+pub struct ReferendaPrecompile<Runtime, Origins>(PhantomData<(Runtime, Origins)>);
+
+#[precompile_utils::precompile]
+impl<Runtime, Origins> ReferendaPrecompile<Runtime, Origins>
+where
+    Runtime: pallet_referenda::Config + pallet_evm::Config + frame_system::Config,
+    Origins: TrackOrigins<TrackIdOf<Runtime, ()>, PalletsOriginOf<Runtime>>,
+    <Runtime as frame_system::Config>::Hash: From<H256>,
+    BlockNumberFor<Runtime>: Into<U256> + From<u32>,
+    TrackIdOf<Runtime, ()>: TryFrom<u16> + Into<u16>,
+    Runtime::RuntimeCall: Dispatchable<PostInfo = PostDispatchInfo> + GetDispatchInfo,
+    Runtime::RuntimeCall: From<pallet_referenda::Call<Runtime>>,
+    <Runtime::RuntimeCall as Dispatchable>::RuntimeOrigin: From<Option<Runtime::AccountId>>,
+    <Runtime::RuntimeCall as Dispatchable>::RuntimeOrigin: OriginTrait,
+{
+    #[precompile::public("referendumCount()")]
+    #[precompile::view]
+    fn referendum_count(handle: &mut impl PrecompileHandle) -> EvmResult<u32> {
+        // Storage item: ReferendumCount:
+        // ReferendumIndex(4)
+        handle.record_db_read::<Runtime>(4)?;
+
+        Ok(pallet_referenda::ReferendumCount::<Runtime>::get())
+    }
+
+    #[precompile::public("referendumStatus(uint32)")]
+    #[precompile::view]
+    fn referendum_status(
+        handle: &mut impl PrecompileHandle,
+        index: ReferendumIndex,
+    ) -> EvmResult<(u8, u16, u32, bool, bool)> {
+        // Storage item: ReferendumInfoFor:
+        // Blake2_128Concat(16) + ReferendumIndex(4) + ReferendumInfo(bounded by its max encoded
+        // length, a few hundred bytes with the tally and the origin)
+        handle.record_db_read::<Runtime>(512)?;
+
+        let info = pallet_referenda::ReferendumInfoFor::<Runtime>::get(index)
+            .ok_or(RevertReason::custom("unknown referendum").in_field("index"))?;
+
+        Ok(match info {
+            ReferendumInfo::Ongoing(status) => {
+                let submitted: U256 = status.submitted.into();
+                (
+                    STATUS_ONGOING,
+                    status.track.into(),
+                    submitted.low_u32(),
+                    status.decision_deposit.is_some(),
+                    status.deciding.is_some(),
+                )
+            }
+            ReferendumInfo::Approved(..) => (STATUS_APPROVED, 0, 0, false, false),
+            ReferendumInfo::Rejected(..) => (STATUS_REJECTED, 0, 0, false, false),
+            ReferendumInfo::Cancelled(..) => (STATUS_CANCELLED, 0, 0, false, false),
+            ReferendumInfo::TimedOut(..) => (STATUS_TIMED_OUT, 0, 0, false, false),
+            ReferendumInfo::Killed(..) => (STATUS_KILLED, 0, 0, false, false),
+        })
+    }
+
+    #[precompile::public("submitAt(uint16,bytes32,uint32,uint32)")]
+    fn submit_at(
+        handle: &mut impl PrecompileHandle,
+        track_id: u16,
+        proposal_hash: H256,
+        proposal_len: u32,
+        block: u32,
+    ) -> EvmResult<u32> {
+        handle.record_log_costs_manual(2, 64)?;
+
+        let referendum_index = Self::submit(
+            handle,
+            track_id,
+            proposal_hash,
+            proposal_len,
+            DispatchTime::At(block.into()),
+        )?;
+
+        log2(
+            handle.context().address,
+            SELECTOR_LOG_SUBMITTED_AT,
+            H256::from_low_u64_be(track_id as u64),
+            solidity::encode_event_data((referendum_index, proposal_hash)),
+        )
+        .record(handle)?;
+
+        Ok(referendum_index)
+    }
+
+    #[precompile::public("submitAfter(uint16,bytes32,uint32,uint32)")]
+    fn submit_after(
+        handle: &mut impl PrecompileHandle,
+        track_id: u16,
+        proposal_hash: H256,
+        proposal_len: u32,
+        block: u32,
+    ) -> EvmResult<u32> {
+        handle.record_log_costs_manual(2, 64)?;
+
+        let referendum_index = Self::submit(
+            handle,
+            track_id,
+            proposal_hash,
+            proposal_len,
+            DispatchTime::After(block.into()),
+        )?;
+
+        log2(
+            handle.context().address,
+            SELECTOR_LOG_SUBMITTED_AFTER,
+            H256::from_low_u64_be(track_id as u64),
+            solidity::encode_event_data((referendum_index, proposal_hash)),
+        )
+        .record(handle)?;
+
+        Ok(referendum_index)
+    }
+
+    #[precompile::public("placeDecisionDeposit(uint32)")]
+    fn place_decision_deposit(handle: &mut impl PrecompileHandle, index: u32) -> EvmResult {
+        Self::dispatch(
+            handle,
+            pallet_referenda::Call::<Runtime>::place_decision_deposit { index },
+        )
+    }
+
+    #[precompile::public("refundDecisionDeposit(uint32)")]
+    fn refund_decision_deposit(handle: &mut impl PrecompileHandle, index: u32) -> EvmResult {
+        Self::dispatch(
+            handle,
+            pallet_referenda::Call::<Runtime>::refund_decision_deposit { index },
+        )
+    }
+
+    fn submit(
+        handle: &mut impl PrecompileHandle,
+        track_id: u16,
+        proposal_hash: H256,
+        proposal_len: u32,
+        enactment_moment: DispatchTime<BlockNumberFor<Runtime>>,
+    ) -> EvmResult<ReferendumIndex> {
+        let track_id: TrackIdOf<Runtime, ()> = track_id
+            .try_into()
+            .map_err(|_| RevertReason::value_is_too_large("track id").in_field("trackId"))?;
+        let proposal_origin = Origins::origin_of(track_id)
+            .ok_or(RevertReason::custom("unknown track").in_field("trackId"))?;
+        let proposal = Bounded::Lookup {
+            hash: proposal_hash.into(),
+            len: proposal_len,
+        };
+
+        // Storage item: ReferendumCount:
+        // ReferendumIndex(4)
+        handle.record_db_read::<Runtime>(4)?;
+        let referendum_index = pallet_referenda::ReferendumCount::<Runtime>::get();
+
+        Self::dispatch(
+            handle,
+            pallet_referenda::Call::<Runtime>::submit {
+                proposal_origin: Box::new(proposal_origin),
+                proposal,
+                enactment_moment,
+            },
+        )?;
+
+        Ok(referendum_index)
+    }
+
+    fn dispatch(
+        handle: &mut impl PrecompileHandle,
+        call: pallet_referenda::Call<Runtime>,
+    ) -> EvmResult {
+        let origin = Runtime::AddressMapping::into_account_id(handle.context().caller);
+        RuntimeHelper::<Runtime>::try_dispatch(handle, Some(origin).into(), call)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
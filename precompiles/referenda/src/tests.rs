@@ -0,0 +1,146 @@
+use crate::{mock::*, *};
+use codec::Encode;
+use frame_support::{assert_ok, traits::ReservableCurrency};
+use precompile_utils::testing::*;
+use sp_runtime::traits::Hash;
+
+type PCall = ReferendaPrecompileCall<Runtime, ReferendaTrackOrigins>;
+
+fn precompiles() -> Precompiles<Runtime> {
+    PrecompilesValue::get()
+}
+
+fn balances() -> ExtBuilder {
+    ExtBuilder::default().with_balances(vec![(Alice.into(), 1000), (Bob.into(), 1000)])
+}
+
+fn db_read() -> u64 {
+    RuntimeHelper::<Runtime>::db_read_gas_cost()
+}
+
+fn dispatch_error(output: &[u8], error: &str) -> bool {
+    core::str::from_utf8(output).map_or(false, |reason| reason.contains(error))
+}
+
+/// Notes the preimage of a remark as Alice, returning its hash and length.
+fn note_proposal() -> (H256, u32) {
+    let proposal = RuntimeCall::System(frame_system::Call::remark { remark: vec![] }).encode();
+    assert_ok!(Preimage::note_preimage(
+        RuntimeOrigin::signed(Alice.into()),
+        proposal.clone()
+    ));
+    (
+        <Runtime as frame_system::Config>::Hashing::hash(&proposal),
+        proposal.len() as u32,
+    )
+}
+
+#[test]
+fn selectors() {
+    assert!(PCall::referendum_count_selectors().contains(&0x3a42ee31));
+    assert!(PCall::referendum_status_selectors().contains(&0x8d407c0b));
+    assert!(PCall::submit_at_selectors().contains(&0x131f3468));
+    assert!(PCall::submit_after_selectors().contains(&0x5b2479db));
+    assert!(PCall::place_decision_deposit_selectors().contains(&0x245ce18d));
+    assert!(PCall::refund_decision_deposit_selectors().contains(&0x1325d528));
+}
+
+#[test]
+fn submit_and_status() {
+    balances().build().execute_with(|| {
+        let (proposal_hash, proposal_len) = note_proposal();
+        precompiles()
+            .prepare_test(Alice, Precompile1, PCall::referendum_count {})
+            .expect_cost(db_read())
+            .expect_no_logs()
+            .execute_returns(0u32);
+
+        precompiles()
+            .prepare_test(
+                Alice,
+                Precompile1,
+                PCall::submit_at {
+                    track_id: 0,
+                    proposal_hash,
+                    proposal_len,
+                    block: 10,
+                },
+            )
+            .expect_log(log2(
+                Precompile1,
+                SELECTOR_LOG_SUBMITTED_AT,
+                H256::from_low_u64_be(0),
+                solidity::encode_event_data((0u32, proposal_hash)),
+            ))
+            .execute_returns(0u32);
+        precompiles()
+            .prepare_test(
+                Alice,
+                Precompile1,
+                PCall::submit_after {
+                    track_id: 0,
+                    proposal_hash,
+                    proposal_len,
+                    block: 10,
+                },
+            )
+            .expect_log(log2(
+                Precompile1,
+                SELECTOR_LOG_SUBMITTED_AFTER,
+                H256::from_low_u64_be(0),
+                solidity::encode_event_data((1u32, proposal_hash)),
+            ))
+            .execute_returns(1u32);
+
+        precompiles()
+            .prepare_test(Alice, Precompile1, PCall::referendum_count {})
+            .execute_returns(2u32);
+        precompiles()
+            .prepare_test(Alice, Precompile1, PCall::referendum_status { index: 0 })
+            .expect_cost(db_read())
+            .expect_no_logs()
+            .execute_returns((0u8, 0u16, 1u32, false, false));
+        assert_eq!(
+            Balances::reserved_balance(AccountId::from(Alice)),
+            2 * SubmissionDeposit::get()
+        );
+
+        precompiles()
+            .prepare_test(Bob, Precompile1, PCall::place_decision_deposit { index: 0 })
+            .execute_returns(());
+        precompiles()
+            .prepare_test(Alice, Precompile1, PCall::referendum_status { index: 0 })
+            .execute_returns((0u8, 0u16, 1u32, true, false));
+        assert_eq!(Balances::reserved_balance(AccountId::from(Bob)), 10);
+    });
+}
+
+#[test]
+fn reverts() {
+    balances().build().execute_with(|| {
+        let (proposal_hash, proposal_len) = note_proposal();
+        precompiles()
+            .prepare_test(
+                Alice,
+                Precompile1,
+                PCall::submit_at {
+                    track_id: 1,
+                    proposal_hash,
+                    proposal_len,
+                    block: 10,
+                },
+            )
+            .execute_reverts(|output| output == b"trackId: unknown track");
+        precompiles()
+            .prepare_test(Alice, Precompile1, PCall::referendum_status { index: 0 })
+            .execute_reverts(|output| output == b"index: unknown referendum");
+        precompiles()
+            .prepare_test(
+                Alice,
+                Precompile1,
+                PCall::place_decision_deposit { index: 0 },
+            )
+            .execute_reverts(|output| dispatch_error(output, "NotOngoing"));
+        assert_eq!(pallet_referenda::ReferendumCount::<Runtime>::get(), 0);
+    });
+}
@@ -0,0 +1,84 @@
+// SPDX-License-Identifier: GPL-3.0-only
+pragma solidity >=0.8.3;
+
+/// @dev The Staking contract's address.
+address constant STAKING_ADDRESS = 0x0000000000000000000000000000000000000801;
+
+/// @dev The Staking contract's instance.
+Staking constant STAKING_CONTRACT = Staking(STAKING_ADDRESS);
+
+/// @title Pallet Staking Interface
+/// @dev The interface through which solidity contracts will interact with pallet-staking.
+/// Every call is dispatched with the caller as the stash account.
+interface Staking {
+    /// @dev Index of the current era.
+    /// @custom:selector 973628f6
+    /// @return The current era, 0 before the first election.
+    function currentEra() external view returns (uint32);
+
+    /// @dev Staking ledger of a stash account.
+    /// @custom:selector fbfa941f
+    /// @param stash The stash account.
+    /// @return total The total amount bonded, including the unlocking chunks.
+    /// @return active The amount actively at stake.
+    /// @return unlocking The amount being unbonded.
+    function ledger(address stash)
+        external
+        view
+        returns (
+            uint256 total,
+            uint256 active,
+            uint256 unlocking
+        );
+
+    /// @dev Nominations of a stash account.
+    /// @custom:selector f95baf26
+    /// @param stash The stash account.
+    /// @return targets The nominated validators.
+    /// @return submittedIn The era the nominations were submitted in.
+    /// @return suppressed Whether the nominations have been suppressed by a slash.
+    function nominations(address stash)
+        external
+        view
+        returns (
+            address[] memory targets,
+            uint32 submittedIn,
+            bool suppressed
+        );
+
+    /// @dev Bond `value` of the caller's balance.
+    /// @custom:selector 70c48766
+    /// @param value The amount to bond.
+    /// @param payee The reward destination: 0 Staked, 1 Stash, 2 Account, 3 None.
+    /// @param rewardAccount The account receiving the rewards when `payee` is 2, ignored otherwise.
+    function bond(
+        uint256 value,
+        uint8 payee,
+        address rewardAccount
+    ) external;
+
+    /// @dev Bond `maxAdditional` more of the caller's balance.
+    /// @custom:selector eaca88de
+    function bondExtra(uint256 maxAdditional) external;
+
+    /// @dev Schedule `value` of the active bond to be unbonded.
+    /// @custom:selector 27de9e32
+    function unbond(uint256 value) external;
+
+    /// @dev Withdraw the unbonded chunks whose bonding duration has passed.
+    /// @custom:selector 548a6706
+    /// @param numSlashingSpans The number of slashing spans of the stash.
+    function withdrawUnbonded(uint32 numSlashingSpans) external;
+
+    /// @dev Nominate the given validators.
+    /// @custom:selector 19f2fdad
+    function nominate(address[] memory targets) external;
+
+    /// @dev Stop nominating or validating.
+    /// @custom:selector 2b8a3ae6
+    function chill() external;
+
+    /// @dev Pay out the rewards of `validatorStash` for `era`.
+    /// @custom:selector 6d4fc25a
+    function payoutStakers(address validatorStash, uint32 era) external;
+}
@@ -0,0 +1,233 @@
+//! Precompile to interact with `pallet_staking` from the EVM.
+//!
+//! Every call is dispatched as a signed origin of the caller, which acts as the stash
+//! (and controller) account of the staker.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use core::marker::PhantomData;
+use fp_evm::PrecompileHandle;
+use frame_support::{
+    dispatch::{GetDispatchInfo, PostDispatchInfo},
+    traits::{Get, OriginTrait},
+};
+use pallet_evm::AddressMapping;
+use pallet_staking::{NominationsQuota, RewardDestination};
+use polkadot_sdk::{frame_support, frame_system, pallet_staking, sp_runtime, sp_std};
+use precompile_utils::prelude::*;
+use sp_core::{H160, U256};
+use sp_runtime::traits::{Dispatchable, Saturating, StaticLookup, Zero};
+use sp_std::vec::Vec;
+
+/// Alias for the Balance type used by `pallet_staking`.
+pub type BalanceOf<Runtime> = pallet_staking::BalanceOf<Runtime>;
+
+/// Upper bound of the number of nomination targets accepted by `nominate`.
+type GetMaxTargets<Runtime> =
+    <<Runtime as pallet_staking::Config>::NominationsQuota as NominationsQuota<
+        BalanceOf<Runtime>,
+    >>::MaxNominations;
+
+/// Reward destination passed to `bond` as an `uint8`.
+const PAYEE_STAKED: u8 = 0;
+const PAYEE_STASH: u8 = 1;
+const PAYEE_ACCOUNT: u8 = 2;
+const PAYEE_NONE: u8 = 3;
+
+/// A precompile to wrap the functionality from `pallet_staking`.
+pub struct StakingPrecompile<Runtime>(PhantomData<Runtime>);
+
+#[precompile_utils::precompile]
+impl<Runtime> StakingPrecompile<Runtime>
+where
+    Runtime: pallet_staking::Config + pallet_evm::Config + frame_system::Config,
+    Runtime::AccountId: Into<H160>,
+    Runtime::RuntimeCall: Dispatchable<PostInfo = PostDispatchInfo> + GetDispatchInfo,
+    Runtime::RuntimeCall: From<pallet_staking::Call<Runtime>>,
+    <Runtime::RuntimeCall as Dispatchable>::RuntimeOrigin: From<Option<Runtime::AccountId>>,
+    <Runtime::RuntimeCall as Dispatchable>::RuntimeOrigin: OriginTrait,
+    BalanceOf<Runtime>: TryFrom<U256> + Into<U256>,
+{
+    #[precompile::public("currentEra()")]
+    #[precompile::view]
+    fn current_era(handle: &mut impl PrecompileHandle) -> EvmResult<u32> {
+        // Storage item: CurrentEra:
+        // EraIndex(4)
+        handle.record_db_read::<Runtime>(4)?;
+
+        Ok(pallet_staking::CurrentEra::<Runtime>::get().unwrap_or_default())
+    }
+
+    #[precompile::public("ledger(address)")]
+    #[precompile::view]
+    fn ledger(handle: &mut impl PrecompileHandle, stash: Address) -> EvmResult<(U256, U256, U256)> {
+        // Storage item: Bonded:
+        // Twox64Concat(8) + AccountId(20) + AccountId(20)
+        handle.record_db_read::<Runtime>(48)?;
+        // Storage item: Ledger:
+        // Blake2_128Concat(16) + AccountId(20) + StakingLedger(AccountId(20) + 2 * Balance(16)
+        // + MaxUnlockingChunks * UnlockChunk(Balance(16) + EraIndex(4)) + legacy claimed rewards)
+        handle.record_db_read::<Runtime>(
+            92 + 20 * <Runtime as pallet_staking::Config>::MaxUnlockingChunks::get() as usize,
+        )?;
+
+        let stash = Runtime::AddressMapping::into_account_id(stash.into());
+        let ledger = pallet_staking::Bonded::<Runtime>::get(&stash)
+            .and_then(|controller| pallet_staking::Ledger::<Runtime>::get(controller));
+
+        Ok(match ledger {
+            Some(ledger) => {
+                let unlocking = ledger
+                    .unlocking
+                    .iter()
+                    .fold(BalanceOf::<Runtime>::zero(), |acc, chunk| {
+                        acc.saturating_add(chunk.value)
+                    });
+                (ledger.total.into(), ledger.active.into(), unlocking.into())
+            }
+            None => Default::default(),
+        })
+    }
+
+    #[precompile::public("nominations(address)")]
+    #[precompile::view]
+    fn nominations(
+        handle: &mut impl PrecompileHandle,
+        stash: Address,
+    ) -> EvmResult<(Vec<Address>, u32, bool)> {
+        // Storage item: Nominators:
+        // Twox64Concat(8) + AccountId(20) + Nominations(MaxNominations * AccountId(20)
+        // + EraIndex(4) + bool(1))
+        handle.record_db_read::<Runtime>(33 + 20 * GetMaxTargets::<Runtime>::get() as usize)?;
+
+        let stash = Runtime::AddressMapping::into_account_id(stash.into());
+
+        Ok(match pallet_staking::Nominators::<Runtime>::get(&stash) {
+            Some(nominations) => (
+                nominations
+                    .targets
+                    .into_iter()
+                    .map(|target| Address(target.into()))
+                    .collect(),
+                nominations.submitted_in,
+                nominations.suppressed,
+            ),
+            None => (Vec::new(), 0, false),
+        })
+    }
+
+    #[precompile::public("bond(uint256,uint8,address)")]
+    fn bond(
+        handle: &mut impl PrecompileHandle,
+        value: U256,
+        payee: u8,
+        reward_account: Address,
+    ) -> EvmResult {
+        let value = Self::u256_to_amount(value).in_field("value")?;
+        let payee = match payee {
+            PAYEE_STAKED => RewardDestination::Staked,
+            PAYEE_STASH => RewardDestination::Stash,
+            PAYEE_ACCOUNT => RewardDestination::Account(Runtime::AddressMapping::into_account_id(
+                reward_account.into(),
+            )),
+            PAYEE_NONE => RewardDestination::None,
+            _ => {
+                return Err(RevertReason::custom("unknown reward destination")
+                    .in_field("payee")
+                    .into())
+            }
+        };
+
+        Self::dispatch(
+            handle,
+            pallet_staking::Call::<Runtime>::bond { value, payee },
+        )
+    }
+
+    #[precompile::public("bondExtra(uint256)")]
+    fn bond_extra(handle: &mut impl PrecompileHandle, max_additional: U256) -> EvmResult {
+        let max_additional = Self::u256_to_amount(max_additional).in_field("maxAdditional")?;
+
+        Self::dispatch(
+            handle,
+            pallet_staking::Call::<Runtime>::bond_extra { max_additional },
+        )
+    }
+
+    #[precompile::public("unbond(uint256)")]
+    fn unbond(handle: &mut impl PrecompileHandle, value: U256) -> EvmResult {
+        let value = Self::u256_to_amount(value).in_field("value")?;
+
+        Self::dispatch(handle, pallet_staking::Call::<Runtime>::unbond { value })
+    }
+
+    #[precompile::public("withdrawUnbonded(uint32)")]
+    fn withdraw_unbonded(handle: &mut impl PrecompileHandle, num_slashing_spans: u32) -> EvmResult {
+        Self::dispatch(
+            handle,
+            pallet_staking::Call::<Runtime>::withdraw_unbonded { num_slashing_spans },
+        )
+    }
+
+    #[precompile::public("nominate(address[])")]
+    fn nominate(
+        handle: &mut impl PrecompileHandle,
+        targets: BoundedVec<Address, GetMaxTargets<Runtime>>,
+    ) -> EvmResult {
+        let targets: Vec<Address> = targets.into();
+        let targets = targets
+            .into_iter()
+            .map(|target| {
+                Runtime::Lookup::unlookup(Runtime::AddressMapping::into_account_id(target.into()))
+            })
+            .collect();
+
+        Self::dispatch(
+            handle,
+            pallet_staking::Call::<Runtime>::nominate { targets },
+        )
+    }
+
+    #[precompile::public("chill()")]
+    fn chill(handle: &mut impl PrecompileHandle) -> EvmResult {
+        Self::dispatch(handle, pallet_staking::Call::<Runtime>::chill {})
+    }
+
+    #[precompile::public("payoutStakers(address,uint32)")]
+    fn payout_stakers(
+        handle: &mut impl PrecompileHandle,
+        validator_stash: Address,
+        era: u32,
+    ) -> EvmResult {
+        let validator_stash = Runtime::AddressMapping::into_account_id(validator_stash.into());
+
+        Self::dispatch(
+            handle,
+            pallet_staking::Call::<Runtime>::payout_stakers {
+                validator_stash,
+                era,
+            },
+        )
+    }
+
+    fn dispatch(
+        handle: &mut impl PrecompileHandle,
+        call: pallet_staking::Call<Runtime>,
+    ) -> EvmResult {
+        let origin = Runtime::AddressMapping::into_account_id(handle.context().caller);
+        RuntimeHelper::<Runtime>::try_dispatch(handle, Some(origin).into(), call)?;
+
+        Ok(())
+    }
+
+    fn u256_to_amount(value: U256) -> MayRevert<BalanceOf<Runtime>> {
+        value
+            .try_into()
+            .map_err(|_| RevertReason::value_is_too_large("balance type").into())
+    }
+}
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
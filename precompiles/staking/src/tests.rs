@@ -0,0 +1,250 @@
+use crate::{mock::*, *};
+use frame_support::assert_ok;
+use precompile_utils::testing::*;
+
+type PCall = StakingPrecompileCall<Runtime>;
+
+fn precompiles() -> Precompiles<Runtime> {
+    PrecompilesValue::get()
+}
+
+fn balances() -> ExtBuilder {
+    ExtBuilder::default().with_balances(vec![(Alice.into(), 1000), (Bob.into(), 1000)])
+}
+
+fn db_read() -> u64 {
+    RuntimeHelper::<Runtime>::db_read_gas_cost()
+}
+
+fn dispatch_error(output: &[u8], error: &str) -> bool {
+    core::str::from_utf8(output).map_or(false, |reason| reason.contains(error))
+}
+
+fn bond(who: impl Into<H160>, value: Balance) {
+    precompiles()
+        .prepare_test(
+            who,
+            Precompile1,
+            PCall::bond {
+                value: value.into(),
+                payee: 0,
+                reward_account: Address(H160::zero()),
+            },
+        )
+        .execute_returns(());
+}
+
+#[test]
+fn selectors() {
+    assert!(PCall::current_era_selectors().contains(&0x973628f6));
+    assert!(PCall::ledger_selectors().contains(&0xfbfa941f));
+    assert!(PCall::nominations_selectors().contains(&0xf95baf26));
+    assert!(PCall::bond_selectors().contains(&0x70c48766));
+    assert!(PCall::bond_extra_selectors().contains(&0xeaca88de));
+    assert!(PCall::unbond_selectors().contains(&0x27de9e32));
+    assert!(PCall::withdraw_unbonded_selectors().contains(&0x548a6706));
+    assert!(PCall::nominate_selectors().contains(&0x19f2fdad));
+    assert!(PCall::chill_selectors().contains(&0x2b8a3ae6));
+    assert!(PCall::payout_stakers_selectors().contains(&0x6d4fc25a));
+}
+
+#[test]
+fn bond_and_ledger() {
+    balances().build().execute_with(|| {
+        precompiles()
+            .prepare_test(Alice, Precompile1, PCall::current_era {})
+            .expect_cost(db_read())
+            .expect_no_logs()
+            .execute_returns(0u32);
+        precompiles()
+            .prepare_test(
+                Alice,
+                Precompile1,
+                PCall::ledger {
+                    stash: Address(Alice.into()),
+                },
+            )
+            .execute_returns((U256::zero(), U256::zero(), U256::zero()));
+
+        bond(Alice, 100);
+
+        let bonded = U256::from(100);
+        precompiles()
+            .prepare_test(
+                Bob,
+                Precompile1,
+                PCall::ledger {
+                    stash: Address(Alice.into()),
+                },
+            )
+            .expect_cost(2 * db_read())
+            .expect_no_logs()
+            .execute_returns((bonded, bonded, U256::zero()));
+        let ledger = pallet_staking::Ledger::<Runtime>::get(AccountId::from(Alice))
+            .expect("Alice is bonded");
+        assert_eq!((ledger.total, ledger.active), (100, 100));
+        assert_eq!(
+            pallet_staking::Payee::<Runtime>::get(AccountId::from(Alice)),
+            Some(RewardDestination::Staked)
+        );
+    });
+}
+
+#[test]
+fn bond_reverts() {
+    balances().build().execute_with(|| {
+        precompiles()
+            .prepare_test(
+                Alice,
+                Precompile1,
+                PCall::bond {
+                    value: 1.into(),
+                    payee: 4,
+                    reward_account: Address(H160::zero()),
+                },
+            )
+            .execute_reverts(|output| output == b"payee: unknown reward destination");
+        precompiles()
+            .prepare_test(
+                Alice,
+                Precompile1,
+                PCall::bond {
+                    value: U256::MAX,
+                    payee: 0,
+                    reward_account: Address(H160::zero()),
+                },
+            )
+            .execute_reverts(|output| output == b"value: Value is too large for balance type");
+
+        bond(Alice, 100);
+        precompiles()
+            .prepare_test(
+                Alice,
+                Precompile1,
+                PCall::bond {
+                    value: 1.into(),
+                    payee: 0,
+                    reward_account: Address(H160::zero()),
+                },
+            )
+            .execute_reverts(|output| dispatch_error(output, "AlreadyBonded"));
+    });
+}
+
+#[test]
+fn bond_with_a_reward_account() {
+    balances().build().execute_with(|| {
+        precompiles()
+            .prepare_test(
+                Alice,
+                Precompile1,
+                PCall::bond {
+                    value: 100.into(),
+                    payee: 2,
+                    reward_account: Address(Charlie.into()),
+                },
+            )
+            .execute_returns(());
+
+        assert_eq!(
+            pallet_staking::Payee::<Runtime>::get(AccountId::from(Alice)),
+            Some(RewardDestination::Account(Charlie.into()))
+        );
+    });
+}
+
+#[test]
+fn bond_extra_and_unbond() {
+    balances().build().execute_with(|| {
+        bond(Alice, 100);
+
+        precompiles()
+            .prepare_test(
+                Alice,
+                Precompile1,
+                PCall::bond_extra {
+                    max_additional: 50.into(),
+                },
+            )
+            .expect_no_logs()
+            .execute_returns(());
+        precompiles()
+            .prepare_test(Alice, Precompile1, PCall::unbond { value: 30.into() })
+            .expect_no_logs()
+            .execute_returns(());
+
+        precompiles()
+            .prepare_test(
+                Alice,
+                Precompile1,
+                PCall::ledger {
+                    stash: Address(Alice.into()),
+                },
+            )
+            .execute_returns((U256::from(150), U256::from(120), U256::from(30)));
+    });
+}
+
+#[test]
+fn nominate_and_chill() {
+    balances().build().execute_with(|| {
+        assert_ok!(Staking::bond(
+            RuntimeOrigin::signed(Bob.into()),
+            100,
+            RewardDestination::Staked
+        ));
+        assert_ok!(Staking::validate(
+            RuntimeOrigin::signed(Bob.into()),
+            pallet_staking::ValidatorPrefs::default()
+        ));
+
+        // Nominating needs a bonded stash.
+        precompiles()
+            .prepare_test(
+                Alice,
+                Precompile1,
+                PCall::nominate {
+                    targets: vec![Address(Bob.into())].into(),
+                },
+            )
+            .execute_reverts(|output| dispatch_error(output, "NotController"));
+
+        bond(Alice, 100);
+        precompiles()
+            .prepare_test(
+                Alice,
+                Precompile1,
+                PCall::nominate {
+                    targets: vec![Address(Bob.into())].into(),
+                },
+            )
+            .execute_returns(());
+        precompiles()
+            .prepare_test(
+                Bob,
+                Precompile1,
+                PCall::nominations {
+                    stash: Address(Alice.into()),
+                },
+            )
+            .expect_cost(db_read())
+            .expect_no_logs()
+            .execute_returns((vec![Address(Bob.into())], 0u32, false));
+
+        precompiles()
+            .prepare_test(Alice, Precompile1, PCall::chill {})
+            .execute_returns(());
+        assert!(!pallet_staking::Nominators::<Runtime>::contains_key(
+            AccountId::from(Alice)
+        ));
+        precompiles()
+            .prepare_test(
+                Bob,
+                Precompile1,
+                PCall::nominations {
+                    stash: Address(Alice.into()),
+                },
+            )
+            .execute_returns((Vec::<Address>::new(), 0u32, false));
+    });
+}
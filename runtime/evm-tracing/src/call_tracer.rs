@@ -0,0 +1,367 @@
+//! Call tracer, building the tree of the calls like the `callTracer` of geth.
+
+use crate::listener::{gas_change, transaction_gas_used, Listener};
+use crate::{CallFrame, CallType};
+use alloc::{format, vec::Vec};
+use evm::tracing::Event as EvmEvent;
+use evm::{CreateScheme, ExitReason};
+use evm_gasometer::tracing::Event as GasometerEvent;
+use sp_core::{H160, U256};
+
+/// Selector of `Error(string)`, the revert reason of Solidity.
+const ERROR_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+
+/// A call not exited yet.
+struct Frame {
+    call: CallFrame,
+    /// Address of the account executing the call, the caller of its subcalls.
+    address: H160,
+    /// Gas limit of the gasometer of the call, once seen.
+    limit: Option<u64>,
+    /// Gas limit of the gasometer of the caller.
+    caller_limit: u64,
+    /// Gas left by the call.
+    gas_left: Option<u64>,
+}
+
+#[derive(Default)]
+pub(crate) struct CallTracer {
+    stack: Vec<Frame>,
+    root: Option<CallFrame>,
+    /// Whether the executor has entered the top call announced by the transaction event.
+    entered: bool,
+    /// Gas limit of the transaction, the limit of its outermost gasometer.
+    gas_limit: u64,
+    /// Gas left and refunded by the outermost gasometer.
+    gas_left: u64,
+    refunded: i64,
+}
+
+impl CallTracer {
+    pub fn finish(mut self, only_top_call: bool) -> CallFrame {
+        while !self.stack.is_empty() {
+            self.exit(None, &[]);
+        }
+        let mut root = self.root.unwrap_or_else(|| CallFrame {
+            call_type: CallType::Call,
+            from: H160::zero(),
+            to: H160::zero(),
+            value: None,
+            gas: 0,
+            gas_used: 0,
+            input: Vec::new(),
+            output: Vec::new(),
+            error: None,
+            revert_reason: None,
+            calls: Vec::new(),
+        });
+        root.gas_used = transaction_gas_used(self.gas_limit, self.gas_left, self.refunded);
+        if only_top_call {
+            root.calls.clear();
+        }
+        root
+    }
+
+    fn start_transaction(
+        &mut self,
+        call_type: CallType,
+        from: H160,
+        to: H160,
+        value: U256,
+        input: &[u8],
+        gas_limit: U256,
+    ) {
+        self.gas_limit = gas_limit.low_u64();
+        self.gas_left = self.gas_limit;
+        self.stack.push(Frame {
+            call: new_call(call_type, from, to, Some(value), self.gas_limit, input),
+            address: to,
+            limit: None,
+            caller_limit: self.gas_limit,
+            gas_left: None,
+        });
+    }
+
+    /// Enters a call, unless it is the top call, already announced by the transaction event.
+    fn enter(&mut self, call: CallFrame, address: H160) {
+        if !self.entered && self.stack.len() == 1 {
+            self.entered = true;
+            return;
+        }
+        let caller_limit = self
+            .stack
+            .last()
+            .and_then(|frame| frame.limit)
+            .unwrap_or_default();
+        self.stack.push(Frame {
+            call,
+            address,
+            limit: None,
+            caller_limit,
+            gas_left: None,
+        });
+    }
+
+    fn exit(&mut self, reason: Option<&ExitReason>, return_value: &[u8]) {
+        let Some(Frame {
+            mut call, gas_left, ..
+        }) = self.stack.pop()
+        else {
+            return;
+        };
+        call.output = return_value.to_vec();
+        call.gas_used = call.gas.saturating_sub(gas_left.unwrap_or(call.gas));
+        match reason {
+            Some(ExitReason::Succeed(_)) => {}
+            Some(ExitReason::Revert(_)) => {
+                call.error = Some(b"execution reverted".to_vec());
+                call.revert_reason = revert_reason(return_value);
+            }
+            Some(ExitReason::Error(error)) => {
+                call.error = Some(format!("{:?}", error).into_bytes());
+                call.gas_used = call.gas;
+            }
+            Some(ExitReason::Fatal(error)) => {
+                call.error = Some(format!("{:?}", error).into_bytes());
+                call.gas_used = call.gas;
+            }
+            None => {}
+        }
+        match self.stack.last_mut() {
+            Some(caller) => caller.call.calls.push(call),
+            None => self.root = Some(call),
+        }
+    }
+}
+
+impl Listener for CallTracer {
+    fn evm_event(&mut self, event: EvmEvent) {
+        match event {
+            EvmEvent::TransactCall {
+                caller,
+                address,
+                value,
+                data,
+                gas_limit,
+            } => self.start_transaction(
+                CallType::Call,
+                caller,
+                address,
+                value,
+                data,
+                U256::from(gas_limit),
+            ),
+            EvmEvent::TransactCreate {
+                caller,
+                value,
+                init_code,
+                gas_limit,
+                address,
+            } => self.start_transaction(
+                CallType::Create,
+                caller,
+                address,
+                value,
+                init_code,
+                U256::from(gas_limit),
+            ),
+            EvmEvent::TransactCreate2 {
+                caller,
+                value,
+                init_code,
+                gas_limit,
+                address,
+                ..
+            } => self.start_transaction(
+                CallType::Create2,
+                caller,
+                address,
+                value,
+                init_code,
+                U256::from(gas_limit),
+            ),
+            EvmEvent::Call {
+                code_address,
+                transfer,
+                input,
+                target_gas,
+                is_static,
+                context,
+            }
+            | EvmEvent::PrecompileSubcall {
+                code_address,
+                transfer,
+                input,
+                target_gas,
+                is_static,
+                context,
+            } => {
+                let call_type = if is_static {
+                    CallType::StaticCall
+                } else if context.address == code_address {
+                    CallType::Call
+                } else if transfer.is_some() {
+                    CallType::CallCode
+                } else {
+                    CallType::DelegateCall
+                };
+                let value = match call_type {
+                    CallType::Call | CallType::CallCode => Some(
+                        transfer
+                            .as_ref()
+                            .map(|transfer| transfer.value)
+                            .unwrap_or_default(),
+                    ),
+                    _ => None,
+                };
+                let from = self
+                    .stack
+                    .last()
+                    .map(|frame| frame.address)
+                    .unwrap_or(context.caller);
+                let call = new_call(
+                    call_type,
+                    from,
+                    code_address,
+                    value,
+                    target_gas.unwrap_or_default(),
+                    input,
+                );
+                self.enter(call, context.address);
+            }
+            EvmEvent::Create {
+                caller,
+                address,
+                scheme,
+                value,
+                init_code,
+                target_gas,
+            } => {
+                let call_type = match scheme {
+                    CreateScheme::Create2 { .. } => CallType::Create2,
+                    _ => CallType::Create,
+                };
+                let call = new_call(
+                    call_type,
+                    caller,
+                    address,
+                    Some(value),
+                    target_gas.unwrap_or_default(),
+                    init_code,
+                );
+                self.enter(call, address);
+            }
+            EvmEvent::Suicide {
+                address,
+                target,
+                balance,
+            } => {
+                if let Some(frame) = self.stack.last_mut() {
+                    let call = new_call(
+                        CallType::SelfDestruct,
+                        address,
+                        target,
+                        Some(balance),
+                        0,
+                        &[],
+                    );
+                    frame.call.calls.push(call);
+                }
+            }
+            EvmEvent::Exit {
+                reason,
+                return_value,
+            } => self.exit(Some(reason), return_value),
+        }
+    }
+
+    fn gasometer_event(&mut self, event: GasometerEvent) {
+        let Some(change) = gas_change(&event) else {
+            return;
+        };
+        if change.limit == self.gas_limit {
+            self.gas_left = change.after;
+            self.refunded = change.refunded;
+        }
+        let Some(frame) = self.stack.last_mut() else {
+            return;
+        };
+        let is_root = self.stack.len() == 1;
+        if frame.limit == Some(change.limit) {
+            frame.gas_left = Some(change.after);
+        } else if change.limit == frame.caller_limit {
+            // The caller gets back the gas left by the call once it exits.
+            if let GasometerEvent::RecordStipend { stipend, .. } = event {
+                frame.gas_left = Some(stipend);
+            }
+        } else if frame.limit.is_none() {
+            frame.limit = Some(change.limit);
+            frame.gas_left = Some(change.after);
+            if !is_root {
+                frame.call.gas = change.limit;
+            }
+        }
+    }
+}
+
+fn new_call(
+    call_type: CallType,
+    from: H160,
+    to: H160,
+    value: Option<U256>,
+    gas: u64,
+    input: &[u8],
+) -> CallFrame {
+    CallFrame {
+        call_type,
+        from,
+        to,
+        value,
+        gas,
+        gas_used: 0,
+        input: input.to_vec(),
+        output: Vec::new(),
+        error: None,
+        revert_reason: None,
+        calls: Vec::new(),
+    }
+}
+
+/// Decodes the message of an `Error(string)` revert.
+fn revert_reason(return_value: &[u8]) -> Option<Vec<u8>> {
+    let data = return_value.strip_prefix(&ERROR_SELECTOR[..])?;
+    let offset = word_to_usize(data.get(..32)?)?;
+    let length = word_to_usize(data.get(offset..offset.checked_add(32)?)?)?;
+    let start = offset.checked_add(32)?;
+    data.get(start..start.checked_add(length)?)
+        .map(<[u8]>::to_vec)
+}
+
+fn word_to_usize(word: &[u8]) -> Option<usize> {
+    let word = U256::from_big_endian(word);
+    (word <= U256::from(usize::MAX)).then(|| word.as_usize())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn word(value: u64) -> [u8; 32] {
+        let mut word = [0u8; 32];
+        U256::from(value).to_big_endian(&mut word);
+        word
+    }
+
+    #[test]
+    fn decodes_revert_reason() {
+        let mut data = ERROR_SELECTOR.to_vec();
+        data.extend_from_slice(&word(32));
+        data.extend_from_slice(&word(4));
+        data.extend_from_slice(b"nope");
+        data.extend_from_slice(&[0u8; 28]);
+        assert_eq!(revert_reason(&data), Some(b"nope".to_vec()));
+
+        assert_eq!(revert_reason(&data[..40]), None);
+        assert_eq!(revert_reason(b"custom error"), None);
+    }
+}
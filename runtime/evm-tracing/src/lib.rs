@@ -0,0 +1,238 @@
+//! Tracing of EVM executions, backing the `debug_trace*` RPC methods.
+//!
+//! The node replays a block on top of the state of its parent through [`DebugRuntimeApi`]. The
+//! runtime executes the traced transactions with one of the listeners of this crate subscribed
+//! to the tracing events of the `evm` crates, and returns the resulting [`Trace`]s, which the
+//! node formats like the tracers of geth do.
+//!
+//! The listeners need the `tracing` feature, which subscribes them to the events of the `evm`
+//! crates and slows down every EVM execution. Without it, only the types and the declaration of
+//! the runtime API are available: the runtime of a tracing node is built with its `evm-tracing`
+//! feature and set with `--wasm-runtime-overrides`, while the runtime of the chain leaves it out.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+#[cfg(feature = "tracing")]
+mod call_tracer;
+#[cfg(feature = "tracing")]
+mod listener;
+mod opcodes;
+#[cfg(feature = "tracing")]
+mod prestate_tracer;
+#[cfg(feature = "tracing")]
+mod struct_logger;
+
+pub use opcodes::opcode_name;
+#[cfg(feature = "tracing")]
+pub use prestate_tracer::{EvmState, PalletEvmState};
+
+use codec::{Decode, Encode};
+#[cfg(feature = "tracing")]
+use frame_support::storage::{with_transaction, TransactionOutcome};
+#[cfg(feature = "tracing")]
+use polkadot_sdk::frame_support;
+use polkadot_sdk::{sp_api, sp_runtime, sp_std};
+use scale_info::TypeInfo;
+use sp_core::{H160, H256, U256};
+use sp_runtime::DispatchError;
+use sp_std::vec::Vec;
+
+/// Tracer an EVM execution is traced with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub enum Tracer {
+    /// Logs every executed opcode, the default tracer of geth.
+    StructLogger(StructLoggerConfig),
+    /// Builds the tree of the calls, like the `callTracer` of geth.
+    CallTracer {
+        /// Whether to leave the subcalls out.
+        only_top_call: bool,
+    },
+    /// Reads the accounts touched by the execution before it runs, like the `prestateTracer`
+    /// of geth.
+    PrestateTracer,
+    /// Lists the accounts and storage slots accessed by the execution, like the access list
+    /// tracer of `eth_createAccessList` in geth.
+    AccessListTracer,
+}
+
+/// Configuration of the struct logger.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub struct StructLoggerConfig {
+    /// Whether to leave the storage out of the logs.
+    pub disable_storage: bool,
+    /// Whether to leave the stack out of the logs.
+    pub disable_stack: bool,
+    /// Whether to include the memory in the logs.
+    pub enable_memory: bool,
+}
+
+/// Trace of an EVM execution.
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub enum Trace {
+    /// Trace of the struct logger.
+    StructLogs(StructLogs),
+    /// Trace of the call tracer, the top call.
+    Call(CallFrame),
+    /// Trace of the prestate tracer, the touched accounts.
+    Prestate(Vec<AccountState>),
+    /// Trace of the access list tracer, the accessed accounts and their accessed storage slots.
+    AccessList(Vec<(H160, Vec<H256>)>),
+}
+
+/// Opcodes executed by a transaction.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub struct StructLogs {
+    /// Gas used by the transaction.
+    pub gas: u64,
+    /// Whether the transaction failed.
+    pub failed: bool,
+    /// Data returned by the transaction.
+    pub return_value: Vec<u8>,
+    /// The executed opcodes, in order.
+    pub struct_logs: Vec<StructLog>,
+}
+
+/// An executed opcode.
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub struct StructLog {
+    /// Program counter.
+    pub pc: u64,
+    /// The opcode.
+    pub op: u8,
+    /// Gas left before the opcode.
+    pub gas: u64,
+    /// Gas cost of the opcode.
+    pub gas_cost: u64,
+    /// Call depth, 1 in the top call.
+    pub depth: u32,
+    /// Stack before the opcode, the top last.
+    pub stack: Option<Vec<H256>>,
+    /// Memory before the opcode.
+    pub memory: Option<Vec<u8>>,
+    /// Storage of the contract accessed so far, logged by `SLOAD` and `SSTORE`.
+    pub storage: Option<Vec<(H256, H256)>>,
+    /// Error the opcode failed with.
+    pub error: Option<Vec<u8>>,
+}
+
+/// Type of a call.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub enum CallType {
+    Call,
+    StaticCall,
+    DelegateCall,
+    CallCode,
+    Create,
+    Create2,
+    SelfDestruct,
+}
+
+/// A call and its subcalls.
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub struct CallFrame {
+    /// Type of the call.
+    pub call_type: CallType,
+    /// The calling account.
+    pub from: H160,
+    /// The called account, or the created contract.
+    pub to: H160,
+    /// Value transferred, if the call transfers value.
+    pub value: Option<U256>,
+    /// Gas given to the call.
+    pub gas: u64,
+    /// Gas used by the call.
+    pub gas_used: u64,
+    /// Call data, or init code.
+    pub input: Vec<u8>,
+    /// Data returned by the call.
+    pub output: Vec<u8>,
+    /// Error the call failed with.
+    pub error: Option<Vec<u8>>,
+    /// Reason the call reverted with, decoded from `Error(string)`.
+    pub revert_reason: Option<Vec<u8>>,
+    /// The subcalls, in order.
+    pub calls: Vec<CallFrame>,
+}
+
+/// State of an account.
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub struct AccountState {
+    pub address: H160,
+    pub balance: U256,
+    pub nonce: U256,
+    pub code: Vec<u8>,
+    /// The storage slots touched by the execution.
+    pub storage: Vec<(H256, H256)>,
+}
+
+/// Traces `execute`, an EVM execution, with `tracer`.
+///
+/// The prestate tracer executes it twice: once in a storage transaction it rolls back, to find
+/// the touched accounts and read their state before the execution, and once for real, so the
+/// state is left as if `execute` was called once.
+#[cfg(feature = "tracing")]
+pub fn trace<S: EvmState>(tracer: Tracer, execute: impl Fn()) -> Trace {
+    match tracer {
+        Tracer::StructLogger(config) => {
+            let (logger, ()) = listener::listen(struct_logger::StructLogger::new(config), execute);
+            Trace::StructLogs(logger.finish())
+        }
+        Tracer::CallTracer { only_top_call } => {
+            let (tracer, ()) = listener::listen(call_tracer::CallTracer::default(), execute);
+            Trace::Call(tracer.finish(only_top_call))
+        }
+        Tracer::PrestateTracer => {
+            let touched = with_transaction(|| {
+                let (touched, ()) =
+                    listener::listen(prestate_tracer::TouchedState::default(), &execute);
+                TransactionOutcome::Rollback(Ok::<_, DispatchError>(touched))
+            })
+            .unwrap_or_default();
+            let prestate = touched.read::<S>();
+            execute();
+            Trace::Prestate(prestate)
+        }
+        Tracer::AccessListTracer => {
+            let (touched, ()) = listener::listen(prestate_tracer::TouchedState::default(), execute);
+            Trace::AccessList(touched.access_list::<S>())
+        }
+    }
+}
+
+sp_api::decl_runtime_apis! {
+    /// API replaying EVM executions with a tracer.
+    pub trait DebugRuntimeApi {
+        /// Replays `extrinsics`, the extrinsics of the block of `header`, tracing the Ethereum
+        /// transaction `transaction_hash`. Must be called on the state of the parent block.
+        fn trace_transaction(
+            extrinsics: Vec<Block::Extrinsic>,
+            header: &Block::Header,
+            transaction_hash: H256,
+            tracer: Tracer,
+        ) -> Result<Trace, DispatchError>;
+
+        /// Replays `extrinsics`, the extrinsics of the block of `header`, tracing all its
+        /// Ethereum transactions. Must be called on the state of the parent block.
+        fn trace_block(
+            extrinsics: Vec<Block::Extrinsic>,
+            header: &Block::Header,
+            tracer: Tracer,
+        ) -> Result<Vec<(H256, Trace)>, DispatchError>;
+
+        /// Traces a call, or a contract creation if `to` is `None`, like `eth_call` executes it.
+        fn trace_call(
+            from: H160,
+            to: Option<H160>,
+            data: Vec<u8>,
+            value: U256,
+            gas_limit: U256,
+            max_fee_per_gas: Option<U256>,
+            max_priority_fee_per_gas: Option<U256>,
+            nonce: Option<U256>,
+            access_list: Option<Vec<(H160, Vec<H256>)>>,
+            tracer: Tracer,
+        ) -> Result<Trace, DispatchError>;
+    }
+}
@@ -0,0 +1,142 @@
+//! Subscription of the listeners to the tracing events of the `evm` crates.
+
+use alloc::rc::Rc;
+use core::cell::RefCell;
+use evm_gasometer::tracing::{Event as GasometerEvent, Snapshot};
+
+/// Listener of the tracing events of the `evm` crates.
+pub(crate) trait Listener {
+    /// Handles an event of the executor: calls, creations and exits.
+    fn evm_event(&mut self, _event: evm::tracing::Event) {}
+
+    /// Handles an event of the interpreter: steps and storage accesses.
+    fn runtime_event(&mut self, _event: evm_runtime::tracing::Event) {}
+
+    /// Handles an event of the gasometer of the current call.
+    fn gasometer_event(&mut self, _event: GasometerEvent) {}
+}
+
+struct EvmProxy<L>(Rc<RefCell<L>>);
+
+impl<L: Listener> evm::tracing::EventListener for EvmProxy<L> {
+    fn event(&mut self, event: evm::tracing::Event) {
+        self.0.borrow_mut().evm_event(event);
+    }
+}
+
+struct RuntimeProxy<L>(Rc<RefCell<L>>);
+
+impl<L: Listener> evm_runtime::tracing::EventListener for RuntimeProxy<L> {
+    fn event(&mut self, event: evm_runtime::tracing::Event) {
+        self.0.borrow_mut().runtime_event(event);
+    }
+}
+
+struct GasometerProxy<L>(Rc<RefCell<L>>);
+
+impl<L: Listener> evm_gasometer::tracing::EventListener for GasometerProxy<L> {
+    fn event(&mut self, event: GasometerEvent) {
+        self.0.borrow_mut().gasometer_event(event);
+    }
+}
+
+/// Runs `f` with `listener` subscribed to the tracing events, and returns the listener.
+pub(crate) fn listen<L: Listener + 'static, R>(listener: L, f: impl FnOnce() -> R) -> (L, R) {
+    let listener = Rc::new(RefCell::new(listener));
+    let result = {
+        let mut evm_proxy = EvmProxy(listener.clone());
+        let mut runtime_proxy = RuntimeProxy(listener.clone());
+        let mut gasometer_proxy = GasometerProxy(listener.clone());
+        evm::tracing::using(&mut evm_proxy, || {
+            evm_runtime::tracing::using(&mut runtime_proxy, || {
+                evm_gasometer::tracing::using(&mut gasometer_proxy, f)
+            })
+        })
+    };
+    let listener = match Rc::try_unwrap(listener) {
+        Ok(listener) => listener.into_inner(),
+        Err(_) => unreachable!("the proxies are dropped; qed"),
+    };
+    (listener, result)
+}
+
+/// Gas of the current call around a gasometer event.
+pub(crate) struct GasChange {
+    /// Gas limit of the gasometer, which tells the gasometers of nested calls apart.
+    pub limit: u64,
+    /// Gas left before the event.
+    pub before: u64,
+    /// Gas left after the event.
+    pub after: u64,
+    /// Gas refunded after the event.
+    pub refunded: i64,
+}
+
+/// Returns the gas change of `event`, or `None` if the event has no snapshot, which happens
+/// once the gasometer ran out of gas.
+pub(crate) fn gas_change(event: &GasometerEvent) -> Option<GasChange> {
+    fn gas_left(snapshot: &Snapshot) -> u64 {
+        snapshot
+            .gas_limit
+            .saturating_sub(snapshot.used_gas)
+            .saturating_sub(snapshot.memory_gas)
+    }
+
+    let change = match event {
+        GasometerEvent::RecordCost {
+            cost,
+            snapshot: Some(snapshot),
+        }
+        | GasometerEvent::RecordTransaction {
+            cost,
+            snapshot: Some(snapshot),
+        } => GasChange {
+            limit: snapshot.gas_limit,
+            before: gas_left(snapshot),
+            after: gas_left(snapshot).saturating_sub(*cost),
+            refunded: snapshot.refunded_gas,
+        },
+        GasometerEvent::RecordDynamicCost {
+            gas_cost,
+            memory_gas,
+            gas_refund,
+            snapshot: Some(snapshot),
+        } => {
+            let cost = gas_cost.saturating_add(memory_gas.saturating_sub(snapshot.memory_gas));
+            GasChange {
+                limit: snapshot.gas_limit,
+                before: gas_left(snapshot),
+                after: gas_left(snapshot).saturating_sub(cost),
+                refunded: snapshot.refunded_gas.saturating_add(*gas_refund),
+            }
+        }
+        GasometerEvent::RecordStipend {
+            stipend,
+            snapshot: Some(snapshot),
+        } => GasChange {
+            limit: snapshot.gas_limit,
+            before: gas_left(snapshot),
+            after: gas_left(snapshot).saturating_add(*stipend),
+            refunded: snapshot.refunded_gas,
+        },
+        GasometerEvent::RecordRefund {
+            refund,
+            snapshot: Some(snapshot),
+        } => GasChange {
+            limit: snapshot.gas_limit,
+            before: gas_left(snapshot),
+            after: gas_left(snapshot),
+            refunded: snapshot.refunded_gas.saturating_add(*refund),
+        },
+        _ => return None,
+    };
+    Some(change)
+}
+
+/// Returns the gas used by a transaction from the gas left by its top call, after the refund
+/// capped to a fifth of the gas used (EIP-3529).
+pub(crate) fn transaction_gas_used(gas_limit: u64, gas_left: u64, refunded: i64) -> u64 {
+    let used = gas_limit.saturating_sub(gas_left);
+    let refunded = u64::try_from(refunded).unwrap_or_default();
+    used.saturating_sub(refunded.min(used / 5))
+}
@@ -0,0 +1,237 @@
+//! Prestate and access list tracers, collecting the accounts and storage slots touched by an
+//! execution.
+
+use crate::listener::Listener;
+use crate::AccountState;
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    vec::Vec,
+};
+use core::marker::PhantomData;
+use evm::tracing::Event as EvmEvent;
+use evm_runtime::tracing::Event as RuntimeEvent;
+use pallet_evm::{IsPrecompileResult, PrecompileSet};
+use polkadot_sdk::frame_support::traits::Get;
+use sp_core::{H160, H256, U256};
+
+/// Opcodes reading the account at the top of the stack.
+const BALANCE: u8 = 0x31;
+const EXTCODESIZE: u8 = 0x3b;
+const EXTCODECOPY: u8 = 0x3c;
+const EXTCODEHASH: u8 = 0x3f;
+
+/// Source of the EVM state read by the prestate tracer.
+pub trait EvmState {
+    /// Returns the balance and the nonce of `address`.
+    fn account(address: H160) -> (U256, U256);
+    /// Returns the code of `address`.
+    fn code(address: H160) -> Vec<u8>;
+    /// Returns the value of the storage slot `index` of `address`.
+    fn storage(address: H160, index: H256) -> H256;
+    /// Returns the author of the block, which receives the priority fees.
+    fn author() -> H160;
+    /// Returns whether `address` is a precompile.
+    fn is_precompile(address: H160) -> bool;
+}
+
+/// The EVM state of `pallet_evm`.
+pub struct PalletEvmState<T>(PhantomData<T>);
+
+impl<T: pallet_evm::Config> EvmState for PalletEvmState<T> {
+    fn account(address: H160) -> (U256, U256) {
+        let (account, _) = pallet_evm::Pallet::<T>::account_basic(&address);
+        (account.balance, account.nonce)
+    }
+
+    fn code(address: H160) -> Vec<u8> {
+        pallet_evm::AccountCodes::<T>::get(address)
+    }
+
+    fn storage(address: H160, index: H256) -> H256 {
+        pallet_evm::AccountStorages::<T>::get(address, index)
+    }
+
+    fn author() -> H160 {
+        pallet_evm::Pallet::<T>::find_author()
+    }
+
+    fn is_precompile(address: H160) -> bool {
+        matches!(
+            T::PrecompilesValue::get().is_precompile(address, u64::MAX),
+            IsPrecompileResult::Answer {
+                is_precompile: true,
+                ..
+            }
+        )
+    }
+}
+
+/// The accounts and storage slots touched by an execution.
+#[derive(Default)]
+pub(crate) struct TouchedState {
+    accounts: BTreeMap<H160, BTreeSet<H256>>,
+    /// Sender and receiver, or created contract, of the transaction.
+    transaction: Option<(H160, H160)>,
+}
+
+impl TouchedState {
+    fn touch(&mut self, address: H160) {
+        self.accounts.entry(address).or_default();
+    }
+
+    /// Returns the access list of the execution, leaving out the accounts warm from the start
+    /// of a transaction unless their storage was touched, like geth does: the sender, the
+    /// receiver and the precompiles.
+    pub fn access_list<S: EvmState>(self) -> Vec<(H160, Vec<H256>)> {
+        let transaction = self.transaction;
+        self.accounts
+            .into_iter()
+            .filter(|(address, slots)| {
+                let warm = transaction
+                    .map_or(false, |(from, to)| *address == from || *address == to)
+                    || S::is_precompile(*address);
+                !slots.is_empty() || !warm
+            })
+            .map(|(address, slots)| (address, slots.into_iter().collect()))
+            .collect()
+    }
+
+    /// Reads the state of the touched accounts and of the author.
+    pub fn read<S: EvmState>(mut self) -> Vec<AccountState> {
+        self.touch(S::author());
+        self.accounts
+            .into_iter()
+            .map(|(address, slots)| {
+                let (balance, nonce) = S::account(address);
+                AccountState {
+                    address,
+                    balance,
+                    nonce,
+                    code: S::code(address),
+                    storage: slots
+                        .into_iter()
+                        .map(|index| (index, S::storage(address, index)))
+                        .collect(),
+                }
+            })
+            .collect()
+    }
+}
+
+impl Listener for TouchedState {
+    fn evm_event(&mut self, event: EvmEvent) {
+        match event {
+            EvmEvent::TransactCall {
+                caller, address, ..
+            }
+            | EvmEvent::TransactCreate {
+                caller, address, ..
+            }
+            | EvmEvent::TransactCreate2 {
+                caller, address, ..
+            } => {
+                self.transaction = Some((caller, address));
+                self.touch(caller);
+                self.touch(address);
+            }
+            EvmEvent::Create {
+                caller, address, ..
+            } => {
+                self.touch(caller);
+                self.touch(address);
+            }
+            EvmEvent::Call {
+                code_address,
+                context,
+                ..
+            }
+            | EvmEvent::PrecompileSubcall {
+                code_address,
+                context,
+                ..
+            } => {
+                self.touch(code_address);
+                self.touch(context.address);
+                self.touch(context.caller);
+            }
+            EvmEvent::Suicide {
+                address, target, ..
+            } => {
+                self.touch(address);
+                self.touch(target);
+            }
+            EvmEvent::Exit { .. } => {}
+        }
+    }
+
+    fn runtime_event(&mut self, event: RuntimeEvent) {
+        match event {
+            RuntimeEvent::Step { opcode, stack, .. } => {
+                if matches!(opcode.0, BALANCE | EXTCODESIZE | EXTCODECOPY | EXTCODEHASH) {
+                    if let Ok(word) = stack.peek(0) {
+                        self.touch(H160::from(word));
+                    }
+                }
+            }
+            RuntimeEvent::SLoad { address, index, .. }
+            | RuntimeEvent::SStore { address, index, .. } => {
+                self.accounts.entry(address).or_default().insert(index);
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct TestState;
+
+    impl EvmState for TestState {
+        fn account(_: H160) -> (U256, U256) {
+            Default::default()
+        }
+
+        fn code(_: H160) -> Vec<u8> {
+            Vec::new()
+        }
+
+        fn storage(_: H160, _: H256) -> H256 {
+            H256::zero()
+        }
+
+        fn author() -> H160 {
+            H160::zero()
+        }
+
+        fn is_precompile(address: H160) -> bool {
+            address == H160::from_low_u64_be(1)
+        }
+    }
+
+    #[test]
+    fn access_list_leaves_out_warm_accounts_without_storage() {
+        let (from, to, callee) = (
+            H160::repeat_byte(0xaa),
+            H160::repeat_byte(0xbb),
+            H160::repeat_byte(0xcc),
+        );
+        let precompile = H160::from_low_u64_be(1);
+        let slot = H256::repeat_byte(1);
+
+        let mut touched = TouchedState {
+            transaction: Some((from, to)),
+            ..Default::default()
+        };
+        for address in [from, callee, precompile] {
+            touched.touch(address);
+        }
+        touched.accounts.entry(to).or_default().insert(slot);
+
+        assert_eq!(
+            touched.access_list::<TestState>(),
+            vec![(to, vec![slot]), (callee, Vec::new())]
+        );
+    }
+}
@@ -0,0 +1,323 @@
+//! Struct logger, logging every executed opcode like the default tracer of geth.
+
+use crate::listener::{gas_change, transaction_gas_used, Listener};
+use crate::{StructLog, StructLoggerConfig, StructLogs};
+use alloc::{collections::BTreeMap, format};
+use evm::tracing::Event as EvmEvent;
+use evm::{Capture, ExitReason};
+use evm_gasometer::tracing::Event as GasometerEvent;
+use evm_runtime::tracing::Event as RuntimeEvent;
+use sp_core::{H160, H256, U256};
+
+pub(crate) struct StructLogger {
+    config: StructLoggerConfig,
+    logs: StructLogs,
+    depth: u32,
+    /// Gas limit of the transaction, the limit of its outermost gasometer.
+    gas_limit: u64,
+    /// Gas left and refunded by the outermost gasometer.
+    gas_left: u64,
+    refunded: i64,
+    /// Gas left by the last gasometer event, for opcodes failing before any.
+    last_gas_left: u64,
+    /// Whether the last logged opcode has not been charged yet.
+    uncharged: bool,
+    storage: BTreeMap<H160, BTreeMap<H256, H256>>,
+}
+
+impl StructLogger {
+    pub fn new(config: StructLoggerConfig) -> Self {
+        Self {
+            config,
+            logs: StructLogs::default(),
+            depth: 0,
+            gas_limit: 0,
+            gas_left: 0,
+            refunded: 0,
+            last_gas_left: 0,
+            uncharged: false,
+            storage: BTreeMap::new(),
+        }
+    }
+
+    pub fn finish(mut self) -> StructLogs {
+        self.logs.gas = transaction_gas_used(self.gas_limit, self.gas_left, self.refunded);
+        self.logs
+    }
+
+    fn start_transaction(&mut self, gas_limit: U256) {
+        self.gas_limit = gas_limit.low_u64();
+        self.gas_left = self.gas_limit;
+    }
+
+    fn record_storage(&mut self, address: H160, index: H256, value: H256) {
+        let storage = self.storage.entry(address).or_default();
+        storage.insert(index, value);
+        if self.config.disable_storage {
+            return;
+        }
+        if let Some(log) = self.logs.struct_logs.last_mut() {
+            log.storage = Some(storage.iter().map(|(k, v)| (*k, *v)).collect());
+        }
+    }
+}
+
+impl Listener for StructLogger {
+    fn evm_event(&mut self, event: EvmEvent) {
+        match event {
+            EvmEvent::TransactCall { gas_limit, .. }
+            | EvmEvent::TransactCreate { gas_limit, .. }
+            | EvmEvent::TransactCreate2 { gas_limit, .. } => {
+                self.start_transaction(U256::from(gas_limit))
+            }
+            EvmEvent::Call { .. }
+            | EvmEvent::PrecompileSubcall { .. }
+            | EvmEvent::Create { .. } => self.depth += 1,
+            EvmEvent::Exit {
+                reason,
+                return_value,
+            } => {
+                self.depth = self.depth.saturating_sub(1);
+                if self.depth == 0 {
+                    self.logs.failed = !matches!(reason, ExitReason::Succeed(_));
+                    self.logs.return_value = return_value.to_vec();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn runtime_event(&mut self, event: RuntimeEvent) {
+        match event {
+            RuntimeEvent::Step {
+                opcode,
+                position,
+                stack,
+                memory,
+                ..
+            } => {
+                let pc = position.as_ref().map(|pc| *pc as u64).unwrap_or_default();
+                self.logs.struct_logs.push(StructLog {
+                    pc,
+                    op: opcode.0,
+                    gas: self.last_gas_left,
+                    gas_cost: 0,
+                    depth: self.depth,
+                    stack: (!self.config.disable_stack).then(|| stack.data().clone()),
+                    memory: self.config.enable_memory.then(|| memory.data().clone()),
+                    storage: None,
+                    error: None,
+                });
+                self.uncharged = true;
+            }
+            RuntimeEvent::StepResult {
+                result: Err(Capture::Exit(ExitReason::Error(error))),
+                ..
+            } => {
+                if let Some(log) = self.logs.struct_logs.last_mut() {
+                    log.error = Some(format!("{:?}", error).into_bytes());
+                }
+            }
+            RuntimeEvent::SLoad {
+                address,
+                index,
+                value,
+            }
+            | RuntimeEvent::SStore {
+                address,
+                index,
+                value,
+            } => self.record_storage(address, index, value),
+            _ => {}
+        }
+    }
+
+    fn gasometer_event(&mut self, event: GasometerEvent) {
+        let Some(change) = gas_change(&event) else {
+            return;
+        };
+        if change.limit == self.gas_limit {
+            self.gas_left = change.after;
+            self.refunded = change.refunded;
+        }
+        if self.uncharged {
+            self.uncharged = false;
+            if let Some(log) = self.logs.struct_logs.last_mut() {
+                log.gas = change.before;
+                log.gas_cost = change.before.saturating_sub(change.after);
+            }
+        }
+        self.last_gas_left = change.after;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::listener::listen;
+    use alloc::{vec, vec::Vec};
+    use evm::backend::{MemoryAccount, MemoryBackend, MemoryVicinity};
+    use evm::executor::stack::{MemoryStackState, StackExecutor, StackSubstateMetadata};
+    use evm::Config;
+
+    const CALLER: H160 = H160::repeat_byte(0xaa);
+    const CONTRACT: H160 = H160::repeat_byte(0xbb);
+    const GAS_LIMIT: u64 = 100_000;
+
+    /// `PUSH1 0x2a PUSH1 0 SSTORE PUSH1 0 SLOAD PUSH1 0 MSTORE PUSH1 0x20 PUSH1 0 RETURN`, which
+    /// stores 42 in slot 0, loads it back and returns it.
+    const STORE_AND_RETURN: [u8; 16] = [
+        0x60, 0x2a, 0x60, 0x00, 0x55, 0x60, 0x00, 0x54, 0x60, 0x00, 0x52, 0x60, 0x20, 0x60, 0x00,
+        0xf3,
+    ];
+
+    fn word(value: u64) -> H256 {
+        H256::from_low_u64_be(value)
+    }
+
+    /// Calls a contract whose code is `code` with the struct logger.
+    fn trace(code: &[u8], config: StructLoggerConfig) -> StructLogs {
+        let vicinity = MemoryVicinity {
+            gas_price: U256::zero(),
+            origin: CALLER,
+            chain_id: U256::one(),
+            block_hashes: Vec::new(),
+            block_number: U256::zero(),
+            block_coinbase: H160::zero(),
+            block_timestamp: U256::zero(),
+            block_difficulty: U256::zero(),
+            block_randomness: None,
+            block_gas_limit: U256::from(GAS_LIMIT),
+            block_base_fee_per_gas: U256::zero(),
+        };
+        let mut accounts = BTreeMap::new();
+        accounts.insert(
+            CONTRACT,
+            MemoryAccount {
+                nonce: U256::one(),
+                balance: U256::zero(),
+                storage: BTreeMap::new(),
+                code: code.to_vec(),
+            },
+        );
+        let backend = MemoryBackend::new(&vicinity, accounts);
+        let evm_config = Config::shanghai();
+        let metadata = StackSubstateMetadata::new(GAS_LIMIT, &evm_config);
+        let state = MemoryStackState::new(metadata, &backend);
+        let mut executor = StackExecutor::new_with_precompiles(state, &evm_config, &());
+
+        let (logger, _) = listen(StructLogger::new(config), || {
+            executor.transact_call(
+                CALLER,
+                CONTRACT,
+                U256::zero(),
+                Vec::new(),
+                GAS_LIMIT,
+                Vec::new(),
+            )
+        });
+        logger.finish()
+    }
+
+    #[test]
+    fn logs_every_opcode() {
+        let logs = trace(&STORE_AND_RETURN, StructLoggerConfig::default());
+
+        assert!(!logs.failed);
+        assert_eq!(logs.return_value, word(42).as_bytes().to_vec());
+        assert_eq!(
+            logs.struct_logs
+                .iter()
+                .map(|log| (log.pc, log.op))
+                .collect::<Vec<_>>(),
+            vec![
+                (0, 0x60),
+                (2, 0x60),
+                (4, 0x55),
+                (5, 0x60),
+                (7, 0x54),
+                (8, 0x60),
+                (10, 0x52),
+                (11, 0x60),
+                (13, 0x60),
+                (15, 0xf3),
+            ]
+        );
+        assert!(logs.struct_logs.iter().all(|log| log.depth == 1));
+        assert!(logs.struct_logs.iter().all(|log| log.error.is_none()));
+    }
+
+    #[test]
+    fn logs_the_gas_of_every_opcode() {
+        let logs = trace(&STORE_AND_RETURN, StructLoggerConfig::default());
+
+        let first = &logs.struct_logs[0];
+        assert_eq!(first.gas_cost, 3);
+        for pair in logs.struct_logs.windows(2) {
+            assert_eq!(pair[1].gas, pair[0].gas - pair[0].gas_cost);
+        }
+        // The intrinsic gas is charged before the first opcode.
+        assert_eq!(first.gas, GAS_LIMIT - 21_000);
+        assert!(logs.gas > 21_000);
+    }
+
+    #[test]
+    fn logs_the_stack_memory_and_storage() {
+        let logs = trace(
+            &STORE_AND_RETURN,
+            StructLoggerConfig {
+                enable_memory: true,
+                ..Default::default()
+            },
+        );
+        let log = |pc| {
+            logs.struct_logs
+                .iter()
+                .find(|log| log.pc == pc)
+                .expect("the opcode is logged")
+        };
+
+        // The stack before `SSTORE`, the top last.
+        assert_eq!(log(4).stack, Some(vec![word(42), word(0)]));
+        assert_eq!(log(0).stack, Some(Vec::new()));
+
+        // The memory before `MSTORE`, and before `RETURN`.
+        assert_eq!(log(10).memory, Some(Vec::new()));
+        assert_eq!(log(15).memory, Some(word(42).as_bytes().to_vec()));
+
+        // The storage accessed so far, logged by `SSTORE` and `SLOAD`.
+        assert_eq!(log(4).storage, Some(vec![(word(0), word(42))]));
+        assert_eq!(log(7).storage, Some(vec![(word(0), word(42))]));
+        assert_eq!(log(5).storage, None);
+    }
+
+    #[test]
+    fn leaves_out_what_the_config_disables() {
+        let logs = trace(
+            &STORE_AND_RETURN,
+            StructLoggerConfig {
+                disable_storage: true,
+                disable_stack: true,
+                enable_memory: false,
+            },
+        );
+
+        assert!(logs
+            .struct_logs
+            .iter()
+            .all(|log| log.stack.is_none() && log.memory.is_none() && log.storage.is_none()));
+    }
+
+    #[test]
+    fn logs_the_error_of_a_failing_opcode() {
+        // `ADD` on an empty stack.
+        let logs = trace(&[0x01], StructLoggerConfig::default());
+
+        assert!(logs.failed);
+        assert_eq!(logs.struct_logs.len(), 1);
+        assert_eq!(logs.struct_logs[0].op, 0x01);
+        assert_eq!(logs.struct_logs[0].error, Some(b"StackUnderflow".to_vec()));
+        // A failed call uses all its gas.
+        assert_eq!(logs.gas, GAS_LIMIT);
+    }
+}
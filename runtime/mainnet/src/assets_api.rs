@@ -21,6 +21,7 @@
 use polkadot_sdk::*;
 
 use codec::Codec;
+use pallet_transaction_payment::{FeeDetails, RuntimeDispatchInfo};
 use sp_std::vec::Vec;
 
 sp_api::decl_runtime_apis! {
@@ -33,4 +34,28 @@ sp_api::decl_runtime_apis! {
         /// Returns the list of `AssetId`s and corresponding balance that an `AccountId` has.
         fn account_balances(account: AccountId) -> Vec<(AssetId, AssetBalance)>;
     }
+
+    pub trait AssetFeePaymentApi<Balance, AssetId>
+    where
+        Balance: Codec,
+        AssetId: Codec,
+    {
+        /// Returns `TransactionPaymentApi::query_info` of `uxt`, with the partial fee in the
+        /// amount of `asset` swapped for it when the fee is paid with `asset`. Returns `None`
+        /// if the native/`asset` pool does not exist or lacks liquidity.
+        fn query_info_in_asset(
+            uxt: Block::Extrinsic,
+            len: u32,
+            asset: AssetId,
+        ) -> Option<RuntimeDispatchInfo<Balance>>;
+
+        /// Returns `TransactionPaymentApi::query_fee_details` of `uxt`, in the amount of `asset`
+        /// swapped for the fee when it is paid with `asset`. Returns `None` if the
+        /// native/`asset` pool does not exist or lacks liquidity.
+        fn query_fee_details_in_asset(
+            uxt: Block::Extrinsic,
+            len: u32,
+            asset: AssetId,
+        ) -> Option<FeeDetails<Balance>>;
+    }
 }
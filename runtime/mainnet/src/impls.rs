@@ -19,13 +19,23 @@
 
 use polkadot_sdk::*;
 
-use frame_support::traits::{
-    fungibles::{Balanced, Credit},
-    Currency, OnUnbalanced,
+use frame_support::{
+    traits::{
+        fungibles::{Balanced, Credit},
+        Currency, FindAuthor, Get, Imbalance, OnRuntimeUpgrade, OnUnbalanced,
+    },
+    weights::Weight,
 };
 use pallet_asset_tx_payment::HandleCredit;
-
-use crate::{AccountId, Assets, Authorship, Balances, NegativeImbalance, Runtime};
+use pallet_evm::{EVMCurrencyAdapter, FeeCalculator, OnChargeEVMTransaction};
+use pallet_transaction_payment::{FeeDetails, InclusionFee, RuntimeDispatchInfo};
+use sp_core::{H160, U256};
+use sp_runtime::{helpers_128bit::multiply_by_rational_with_rounding, Rounding};
+
+use crate::{
+    AccountId, AssetConversion, Assets, Authorship, Babe, Balance, Balances, BaseFee, DynamicFee,
+    FindAuthorTruncated, NativeOrWithId, NegativeImbalance, Runtime, System, Treasury,
+};
 
 pub struct Author;
 impl OnUnbalanced<NegativeImbalance> for Author {
@@ -48,6 +58,294 @@ impl HandleCredit<AccountId, Assets> for CreditToBlockAuthor {
     }
 }
 
+/// Converts the fees quoted by `pallet_transaction_payment` into the amount of an asset that
+/// `ChargeAssetTxPayment` swaps for them through the native/asset pool.
+pub struct AssetFeeQuote;
+impl AssetFeeQuote {
+    /// The amount of `asset` swapped for `native_fee`, or `None` if the native/`asset` pool
+    /// does not exist or lacks liquidity.
+    pub fn fee(native_fee: Balance, asset: u32) -> Option<Balance> {
+        if native_fee == 0 {
+            return Some(0);
+        }
+        AssetConversion::quote_price_tokens_for_exact_tokens(
+            NativeOrWithId::WithId(asset),
+            NativeOrWithId::Native,
+            native_fee,
+            true,
+        )
+    }
+
+    /// `info`, with its partial fee in `asset`.
+    pub fn dispatch_info(
+        info: RuntimeDispatchInfo<Balance>,
+        asset: u32,
+    ) -> Option<RuntimeDispatchInfo<Balance>> {
+        let partial_fee = Self::fee(info.partial_fee, asset)?;
+        Some(RuntimeDispatchInfo {
+            partial_fee,
+            ..info
+        })
+    }
+
+    /// `details`, in `asset`. The final fee is swapped at once, so every part gets its share
+    /// of the amount swapped for the final fee, the adjusted weight fee getting the rounding
+    /// remainder.
+    pub fn fee_details(details: FeeDetails<Balance>, asset: u32) -> Option<FeeDetails<Balance>> {
+        let native_fee = details.final_fee();
+        let asset_fee = Self::fee(native_fee, asset)?;
+        let Some(inclusion_fee) = details.inclusion_fee else {
+            return Some(FeeDetails {
+                inclusion_fee: None,
+                tip: asset_fee,
+            });
+        };
+
+        let share = |part: Balance| {
+            multiply_by_rational_with_rounding(part, asset_fee, native_fee, Rounding::Down)
+                .unwrap_or_default()
+        };
+        let base_fee = share(inclusion_fee.base_fee);
+        let len_fee = share(inclusion_fee.len_fee);
+        let tip = share(details.tip);
+        Some(FeeDetails {
+            inclusion_fee: Some(InclusionFee {
+                base_fee,
+                len_fee,
+                adjusted_weight_fee: asset_fee.saturating_sub(base_fee + len_fee + tip),
+            }),
+            tip,
+        })
+    }
+}
+
+/// Transfers the imbalance to the block author found by `pallet_evm`, that is the author of
+/// the BABE pre-digest. Burns the imbalance if there is no author.
+pub struct EVMAuthor;
+impl OnUnbalanced<NegativeImbalance> for EVMAuthor {
+    fn on_nonzero_unbalanced(amount: NegativeImbalance) {
+        let digest = System::digest();
+        let pre_runtime_digests = digest.logs.iter().filter_map(|d| d.as_pre_runtime());
+        if let Some(author) = FindAuthorTruncated::<Babe>::find_author(pre_runtime_digests) {
+            Balances::resolve_creating(&author.into(), amount);
+        }
+    }
+}
+
+/// Splits EVM fees like `DealWithFees` splits native ones: 80% to treasury, 20% to author.
+pub struct DealWithEVMFees;
+impl OnUnbalanced<NegativeImbalance> for DealWithEVMFees {
+    fn on_nonzero_unbalanced(amount: NegativeImbalance) {
+        let (to_treasury, to_author) = amount.ration(80, 20);
+        Treasury::on_unbalanced(to_treasury);
+        EVMAuthor::on_unbalanced(to_author);
+    }
+}
+
+type EVMFeeAdapter = EVMCurrencyAdapter<Balances, DealWithEVMFees>;
+
+/// Charges EVM transactions like `pallet_evm::EVMCurrencyAdapter`, but hands the priority fee
+/// to [`DealWithEVMFees`] as well as the base fee, where the adapter alone would pay the whole
+/// priority fee to the block author.
+pub struct EVMFeeHandler;
+impl OnChargeEVMTransaction<Runtime> for EVMFeeHandler {
+    type LiquidityInfo = Option<NegativeImbalance>;
+
+    fn withdraw_fee(
+        who: &H160,
+        fee: U256,
+    ) -> Result<Self::LiquidityInfo, pallet_evm::Error<Runtime>> {
+        <EVMFeeAdapter as OnChargeEVMTransaction<Runtime>>::withdraw_fee(who, fee)
+    }
+
+    fn correct_and_deposit_fee(
+        who: &H160,
+        corrected_fee: U256,
+        base_fee: U256,
+        already_withdrawn: Self::LiquidityInfo,
+    ) -> Self::LiquidityInfo {
+        // Hands the base fee to `DealWithEVMFees` and returns the priority fee.
+        <EVMFeeAdapter as OnChargeEVMTransaction<Runtime>>::correct_and_deposit_fee(
+            who,
+            corrected_fee,
+            base_fee,
+            already_withdrawn,
+        )
+    }
+
+    fn pay_priority_fee(tip: Self::LiquidityInfo) {
+        if let Some(tip) = tip {
+            DealWithEVMFees::on_unbalanced(tip);
+        }
+    }
+}
+
+/// The base fee of EVM transactions: the EIP-1559 base fee of `BaseFee`, floored by the minimum
+/// gas price that block authors move towards their target gas price through `DynamicFee`.
+pub struct EVMMinGasPrice;
+impl FeeCalculator for EVMMinGasPrice {
+    fn min_gas_price() -> (U256, Weight) {
+        let (base_fee, base_fee_weight) = <BaseFee as FeeCalculator>::min_gas_price();
+        let (floor, floor_weight) = <DynamicFee as FeeCalculator>::min_gas_price();
+        (
+            base_fee.max(floor),
+            base_fee_weight.saturating_add(floor_weight),
+        )
+    }
+}
+
+/// Starts the minimum gas price of `DynamicFee` at the current base fee on chains created
+/// without the pallet, where it would otherwise start at zero.
+pub struct InitializeMinGasPrice;
+impl OnRuntimeUpgrade for InitializeMinGasPrice {
+    fn on_runtime_upgrade() -> Weight {
+        let db_weight = <Runtime as frame_system::Config>::DbWeight::get();
+        if pallet_dynamic_fee::MinGasPrice::<Runtime>::exists() {
+            return db_weight.reads(1);
+        }
+        pallet_dynamic_fee::MinGasPrice::<Runtime>::put(
+            pallet_base_fee::BaseFeePerGas::<Runtime>::get(),
+        );
+        db_weight.reads_writes(2, 1)
+    }
+}
+
+#[cfg(test)]
+mod min_gas_price_tests {
+    use super::*;
+    use sp_runtime::BuildStorage;
+
+    fn new_test_ext() -> sp_io::TestExternalities {
+        frame_system::GenesisConfig::<Runtime>::default()
+            .build_storage()
+            .unwrap()
+            .into()
+    }
+
+    #[test]
+    fn min_gas_price_is_the_base_fee_floored_by_the_dynamic_fee() {
+        new_test_ext().execute_with(|| {
+            let base_fee = pallet_base_fee::BaseFeePerGas::<Runtime>::get();
+
+            pallet_dynamic_fee::MinGasPrice::<Runtime>::put(base_fee / 2);
+            assert_eq!(EVMMinGasPrice::min_gas_price().0, base_fee);
+
+            pallet_dynamic_fee::MinGasPrice::<Runtime>::put(base_fee * 2);
+            assert_eq!(EVMMinGasPrice::min_gas_price().0, base_fee * 2);
+        });
+    }
+
+    #[test]
+    fn migration_starts_the_min_gas_price_at_the_base_fee() {
+        new_test_ext().execute_with(|| {
+            pallet_dynamic_fee::MinGasPrice::<Runtime>::kill();
+            InitializeMinGasPrice::on_runtime_upgrade();
+            assert_eq!(
+                pallet_dynamic_fee::MinGasPrice::<Runtime>::get(),
+                pallet_base_fee::BaseFeePerGas::<Runtime>::get()
+            );
+
+            pallet_dynamic_fee::MinGasPrice::<Runtime>::put(U256::from(7));
+            InitializeMinGasPrice::on_runtime_upgrade();
+            assert_eq!(
+                pallet_dynamic_fee::MinGasPrice::<Runtime>::get(),
+                U256::from(7)
+            );
+        });
+    }
+}
+
+#[cfg(test)]
+mod evm_fee_tests {
+    use super::*;
+    use crate::{constants::currency::*, Balance, DealWithFees};
+    use codec::Encode;
+    use sp_consensus_babe::{
+        digests::{PreDigest, SecondaryPlainPreDigest},
+        BABE_ENGINE_ID,
+    };
+    use sp_runtime::{BuildStorage, Digest, DigestItem};
+
+    const AUTHOR: H160 = H160::repeat_byte(0xaa);
+    const SENDER: H160 = H160::repeat_byte(0xbb);
+
+    fn new_test_ext() -> sp_io::TestExternalities {
+        let mut ext: sp_io::TestExternalities = frame_system::GenesisConfig::<Runtime>::default()
+            .build_storage()
+            .unwrap()
+            .into();
+        ext.execute_with(|| {
+            pallet_session::Validators::<Runtime>::put(vec![AccountId::from(AUTHOR)]);
+            let pre_digest = PreDigest::SecondaryPlain(SecondaryPlainPreDigest {
+                authority_index: 0,
+                slot: 1.into(),
+            });
+            let digest = Digest {
+                logs: vec![DigestItem::PreRuntime(BABE_ENGINE_ID, pre_digest.encode())],
+            };
+            System::initialize(&1, &Default::default(), &digest);
+        });
+        ext
+    }
+
+    fn treasury_and_author_balances() -> (Balance, Balance) {
+        (
+            Balances::free_balance(Treasury::account_id()),
+            Balances::free_balance(AccountId::from(AUTHOR)),
+        )
+    }
+
+    fn charge_evm_transaction(withdrawn_fee: Balance, corrected_fee: Balance, base_fee: Balance) {
+        let withdrawn = EVMFeeHandler::withdraw_fee(&SENDER, withdrawn_fee.into()).unwrap();
+        let tip = EVMFeeHandler::correct_and_deposit_fee(
+            &SENDER,
+            corrected_fee.into(),
+            base_fee.into(),
+            withdrawn,
+        );
+        EVMFeeHandler::pay_priority_fee(tip);
+    }
+
+    #[test]
+    fn evm_fees_are_split_like_native_fees() {
+        let (fee, tip) = (10 * DOLLARS, DOLLARS);
+
+        let native = new_test_ext().execute_with(|| {
+            DealWithFees::on_unbalanceds::<Balance>(
+                vec![Balances::issue(fee), Balances::issue(tip)].into_iter(),
+            );
+            treasury_and_author_balances()
+        });
+        let evm = new_test_ext().execute_with(|| {
+            let _ = Balances::deposit_creating(&SENDER.into(), 100 * DOLLARS);
+            charge_evm_transaction(fee + tip, fee + tip, fee);
+
+            assert_eq!(
+                Balances::free_balance(AccountId::from(SENDER)),
+                100 * DOLLARS - fee - tip
+            );
+            treasury_and_author_balances()
+        });
+
+        assert_eq!(native, evm);
+        assert_eq!(evm, (880 * CENTS, 220 * CENTS));
+    }
+
+    #[test]
+    fn unused_evm_fees_are_refunded() {
+        new_test_ext().execute_with(|| {
+            let _ = Balances::deposit_creating(&SENDER.into(), 100 * DOLLARS);
+            charge_evm_transaction(20 * DOLLARS, 10 * DOLLARS, 10 * DOLLARS);
+
+            assert_eq!(
+                Balances::free_balance(AccountId::from(SENDER)),
+                90 * DOLLARS
+            );
+            assert_eq!(treasury_and_author_balances(), (8 * DOLLARS, 2 * DOLLARS));
+        });
+    }
+}
+
 #[cfg(test)]
 mod multiplier_tests {
     use frame_support::{
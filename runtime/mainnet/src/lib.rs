@@ -104,7 +104,7 @@ use pallet_ethereum::{Call::transact, Transaction as EthereumTransaction};
 use pallet_evm::{EnsureAccountId20, IdentityAddressMapping};
 mod precompiles;
 use frame_support::traits::FindAuthor;
-use precompiles::FrontierPrecompiles;
+use precompiles::{FrontierPrecompiles, RegistryPrecompileAddresses};
 use sp_runtime::{
     create_runtime_str,
     curve::PiecewiseLinear,
@@ -138,9 +138,7 @@ pub use sp_runtime::BuildStorage;
 // use sp_core::H160;
 /// Implementations of some helper traits passed into runtime modules as associated types.
 pub mod impls;
-#[cfg(not(feature = "runtime-benchmarks"))]
-// use impls::AllianceIdentityVerifier;
-use impls::Author;
+use impls::{Author, EVMFeeHandler, EVMMinGasPrice};
 
 /// Constant values used within the runtime.
 pub mod constants;
@@ -200,9 +198,15 @@ pub type Executive = frame_executive::Executive<
     frame_system::ChainContext<Runtime>,
     Runtime,
     AllPalletsWithSystem,
-    (),
+    Migrations,
 >;
 
+/// All migrations of the runtime, aside from the ones declared in the pallets.
+type Migrations = (
+    pallet_deployment_allowlist::migrations::SeedOpenMode<Runtime>,
+    impls::InitializeMinGasPrice,
+);
+
 pub mod opaque {
     use super::*;
 
@@ -233,7 +237,7 @@ pub type SignedExtra = (
     frame_system::CheckEra<Runtime>,
     frame_system::CheckNonce<Runtime>,
     frame_system::CheckWeight<Runtime>,
-    pallet_transaction_payment::ChargeTransactionPayment<Runtime>,
+    pallet_asset_conversion_tx_payment::ChargeAssetTxPayment<Runtime>,
     frame_metadata_hash_extension::CheckMetadataHash<Runtime>,
 );
 
@@ -252,10 +256,10 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
     // and set impl_version to 0. If only runtime
     // implementation changes and behavior does not, then leave spec_version as
     // is and increment impl_version.
-    spec_version: 272,
+    spec_version: 312,
     impl_version: 0,
     apis: RUNTIME_API_VERSIONS,
-    transaction_version: 2,
+    transaction_version: 3,
     state_version: 1,
 };
 
@@ -372,7 +376,7 @@ impl frame_system::Config for Runtime {
     type SystemWeightInfo = frame_system::weights::SubstrateWeight<Runtime>;
     type SS58Prefix = ConstU16<42>;
     type MaxConsumers = ConstU32<16>;
-    type MultiBlockMigrator = ();
+    type MultiBlockMigrator = MultiBlockMigrations;
 }
 
 impl pallet_utility::Config for Runtime {
@@ -471,6 +475,38 @@ impl InstanceFilter<RuntimeCall> for ProxyType {
     }
 }
 
+/// The calls the dispatch precompile accepts, in the fashion of `ProxyType`'s
+/// `InstanceFilter`. Calls wrapping another call are only accepted if the inner call is.
+///
+/// `pallet_balances` is left out: `BaseCallFilter` rejects its calls, native transfers going
+/// through the ERC-20 precompile of the native token.
+pub struct DispatchPrecompileFilter;
+impl Contains<RuntimeCall> for DispatchPrecompileFilter {
+    fn contains(c: &RuntimeCall) -> bool {
+        match c {
+            RuntimeCall::Proxy(pallet_proxy::Call::proxy { call, .. })
+            | RuntimeCall::Proxy(pallet_proxy::Call::proxy_announced { call, .. })
+            | RuntimeCall::Multisig(pallet_multisig::Call::as_multi { call, .. })
+            | RuntimeCall::Multisig(pallet_multisig::Call::as_multi_threshold_1 { call, .. }) => {
+                Self::contains(call)
+            }
+            _ => matches!(
+                c,
+                RuntimeCall::Identity(..)
+                    | RuntimeCall::Proxy(..)
+                    | RuntimeCall::Multisig(..)
+                    | RuntimeCall::Assets(..)
+                    | RuntimeCall::Nfts(..)
+                    | RuntimeCall::Staking(..)
+                    | RuntimeCall::NominationPools(..)
+                    | RuntimeCall::Preimage(..)
+                    | RuntimeCall::Referenda(..)
+                    | RuntimeCall::ConvictionVoting(..)
+            ),
+        }
+    }
+}
+
 impl pallet_proxy::Config for Runtime {
     type RuntimeEvent = RuntimeEvent;
     type RuntimeCall = RuntimeCall;
@@ -565,10 +601,13 @@ parameter_types! {
     pub PrecompilesValue: FrontierPrecompiles<Runtime> = FrontierPrecompiles::<_>::new();
     pub WeightPerGas: Weight = Weight::from_parts(weight_per_gas(BLOCK_GAS_LIMIT, NORMAL_DISPATCH_RATIO, MILLISECS_PER_BLOCK), 0);
     pub SuicideQuickClearLimit: u32 = 0;
+    /// Reserved from the requester of a randomness request: a `Requests` and a
+    /// `RequestDeposits` entry of the randomness precompile.
+    pub const RandomnessRequestDeposit: Balance = deposit(2, 125);
 }
 
 impl pallet_evm::Config for Runtime {
-    type FeeCalculator = BaseFee;
+    type FeeCalculator = EVMMinGasPrice;
     type GasWeightMapping = pallet_evm::FixedGasWeightMapping<Self>;
     type WeightPerGas = WeightPerGas;
     type BlockHashMapping = pallet_ethereum::EthereumBlockHashMapping<Self>;
@@ -581,8 +620,9 @@ impl pallet_evm::Config for Runtime {
     type PrecompilesValue = PrecompilesValue;
     type ChainId = EVMChainId;
     type BlockGasLimit = BlockGasLimit;
-    type Runner = pallet_evm::runner::stack::Runner<Self>;
-    type OnChargeTransaction = ();
+    type Runner =
+        pallet_deployment_allowlist::Runner<Self, pallet_evm::runner::stack::Runner<Self>>;
+    type OnChargeTransaction = EVMFeeHandler;
     type OnCreate = ();
     type FindAuthor = FindAuthorTruncated<Babe>;
     type GasLimitPovSizeRatio = GasLimitPovSizeRatio;
@@ -591,6 +631,17 @@ impl pallet_evm::Config for Runtime {
     type WeightInfo = pallet_evm::weights::SubstrateWeight<Self>;
 }
 
+impl pallet_deployment_allowlist::Config for Runtime {
+    type RuntimeEvent = RuntimeEvent;
+    type ManagerOrigin = EnsureRootOrHalfCouncil;
+}
+
+impl pallet_precompile_registry::Config for Runtime {
+    type RuntimeEvent = RuntimeEvent;
+    type PrecompileAddresses = RegistryPrecompileAddresses;
+    type ToggleOrigin = EnsureRootOrHalfCouncil;
+}
+
 impl pallet_preimage::Config for Runtime {
     type WeightInfo = pallet_preimage::weights::SubstrateWeight<Runtime>;
     type RuntimeEvent = RuntimeEvent;
@@ -1480,7 +1531,7 @@ where
             frame_system::CheckEra::<Runtime>::from(era),
             frame_system::CheckNonce::<Runtime>::from(nonce),
             frame_system::CheckWeight::<Runtime>::new(),
-            pallet_transaction_payment::ChargeTransactionPayment::<Runtime>::from(0),
+            pallet_asset_conversion_tx_payment::ChargeAssetTxPayment::<Runtime>::from(0, None),
             frame_metadata_hash_extension::CheckMetadataHash::new(false),
         );
         let raw_payload = SignedPayload::new(call, extra)
@@ -1666,7 +1717,7 @@ impl pallet_assets::Config<Instance1> for Runtime {
     type StringLimit = StringLimit;
     type Freezer = ();
     type Extra = ();
-    type CallbackHandle = ();
+    type CallbackHandle = pallet_evm_precompile_assets_erc20::AssetsErc20Callback<Runtime>;
     type WeightInfo = pallet_assets::weights::SubstrateWeight<Runtime>;
     type RemoveItemsLimit = ConstU32<1000>;
     #[cfg(feature = "runtime-benchmarks")]
@@ -1708,6 +1759,15 @@ parameter_types! {
     pub const Native: NativeOrWithId<u32> = NativeOrWithId::Native;
 }
 
+/// Fees paid in an asset are swapped for the native fee through the native/asset pool. They
+/// are quoted in the asset with `AssetFeePaymentApi`.
+impl pallet_asset_conversion_tx_payment::Config for Runtime {
+    type RuntimeEvent = RuntimeEvent;
+    type Fungibles = Assets;
+    type OnChargeAssetTransaction =
+        pallet_asset_conversion_tx_payment::AssetConversionAdapter<Balances, AssetConversion, Native>;
+}
+
 impl pallet_asset_conversion::Config for Runtime {
     type RuntimeEvent = RuntimeEvent;
     type Balance = u128;
@@ -1922,6 +1982,23 @@ parameter_types! {
     pub MbmServiceWeight: Weight = Perbill::from_percent(80) * RuntimeBlockWeights::get().max_block;
 }
 
+impl pallet_migrations::Config for Runtime {
+    type RuntimeEvent = RuntimeEvent;
+    #[cfg(not(feature = "runtime-benchmarks"))]
+    type Migrations = (
+        pallet_evm_precompile_assets_erc20::InsertAssetsDummyCode<Runtime, Instance1>,
+    );
+    // Benchmarks need mocked migrations to guarantee that they succeed.
+    #[cfg(feature = "runtime-benchmarks")]
+    type Migrations = pallet_migrations::mock_helpers::MockedMigrations;
+    type CursorMaxLen = ConstU32<65_536>;
+    type IdentifierMaxLen = ConstU32<256>;
+    type MigrationStatusHandler = ();
+    type FailedMigrationHandler = frame_support::migrations::FreezeChainOnFailedMigration;
+    type MaxServiceWeight = MbmServiceWeight;
+    type WeightInfo = pallet_migrations::weights::SubstrateWeight<Runtime>;
+}
+
 parameter_types! {
     pub const BrokerPalletId: PalletId = PalletId(*b"py/broke");
 }
@@ -2016,6 +2093,9 @@ mod runtime {
     #[runtime::pallet_index(7)]
     pub type TransactionPayment = pallet_transaction_payment;
 
+    #[runtime::pallet_index(9)]
+    pub type AssetConversionTxPayment = pallet_asset_conversion_tx_payment;
+
     #[runtime::pallet_index(10)]
     pub type ElectionProviderMultiPhase = pallet_election_provider_multi_phase;
 
@@ -2159,6 +2239,9 @@ mod runtime {
     #[runtime::pallet_index(75)]
     pub type Mixnet = pallet_mixnet;
 
+    #[runtime::pallet_index(76)]
+    pub type MultiBlockMigrations = pallet_migrations;
+
     #[runtime::pallet_index(80)]
     pub type Ethereum = pallet_ethereum;
 
@@ -2170,6 +2253,15 @@ mod runtime {
 
     #[runtime::pallet_index(83)]
     pub type BaseFee = pallet_base_fee;
+
+    #[runtime::pallet_index(84)]
+    pub type DynamicFee = pallet_dynamic_fee;
+
+    #[runtime::pallet_index(85)]
+    pub type PrecompileRegistry = pallet_precompile_registry;
+
+    #[runtime::pallet_index(86)]
+    pub type DeploymentAllowlist = pallet_deployment_allowlist;
 }
 
 #[derive(Clone)]
@@ -2577,6 +2669,26 @@ impl_runtime_apis! {
         }
     }
 
+    impl assets_api::AssetFeePaymentApi<Block, Balance, u32> for Runtime {
+        fn query_info_in_asset(
+            uxt: <Block as BlockT>::Extrinsic,
+            len: u32,
+            asset: u32,
+        ) -> Option<RuntimeDispatchInfo<Balance>> {
+            impls::AssetFeeQuote::dispatch_info(TransactionPayment::query_info(uxt, len), asset)
+        }
+
+        fn query_fee_details_in_asset(
+            uxt: <Block as BlockT>::Extrinsic,
+            len: u32,
+            asset: u32,
+        ) -> Option<FeeDetails<Balance>> {
+            impls::AssetFeeQuote::fee_details(
+                TransactionPayment::query_fee_details(uxt, len),
+                asset,
+            )
+        }
+    }
 
     impl pallet_transaction_payment_rpc_runtime_api::TransactionPaymentApi<
         Block,
@@ -3042,6 +3154,122 @@ impl_runtime_apis! {
         }
     }
 
+    #[cfg(feature = "evm-tracing")]
+    impl evm_tracing::DebugRuntimeApi<Block> for Runtime {
+        fn trace_transaction(
+            extrinsics: Vec<<Block as BlockT>::Extrinsic>,
+            header: &<Block as BlockT>::Header,
+            transaction_hash: H256,
+            tracer: evm_tracing::Tracer,
+        ) -> Result<evm_tracing::Trace, sp_runtime::DispatchError> {
+            Executive::initialize_block(header);
+
+            for ext in extrinsics.into_iter() {
+                match &ext.0.function {
+                    RuntimeCall::Ethereum(transact { transaction }) if transaction.hash() == transaction_hash => {
+                        return Ok(evm_tracing::trace::<evm_tracing::PalletEvmState<Runtime>>(tracer, || {
+                            let _ = Executive::apply_extrinsic(ext.clone());
+                        }));
+                    }
+                    _ => {
+                        let _ = Executive::apply_extrinsic(ext);
+                    }
+                }
+            }
+
+            Err(sp_runtime::DispatchError::Other("Ethereum transaction not found in the block"))
+        }
+
+        fn trace_block(
+            extrinsics: Vec<<Block as BlockT>::Extrinsic>,
+            header: &<Block as BlockT>::Header,
+            tracer: evm_tracing::Tracer,
+        ) -> Result<Vec<(H256, evm_tracing::Trace)>, sp_runtime::DispatchError> {
+            Executive::initialize_block(header);
+
+            let mut traces = Vec::new();
+            for ext in extrinsics.into_iter() {
+                match &ext.0.function {
+                    RuntimeCall::Ethereum(transact { transaction }) => {
+                        let trace = evm_tracing::trace::<evm_tracing::PalletEvmState<Runtime>>(tracer, || {
+                            let _ = Executive::apply_extrinsic(ext.clone());
+                        });
+                        traces.push((transaction.hash(), trace));
+                    }
+                    _ => {
+                        let _ = Executive::apply_extrinsic(ext);
+                    }
+                }
+            }
+
+            Ok(traces)
+        }
+
+        fn trace_call(
+            from: H160,
+            to: Option<H160>,
+            data: Vec<u8>,
+            value: U256,
+            gas_limit: U256,
+            max_fee_per_gas: Option<U256>,
+            max_priority_fee_per_gas: Option<U256>,
+            nonce: Option<U256>,
+            access_list: Option<Vec<(H160, Vec<H256>)>>,
+            tracer: evm_tracing::Tracer,
+        ) -> Result<evm_tracing::Trace, sp_runtime::DispatchError> {
+            let gas_limit = if gas_limit > U256::from(u64::MAX) {
+                u64::MAX
+            } else {
+                gas_limit.low_u64()
+            };
+            let access_list = access_list.unwrap_or_default();
+            let config = <Runtime as pallet_evm::Config>::config();
+
+            let error: core::cell::RefCell<Option<sp_runtime::DispatchError>> = Default::default();
+            let trace = evm_tracing::trace::<evm_tracing::PalletEvmState<Runtime>>(tracer, || {
+                let result = match to {
+                    Some(to) => <Runtime as pallet_evm::Config>::Runner::call(
+                        from,
+                        to,
+                        data.clone(),
+                        value,
+                        gas_limit,
+                        max_fee_per_gas,
+                        max_priority_fee_per_gas,
+                        nonce,
+                        access_list.clone(),
+                        false,
+                        true,
+                        None,
+                        None,
+                        config,
+                    ).map(|_| ()),
+                    None => <Runtime as pallet_evm::Config>::Runner::create(
+                        from,
+                        data.clone(),
+                        value,
+                        gas_limit,
+                        max_fee_per_gas,
+                        max_priority_fee_per_gas,
+                        nonce,
+                        access_list.clone(),
+                        false,
+                        true,
+                        None,
+                        None,
+                        config,
+                    ).map(|_| ()),
+                };
+                *error.borrow_mut() = result.err().map(|err| err.error.into());
+            });
+
+            match error.into_inner() {
+                Some(error) => Err(error),
+                None => Ok(trace),
+            }
+        }
+    }
+
     #[cfg(feature = "try-runtime")]
     impl frame_try_runtime::TryRuntime<Block> for Runtime {
         fn on_runtime_upgrade(checks: frame_try_runtime::UpgradeCheckSelect) -> (Weight, Weight) {
@@ -3175,6 +3403,137 @@ mod tests {
             .fold(0, |acc, x| acc.checked_add(*x).unwrap());
     }
 
+    /// Creates asset 1, and a native/asset 1 pool pricing the native token at 4 units of the
+    /// asset.
+    fn create_pool_with_asset(who: AccountId) {
+        use frame_support::assert_ok;
+
+        let _ = Balances::deposit_creating(&who, 10_000 * DOLLARS);
+        assert_ok!(Assets::force_create(
+            RuntimeOrigin::root(),
+            codec::Compact(1),
+            who,
+            true,
+            1
+        ));
+        assert_ok!(Assets::mint(
+            RuntimeOrigin::signed(who),
+            codec::Compact(1),
+            who,
+            10_000 * DOLLARS
+        ));
+        assert_ok!(AssetConversion::create_pool(
+            RuntimeOrigin::signed(who),
+            Box::new(NativeOrWithId::Native),
+            Box::new(NativeOrWithId::WithId(1))
+        ));
+        assert_ok!(AssetConversion::add_liquidity(
+            RuntimeOrigin::signed(who),
+            Box::new(NativeOrWithId::Native),
+            Box::new(NativeOrWithId::WithId(1)),
+            1_000 * DOLLARS,
+            4_000 * DOLLARS,
+            1,
+            1,
+            who
+        ));
+    }
+
+    #[test]
+    fn fee_paid_in_asset_is_the_quoted_fee() {
+        use frame_support::{
+            assert_ok,
+            dispatch::{GetDispatchInfo, Pays, PostDispatchInfo},
+        };
+        use pallet_asset_conversion_tx_payment::ChargeAssetTxPayment;
+        use sp_runtime::traits::SignedExtension;
+
+        sp_io::TestExternalities::default().execute_with(|| {
+            let who = AccountId::from(H160::repeat_byte(0xaa));
+            create_pool_with_asset(who);
+
+            let call = RuntimeCall::System(frame_system::Call::remark { remark: vec![0; 32] });
+            let info = call.get_dispatch_info();
+            let len = 100;
+            let fee = TransactionPayment::compute_fee(len as u32, &info, 0);
+            let quoted = impls::AssetFeeQuote::dispatch_info(
+                RuntimeDispatchInfo {
+                    weight: info.weight,
+                    class: info.class,
+                    partial_fee: fee,
+                },
+                1,
+            )
+            .expect("the pool has liquidity");
+            assert!(quoted.partial_fee > fee);
+
+            let native_before = Balances::free_balance(who);
+            let asset_before = Assets::balance(1, who);
+            let pre = ChargeAssetTxPayment::<Runtime>::from(0, Some(1))
+                .pre_dispatch(&who, &call, &info, len)
+                .expect("the fee is paid in the asset");
+            let post_info = PostDispatchInfo {
+                actual_weight: Some(info.weight),
+                pays_fee: Pays::Yes,
+            };
+            assert_ok!(ChargeAssetTxPayment::<Runtime>::post_dispatch(
+                Some(pre),
+                &info,
+                &post_info,
+                len,
+                &Ok(())
+            ));
+
+            assert_eq!(asset_before - Assets::balance(1, who), quoted.partial_fee);
+            assert_eq!(Balances::free_balance(who), native_before);
+        });
+    }
+
+    #[test]
+    fn fee_details_in_asset_add_up_to_the_quoted_fee() {
+        use frame_support::dispatch::GetDispatchInfo;
+
+        sp_io::TestExternalities::default().execute_with(|| {
+            create_pool_with_asset(AccountId::from(H160::repeat_byte(0xaa)));
+
+            let call = RuntimeCall::System(frame_system::Call::remark { remark: vec![0; 32] });
+            let info = call.get_dispatch_info();
+            let details = TransactionPayment::compute_fee_details(100, &info, 0);
+            let native_fee = details.final_fee();
+            let quoted = impls::AssetFeeQuote::fee_details(details.clone(), 1)
+                .expect("the pool has liquidity");
+
+            assert_eq!(
+                quoted.final_fee(),
+                impls::AssetFeeQuote::fee(native_fee, 1).unwrap()
+            );
+            let native = details.inclusion_fee.unwrap();
+            let in_asset = quoted.inclusion_fee.unwrap();
+            // The pool prices the native token at about 4 units of the asset.
+            assert!(in_asset.base_fee >= 4 * native.base_fee);
+            assert!(in_asset.len_fee >= 4 * native.len_fee);
+            assert_eq!(quoted.tip, 0);
+        });
+    }
+
+    #[test]
+    fn fees_are_not_quoted_in_assets_without_a_pool() {
+        sp_io::TestExternalities::default().execute_with(|| {
+            assert_eq!(impls::AssetFeeQuote::fee(DOLLARS, 1), None);
+            assert_eq!(impls::AssetFeeQuote::fee(0, 1), Some(0));
+            assert_eq!(
+                impls::AssetFeeQuote::fee_details(
+                    FeeDetails {
+                        inclusion_fee: None,
+                        tip: DOLLARS
+                    },
+                    1
+                ),
+                None
+            );
+        });
+    }
+
     #[test]
     fn call_size() {
         let size = core::mem::size_of::<RuntimeCall>();
@@ -1,12 +1,223 @@
-use crate::sp_core::H160;
+use crate::{
+    frame_support::{
+        dispatch::{DispatchClass, GetDispatchInfo, Pays},
+        instances::Instance1,
+        traits::{fungible::NativeOrWithId, Contains, Get, InstanceFilter},
+    },
+    frame_system, pallet_assets, pallet_nfts, pallet_proxy,
+    sp_core::H160,
+    sp_std::vec::Vec,
+    AccountId, DispatchPrecompileFilter, MaxAdditionalFields, OriginCaller, ProxyType,
+    RandomnessRequestDeposit, Runtime, RuntimeCall,
+};
+use codec::DecodeLimit;
 use core::marker::PhantomData;
+use fp_evm::{ExitError, PrecompileFailure};
 use pallet_evm::{
-    IsPrecompileResult, Precompile, PrecompileHandle, PrecompileResult, PrecompileSet,
+    GasWeightMapping, IsPrecompileResult, Precompile, PrecompileHandle, PrecompileResult,
+    PrecompileSet,
+};
+use precompile_utils::{
+    precompile_set::{
+        AddressU64, CallableByContract, PrecompileAt, PrecompileSetBuilder, SubcallWithMaxNesting,
+    },
+    prelude::revert,
 };
 
+use pallet_evm_precompile_asset_conversion::{AddressToAssetKind, AssetConversionRouterPrecompile};
+use pallet_evm_precompile_assets_erc20::{AddressToAssetId, Erc20AssetsPrecompileSet};
+use pallet_evm_precompile_balances_erc20::{Erc20BalancesPrecompile, Erc20Metadata};
+use pallet_evm_precompile_batch::BatchPrecompile;
+use pallet_evm_precompile_blake2::Blake2F;
+use pallet_evm_precompile_bls12381::{
+    Bls12381G1Add, Bls12381G1Msm, Bls12381G2Add, Bls12381G2Msm, Bls12381MapFp2ToG2,
+    Bls12381MapFpToG1, Bls12381Pairing,
+};
+use pallet_evm_precompile_bn128::{Bn128Add, Bn128Mul, Bn128Pairing};
+use pallet_evm_precompile_call_permit::CallPermitPrecompile;
+use pallet_evm_precompile_conviction_voting::ConvictionVotingPrecompile;
+use pallet_evm_precompile_dispatch::{Dispatch, DispatchValidateT};
+use pallet_evm_precompile_identity::IdentityPrecompile;
 use pallet_evm_precompile_modexp::Modexp;
+use pallet_evm_precompile_nfts_erc721::{AddressToCollectionId, Erc721NftsPrecompileSet};
+use pallet_evm_precompile_nomination_pools::NominationPoolsPrecompile;
+use pallet_evm_precompile_p256verify::P256Verify;
+use pallet_evm_precompile_preimage::PreimagePrecompile;
+use pallet_evm_precompile_proxy::{EvmProxyCallFilter, EvmSubCall, ProxyPrecompile};
+use pallet_evm_precompile_randomness::RandomnessPrecompile;
+use pallet_evm_precompile_referenda::{ReferendaPrecompile, TrackOrigins};
 use pallet_evm_precompile_sha3fips::Sha3FIPS256;
 use pallet_evm_precompile_simple::{ECRecover, ECRecoverPublicKey, Identity, Ripemd160, Sha256};
+use pallet_evm_precompile_staking::StakingPrecompile;
+
+/// The first four bytes of the address of every `pallet_assets` ERC-20 precompile.
+/// The asset id is encoded big-endian in the last four bytes.
+pub const ASSET_PRECOMPILE_ADDRESS_PREFIX: &[u8] = &[255u8; 4];
+
+/// The first four bytes of the address of every `pallet_nfts` ERC-721 precompile.
+/// The collection id is encoded big-endian in the last four bytes.
+pub const NFT_COLLECTION_PRECOMPILE_ADDRESS_PREFIX: &[u8] = &[255u8, 255u8, 255u8, 254u8];
+
+type AssetId = <Runtime as pallet_assets::Config<Instance1>>::AssetId;
+type CollectionId = <Runtime as pallet_nfts::Config>::CollectionId;
+
+/// ERC-20 metadata of the native currency.
+pub struct NativeErc20Metadata;
+
+impl Erc20Metadata for NativeErc20Metadata {
+    fn name() -> &'static str {
+        "SCS token"
+    }
+
+    fn symbol() -> &'static str {
+        "SCS"
+    }
+
+    fn decimals() -> u8 {
+        18
+    }
+}
+
+/// Origins of the proposals submitted through the referenda precompile, one per track of
+/// `TracksInfo`.
+pub struct ReferendaTrackOrigins;
+
+impl TrackOrigins<u16, OriginCaller> for ReferendaTrackOrigins {
+    fn origin_of(track_id: u16) -> Option<OriginCaller> {
+        match track_id {
+            0 => Some(frame_system::RawOrigin::Root.into()),
+            _ => None,
+        }
+    }
+}
+
+/// Validates the calls of the dispatch precompile: only calls accepted by `F`, paying fees
+/// and of the normal dispatch class may be dispatched.
+pub struct DispatchFilterValidator<F>(PhantomData<F>);
+
+impl<F: Contains<RuntimeCall>> DispatchValidateT<AccountId, RuntimeCall>
+    for DispatchFilterValidator<F>
+{
+    fn validate_before_dispatch(
+        _origin: &AccountId,
+        call: &RuntimeCall,
+    ) -> Option<PrecompileFailure> {
+        let info = call.get_dispatch_info();
+        if !(info.pays_fee == Pays::Yes && info.class == DispatchClass::Normal) {
+            return Some(PrecompileFailure::Error {
+                exit_status: ExitError::Other("Fee payment or dispatch class not supported".into()),
+            });
+        }
+        if !F::contains(call) {
+            return Some(PrecompileFailure::Error {
+                exit_status: ExitError::Other("Call filtered out".into()),
+            });
+        }
+        None
+    }
+}
+
+/// The dispatch precompile, restricted to the calls of `DispatchPrecompileFilter`.
+pub type DispatchPrecompile<R> = Dispatch<R, DispatchFilterValidator<DispatchPrecompileFilter>>;
+
+/// Maximum number of nested calls to the batch precompile, through its own subcalls.
+const BATCH_SUBCALL_NESTING_LIMIT: u16 = 2;
+
+/// The batch precompile, which may be nested at most `BATCH_SUBCALL_NESTING_LIMIT` times.
+pub type BatchPrecompileSet<R> = PrecompileSetBuilder<
+    R,
+    (
+        PrecompileAt<
+            AddressU64<2055>,
+            BatchPrecompile<R>,
+            (
+                SubcallWithMaxNesting<BATCH_SUBCALL_NESTING_LIMIT>,
+                CallableByContract,
+            ),
+        >,
+    ),
+>;
+
+/// Maximum nesting depth of the calls decoded when filtering proxied calls to the dispatch
+/// precompile.
+const PROXY_DISPATCH_DECODE_DEPTH_LIMIT: u32 = 8;
+
+/// Mirrors the filter `pallet_proxy` puts on the origin of a proxied call, for the calls of the
+/// dispatch precompile: these are dispatched with the plain signed origin of the real account.
+///
+/// A proxy may only add or remove proxies of a type it is a superset of, and only `Any` proxies
+/// may remove every proxy or create and kill pure proxies. Calls dispatching other calls are only
+/// allowed to `Any` proxies, as their inner calls would escape this filter.
+fn is_proxied_dispatch_allowed(proxy_type: &ProxyType, call: &RuntimeCall) -> bool {
+    match call {
+        RuntimeCall::Proxy(
+            pallet_proxy::Call::add_proxy {
+                proxy_type: changed,
+                ..
+            }
+            | pallet_proxy::Call::remove_proxy {
+                proxy_type: changed,
+                ..
+            },
+        ) => proxy_type.is_superset(changed) && proxy_type.filter(call),
+        RuntimeCall::Proxy(
+            pallet_proxy::Call::remove_proxies { .. }
+            | pallet_proxy::Call::create_pure { .. }
+            | pallet_proxy::Call::kill_pure { .. }
+            | pallet_proxy::Call::proxy { .. }
+            | pallet_proxy::Call::proxy_announced { .. },
+        )
+        | RuntimeCall::Multisig(..)
+        | RuntimeCall::Utility(..) => *proxy_type == ProxyType::Any,
+        _ => proxy_type.filter(call),
+    }
+}
+
+/// Mirrors the `InstanceFilter<RuntimeCall>` of `ProxyType` for the calls made through the proxy
+/// precompile: besides `Any`, a proxy may not transfer value and may only call the precompiles
+/// wrapping the pallets its type allows, or the dispatch precompile with a call allowed by
+/// [`is_proxied_dispatch_allowed`].
+impl EvmProxyCallFilter for ProxyType {
+    fn is_evm_proxy_call_allowed(&self, call: &EvmSubCall) -> bool {
+        if *self == ProxyType::Any {
+            return true;
+        }
+        if !call.value.is_zero() {
+            return false;
+        }
+        if call.to == hash(2054) {
+            return RuntimeCall::decode_with_depth_limit(
+                PROXY_DISPATCH_DECODE_DEPTH_LIMIT,
+                &mut &call.call_data[..],
+            )
+            .map_or(false, |inner| is_proxied_dispatch_allowed(self, &inner));
+        }
+        match self {
+            // Staking, nomination pools, preimage, referenda, conviction voting and identity.
+            ProxyType::NonTransfer => (2049..=2053).chain([2058]).any(|a| call.to == hash(a)),
+            ProxyType::Staking => call.to == hash(2049),
+            _ => false,
+        }
+    }
+}
+
+/// The precompiles managed by the precompile registry: every precompile at a fixed address,
+/// aside from the Ethereum precompiles.
+pub struct RegistryPrecompileAddresses;
+
+impl Get<Vec<H160>> for RegistryPrecompileAddresses {
+    fn get() -> Vec<H160> {
+        FrontierPrecompiles::<Runtime>::used_addresses()
+            .into_iter()
+            .filter(|address| !FrontierPrecompiles::<Runtime>::is_ethereum_precompile(*address))
+            .collect()
+    }
+}
+
+/// Gas cost of checking in the precompile registry whether a precompile is active.
+fn registry_read_cost<R: pallet_evm::Config>() -> u64 {
+    R::GasWeightMapping::weight_to_gas(<R as frame_system::Config>::DbWeight::get().reads(1))
+}
 
 pub struct FrontierPrecompiles<R>(PhantomData<R>);
 
@@ -17,45 +228,877 @@ where
     pub fn new() -> Self {
         Self(Default::default())
     }
-    pub fn used_addresses() -> [H160; 7] {
+    pub fn used_addresses() -> [H160; 32] {
         [
             hash(1),
             hash(2),
             hash(3),
             hash(4),
             hash(5),
+            hash(6),
+            hash(7),
+            hash(8),
+            hash(9),
+            hash(11),
+            hash(12),
+            hash(13),
+            hash(14),
+            hash(15),
+            hash(16),
+            hash(17),
+            hash(256),
             hash(1024),
             hash(1025),
+            hash(2048),
+            hash(2049),
+            hash(2050),
+            hash(2051),
+            hash(2052),
+            hash(2053),
+            hash(2054),
+            hash(2055),
+            hash(2056),
+            hash(2057),
+            hash(2058),
+            hash(2059),
+            hash(2060),
         ]
     }
+
+    /// Whether `address` is one of the Ethereum precompiles at 0x01-0x09. They are always
+    /// active, as contracts rely on them being available.
+    pub fn is_ethereum_precompile(address: H160) -> bool {
+        (1..=9).any(|a| address == hash(a))
+    }
+
+    /// Whether the precompile at `address` can be toggled in the precompile registry.
+    fn is_registry_precompile(address: H160) -> bool {
+        Self::used_addresses().contains(&address) && !Self::is_ethereum_precompile(address)
+    }
 }
+impl<R> FrontierPrecompiles<R>
+where
+    Self: PrecompileSet,
+{
+    /// Returns whether `address` is a precompile acting on behalf of its caller, such as
+    /// moving its funds or dispatching calls with its origin.
+    fn is_stateful_precompile(&self, address: H160, gas: u64) -> IsPrecompileResult {
+        if address
+            .as_bytes()
+            .starts_with(ASSET_PRECOMPILE_ADDRESS_PREFIX)
+            || address
+                .as_bytes()
+                .starts_with(NFT_COLLECTION_PRECOMPILE_ADDRESS_PREFIX)
+        {
+            return self.is_precompile(address, gas);
+        }
+        IsPrecompileResult::Answer {
+            is_precompile: (2048..=2060).any(|a| address == hash(a)),
+            extra_cost: 0,
+        }
+    }
+}
+
 impl<R> PrecompileSet for FrontierPrecompiles<R>
 where
-    R: pallet_evm::Config,
+    R: pallet_evm::Config + pallet_precompile_registry::Config,
+    Erc20AssetsPrecompileSet<R, Instance1>: PrecompileSet,
+    Erc721NftsPrecompileSet<R>: PrecompileSet,
+    Erc20BalancesPrecompile<R, NativeErc20Metadata>: Precompile,
+    StakingPrecompile<R>: Precompile,
+    NominationPoolsPrecompile<R>: Precompile,
+    PreimagePrecompile<R>: Precompile,
+    ReferendaPrecompile<R, ReferendaTrackOrigins>: Precompile,
+    ConvictionVotingPrecompile<R>: Precompile,
+    DispatchPrecompile<R>: Precompile,
+    BatchPrecompileSet<R>: PrecompileSet,
+    CallPermitPrecompile<R>: Precompile,
+    ProxyPrecompile<R>: Precompile,
+    IdentityPrecompile<R, MaxAdditionalFields>: Precompile,
+    AssetConversionRouterPrecompile<R>: Precompile,
+    RandomnessPrecompile<R, RandomnessRequestDeposit>: Precompile,
 {
     fn execute(&self, handle: &mut impl PrecompileHandle) -> Option<PrecompileResult> {
-        match handle.code_address() {
+        let address = handle.code_address();
+        // Disabled precompiles are left to the EVM, which runs their dummy code.
+        if Self::is_registry_precompile(address) {
+            if let Err(error) = handle.record_cost(registry_read_cost::<R>()) {
+                return Some(Err(error.into()));
+            }
+            if !pallet_precompile_registry::Pallet::<R>::is_active(address) {
+                return None;
+            }
+        }
+
+        // Stateful precompiles act on behalf of their caller, so any contract could act on
+        // behalf of the accounts calling it if it could run them in its own context.
+        if address != handle.context().address {
+            let (is_stateful, extra_cost) =
+                match self.is_stateful_precompile(address, handle.remaining_gas()) {
+                    IsPrecompileResult::Answer {
+                        is_precompile,
+                        extra_cost,
+                    } => (is_precompile, extra_cost),
+                    IsPrecompileResult::OutOfGas => return Some(Err(ExitError::OutOfGas.into())),
+                };
+            if let Err(error) = handle.record_cost(extra_cost) {
+                return Some(Err(error.into()));
+            }
+            if is_stateful {
+                return Some(Err(revert(
+                    "Cannot be called with DELEGATECALL or CALLCODE",
+                )));
+            }
+        }
+
+        match address {
             // Ethereum precompiles :
             a if a == hash(1) => Some(ECRecover::execute(handle)),
             a if a == hash(2) => Some(Sha256::execute(handle)),
             a if a == hash(3) => Some(Ripemd160::execute(handle)),
             a if a == hash(4) => Some(Identity::execute(handle)),
             a if a == hash(5) => Some(Modexp::execute(handle)),
+            a if a == hash(6) => Some(Bn128Add::execute(handle)),
+            a if a == hash(7) => Some(Bn128Mul::execute(handle)),
+            a if a == hash(8) => Some(Bn128Pairing::execute(handle)),
+            a if a == hash(9) => Some(Blake2F::execute(handle)),
+            // EIP-2537 BLS12-381 precompiles :
+            a if a == hash(11) => Some(Bls12381G1Add::execute(handle)),
+            a if a == hash(12) => Some(Bls12381G1Msm::execute(handle)),
+            a if a == hash(13) => Some(Bls12381G2Add::execute(handle)),
+            a if a == hash(14) => Some(Bls12381G2Msm::execute(handle)),
+            a if a == hash(15) => Some(Bls12381Pairing::execute(handle)),
+            a if a == hash(16) => Some(Bls12381MapFpToG1::execute(handle)),
+            a if a == hash(17) => Some(Bls12381MapFp2ToG2::execute(handle)),
+            // RIP-7212 precompiles :
+            a if a == hash(256) => Some(P256Verify::execute(handle)),
             // Non-Frontier specific nor Ethereum precompiles :
             a if a == hash(1024) => Some(Sha3FIPS256::execute(handle)),
             a if a == hash(1025) => Some(ECRecoverPublicKey::execute(handle)),
+            // Substrate pallets precompiles :
+            a if a == hash(2048) => Some(
+                Erc20BalancesPrecompile::<R, NativeErc20Metadata>::execute(handle),
+            ),
+            a if a == hash(2049) => Some(StakingPrecompile::<R>::execute(handle)),
+            a if a == hash(2050) => Some(NominationPoolsPrecompile::<R>::execute(handle)),
+            a if a == hash(2051) => Some(PreimagePrecompile::<R>::execute(handle)),
+            a if a == hash(2052) => Some(ReferendaPrecompile::<R, ReferendaTrackOrigins>::execute(
+                handle,
+            )),
+            a if a == hash(2053) => Some(ConvictionVotingPrecompile::<R>::execute(handle)),
+            a if a == hash(2054) => Some(DispatchPrecompile::<R>::execute(handle)),
+            a if a == hash(2055) => BatchPrecompileSet::<R>::new().execute(handle),
+            a if a == hash(2056) => Some(CallPermitPrecompile::<R>::execute(handle)),
+            a if a == hash(2057) => Some(ProxyPrecompile::<R>::execute(handle)),
+            a if a == hash(2058) => Some(IdentityPrecompile::<R, MaxAdditionalFields>::execute(
+                handle,
+            )),
+            a if a == hash(2059) => Some(AssetConversionRouterPrecompile::<R>::execute(handle)),
+            a if a == hash(2060) => {
+                Some(RandomnessPrecompile::<R, RandomnessRequestDeposit>::execute(handle))
+            }
+            // pallet-assets ERC-20 precompiles :
+            a if a.as_bytes().starts_with(ASSET_PRECOMPILE_ADDRESS_PREFIX) => {
+                Erc20AssetsPrecompileSet::<R, Instance1>::new().execute(handle)
+            }
+            // pallet-nfts ERC-721 precompiles :
+            a if a
+                .as_bytes()
+                .starts_with(NFT_COLLECTION_PRECOMPILE_ADDRESS_PREFIX) =>
+            {
+                Erc721NftsPrecompileSet::<R>::new().execute(handle)
+            }
             _ => None,
         }
     }
 
-    fn is_precompile(&self, address: H160, _gas: u64) -> IsPrecompileResult {
+    fn is_precompile(&self, address: H160, gas: u64) -> IsPrecompileResult {
+        if address
+            .as_bytes()
+            .starts_with(ASSET_PRECOMPILE_ADDRESS_PREFIX)
+        {
+            return Erc20AssetsPrecompileSet::<R, Instance1>::new().is_precompile(address, gas);
+        }
+        if address
+            .as_bytes()
+            .starts_with(NFT_COLLECTION_PRECOMPILE_ADDRESS_PREFIX)
+        {
+            return Erc721NftsPrecompileSet::<R>::new().is_precompile(address, gas);
+        }
+        if !Self::is_registry_precompile(address) {
+            return IsPrecompileResult::Answer {
+                is_precompile: Self::is_ethereum_precompile(address),
+                extra_cost: 0,
+            };
+        }
         IsPrecompileResult::Answer {
-            is_precompile: Self::used_addresses().contains(&address),
-            extra_cost: 0,
+            is_precompile: pallet_precompile_registry::Pallet::<R>::is_active(address),
+            extra_cost: registry_read_cost::<R>(),
+        }
+    }
+}
+
+impl AddressToAssetId<AssetId> for Runtime {
+    fn address_to_asset_id(address: H160) -> Option<AssetId> {
+        let (prefix, id) = address.as_bytes().split_at(16);
+        if prefix.starts_with(ASSET_PRECOMPILE_ADDRESS_PREFIX)
+            && prefix[ASSET_PRECOMPILE_ADDRESS_PREFIX.len()..]
+                .iter()
+                .all(|b| *b == 0)
+        {
+            let mut buf = [0u8; 4];
+            buf.copy_from_slice(id);
+            Some(AssetId::from_be_bytes(buf))
+        } else {
+            None
+        }
+    }
+
+    fn asset_id_to_address(asset_id: AssetId) -> H160 {
+        let mut address = [0u8; 20];
+        address[..4].copy_from_slice(ASSET_PRECOMPILE_ADDRESS_PREFIX);
+        address[16..].copy_from_slice(&asset_id.to_be_bytes());
+        H160(address)
+    }
+}
+
+/// The tokens of the asset conversion router: the native currency at its ERC-20 precompile
+/// address, and the assets at their ERC-20 precompile address.
+impl AddressToAssetKind<NativeOrWithId<AssetId>> for Runtime {
+    fn address_to_asset_kind(address: H160) -> Option<NativeOrWithId<AssetId>> {
+        if address == hash(2048) {
+            return Some(NativeOrWithId::Native);
+        }
+        Runtime::address_to_asset_id(address).map(NativeOrWithId::WithId)
+    }
+}
+
+impl AddressToCollectionId<CollectionId> for Runtime {
+    fn address_to_collection_id(address: H160) -> Option<CollectionId> {
+        let (prefix, id) = address.as_bytes().split_at(16);
+        if prefix.starts_with(NFT_COLLECTION_PRECOMPILE_ADDRESS_PREFIX)
+            && prefix[NFT_COLLECTION_PRECOMPILE_ADDRESS_PREFIX.len()..]
+                .iter()
+                .all(|b| *b == 0)
+        {
+            let mut buf = [0u8; 4];
+            buf.copy_from_slice(id);
+            Some(CollectionId::from_be_bytes(buf))
+        } else {
+            None
         }
     }
+
+    fn collection_id_to_address(collection_id: CollectionId) -> H160 {
+        let mut address = [0u8; 20];
+        address[..4].copy_from_slice(NFT_COLLECTION_PRECOMPILE_ADDRESS_PREFIX);
+        address[16..].copy_from_slice(&collection_id.to_be_bytes());
+        H160(address)
+    }
 }
 
 fn hash(a: u64) -> H160 {
     H160::from_low_u64_be(a)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        constants::currency::DOLLARS,
+        frame_support::{
+            assert_noop, assert_ok,
+            traits::{BeforeAllRuntimeMigrations, Currency, GetStorageVersion, OnRuntimeUpgrade},
+        },
+        pallet_multisig, pallet_preimage, pallet_staking,
+        sp_core::U256,
+        sp_runtime::traits::Hash as _,
+        Assets, Balances, Nfts, PrecompileRegistry, RuntimeOrigin,
+    };
+    use codec::Encode;
+    use fp_evm::Context;
+    use pallet_evm_precompile_balances_erc20::Erc20BalancesPrecompileCall;
+    use pallet_evm_precompile_conviction_voting::ConvictionVotingPrecompileCall;
+    use pallet_evm_precompile_nomination_pools::NominationPoolsPrecompileCall;
+    use pallet_evm_precompile_referenda::ReferendaPrecompileCall;
+    use pallet_evm_precompile_staking::StakingPrecompileCall;
+    use pallet_evm_test_vector_support::{
+        test_precompile_failure_test_vectors, test_precompile_test_vectors,
+    };
+    use precompile_utils::{
+        prelude::{Address, RuntimeHelper},
+        testing::{decode_revert_message, MockHandle, PrecompileTesterExt},
+    };
+
+    const ALICE: H160 = H160::repeat_byte(0xaa);
+    const BOB: H160 = H160::repeat_byte(0xbb);
+
+    /// Externalities where Alice and Bob are funded and the precompile at `address` is enabled.
+    fn precompile_test_ext(address: H160) -> crate::sp_io::TestExternalities {
+        let mut ext = crate::sp_io::TestExternalities::default();
+        ext.execute_with(|| {
+            crate::frame_system::Pallet::<Runtime>::set_block_number(1);
+            for account in [ALICE, BOB] {
+                let _ = Balances::deposit_creating(&account.into(), 1_000 * DOLLARS);
+            }
+            assert_ok!(PrecompileRegistry::enable_precompile(
+                RuntimeOrigin::root(),
+                address
+            ));
+        });
+        ext
+    }
+
+    fn db_read() -> u64 {
+        RuntimeHelper::<Runtime>::db_read_gas_cost()
+    }
+
+    fn dispatch_error(output: &[u8], error: &str) -> bool {
+        core::str::from_utf8(output).map_or(false, |reason| reason.contains(error))
+    }
+
+    #[test]
+    fn process_consensus_tests_for_bn128_add() -> Result<(), String> {
+        test_precompile_test_vectors::<Bn128Add>("../../precompiles/testdata/bn128_add.json")?;
+        test_precompile_failure_test_vectors::<Bn128Add>(
+            "../../precompiles/testdata/bn128_fail_add.json",
+        )
+    }
+
+    #[test]
+    fn process_consensus_tests_for_bn128_mul() -> Result<(), String> {
+        test_precompile_test_vectors::<Bn128Mul>("../../precompiles/testdata/bn128_mul.json")?;
+        test_precompile_failure_test_vectors::<Bn128Mul>(
+            "../../precompiles/testdata/bn128_fail_mul.json",
+        )
+    }
+
+    #[test]
+    fn process_consensus_tests_for_bn128_pairing() -> Result<(), String> {
+        test_precompile_test_vectors::<Bn128Pairing>(
+            "../../precompiles/testdata/bn128_pairing.json",
+        )?;
+        test_precompile_failure_test_vectors::<Bn128Pairing>(
+            "../../precompiles/testdata/bn128_fail_pairing.json",
+        )
+    }
+
+    #[test]
+    fn process_consensus_tests_for_blake2f() -> Result<(), String> {
+        test_precompile_test_vectors::<Blake2F>("../../precompiles/testdata/blake2f.json")?;
+        test_precompile_failure_test_vectors::<Blake2F>(
+            "../../precompiles/testdata/blake2f_fail.json",
+        )
+    }
+
+    #[test]
+    fn collection_addresses_round_trip() {
+        let address = Runtime::collection_id_to_address(42);
+        assert!(address
+            .as_bytes()
+            .starts_with(NFT_COLLECTION_PRECOMPILE_ADDRESS_PREFIX));
+        assert_eq!(Runtime::address_to_collection_id(address), Some(42));
+        assert_eq!(
+            Runtime::address_to_collection_id(Runtime::asset_id_to_address(42)),
+            None
+        );
+    }
+
+    #[test]
+    fn router_tokens_are_erc20_precompiles() {
+        assert_eq!(
+            Runtime::address_to_asset_kind(hash(2048)),
+            Some(NativeOrWithId::Native)
+        );
+        assert_eq!(
+            Runtime::address_to_asset_kind(Runtime::asset_id_to_address(7)),
+            Some(NativeOrWithId::WithId(7))
+        );
+        assert_eq!(Runtime::address_to_asset_kind(hash(2049)), None);
+    }
+
+    #[test]
+    fn registry_toggles_precompiles() {
+        crate::sp_io::TestExternalities::default().execute_with(|| {
+            let precompiles = FrontierPrecompiles::<Runtime>::new();
+            let address = hash(2055);
+            let is_precompile = |address| match precompiles.is_precompile(address, u64::MAX) {
+                IsPrecompileResult::Answer { is_precompile, .. } => is_precompile,
+                _ => unreachable!(),
+            };
+            assert!(!is_precompile(address));
+
+            assert_ok!(PrecompileRegistry::enable_precompile(
+                RuntimeOrigin::root(),
+                address
+            ));
+            assert!(is_precompile(address));
+            assert_eq!(
+                pallet_evm::AccountCodes::<Runtime>::get(address),
+                pallet_precompile_registry::DUMMY_CODE.to_vec()
+            );
+
+            assert_ok!(PrecompileRegistry::disable_precompile(
+                RuntimeOrigin::root(),
+                address
+            ));
+            assert!(!is_precompile(address));
+            assert_noop!(
+                PrecompileRegistry::enable_precompile(RuntimeOrigin::root(), hash(10)),
+                pallet_precompile_registry::Error::<Runtime>::NotAPrecompile
+            );
+        });
+    }
+
+    #[test]
+    fn stateful_precompiles_reject_delegatecall() {
+        crate::sp_io::TestExternalities::default().execute_with(|| {
+            let owner = AccountId::from(H160::repeat_byte(0xaa));
+            assert_ok!(Assets::force_create(
+                RuntimeOrigin::root(),
+                codec::Compact(1),
+                owner,
+                true,
+                1
+            ));
+            assert_ok!(Nfts::force_create(
+                RuntimeOrigin::root(),
+                owner,
+                pallet_nfts::CollectionConfig {
+                    settings: pallet_nfts::CollectionSettings::all_enabled(),
+                    max_supply: None,
+                    mint_settings: pallet_nfts::MintSettings::default(),
+                }
+            ));
+
+            let precompiles = FrontierPrecompiles::<Runtime>::new();
+            let stateful = (2048..=2060).map(hash).chain([
+                Runtime::asset_id_to_address(1),
+                Runtime::collection_id_to_address(0),
+            ]);
+            for address in stateful {
+                if RegistryPrecompileAddresses::get().contains(&address) {
+                    assert_ok!(PrecompileRegistry::enable_precompile(
+                        RuntimeOrigin::root(),
+                        address
+                    ));
+                }
+
+                // A contract delegate calling the precompile, on behalf of the account calling it.
+                let mut handle = MockHandle::new(
+                    address,
+                    Context {
+                        address: H160::repeat_byte(0xdd),
+                        caller: H160::repeat_byte(0xaa),
+                        apparent_value: U256::zero(),
+                    },
+                );
+                handle.input = vec![0xa9, 0x05, 0x9c, 0xbb];
+
+                match precompiles.execute(&mut handle) {
+                    Some(Err(PrecompileFailure::Revert { output, .. })) => assert_eq!(
+                        decode_revert_message(&output),
+                        b"Cannot be called with DELEGATECALL or CALLCODE",
+                        "{address:?}"
+                    ),
+                    _ => panic!("{address:?} accepts delegatecall"),
+                }
+            }
+
+            // Stateless precompiles may still be delegate called.
+            let mut handle = MockHandle::new(
+                hash(4),
+                Context {
+                    address: H160::repeat_byte(0xdd),
+                    caller: H160::repeat_byte(0xaa),
+                    apparent_value: U256::zero(),
+                },
+            );
+            handle.input = vec![1, 2, 3];
+            assert!(matches!(precompiles.execute(&mut handle), Some(Ok(_))));
+        });
+    }
+
+    #[test]
+    fn dispatch_precompile_rejects_delegatecall() {
+        precompile_test_ext(hash(2054)).execute_with(|| {
+            let precompiles = FrontierPrecompiles::<Runtime>::new();
+            let bytes = b"a proposal".to_vec();
+            let preimage_hash = crate::Hashing::hash(&bytes);
+            let call = RuntimeCall::Preimage(pallet_preimage::Call::note_preimage { bytes });
+            let dispatch = |context_address: H160, call: &RuntimeCall| {
+                let mut handle = MockHandle::new(
+                    hash(2054),
+                    Context {
+                        address: context_address,
+                        caller: ALICE,
+                        apparent_value: U256::zero(),
+                    },
+                );
+                handle.input = call.encode();
+                precompiles.execute(&mut handle)
+            };
+
+            // A contract delegate calling the dispatch precompile would dispatch as its caller.
+            match dispatch(H160::repeat_byte(0xdd), &call) {
+                Some(Err(PrecompileFailure::Revert { output, .. })) => assert_eq!(
+                    decode_revert_message(&output),
+                    b"Cannot be called with DELEGATECALL or CALLCODE"
+                ),
+                _ => panic!("the dispatch precompile accepts delegatecall"),
+            }
+            assert!(!pallet_preimage::RequestStatusFor::<Runtime>::contains_key(
+                preimage_hash
+            ));
+
+            assert!(matches!(
+                dispatch(
+                    hash(2054),
+                    &RuntimeCall::System(frame_system::Call::remark { remark: vec![] })
+                ),
+                Some(Err(PrecompileFailure::Error { .. }))
+            ));
+
+            assert!(matches!(dispatch(hash(2054), &call), Some(Ok(_))));
+            assert!(pallet_preimage::RequestStatusFor::<Runtime>::contains_key(
+                preimage_hash
+            ));
+        });
+    }
+
+    #[test]
+    fn dispatch_precompile_filter_rejects_balances_calls() {
+        let transfer = RuntimeCall::Balances(pallet_balances::Call::transfer_allow_death {
+            dest: AccountId::from(BOB),
+            value: DOLLARS,
+        });
+        assert!(!DispatchPrecompileFilter::contains(&transfer));
+        assert!(!DispatchPrecompileFilter::contains(&RuntimeCall::Proxy(
+            pallet_proxy::Call::proxy {
+                real: AccountId::from(ALICE),
+                force_proxy_type: None,
+                call: Box::new(transfer),
+            }
+        )));
+    }
+
+    /// Whether a proxy of type `proxy_type` may dispatch `call` through the dispatch precompile.
+    fn proxy_may_dispatch(proxy_type: ProxyType, call: RuntimeCall) -> bool {
+        proxy_type.is_evm_proxy_call_allowed(&EvmSubCall {
+            to: hash(2054),
+            value: U256::zero(),
+            call_data: call.encode(),
+        })
+    }
+
+    fn add_proxy(proxy_type: ProxyType) -> RuntimeCall {
+        RuntimeCall::Proxy(pallet_proxy::Call::add_proxy {
+            delegate: AccountId::from(BOB),
+            proxy_type,
+            delay: 0,
+        })
+    }
+
+    fn assets_transfer() -> RuntimeCall {
+        RuntimeCall::Assets(pallet_assets::Call::transfer {
+            id: 1.into(),
+            target: AccountId::from(BOB),
+            amount: 1,
+        })
+    }
+
+    #[test]
+    fn proxies_only_add_proxies_they_are_a_superset_of() {
+        assert!(!proxy_may_dispatch(
+            ProxyType::NonTransfer,
+            add_proxy(ProxyType::Any)
+        ));
+        assert!(!proxy_may_dispatch(
+            ProxyType::NonTransfer,
+            RuntimeCall::Proxy(pallet_proxy::Call::remove_proxy {
+                delegate: AccountId::from(BOB),
+                proxy_type: ProxyType::Any,
+                delay: 0,
+            })
+        ));
+        assert!(proxy_may_dispatch(
+            ProxyType::NonTransfer,
+            add_proxy(ProxyType::Staking)
+        ));
+        assert!(proxy_may_dispatch(
+            ProxyType::Any,
+            add_proxy(ProxyType::Any)
+        ));
+    }
+
+    #[test]
+    fn only_any_proxies_remove_proxies_and_manage_pure_proxies() {
+        let calls = [
+            pallet_proxy::Call::remove_proxies {},
+            pallet_proxy::Call::create_pure {
+                proxy_type: ProxyType::Any,
+                delay: 0,
+                index: 0,
+            },
+            pallet_proxy::Call::kill_pure {
+                spawner: AccountId::from(BOB),
+                proxy_type: ProxyType::Any,
+                index: 0,
+                height: 0,
+                ext_index: 0,
+            },
+        ];
+        for call in calls.map(RuntimeCall::Proxy) {
+            assert!(!proxy_may_dispatch(ProxyType::NonTransfer, call.clone()));
+            assert!(proxy_may_dispatch(ProxyType::Any, call));
+        }
+    }
+
+    #[test]
+    fn proxies_may_not_proxy_calls_of_another_account() {
+        let proxied = RuntimeCall::Proxy(pallet_proxy::Call::proxy {
+            real: AccountId::from(BOB),
+            force_proxy_type: None,
+            call: Box::new(assets_transfer()),
+        });
+        assert!(!proxy_may_dispatch(
+            ProxyType::NonTransfer,
+            assets_transfer()
+        ));
+        assert!(!proxy_may_dispatch(ProxyType::NonTransfer, proxied.clone()));
+        assert!(proxy_may_dispatch(ProxyType::Any, proxied));
+    }
+
+    #[test]
+    fn proxies_may_not_dispatch_multisig_calls() {
+        let multisig = RuntimeCall::Multisig(pallet_multisig::Call::as_multi_threshold_1 {
+            other_signatories: vec![AccountId::from(BOB)],
+            call: Box::new(assets_transfer()),
+        });
+        assert!(!proxy_may_dispatch(
+            ProxyType::NonTransfer,
+            multisig.clone()
+        ));
+        assert!(proxy_may_dispatch(ProxyType::Any, multisig));
+        assert!(proxy_may_dispatch(
+            ProxyType::Staking,
+            RuntimeCall::Staking(pallet_staking::Call::chill {})
+        ));
+    }
+
+    #[test]
+    fn native_erc20_transfers_are_not_call_filtered() {
+        crate::sp_io::TestExternalities::default().execute_with(|| {
+            let (alice, bob) = (H160::repeat_byte(0xaa), H160::repeat_byte(0xbb));
+            let _ = Balances::deposit_creating(&alice.into(), 10 * DOLLARS);
+            assert_ok!(PrecompileRegistry::enable_precompile(
+                RuntimeOrigin::root(),
+                hash(2048)
+            ));
+
+            FrontierPrecompiles::<Runtime>::new()
+                .prepare_test(
+                    alice,
+                    hash(2048),
+                    Erc20BalancesPrecompileCall::<Runtime, NativeErc20Metadata>::transfer {
+                        to: Address(bob),
+                        value: DOLLARS.into(),
+                    },
+                )
+                .execute_returns(true);
+
+            assert_eq!(Balances::free_balance(AccountId::from(bob)), DOLLARS);
+        });
+    }
+
+    #[test]
+    fn ethereum_precompiles_are_used_addresses() {
+        let used = FrontierPrecompiles::<Runtime>::used_addresses();
+        for address in (1..=9).chain(11..=17).chain([256]) {
+            assert!(used.contains(&hash(address)));
+        }
+    }
+
+    #[test]
+    fn ethereum_precompiles_bypass_the_registry() {
+        crate::sp_io::TestExternalities::default().execute_with(|| {
+            let precompiles = FrontierPrecompiles::<Runtime>::new();
+            let registry = RegistryPrecompileAddresses::get();
+            for address in (1..=9).map(hash) {
+                assert!(!registry.contains(&address));
+                assert!(matches!(
+                    precompiles.is_precompile(address, u64::MAX),
+                    IsPrecompileResult::Answer {
+                        is_precompile: true,
+                        extra_cost: 0
+                    }
+                ));
+                assert!(!pallet_evm::AccountCodes::<Runtime>::contains_key(address));
+                assert_noop!(
+                    PrecompileRegistry::enable_precompile(RuntimeOrigin::root(), address),
+                    pallet_precompile_registry::Error::<Runtime>::NotAPrecompile
+                );
+            }
+
+            // Ethereum precompiles are run without paying for a registry read.
+            let mut handle = MockHandle::new(
+                hash(4),
+                Context {
+                    address: hash(4),
+                    caller: ALICE,
+                    apparent_value: U256::zero(),
+                },
+            );
+            handle.input = vec![1, 2, 3];
+            assert!(matches!(precompiles.execute(&mut handle), Some(Ok(_))));
+            assert_eq!(handle.gas_used, 15 + 3);
+        });
+    }
+
+    #[test]
+    fn registry_is_initialized_when_added_to_a_running_chain() {
+        crate::sp_io::TestExternalities::default().execute_with(|| {
+            // Done by the executive for the pallets added by a runtime upgrade, before the
+            // migrations run.
+            <PrecompileRegistry as BeforeAllRuntimeMigrations>::before_all_runtime_migrations();
+            assert_eq!(PrecompileRegistry::on_chain_storage_version(), 1);
+
+            <PrecompileRegistry as OnRuntimeUpgrade>::on_runtime_upgrade();
+            for address in RegistryPrecompileAddresses::get() {
+                assert!(PrecompileRegistry::is_active(address));
+                assert_eq!(
+                    pallet_evm::AccountCodes::<Runtime>::get(address),
+                    pallet_precompile_registry::DUMMY_CODE.to_vec()
+                );
+            }
+
+            // Precompiles disabled by governance stay disabled on later upgrades.
+            assert_ok!(PrecompileRegistry::disable_precompile(
+                RuntimeOrigin::root(),
+                hash(2055)
+            ));
+            <PrecompileRegistry as OnRuntimeUpgrade>::on_runtime_upgrade();
+            assert!(!PrecompileRegistry::is_active(hash(2055)));
+        });
+    }
+
+    type StakingCall = StakingPrecompileCall<Runtime>;
+    type PoolsCall = NominationPoolsPrecompileCall<Runtime>;
+    type ReferendaCall = ReferendaPrecompileCall<Runtime, ReferendaTrackOrigins>;
+    type ConvictionVotingCall = ConvictionVotingPrecompileCall<Runtime>;
+
+    #[test]
+    fn staking_selectors() {
+        assert!(StakingCall::current_era_selectors().contains(&0x973628f6));
+        assert!(StakingCall::ledger_selectors().contains(&0xfbfa941f));
+        assert!(StakingCall::nominations_selectors().contains(&0xf95baf26));
+        assert!(StakingCall::bond_selectors().contains(&0x70c48766));
+        assert!(StakingCall::bond_extra_selectors().contains(&0xeaca88de));
+        assert!(StakingCall::unbond_selectors().contains(&0x27de9e32));
+        assert!(StakingCall::withdraw_unbonded_selectors().contains(&0x548a6706));
+        assert!(StakingCall::nominate_selectors().contains(&0x19f2fdad));
+        assert!(StakingCall::chill_selectors().contains(&0x2b8a3ae6));
+        assert!(StakingCall::payout_stakers_selectors().contains(&0x6d4fc25a));
+    }
+
+    #[test]
+    fn nomination_pools_selectors() {
+        assert!(PoolsCall::pending_rewards_selectors().contains(&0x31d7a262));
+        assert!(PoolsCall::pool_member_selectors().contains(&0x24ac6ae2));
+        assert!(PoolsCall::join_selectors().contains(&0x98f15d02));
+        assert!(PoolsCall::bond_extra_selectors().contains(&0xeaca88de));
+        assert!(PoolsCall::bond_extra_rewards_selectors().contains(&0x026db81f));
+        assert!(PoolsCall::claim_payout_selectors().contains(&0xe88499a9));
+        assert!(PoolsCall::unbond_selectors().contains(&0xa5d059ca));
+        assert!(PoolsCall::pool_withdraw_unbonded_selectors().contains(&0x1332e2ef));
+        assert!(PoolsCall::withdraw_unbonded_selectors().contains(&0xb5fb9875));
+    }
+
+    #[test]
+    fn referenda_selectors() {
+        assert!(ReferendaCall::referendum_count_selectors().contains(&0x3a42ee31));
+        assert!(ReferendaCall::referendum_status_selectors().contains(&0x8d407c0b));
+        assert!(ReferendaCall::submit_at_selectors().contains(&0x131f3468));
+        assert!(ReferendaCall::submit_after_selectors().contains(&0x5b2479db));
+        assert!(ReferendaCall::place_decision_deposit_selectors().contains(&0x245ce18d));
+        assert!(ReferendaCall::refund_decision_deposit_selectors().contains(&0x1325d528));
+    }
+
+    #[test]
+    fn conviction_voting_selectors() {
+        assert!(ConvictionVotingCall::vote_yes_selectors().contains(&0xda9df518));
+        assert!(ConvictionVotingCall::vote_no_selectors().contains(&0xcc600eba));
+        assert!(ConvictionVotingCall::vote_split_selectors().contains(&0xdd6c52a4));
+        assert!(ConvictionVotingCall::vote_split_abstain_selectors().contains(&0x52004540));
+        assert!(ConvictionVotingCall::remove_vote_selectors().contains(&0x79cae220));
+        assert!(ConvictionVotingCall::delegate_selectors().contains(&0x681750e8));
+        assert!(ConvictionVotingCall::undelegate_selectors().contains(&0x98be4094));
+        assert!(ConvictionVotingCall::unlock_selectors().contains(&0x4259d98c));
+    }
+
+    /// The behavior of these precompiles is tested in their crates, this only checks that their
+    /// addresses are routed to the pallets of the runtime.
+    #[test]
+    fn staking_and_governance_precompiles_are_wired() {
+        precompile_test_ext(hash(2049)).execute_with(|| {
+            for address in [hash(2050), hash(2052), hash(2053)] {
+                assert_ok!(PrecompileRegistry::enable_precompile(
+                    RuntimeOrigin::root(),
+                    address
+                ));
+            }
+            let precompiles = FrontierPrecompiles::<Runtime>::new();
+
+            precompiles
+                .prepare_test(
+                    ALICE,
+                    hash(2049),
+                    StakingCall::bond {
+                        value: (100 * DOLLARS).into(),
+                        payee: 0,
+                        reward_account: Address(H160::zero()),
+                    },
+                )
+                .execute_returns(());
+            let bonded = U256::from(100 * DOLLARS);
+            precompiles
+                .prepare_test(
+                    BOB,
+                    hash(2049),
+                    StakingCall::ledger {
+                        stash: Address(ALICE),
+                    },
+                )
+                .expect_cost(registry_read_cost::<Runtime>() + 2 * db_read())
+                .execute_returns((bonded, bonded, U256::zero()));
+
+            precompiles
+                .prepare_test(
+                    ALICE,
+                    hash(2050),
+                    PoolsCall::join {
+                        amount: DOLLARS.into(),
+                        pool_id: 1,
+                    },
+                )
+                .execute_reverts(|output| dispatch_error(output, "PoolNotFound"));
+
+            precompiles
+                .prepare_test(ALICE, hash(2052), ReferendaCall::referendum_count {})
+                .expect_cost(registry_read_cost::<Runtime>() + db_read())
+                .execute_returns(0u32);
+
+            precompiles
+                .prepare_test(
+                    ALICE,
+                    hash(2053),
+                    ConvictionVotingCall::vote_yes {
+                        poll_index: 0,
+                        vote_amount: DOLLARS.into(),
+                        conviction: 1,
+                    },
+                )
+                .execute_reverts(|output| dispatch_error(output, "NotOngoing"));
+        });
+    }
+}